@@ -0,0 +1,319 @@
+use std::sync::OnceLock;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::mapping::{MappingAdapter, MappingDefinition};
+use crate::{CanonicalEvent, ConnectorAdapter, ConnectorError, RawEvent};
+
+const ADYEN_MAPPING: &str = include_str!("../mappings/adyen.json");
+
+/// Adyen standard notifications, normalized through the bundled `mappings/adyen.json`.
+/// Only CAPTURE becomes `order.captured.v1`, so a payment is booked once whether it was captured
+/// automatically or manually. AUTHORISATION, `*_FAILED` and `success=false` notifications are
+/// skipped: they report nothing to book.
+#[derive(Debug, Default, Clone)]
+pub struct AdyenAdapter;
+
+fn mapping() -> &'static MappingAdapter {
+    static MAPPING: OnceLock<MappingAdapter> = OnceLock::new();
+    MAPPING.get_or_init(|| {
+        MappingDefinition::from_json(ADYEN_MAPPING)
+            .and_then(MappingAdapter::new)
+            .expect("bundled adyen mapping should be valid")
+    })
+}
+
+/// The `NotificationRequestItem`s of an Adyen delivery. A bare item is returned as-is.
+pub fn notification_items(payload: &Value) -> Vec<Value> {
+    match payload.get("notificationItems").and_then(Value::as_array) {
+        Some(items) => items
+            .iter()
+            .map(|item| item.get("NotificationRequestItem").unwrap_or(item).clone())
+            .collect(),
+        None => vec![payload.clone()],
+    }
+}
+
+#[async_trait]
+impl ConnectorAdapter for AdyenAdapter {
+    fn source_system(&self) -> &'static str {
+        "adyen"
+    }
+
+    fn adapter_version(&self) -> &'static str {
+        mapping().adapter_version()
+    }
+
+    /// Each item of a batched delivery becomes a single-item delivery of its own.
+    fn split_delivery(&self, payload: Value) -> Vec<Value> {
+        let items = notification_items(&payload);
+        if items.len() <= 1 {
+            return vec![payload];
+        }
+        let live = payload.get("live").cloned().unwrap_or(Value::Null);
+        items
+            .into_iter()
+            .map(|item| {
+                json!({
+                    "live": live,
+                    "notificationItems": [{"NotificationRequestItem": item}]
+                })
+            })
+            .collect()
+    }
+
+    async fn normalize(&self, raw: RawEvent) -> Result<CanonicalEvent, ConnectorError> {
+        let RawEvent {
+            source_event_id,
+            occurred_at,
+            payload,
+        } = raw;
+
+        let mut items = notification_items(&payload);
+        if items.len() != 1 {
+            return Err(ConnectorError::Normalize(format!(
+                "adyen delivery carries {} notification items; split the delivery first",
+                items.len()
+            )));
+        }
+        let item = items.remove(0);
+        let event_code = item
+            .get("eventCode")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let succeeded = match item.get("success") {
+            None => true,
+            Some(Value::Bool(success)) => *success,
+            Some(Value::String(success)) => success.eq_ignore_ascii_case("true"),
+            Some(_) => false,
+        };
+        if !succeeded {
+            return Err(ConnectorError::Skipped(format!(
+                "adyen {event_code} notification reports success=false"
+            )));
+        }
+        if event_code == "AUTHORISATION" || event_code.ends_with("_FAILED") {
+            return Err(ConnectorError::Skipped(format!(
+                "adyen {event_code} notifications are not booked"
+            )));
+        }
+
+        mapping()
+            .normalize(RawEvent {
+                source_event_id,
+                occurred_at,
+                payload: item,
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::Utc;
+    use serde_json::{json, Value};
+
+    use crate::backfill::testing::replay_with_transient_failures;
+    use crate::{
+        evaluate_cutover_rehearsal, ConnectorAdapter, ConnectorError, CutoverCheckpoint, RawEvent,
+    };
+
+    use super::{notification_items, AdyenAdapter};
+
+    fn fixture(name: &str) -> Value {
+        let raw = match name {
+            "authorisation" => include_str!("../fixtures/adyen/authorisation.json"),
+            "capture" => include_str!("../fixtures/adyen/capture.json"),
+            "refund" => include_str!("../fixtures/adyen/refund.json"),
+            "chargeback" => include_str!("../fixtures/adyen/chargeback.json"),
+            "payout" => include_str!("../fixtures/adyen/payout.json"),
+            _ => unreachable!("unknown adyen fixture `{name}`"),
+        };
+        serde_json::from_str(raw).unwrap()
+    }
+
+    fn raw(name: &str) -> RawEvent {
+        let payload = fixture(name);
+        let item = &notification_items(&payload)[0];
+        RawEvent {
+            source_event_id: format!(
+                "{}:{}",
+                item["pspReference"].as_str().unwrap(),
+                item["eventCode"].as_str().unwrap()
+            ),
+            occurred_at: Utc::now(),
+            payload,
+        }
+    }
+
+    #[tokio::test]
+    async fn normalizes_only_adyen_capture_as_order_captured() {
+        let adapter = AdyenAdapter;
+
+        let capture = adapter.normalize(raw("capture")).await.unwrap();
+        assert_eq!(capture.event_type, "order.captured.v1");
+        assert_eq!(capture.tenant_id, "tenant_1");
+        assert_eq!(capture.legal_entity_id, "US_CO_01");
+        assert_eq!(capture.business_date, "2026-03-15");
+        assert_eq!(capture.payload["amount_minor"], json!(17120));
+        assert_eq!(capture.payload["currency"], json!("USD"));
+        assert_eq!(capture.payload["location_id"], json!("BRECK_BASE_AREA"));
+        assert_eq!(capture.payload["psp_reference"], json!("8825073381342291"));
+        assert_eq!(
+            capture.payload["payment_psp_reference"],
+            json!("7914073381342284")
+        );
+        assert_eq!(
+            capture.idempotency_key,
+            "adyen:8825073381342291:CAPTURE:charge_captured"
+        );
+        assert_eq!(capture.trace_context.correlation_id, "ORD-20260314-0001");
+        assert_eq!(adapter.adapter_version(), "adyen-mapping-1");
+
+        let mut traced = raw("capture");
+        let metadata = &mut traced.payload["notificationItems"][0]["NotificationRequestItem"]
+            ["additionalData"];
+        metadata["metadata.correlation_id"] = json!("corr_adyen_capture");
+        metadata["metadata.traceparent"] =
+            json!("00-4bf92f3577b34da6a3ce929d0e0e4736-dddddddddddddddd-01");
+        let traced = adapter.normalize(traced).await.unwrap();
+        assert_eq!(traced.trace_context.correlation_id, "corr_adyen_capture");
+        assert_eq!(
+            traced.trace_context.traceparent.as_deref(),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-dddddddddddddddd-01")
+        );
+
+        let error = adapter.normalize(raw("authorisation")).await.unwrap_err();
+        assert!(matches!(error, ConnectorError::Skipped(message)
+            if message == "adyen AUTHORISATION notifications are not booked"));
+    }
+
+    #[tokio::test]
+    async fn normalizes_adyen_refund_chargeback_and_payout() {
+        let adapter = AdyenAdapter;
+
+        let refund = adapter.normalize(raw("refund")).await.unwrap();
+        assert_eq!(refund.event_type, "refund.v1");
+        assert_eq!(refund.payload["refund_id"], json!("8835073381345521"));
+        assert_eq!(refund.payload["amount_minor"], json!(2500));
+        assert_eq!(
+            refund.idempotency_key,
+            "adyen:8835073381345521:REFUND:refund"
+        );
+
+        let chargeback = adapter.normalize(raw("chargeback")).await.unwrap();
+        assert_eq!(chargeback.event_type, "chargeback.created.v1");
+        assert_eq!(
+            chargeback.payload["reason"],
+            json!("Fraudulent transaction")
+        );
+        assert_eq!(chargeback.payload["amount_minor"], json!(17120));
+        assert_eq!(chargeback.idempotency_key, "adyen:idem:chargeback");
+
+        let payout = adapter.normalize(raw("payout")).await.unwrap();
+        assert_eq!(payout.event_type, "payout.cleared.v1");
+        assert_eq!(payout.payload["amount_minor"], json!(965000));
+        assert_eq!(payout.business_date, "2026-03-17");
+        assert_eq!(payout.payload["routing"]["location_id"], json!(null));
+    }
+
+    #[tokio::test]
+    async fn skips_unsuccessful_and_rejects_batched_notifications() {
+        let adapter = AdyenAdapter;
+
+        for success in [json!("false"), json!(false)] {
+            let mut failed = raw("capture");
+            failed.payload["notificationItems"][0]["NotificationRequestItem"]["success"] = success;
+            let error = adapter.normalize(failed).await.unwrap_err();
+            assert!(matches!(error, ConnectorError::Skipped(message)
+                if message == "adyen CAPTURE notification reports success=false"));
+        }
+        let mut succeeded = raw("capture");
+        succeeded.payload["notificationItems"][0]["NotificationRequestItem"]["success"] =
+            json!(true);
+        assert!(adapter.normalize(succeeded).await.is_ok());
+
+        let mut refund_failed = raw("refund");
+        refund_failed.payload["notificationItems"][0]["NotificationRequestItem"]["eventCode"] =
+            json!("REFUND_FAILED");
+        let error = adapter.normalize(refund_failed).await.unwrap_err();
+        assert!(matches!(error, ConnectorError::Skipped(message)
+            if message == "adyen REFUND_FAILED notifications are not booked"));
+
+        let mut unknown = raw("refund");
+        unknown.payload["notificationItems"][0]["NotificationRequestItem"]["eventCode"] =
+            json!("OFFER_CLOSED");
+        let error = adapter.normalize(unknown).await.unwrap_err();
+        assert!(matches!(error, ConnectorError::Normalize(message)
+            if message == "unsupported adyen event kind"));
+
+        let mut batch = raw("refund");
+        let second = fixture("payout")["notificationItems"][0].clone();
+        batch.payload["notificationItems"]
+            .as_array_mut()
+            .unwrap()
+            .push(second);
+        assert_eq!(notification_items(&batch.payload).len(), 2);
+        let error = adapter
+            .normalize(RawEvent {
+                source_event_id: batch.source_event_id.clone(),
+                occurred_at: batch.occurred_at,
+                payload: batch.payload.clone(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ConnectorError::Normalize(message)
+            if message.contains("carries 2 notification items")));
+
+        let split = adapter.split_delivery(batch.payload);
+        assert_eq!(split.len(), 2);
+        let event_codes = split
+            .iter()
+            .map(|delivery| {
+                let items = notification_items(delivery);
+                assert_eq!(items.len(), 1);
+                items[0]["eventCode"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(event_codes, ["REFUND", "PAYOUT_THIRDPARTY"]);
+        assert_eq!(split[0]["live"], fixture("refund")["live"]);
+        assert_eq!(adapter.split_delivery(fixture("capture")).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn replay_backfill_resiliency_meets_target_for_adyen() {
+        let adapter = AdyenAdapter;
+        let events = vec![raw("capture"), raw("refund"), raw("payout")];
+        let failures = BTreeSet::from([1_usize]);
+
+        let result = replay_with_transient_failures(&adapter, &events, &failures, 1000).await;
+        assert_eq!(result.hashes.len(), 3);
+        assert_eq!(result.telemetry.first_attempt_failures, 1);
+        assert_eq!(result.telemetry.recovered_events, 1);
+        assert!(result.telemetry.objective_met);
+    }
+
+    #[tokio::test]
+    async fn cutover_rehearsal_passes_for_adyen_when_all_checkpoints_pass() {
+        let adapter = AdyenAdapter;
+        let events = vec![raw("capture")];
+
+        let replay =
+            replay_with_transient_failures(&adapter, &events, &BTreeSet::new(), 1000).await;
+        let rehearsal = evaluate_cutover_rehearsal(
+            &replay,
+            true,
+            &[CutoverCheckpoint {
+                name: "adyen_cutover_dry_run".to_string(),
+                passed: true,
+            }],
+        );
+
+        assert!(rehearsal.passed);
+        assert!(rehearsal.replay_objective_met);
+        assert!(rehearsal.rollback_validated);
+    }
+}
@@ -0,0 +1,662 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{CanonicalEvent, ConnectorAdapter, ConnectorError, RawEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FailureClass {
+    Transient,
+    Permanent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message}")]
+pub struct PostingError {
+    pub class: FailureClass,
+    pub message: String,
+}
+
+impl PostingError {
+    pub fn transient(message: impl Into<String>) -> Self {
+        Self {
+            class: FailureClass::Transient,
+            message: message.into(),
+        }
+    }
+
+    pub fn permanent(message: impl Into<String>) -> Self {
+        Self {
+            class: FailureClass::Permanent,
+            message: message.into(),
+        }
+    }
+}
+
+/// Where a backfill sends each canonical event once the adapter has normalized it.
+#[async_trait]
+pub trait BackfillPoster: Send + Sync {
+    async fn post(&self, event: &CanonicalEvent) -> Result<(), PostingError>;
+}
+
+/// An event the backfill gave up on, either on a permanent error or after its last attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackfillDeadLetter {
+    pub index: usize,
+    pub raw_event: RawEvent,
+    pub class: FailureClass,
+    pub error: String,
+    pub attempts: u32,
+}
+
+/// A dead-letter sink could not take a letter. The run stops before checkpointing the event, so
+/// a resumed run replays it.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message}")]
+pub struct DeadLetterSinkError {
+    pub message: String,
+}
+
+impl DeadLetterSinkError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+pub trait DeadLetterSink: Send + Sync {
+    fn accept(&self, letter: BackfillDeadLetter) -> Result<(), DeadLetterSinkError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryDeadLetterSink {
+    letters: Mutex<Vec<BackfillDeadLetter>>,
+}
+
+impl InMemoryDeadLetterSink {
+    pub fn letters(&self) -> Vec<BackfillDeadLetter> {
+        self.letters
+            .lock()
+            .expect("dead letter sink lock should work")
+            .clone()
+    }
+}
+
+impl DeadLetterSink for InMemoryDeadLetterSink {
+    fn accept(&self, letter: BackfillDeadLetter) -> Result<(), DeadLetterSinkError> {
+        self.letters
+            .lock()
+            .map_err(|_| DeadLetterSinkError::new("dead letter sink lock poisoned"))?
+            .push(letter);
+        Ok(())
+    }
+}
+
+/// Exponential backoff with equal jitter: attempt `n` waits between half and all of
+/// `base_delay * 2^(n-1)`, capped at `max_delay`. The jitter is derived from the event id, so
+/// reruns wait the same while different events spread out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn backoff(&self, attempt: u32, jitter_key: &str) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let ceiling = self
+            .base_delay
+            .saturating_mul(1_u32 << exponent)
+            .min(self.max_delay);
+        let half = ceiling / 2;
+        let digest = payload_hash(&serde_json::json!({ "key": jitter_key, "attempt": attempt }));
+        let sample = u64::from_str_radix(&digest[..16], 16).unwrap_or_default();
+        let spread = (ceiling - half).as_nanos() as u64;
+        half + Duration::from_nanos(if spread == 0 {
+            0
+        } else {
+            sample % (spread + 1)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplayBackfillTelemetry {
+    pub total_events: u32,
+    pub total_attempts: u32,
+    pub first_attempt_failures: u32,
+    pub recovered_events: u32,
+    pub failed_events: u32,
+    /// Events the adapter acknowledged as having nothing to book.
+    #[serde(default)]
+    pub skipped_events: u32,
+    /// Wall time from each recovered event's first failure to its success, summed.
+    pub recovery_time_ms: u64,
+    pub recovery_target_ms: u64,
+    pub objective_met: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplayBackfillResult {
+    pub hashes: Vec<String>,
+    pub telemetry: ReplayBackfillTelemetry,
+}
+
+/// Progress after the last finished event. A run resumed from it skips `next_index` events.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BackfillCheckpoint {
+    pub next_index: usize,
+    /// Chained hash of the raw events before `next_index`. A resumed run must supply the same
+    /// events in the same order.
+    #[serde(default)]
+    pub cursor: String,
+    pub hashes: Vec<String>,
+    pub telemetry: ReplayBackfillTelemetry,
+}
+
+fn advance_cursor(cursor: &str, raw: &RawEvent) -> String {
+    payload_hash(&serde_json::json!({ "previous": cursor, "event": raw }))
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BackfillError {
+    #[error("checkpoint resumes at event {next_index} of a {checkpoint_events}-event run, but {total_events} events were supplied")]
+    CheckpointMismatch {
+        next_index: usize,
+        checkpoint_events: u32,
+        total_events: usize,
+    },
+    #[error("checkpoint at event {next_index} was taken over different events than were supplied")]
+    CheckpointCursorMismatch { next_index: usize },
+    #[error("event {index} could not be dead-lettered: {source}")]
+    DeadLetter {
+        index: usize,
+        source: DeadLetterSinkError,
+    },
+}
+
+enum AttemptError {
+    Adapter(ConnectorError),
+    Posting(PostingError),
+}
+
+impl AttemptError {
+    fn class(&self) -> FailureClass {
+        match self {
+            Self::Adapter(error) => error.class(),
+            Self::Posting(error) => error.class,
+        }
+    }
+}
+
+impl std::fmt::Display for AttemptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Adapter(error) => write!(f, "{error}"),
+            Self::Posting(error) => write!(f, "posting failed: {error}"),
+        }
+    }
+}
+
+pub struct ReplayBackfill<'a, A, P, D> {
+    adapter: &'a A,
+    poster: &'a P,
+    dead_letters: &'a D,
+    policy: RetryPolicy,
+    recovery_target: Duration,
+}
+
+impl<'a, A, P, D> ReplayBackfill<'a, A, P, D>
+where
+    A: ConnectorAdapter + Sync,
+    P: BackfillPoster,
+    D: DeadLetterSink,
+{
+    pub fn new(adapter: &'a A, poster: &'a P, dead_letters: &'a D) -> Self {
+        Self {
+            adapter,
+            poster,
+            dead_letters,
+            policy: RetryPolicy::default(),
+            recovery_target: Duration::from_secs(60),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_recovery_target(mut self, recovery_target: Duration) -> Self {
+        self.recovery_target = recovery_target;
+        self
+    }
+
+    /// Replays `raw_events` in order, starting after `resume_from` if given. `on_checkpoint` is
+    /// called after every event so the caller can persist progress.
+    pub async fn run(
+        &self,
+        raw_events: &[RawEvent],
+        resume_from: Option<BackfillCheckpoint>,
+        mut on_checkpoint: impl FnMut(&BackfillCheckpoint),
+    ) -> Result<ReplayBackfillResult, BackfillError> {
+        let mut checkpoint = match resume_from {
+            Some(checkpoint)
+                if checkpoint.next_index > raw_events.len()
+                    || checkpoint.telemetry.total_events as usize != raw_events.len() =>
+            {
+                return Err(BackfillError::CheckpointMismatch {
+                    next_index: checkpoint.next_index,
+                    checkpoint_events: checkpoint.telemetry.total_events,
+                    total_events: raw_events.len(),
+                });
+            }
+            Some(checkpoint) => {
+                let cursor = raw_events[..checkpoint.next_index]
+                    .iter()
+                    .fold(String::new(), |cursor, raw| advance_cursor(&cursor, raw));
+                if cursor != checkpoint.cursor {
+                    return Err(BackfillError::CheckpointCursorMismatch {
+                        next_index: checkpoint.next_index,
+                    });
+                }
+                checkpoint
+            }
+            None => BackfillCheckpoint {
+                telemetry: ReplayBackfillTelemetry {
+                    total_events: raw_events.len() as u32,
+                    ..ReplayBackfillTelemetry::default()
+                },
+                ..BackfillCheckpoint::default()
+            },
+        };
+        checkpoint.telemetry.recovery_target_ms = self.recovery_target.as_millis() as u64;
+
+        for (index, raw) in raw_events.iter().enumerate().skip(checkpoint.next_index) {
+            self.replay_event(index, raw, &mut checkpoint).await?;
+            checkpoint.next_index = index + 1;
+            checkpoint.cursor = advance_cursor(&checkpoint.cursor, raw);
+            on_checkpoint(&checkpoint);
+        }
+
+        let mut telemetry = checkpoint.telemetry;
+        telemetry.objective_met = telemetry.recovery_time_ms <= telemetry.recovery_target_ms;
+        Ok(ReplayBackfillResult {
+            hashes: checkpoint.hashes,
+            telemetry,
+        })
+    }
+
+    async fn replay_event(
+        &self,
+        index: usize,
+        raw: &RawEvent,
+        checkpoint: &mut BackfillCheckpoint,
+    ) -> Result<(), BackfillError> {
+        let telemetry = &mut checkpoint.telemetry;
+        let mut first_failure: Option<Instant> = None;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            telemetry.total_attempts += 1;
+            let error = match self.attempt(raw).await {
+                Ok(hash) => {
+                    checkpoint.hashes.push(hash);
+                    if let Some(first_failure) = first_failure {
+                        telemetry.recovered_events += 1;
+                        telemetry.recovery_time_ms += first_failure.elapsed().as_millis() as u64;
+                    }
+                    return Ok(());
+                }
+                Err(AttemptError::Adapter(ConnectorError::Skipped(_))) => {
+                    telemetry.skipped_events += 1;
+                    return Ok(());
+                }
+                Err(error) => error,
+            };
+            if attempt == 1 {
+                telemetry.first_attempt_failures += 1;
+                first_failure = Some(Instant::now());
+            }
+
+            let class = error.class();
+            if class == FailureClass::Permanent || attempt >= self.policy.max_attempts {
+                telemetry.failed_events += 1;
+                return self
+                    .dead_letters
+                    .accept(BackfillDeadLetter {
+                        index,
+                        raw_event: raw.clone(),
+                        class,
+                        error: error.to_string(),
+                        attempts: attempt,
+                    })
+                    .map_err(|source| BackfillError::DeadLetter { index, source });
+            }
+            tokio::time::sleep(self.policy.backoff(attempt, &raw.source_event_id)).await;
+        }
+    }
+
+    async fn attempt(&self, raw: &RawEvent) -> Result<String, AttemptError> {
+        let canonical = self
+            .adapter
+            .normalize(raw.clone())
+            .await
+            .map_err(AttemptError::Adapter)?;
+        self.poster
+            .post(&canonical)
+            .await
+            .map_err(AttemptError::Posting)?;
+        let encoded = serde_json::to_value(&canonical)
+            .map_err(|e| AttemptError::Adapter(ConnectorError::Normalize(e.to_string())))?;
+        Ok(payload_hash(&encoded))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::collections::{BTreeSet, HashMap};
+
+    use super::*;
+
+    /// Fails the first post of each listed source event with a transient error.
+    #[derive(Default)]
+    pub(crate) struct FlakyPoster {
+        fail_once: BTreeSet<String>,
+        failures: Mutex<HashMap<String, u32>>,
+    }
+
+    impl FlakyPoster {
+        pub(crate) fn failing_once(source_event_ids: impl IntoIterator<Item = String>) -> Self {
+            Self {
+                fail_once: source_event_ids.into_iter().collect(),
+                failures: Mutex::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl BackfillPoster for FlakyPoster {
+        async fn post(&self, event: &CanonicalEvent) -> Result<(), PostingError> {
+            if !self.fail_once.contains(&event.source_event_id) {
+                return Ok(());
+            }
+            let mut failures = self.failures.lock().unwrap();
+            let count = failures.entry(event.source_event_id.clone()).or_default();
+            *count += 1;
+            if *count == 1 {
+                return Err(PostingError::transient("ledger unavailable"));
+            }
+            Ok(())
+        }
+    }
+
+    pub(crate) fn fast_retries() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+        }
+    }
+
+    /// Replays `raw_events` with the events at `transient_failure_indices` failing their first
+    /// posting attempt.
+    pub(crate) async fn replay_with_transient_failures<A: ConnectorAdapter + Sync>(
+        adapter: &A,
+        raw_events: &[RawEvent],
+        transient_failure_indices: &BTreeSet<usize>,
+        recovery_target_ms: u64,
+    ) -> ReplayBackfillResult {
+        let poster = FlakyPoster::failing_once(
+            transient_failure_indices
+                .iter()
+                .map(|index| raw_events[*index].source_event_id.clone()),
+        );
+        let dead_letters = InMemoryDeadLetterSink::default();
+        ReplayBackfill::new(adapter, &poster, &dead_letters)
+            .with_retry_policy(fast_retries())
+            .with_recovery_target(Duration::from_millis(recovery_target_ms))
+            .run(raw_events, None, |_| {})
+            .await
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::testing::{fast_retries, FlakyPoster};
+    use super::*;
+    use crate::CanonicalTraceContext;
+
+    /// Normalizes `ok_*` events, fails `transient_*` ones until the third call, skips `skip_*`
+    /// ones and rejects the rest as malformed.
+    #[derive(Default)]
+    struct ScriptedAdapter {
+        transient_calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl ConnectorAdapter for ScriptedAdapter {
+        fn source_system(&self) -> &'static str {
+            "scripted"
+        }
+
+        async fn normalize(&self, raw: RawEvent) -> Result<CanonicalEvent, ConnectorError> {
+            if raw.source_event_id.starts_with("transient_")
+                && self.transient_calls.fetch_add(1, Ordering::SeqCst) < 2
+            {
+                return Err(ConnectorError::Transient("lookup timed out".to_string()));
+            }
+            if raw.source_event_id.starts_with("skip_") {
+                return Err(ConnectorError::Skipped("authorisation only".to_string()));
+            }
+            if !raw.source_event_id.starts_with("ok_")
+                && !raw.source_event_id.starts_with("transient_")
+            {
+                return Err(ConnectorError::Normalize(
+                    "missing field `legal_entity_id`".to_string(),
+                ));
+            }
+            Ok(CanonicalEvent {
+                event_id: format!("canon-{}", raw.source_event_id),
+                event_type: "order.captured.v1".to_string(),
+                schema_version: "1.0.0".to_string(),
+                source_system: self.source_system().to_string(),
+                source_event_id: raw.source_event_id.clone(),
+                occurred_at: raw.occurred_at,
+                business_date: "2026-02-21".to_string(),
+                tenant_id: "tenant_1".to_string(),
+                legal_entity_id: "US_CO_01".to_string(),
+                idempotency_key: format!("scripted:{}", raw.source_event_id),
+                payload: raw.payload,
+                trace_context: CanonicalTraceContext {
+                    idempotency_key: format!("scripted:{}", raw.source_event_id),
+                    correlation_id: "corr_1".to_string(),
+                    causation_id: None,
+                    traceparent: None,
+                    tracestate: None,
+                },
+            })
+        }
+    }
+
+    fn events(ids: &[&str]) -> Vec<RawEvent> {
+        ids.iter()
+            .map(|id| RawEvent {
+                source_event_id: id.to_string(),
+                occurred_at: Utc::now(),
+                payload: json!({ "id": id }),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_within_jitter_bounds() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+        for (attempt, ceiling_ms) in [(1, 100), (2, 200), (3, 400), (4, 800), (5, 1000), (9, 1000)]
+        {
+            let delay = policy.backoff(attempt, "evt_1");
+            let ceiling = Duration::from_millis(ceiling_ms);
+            assert!(
+                delay >= ceiling / 2 && delay <= ceiling,
+                "attempt {attempt}: {delay:?}"
+            );
+            assert_eq!(delay, policy.backoff(attempt, "evt_1"));
+        }
+        assert_ne!(policy.backoff(3, "evt_1"), policy.backoff(3, "evt_2"));
+    }
+
+    #[tokio::test]
+    async fn transient_errors_retry_and_permanent_or_exhausted_ones_dead_letter() {
+        let adapter = ScriptedAdapter::default();
+        let poster = FlakyPoster::failing_once(["ok_2".to_string()]);
+        let dead_letters = InMemoryDeadLetterSink::default();
+        let raw_events = events(&["ok_1", "ok_2", "bad_3", "transient_4", "skip_5"]);
+
+        let result = ReplayBackfill::new(&adapter, &poster, &dead_letters)
+            .with_retry_policy(fast_retries())
+            .run(&raw_events, None, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(result.hashes.len(), 3);
+        let telemetry = &result.telemetry;
+        assert_eq!(telemetry.total_events, 5);
+        // ok_1 once, ok_2 twice, bad_3 once, transient_4 three times, skip_5 once.
+        assert_eq!(telemetry.total_attempts, 8);
+        assert_eq!(telemetry.first_attempt_failures, 3);
+        assert_eq!(telemetry.recovered_events, 2);
+        assert_eq!(telemetry.failed_events, 1);
+        assert_eq!(telemetry.skipped_events, 1);
+        assert!(telemetry.objective_met);
+
+        let letters = dead_letters.letters();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].index, 2);
+        assert_eq!(letters[0].class, FailureClass::Permanent);
+        assert_eq!(letters[0].attempts, 1);
+
+        let exhausted = InMemoryDeadLetterSink::default();
+        let result = ReplayBackfill::new(&ScriptedAdapter::default(), &poster, &exhausted)
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 2,
+                ..fast_retries()
+            })
+            .run(&events(&["transient_1"]), None, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(result.telemetry.failed_events, 1);
+        assert_eq!(exhausted.letters()[0].class, FailureClass::Transient);
+        assert_eq!(exhausted.letters()[0].attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn resumed_run_matches_an_uninterrupted_one() {
+        let adapter = ScriptedAdapter::default();
+        let poster = FlakyPoster::default();
+        let dead_letters = InMemoryDeadLetterSink::default();
+        let raw_events = events(&["ok_1", "ok_2", "ok_3"]);
+        let backfill = ReplayBackfill::new(&adapter, &poster, &dead_letters);
+
+        let mut checkpoints = Vec::new();
+        let full = backfill
+            .run(&raw_events, None, |checkpoint| {
+                checkpoints.push(checkpoint.clone())
+            })
+            .await
+            .unwrap();
+        assert_eq!(checkpoints.len(), 3);
+
+        let mut resumed_checkpoints = Vec::new();
+        let resumed = backfill
+            .run(&raw_events, Some(checkpoints[0].clone()), |checkpoint| {
+                resumed_checkpoints.push(checkpoint.next_index)
+            })
+            .await
+            .unwrap();
+        assert_eq!(resumed_checkpoints, vec![2, 3]);
+        assert_eq!(resumed, full);
+
+        assert!(matches!(
+            backfill
+                .run(&raw_events[..1], Some(checkpoints[1].clone()), |_| {})
+                .await,
+            Err(BackfillError::CheckpointMismatch { next_index: 2, .. })
+        ));
+
+        let mut reordered = raw_events.clone();
+        reordered.swap(0, 1);
+        assert_eq!(
+            backfill
+                .run(&reordered, Some(checkpoints[0].clone()), |_| {})
+                .await,
+            Err(BackfillError::CheckpointCursorMismatch { next_index: 1 })
+        );
+    }
+
+    struct UnavailableSink;
+
+    impl DeadLetterSink for UnavailableSink {
+        fn accept(&self, _letter: BackfillDeadLetter) -> Result<(), DeadLetterSinkError> {
+            Err(DeadLetterSinkError::new("dead letter store is unavailable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn run_stops_before_checkpointing_an_event_it_could_not_dead_letter() {
+        let adapter = ScriptedAdapter::default();
+        let poster = FlakyPoster::default();
+        let raw_events = events(&["ok_1", "bad_2", "ok_3"]);
+
+        let mut checkpoints = Vec::new();
+        let error = ReplayBackfill::new(&adapter, &poster, &UnavailableSink)
+            .run(&raw_events, None, |checkpoint| {
+                checkpoints.push(checkpoint.clone())
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            BackfillError::DeadLetter {
+                index: 1,
+                source: DeadLetterSinkError::new("dead letter store is unavailable"),
+            }
+        );
+        assert_eq!(checkpoints.len(), 1);
+
+        // Resuming from the last checkpoint replays the event that was not dead-lettered.
+        let dead_letters = InMemoryDeadLetterSink::default();
+        let resumed = ReplayBackfill::new(&adapter, &poster, &dead_letters)
+            .run(&raw_events, checkpoints.pop(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(resumed.hashes.len(), 2);
+        assert_eq!(dead_letters.letters()[0].index, 1);
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::ConnectorError;
+
+/// The first string found at any of `pointers`, in order.
+pub(crate) fn first_string<P: AsRef<str>>(payload: &Value, pointers: &[P]) -> Option<String> {
+    pointers
+        .iter()
+        .find_map(|pointer| payload.pointer(pointer.as_ref()).and_then(Value::as_str))
+        .map(ToString::to_string)
+}
+
+/// The first date found at any of `pointers` as `YYYY-MM-DD`: the leading ten characters of a
+/// string, or the UTC date of a Unix timestamp in seconds.
+pub(crate) fn first_date_string<P: AsRef<str>>(payload: &Value, pointers: &[P]) -> Option<String> {
+    pointers.iter().find_map(|pointer| {
+        payload
+            .pointer(pointer.as_ref())
+            .and_then(|value| match value {
+                Value::String(string) => string.get(0..10).map(ToString::to_string),
+                Value::Number(number) => number
+                    .as_i64()
+                    .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0))
+                    .map(|timestamp| timestamp.format("%Y-%m-%d").to_string()),
+                _ => None,
+            })
+    })
+}
+
+/// The first integer found at any of `pointers`, accepting numeric strings.
+pub(crate) fn optional_i64<P: AsRef<str>>(payload: &Value, pointers: &[P]) -> Option<i64> {
+    pointers.iter().find_map(|pointer| {
+        payload
+            .pointer(pointer.as_ref())
+            .and_then(|value| match value {
+                Value::Number(number) => number.as_i64(),
+                Value::String(string) => string.parse().ok(),
+                _ => None,
+            })
+    })
+}
+
+pub(crate) fn has_any<P: AsRef<str>>(payload: &Value, pointers: &[P]) -> bool {
+    pointers
+        .iter()
+        .any(|pointer| payload.pointer(pointer.as_ref()).is_some())
+}
+
+pub(crate) fn required_string<P: AsRef<str>>(
+    payload: &Value,
+    pointers: &[P],
+    field_name: &str,
+) -> Result<String, ConnectorError> {
+    first_string(payload, pointers)
+        .ok_or_else(|| ConnectorError::Normalize(format!("missing field `{field_name}`")))
+}
+
+pub(crate) fn required_amount<P: AsRef<str>>(
+    payload: &Value,
+    pointers: &[P],
+    field_name: &str,
+) -> Result<i64, ConnectorError> {
+    let value = optional_i64(payload, pointers)
+        .ok_or_else(|| ConnectorError::Normalize(format!("missing field `{field_name}`")))?;
+    normalize_amount(value, field_name)
+}
+
+/// Sources sign amounts by direction; canonical amounts are positive minor units, and a zero
+/// amount is never a real movement.
+pub(crate) fn normalize_amount(value: i64, field_name: &str) -> Result<i64, ConnectorError> {
+    match value.checked_abs() {
+        Some(normalized) if normalized != 0 => Ok(normalized),
+        _ => Err(ConnectorError::Normalize(format!(
+            "invalid amount for `{field_name}`"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{first_date_string, first_string, normalize_amount, optional_i64, required_amount};
+    use crate::ConnectorError;
+
+    #[test]
+    fn lookups_take_the_first_pointer_with_a_usable_value() {
+        let payload = json!({
+            "id": 42,
+            "data": {"id": "ch_1", "amount": "2500", "fee": 1.5},
+            "created": 1_771_632_000,
+        });
+        assert_eq!(
+            first_string(&payload, &["/id", "/data/id"]),
+            Some("ch_1".to_string())
+        );
+        assert_eq!(first_string(&payload, &["/missing"]), None);
+        assert_eq!(first_string::<&str>(&payload, &[]), None);
+
+        assert_eq!(
+            optional_i64(&payload, &["/data/fee", "/data/amount"]),
+            Some(2500)
+        );
+        assert_eq!(optional_i64(&payload, &["/data/fee"]), None);
+        assert_eq!(
+            optional_i64(&json!({"amount": "-30"}), &["/amount"]),
+            Some(-30)
+        );
+        assert_eq!(
+            optional_i64(&json!({"amount": "12.50"}), &["/amount"]),
+            None
+        );
+    }
+
+    #[test]
+    fn dates_come_from_iso_prefixes_or_utc_timestamps() {
+        let payload = json!({
+            "booked": "2026-02-21T23:59:59-07:00",
+            "short": "2026-02",
+            "last_second": 1_771_718_399,
+            "midnight": 1_771_718_400,
+            "fractional": 1_771_718_400.5,
+        });
+        assert_eq!(
+            first_date_string(&payload, &["/booked"]),
+            Some("2026-02-21".to_string())
+        );
+        assert_eq!(
+            first_date_string(&payload, &["/last_second"]),
+            Some("2026-02-21".to_string())
+        );
+        assert_eq!(
+            first_date_string(&payload, &["/midnight"]),
+            Some("2026-02-22".to_string())
+        );
+        assert_eq!(
+            first_date_string(&payload, &["/short", "/fractional", "/booked"]),
+            Some("2026-02-21".to_string())
+        );
+        assert_eq!(first_date_string(&json!({"at": i64::MAX}), &["/at"]), None);
+    }
+
+    #[test]
+    fn amounts_are_positive_and_never_zero() {
+        assert_eq!(normalize_amount(-2500, "amount").unwrap(), 2500);
+        assert_eq!(normalize_amount(2500, "amount").unwrap(), 2500);
+        for invalid in [0, i64::MIN] {
+            assert!(matches!(
+                normalize_amount(invalid, "amount"),
+                Err(ConnectorError::Normalize(message)) if message == "invalid amount for `amount`"
+            ));
+        }
+        assert!(matches!(
+            required_amount(&json!({}), &["/amount"], "amount"),
+            Err(ConnectorError::Normalize(message)) if message == "missing field `amount`"
+        ));
+        assert_eq!(
+            required_amount(&json!({"amount": "-75"}), &["/amount"], "amount").unwrap(),
+            75
+        );
+    }
+}
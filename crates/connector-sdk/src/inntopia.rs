@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use platform_core::payload_hash;
 use serde_json::{json, Value};
 
+use crate::fields::{first_string, optional_i64};
 use crate::{CanonicalEvent, CanonicalTraceContext, ConnectorAdapter, ConnectorError, RawEvent};
 
 #[derive(Debug, Default, Clone)]
@@ -34,7 +35,7 @@ impl ConnectorAdapter for InntopiaAdapter {
         )
         .ok_or_else(|| ConnectorError::Normalize("missing field `location_id`".to_string()))?;
 
-        let total_amount_minor = first_i64(
+        let total_amount_minor = optional_i64(
             &payload,
             &[
                 "/total_amount_minor",
@@ -138,23 +139,6 @@ fn required_string(payload: &Value, key: &str) -> Result<String, ConnectorError>
         .ok_or_else(|| ConnectorError::Normalize(format!("missing field `{key}`")))
 }
 
-fn first_string(payload: &Value, pointers: &[&str]) -> Option<String> {
-    pointers
-        .iter()
-        .find_map(|pointer| payload.pointer(pointer).and_then(Value::as_str))
-        .map(ToString::to_string)
-}
-
-fn first_i64(payload: &Value, pointers: &[&str]) -> Option<i64> {
-    pointers.iter().find_map(|pointer| {
-        payload.pointer(pointer).and_then(|value| match value {
-            Value::Number(number) => number.as_i64(),
-            Value::String(string) => string.parse().ok(),
-            _ => None,
-        })
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -162,10 +146,8 @@ mod tests {
     use chrono::Utc;
     use serde_json::json;
 
-    use crate::{
-        evaluate_cutover_rehearsal, run_replay_backfill_resiliency, ConnectorAdapter,
-        CutoverCheckpoint, RawEvent,
-    };
+    use crate::backfill::testing::replay_with_transient_failures;
+    use crate::{evaluate_cutover_rehearsal, ConnectorAdapter, CutoverCheckpoint, RawEvent};
 
     use super::InntopiaAdapter;
 
@@ -282,9 +264,7 @@ mod tests {
         ];
 
         let failures = BTreeSet::from([1_usize]);
-        let result = run_replay_backfill_resiliency(&adapter, &events, &failures, 1000, 200)
-            .await
-            .unwrap();
+        let result = replay_with_transient_failures(&adapter, &events, &failures, 1000).await;
 
         assert_eq!(result.hashes.len(), 2);
         assert_eq!(result.telemetry.first_attempt_failures, 1);
@@ -308,9 +288,8 @@ mod tests {
             }),
         }];
 
-        let replay = run_replay_backfill_resiliency(&adapter, &events, &BTreeSet::new(), 1000, 200)
-            .await
-            .unwrap();
+        let replay =
+            replay_with_transient_failures(&adapter, &events, &BTreeSet::new(), 1000).await;
         let rehearsal = evaluate_cutover_rehearsal(
             &replay,
             true,
@@ -3,13 +3,32 @@ use chrono::{DateTime, Utc};
 use platform_core::payload_hash;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::BTreeSet;
 use thiserror::Error;
 
+pub mod adyen;
+pub mod backfill;
+mod fields;
 pub mod inntopia;
+pub mod mapping;
+pub mod signature;
 pub mod square;
 pub mod stripe;
+pub use adyen::AdyenAdapter;
+pub use backfill::{
+    BackfillCheckpoint, BackfillDeadLetter, BackfillError, BackfillPoster, DeadLetterSink,
+    DeadLetterSinkError, FailureClass, InMemoryDeadLetterSink, PostingError, ReplayBackfill,
+    ReplayBackfillResult, ReplayBackfillTelemetry, RetryPolicy,
+};
 pub use inntopia::InntopiaAdapter;
+pub use mapping::{
+    Coercion, DetectionRule, FieldDefault, FieldMapping, KindMapping, MappedField, MappingAdapter,
+    MappingDefinition, MappingError, TraceMapping,
+};
+pub use signature::{
+    AdyenSignatureVerifier, BodyHmacVerifier, SignatureError, SigningSecret,
+    SquareSignatureVerifier, StripeSignatureVerifier, VerifiedWebhook, WebhookRequest,
+    WebhookVerifier,
+};
 pub use square::SquareAdapter;
 pub use stripe::StripeAdapter;
 
@@ -49,24 +68,24 @@ pub struct CanonicalEvent {
 pub enum ConnectorError {
     #[error("normalization failed: {0}")]
     Normalize(String),
+    /// A dependency the adapter consults was unavailable; the same event may normalize later.
+    #[error("normalization unavailable: {0}")]
+    Transient(String),
+    /// The source reported something with nothing to book. The delivery is acknowledged and
+    /// neither posted nor retried.
+    #[error("nothing to book: {0}")]
+    Skipped(String),
+    #[error("webhook rejected: {0}")]
+    Signature(#[from] SignatureError),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct ReplayBackfillTelemetry {
-    pub total_events: u32,
-    pub total_attempts: u32,
-    pub first_attempt_failures: u32,
-    pub recovered_events: u32,
-    pub failed_events: u32,
-    pub simulated_recovery_time_ms: u64,
-    pub recovery_target_ms: u64,
-    pub objective_met: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct ReplayBackfillResult {
-    pub hashes: Vec<String>,
-    pub telemetry: ReplayBackfillTelemetry,
+impl ConnectorError {
+    pub fn class(&self) -> FailureClass {
+        match self {
+            Self::Transient(_) => FailureClass::Transient,
+            Self::Normalize(_) | Self::Skipped(_) | Self::Signature(_) => FailureClass::Permanent,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -85,7 +104,16 @@ pub struct CutoverRehearsalResult {
 
 #[async_trait]
 pub trait ConnectorAdapter {
-    fn source_system(&self) -> &'static str;
+    fn source_system(&self) -> &str;
+    /// Version of the normalization logic; defaults to the SDK release.
+    fn adapter_version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+    /// Splits a delivery body that batches several source events into one body per event.
+    /// Most sources send one event per delivery.
+    fn split_delivery(&self, payload: Value) -> Vec<Value> {
+        vec![payload]
+    }
     async fn normalize(&self, raw: RawEvent) -> Result<CanonicalEvent, ConnectorError>;
 }
 
@@ -103,53 +131,6 @@ pub async fn replay_hashes<A: ConnectorAdapter + Sync>(
     Ok(hashes)
 }
 
-pub async fn run_replay_backfill_resiliency<A: ConnectorAdapter + Sync>(
-    adapter: &A,
-    raw_events: &[RawEvent],
-    transient_failure_indices: &BTreeSet<usize>,
-    recovery_target_ms: u64,
-    retry_penalty_ms: u64,
-) -> Result<ReplayBackfillResult, ConnectorError> {
-    let mut hashes = Vec::with_capacity(raw_events.len());
-    let mut total_attempts = 0_u32;
-    let mut first_attempt_failures = 0_u32;
-    let mut recovered_events = 0_u32;
-    let retry_penalty = if retry_penalty_ms == 0 {
-        1
-    } else {
-        retry_penalty_ms
-    };
-    let mut simulated_recovery_time_ms = 0_u64;
-
-    for (index, raw) in raw_events.iter().enumerate() {
-        total_attempts += 1;
-        if transient_failure_indices.contains(&index) {
-            first_attempt_failures += 1;
-            total_attempts += 1;
-            recovered_events += 1;
-            simulated_recovery_time_ms += retry_penalty;
-        }
-
-        let canonical = adapter.normalize(raw.clone()).await?;
-        let encoded = serde_json::to_value(canonical)
-            .map_err(|e| ConnectorError::Normalize(e.to_string()))?;
-        hashes.push(payload_hash(&encoded));
-    }
-
-    let telemetry = ReplayBackfillTelemetry {
-        total_events: raw_events.len() as u32,
-        total_attempts,
-        first_attempt_failures,
-        recovered_events,
-        failed_events: 0,
-        simulated_recovery_time_ms,
-        recovery_target_ms,
-        objective_met: simulated_recovery_time_ms <= recovery_target_ms,
-    };
-
-    Ok(ReplayBackfillResult { hashes, telemetry })
-}
-
 pub fn evaluate_cutover_rehearsal(
     replay_result: &ReplayBackfillResult,
     rollback_validated: bool,
@@ -229,37 +210,6 @@ mod tests {
         assert_eq!(first, second);
     }
 
-    #[tokio::test]
-    async fn replay_backfill_resiliency_tracks_recovery_objective() {
-        let adapter = FakeAdapter;
-        let events = vec![
-            RawEvent {
-                source_event_id: "evt_1".to_string(),
-                occurred_at: Utc::now(),
-                payload: json!({"a": 1}),
-            },
-            RawEvent {
-                source_event_id: "evt_2".to_string(),
-                occurred_at: Utc::now(),
-                payload: json!({"a": 2}),
-            },
-        ];
-        let failures = BTreeSet::from([1_usize]);
-
-        let result = run_replay_backfill_resiliency(&adapter, &events, &failures, 1000, 250)
-            .await
-            .unwrap();
-
-        assert_eq!(result.hashes.len(), 2);
-        assert_eq!(result.telemetry.total_events, 2);
-        assert_eq!(result.telemetry.total_attempts, 3);
-        assert_eq!(result.telemetry.first_attempt_failures, 1);
-        assert_eq!(result.telemetry.recovered_events, 1);
-        assert_eq!(result.telemetry.failed_events, 0);
-        assert_eq!(result.telemetry.simulated_recovery_time_ms, 250);
-        assert!(result.telemetry.objective_met);
-    }
-
     #[test]
     fn cutover_rehearsal_fails_when_checkpoint_fails() {
         let replay = ReplayBackfillResult {
@@ -270,7 +220,8 @@ mod tests {
                 first_attempt_failures: 0,
                 recovered_events: 0,
                 failed_events: 0,
-                simulated_recovery_time_ms: 100,
+                skipped_events: 0,
+                recovery_time_ms: 100,
                 recovery_target_ms: 1000,
                 objective_met: true,
             },
@@ -296,7 +247,8 @@ mod tests {
                 first_attempt_failures: 0,
                 recovered_events: 0,
                 failed_events: 0,
-                simulated_recovery_time_ms: 100,
+                skipped_events: 0,
+                recovery_time_ms: 100,
                 recovery_target_ms: 1000,
                 objective_met: true,
             },
@@ -0,0 +1,621 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::fields::{first_date_string, first_string, has_any, normalize_amount, optional_i64};
+use crate::{CanonicalEvent, CanonicalTraceContext, ConnectorAdapter, ConnectorError, RawEvent};
+
+/// Declarative description of how one source's payloads become canonical events: which rule
+/// picks the event kind, and where each canonical field is read from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MappingDefinition {
+    pub source_system: String,
+    pub mapping_version: String,
+    #[serde(default = "default_schema_version")]
+    pub schema_version: String,
+    pub tenant_id: FieldMapping,
+    pub legal_entity_id: FieldMapping,
+    #[serde(default)]
+    pub location_id: Option<FieldMapping>,
+    /// Evaluated in order; the first rule that matches picks the kind.
+    pub detection: Vec<DetectionRule>,
+    pub kinds: Vec<KindMapping>,
+    /// Pointers into the canonical payload, tried in order; falls back to the occurred date.
+    #[serde(default = "default_business_date")]
+    pub business_date: Vec<String>,
+    #[serde(default)]
+    pub trace: TraceMapping,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionRule {
+    pub kind: String,
+    pub pointers: Vec<String>,
+    /// Matches when the first string found equals one of these, ignoring case.
+    #[serde(default)]
+    pub equals: Vec<String>,
+    /// Matches when the first string found contains any of these, ignoring case. With neither
+    /// `equals` nor `contains`, the rule matches as soon as any pointer is present.
+    #[serde(default)]
+    pub contains: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KindMapping {
+    pub kind: String,
+    pub event_type: String,
+    /// Used in `{source}:{source_event_id}:{suffix}` when the payload carries no idempotency key.
+    pub idempotency_suffix: String,
+    pub fields: Vec<MappedField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MappedField {
+    pub target: String,
+    #[serde(flatten)]
+    pub mapping: FieldMapping,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldMapping {
+    /// Fallback pointers into the source payload, tried in order.
+    #[serde(default)]
+    pub pointers: Vec<String>,
+    #[serde(default)]
+    pub coerce: Coercion,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<FieldDefault>,
+    #[serde(default)]
+    pub uppercase: bool,
+    #[serde(default)]
+    pub abs: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Coercion {
+    #[default]
+    String,
+    /// A date string truncated to `YYYY-MM-DD`, or unix seconds.
+    Date,
+    /// A number, or a string that parses as one.
+    Integer,
+    /// A non-zero integer, taken as its absolute value.
+    Amount,
+    /// The JSON value as-is.
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FieldDefault {
+    Value(Value),
+    OccurredDate,
+    /// Sum of fields mapped earlier in the same kind.
+    Sum(Vec<String>),
+}
+
+/// Pointers into the canonical payload, so `/extensions/source_payload/...` reaches the source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceMapping {
+    pub idempotency_key: Vec<String>,
+    /// Falls back to the source event ID.
+    pub correlation_id: Vec<String>,
+    pub causation_id: Vec<String>,
+    pub traceparent: Vec<String>,
+    pub tracestate: Vec<String>,
+}
+
+impl Default for TraceMapping {
+    fn default() -> Self {
+        let source = |paths: &[&str]| {
+            paths
+                .iter()
+                .map(|path| format!("/extensions/source_payload{path}"))
+                .collect()
+        };
+        Self {
+            idempotency_key: source(&["/idempotency_key"]),
+            correlation_id: source(&["/correlation_id"]),
+            causation_id: source(&["/causation_id"]),
+            traceparent: source(&["/trace/traceparent", "/traceparent"]),
+            tracestate: source(&["/trace/tracestate", "/tracestate"]),
+        }
+    }
+}
+
+fn default_schema_version() -> String {
+    "1.0.0".to_string()
+}
+
+fn default_business_date() -> Vec<String> {
+    vec!["/business_date".to_string()]
+}
+
+#[derive(Debug, Error)]
+pub enum MappingError {
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse mapping: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("mapping for `{source_system}` is invalid: {reason}")]
+    Invalid {
+        source_system: String,
+        reason: String,
+    },
+}
+
+impl MappingDefinition {
+    pub fn load(path: &Path) -> Result<Self, MappingError> {
+        let raw = std::fs::read_to_string(path).map_err(|source| MappingError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(&raw)
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, MappingError> {
+        let definition: Self = serde_json::from_str(raw)?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn validate(&self) -> Result<(), MappingError> {
+        let invalid = |reason: String| MappingError::Invalid {
+            source_system: self.source_system.clone(),
+            reason,
+        };
+        if self.source_system.trim().is_empty() || self.mapping_version.trim().is_empty() {
+            return Err(invalid(
+                "source_system and mapping_version are required".to_string(),
+            ));
+        }
+        for (name, field) in [
+            ("tenant_id", Some(&self.tenant_id)),
+            ("legal_entity_id", Some(&self.legal_entity_id)),
+            ("location_id", self.location_id.as_ref()),
+        ] {
+            let Some(field) = field else { continue };
+            if field.coerce != Coercion::String {
+                return Err(invalid(format!("`{name}` must be coerced as STRING")));
+            }
+            validate_field(name, field, &[]).map_err(invalid)?;
+        }
+        if self.kinds.is_empty() || self.detection.is_empty() {
+            return Err(invalid(
+                "at least one kind and one detection rule are required".to_string(),
+            ));
+        }
+
+        let mut kinds = HashSet::new();
+        for kind in &self.kinds {
+            if !kinds.insert(kind.kind.as_str()) {
+                return Err(invalid(format!("kind `{}` is defined twice", kind.kind)));
+            }
+            if kind.event_type.trim().is_empty() || kind.idempotency_suffix.trim().is_empty() {
+                return Err(invalid(format!(
+                    "kind `{}` needs an event_type and idempotency_suffix",
+                    kind.kind
+                )));
+            }
+            let mut targets: Vec<&str> = Vec::new();
+            for field in &kind.fields {
+                if matches!(
+                    field.target.as_str(),
+                    "routing" | "extensions" | "location_id"
+                ) || targets.contains(&field.target.as_str())
+                {
+                    return Err(invalid(format!(
+                        "kind `{}` cannot map `{}`",
+                        kind.kind, field.target
+                    )));
+                }
+                validate_field(&field.target, &field.mapping, &targets).map_err(invalid)?;
+                targets.push(&field.target);
+            }
+        }
+        for rule in &self.detection {
+            if !kinds.contains(rule.kind.as_str()) {
+                return Err(invalid(format!(
+                    "detection rule names unknown kind `{}`",
+                    rule.kind
+                )));
+            }
+            if rule.pointers.is_empty() {
+                return Err(invalid(format!(
+                    "detection rule for `{}` has no pointers",
+                    rule.kind
+                )));
+            }
+            validate_pointers(&rule.pointers).map_err(invalid)?;
+        }
+        let trace = &self.trace;
+        for pointers in [
+            &self.business_date,
+            &trace.idempotency_key,
+            &trace.correlation_id,
+            &trace.causation_id,
+            &trace.traceparent,
+            &trace.tracestate,
+        ] {
+            validate_pointers(pointers).map_err(invalid)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_field(name: &str, field: &FieldMapping, earlier: &[&str]) -> Result<(), String> {
+    if field.pointers.is_empty() && field.default.is_none() {
+        return Err(format!("`{name}` needs pointers or a default"));
+    }
+    validate_pointers(&field.pointers)?;
+    if let Some(FieldDefault::Sum(terms)) = &field.default {
+        if let Some(term) = terms.iter().find(|term| !earlier.contains(&term.as_str())) {
+            return Err(format!(
+                "`{name}` sums `{term}`, which is not mapped before it"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_pointers(pointers: &[String]) -> Result<(), String> {
+    match pointers.iter().find(|pointer| !pointer.starts_with('/')) {
+        Some(pointer) => Err(format!("`{pointer}` is not a JSON pointer")),
+        None => Ok(()),
+    }
+}
+
+/// A `ConnectorAdapter` driven by a `MappingDefinition` instead of hand-written Rust. Event IDs
+/// and idempotency keys are derived exactly as the hand-written adapters derive them.
+#[derive(Debug, Clone)]
+pub struct MappingAdapter {
+    definition: MappingDefinition,
+}
+
+impl MappingAdapter {
+    pub fn new(definition: MappingDefinition) -> Result<Self, MappingError> {
+        definition.validate()?;
+        Ok(Self { definition })
+    }
+
+    pub fn definition(&self) -> &MappingDefinition {
+        &self.definition
+    }
+
+    fn detect_kind(&self, payload: &Value) -> Result<&KindMapping, ConnectorError> {
+        let rule = self
+            .definition
+            .detection
+            .iter()
+            .find(|rule| {
+                if rule.equals.is_empty() && rule.contains.is_empty() {
+                    return has_any(payload, &rule.pointers);
+                }
+                first_string(payload, &rule.pointers).is_some_and(|value| {
+                    let value = value.to_ascii_lowercase();
+                    rule.equals
+                        .iter()
+                        .any(|expected| value == expected.to_ascii_lowercase())
+                        || rule
+                            .contains
+                            .iter()
+                            .any(|needle| value.contains(&needle.to_ascii_lowercase()))
+                })
+            })
+            .ok_or_else(|| {
+                ConnectorError::Normalize(format!(
+                    "unsupported {} event kind",
+                    self.definition.source_system
+                ))
+            })?;
+        Ok(self
+            .definition
+            .kinds
+            .iter()
+            .find(|kind| kind.kind == rule.kind)
+            .expect("validated detection rules name a defined kind"))
+    }
+}
+
+#[async_trait]
+impl ConnectorAdapter for MappingAdapter {
+    fn source_system(&self) -> &str {
+        &self.definition.source_system
+    }
+
+    fn adapter_version(&self) -> &str {
+        &self.definition.mapping_version
+    }
+
+    async fn normalize(&self, raw: RawEvent) -> Result<CanonicalEvent, ConnectorError> {
+        let RawEvent {
+            source_event_id,
+            occurred_at,
+            payload,
+        } = raw;
+        let definition = &self.definition;
+
+        let envelope_string = |name: &str, field: &FieldMapping| {
+            resolve_field(name, field, &payload, occurred_at, &Map::new())
+                .map(|value| value.as_str().map(ToString::to_string))
+        };
+        let missing = |name: &str| ConnectorError::Normalize(format!("missing field `{name}`"));
+        let tenant_id = envelope_string("tenant_id", &definition.tenant_id)?
+            .ok_or_else(|| missing("tenant_id"))?;
+        let legal_entity_id = envelope_string("legal_entity_id", &definition.legal_entity_id)?
+            .ok_or_else(|| missing("legal_entity_id"))?;
+        let location_id = match &definition.location_id {
+            Some(field) => envelope_string("location_id", field)?,
+            None => None,
+        };
+
+        let kind = self.detect_kind(&payload)?;
+        let mut fields = Map::new();
+        for field in &kind.fields {
+            let value = resolve_field(
+                &field.target,
+                &field.mapping,
+                &payload,
+                occurred_at,
+                &fields,
+            )?;
+            fields.insert(field.target.clone(), value);
+        }
+        fields.insert(
+            "routing".to_string(),
+            json!({
+                "legal_entity_id": legal_entity_id,
+                "location_id": location_id
+            }),
+        );
+        if let Some(location_id) = location_id {
+            fields.insert("location_id".to_string(), Value::String(location_id));
+        }
+        fields.insert(
+            "extensions".to_string(),
+            json!({
+                "source_payload": payload
+            }),
+        );
+        let canonical_payload = Value::Object(fields);
+
+        let source = &definition.source_system;
+        let payload_digest = payload_hash(&canonical_payload);
+        let event_id = format!(
+            "{source}-{}-{}",
+            source_event_id,
+            &payload_digest[..12].to_ascii_lowercase()
+        );
+        let business_date = first_string(&canonical_payload, &definition.business_date)
+            .unwrap_or_else(|| occurred_at.format("%Y-%m-%d").to_string());
+        let trace = &definition.trace;
+        let idempotency_key = first_string(&canonical_payload, &trace.idempotency_key)
+            .unwrap_or_else(|| format!("{source}:{source_event_id}:{}", kind.idempotency_suffix));
+
+        let trace_context = CanonicalTraceContext {
+            idempotency_key: idempotency_key.clone(),
+            correlation_id: first_string(&canonical_payload, &trace.correlation_id)
+                .unwrap_or_else(|| source_event_id.clone()),
+            causation_id: first_string(&canonical_payload, &trace.causation_id),
+            traceparent: first_string(&canonical_payload, &trace.traceparent),
+            tracestate: first_string(&canonical_payload, &trace.tracestate),
+        };
+
+        Ok(CanonicalEvent {
+            event_id,
+            event_type: kind.event_type.clone(),
+            schema_version: definition.schema_version.clone(),
+            source_system: source.clone(),
+            source_event_id,
+            occurred_at,
+            business_date,
+            tenant_id,
+            legal_entity_id,
+            idempotency_key,
+            payload: canonical_payload,
+            trace_context,
+        })
+    }
+}
+
+/// Reads, coerces and defaults one field. A missing optional field maps to `null`.
+fn resolve_field(
+    name: &str,
+    field: &FieldMapping,
+    payload: &Value,
+    occurred_at: DateTime<Utc>,
+    mapped: &Map<String, Value>,
+) -> Result<Value, ConnectorError> {
+    let pointers = &field.pointers;
+    let found = match field.coerce {
+        Coercion::String => first_string(payload, pointers).map(Value::String),
+        Coercion::Date => first_date_string(payload, pointers).map(Value::String),
+        Coercion::Integer | Coercion::Amount => optional_i64(payload, pointers).map(Value::from),
+        Coercion::Json => pointers
+            .iter()
+            .find_map(|pointer| payload.pointer(pointer))
+            .cloned(),
+    };
+    let value = found.or_else(|| {
+        field.default.as_ref().map(|default| match default {
+            FieldDefault::Value(value) => value.clone(),
+            FieldDefault::OccurredDate => Value::String(occurred_at.format("%Y-%m-%d").to_string()),
+            FieldDefault::Sum(terms) => Value::from(
+                terms
+                    .iter()
+                    .filter_map(|term| mapped.get(term).and_then(Value::as_i64))
+                    .sum::<i64>(),
+            ),
+        })
+    });
+    let Some(mut value) = value else {
+        if field.required {
+            return Err(ConnectorError::Normalize(format!("missing field `{name}`")));
+        }
+        return Ok(Value::Null);
+    };
+
+    if field.uppercase {
+        if let Some(string) = value.as_str() {
+            value = Value::String(string.to_ascii_uppercase());
+        }
+    }
+    if let Some(number) = value.as_i64() {
+        if field.coerce == Coercion::Amount {
+            value = Value::from(normalize_amount(number, name)?);
+        } else if field.abs {
+            value = Value::from(number.abs());
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use crate::{ConnectorAdapter, ConnectorError, RawEvent, StripeAdapter};
+
+    use super::{MappingAdapter, MappingDefinition, MappingError};
+
+    const STRIPE_MAPPING: &str = include_str!("../mappings/stripe.json");
+
+    fn stripe_mapping() -> MappingAdapter {
+        MappingAdapter::new(MappingDefinition::from_json(STRIPE_MAPPING).unwrap()).unwrap()
+    }
+
+    fn raw(source_event_id: &str, payload: serde_json::Value) -> RawEvent {
+        RawEvent {
+            source_event_id: source_event_id.to_string(),
+            occurred_at: Utc.with_ymd_and_hms(2026, 3, 14, 18, 30, 0).unwrap(),
+            payload,
+        }
+    }
+
+    #[tokio::test]
+    async fn stripe_mapping_matches_hand_written_adapter() {
+        let mapping = stripe_mapping();
+        let events = [
+            raw(
+                "st_evt_charge_1",
+                json!({
+                    "type": "charge.succeeded",
+                    "tenant_id": "tenant_1",
+                    "legal_entity_id": "US_CO_01",
+                    "location_id": "BRECK_BASE_AREA",
+                    "charge_id": "ch_123",
+                    "amount": -17120,
+                    "currency": "usd",
+                    "created": 1773446400,
+                    "idempotency_key": "stripe:idem:charge",
+                    "trace": {
+                        "traceparent": "00-4bf92f3577b34da6a3ce929d0e0e4736-cccccccccccccccc-01"
+                    }
+                }),
+            ),
+            raw(
+                "st_evt_refund_1",
+                json!({
+                    "type": "charge.refunded",
+                    "tenant_id": "tenant_1",
+                    "legal_entity_id": "US_CO_01",
+                    "refund_id": "re_456",
+                    "charge_id": "ch_123",
+                    "amount": 2500
+                }),
+            ),
+            raw(
+                "st_evt_txn_1",
+                json!({
+                    "data": {
+                        "object": {
+                            "tenant_id": "tenant_1",
+                            "legal_entity_id": "US_CO_01",
+                            "balance_transaction_id": "txn_789",
+                            "payout_id": "po_1",
+                            "net": "9650",
+                            "fee": -350,
+                            "available_on": "2026-03-16T00:00:00Z",
+                            "currency": "cad"
+                        }
+                    },
+                    "request": { "id": "req_42" }
+                }),
+            ),
+        ];
+
+        for event in events {
+            let expected = StripeAdapter.normalize(event.clone()).await.unwrap();
+            let mapped = mapping.normalize(event).await.unwrap();
+            assert_eq!(mapped, expected);
+        }
+        assert_eq!(mapping.source_system(), "stripe");
+        assert_eq!(mapping.adapter_version(), "stripe-mapping-1");
+    }
+
+    #[tokio::test]
+    async fn mapped_adapter_reports_the_same_failures() {
+        let mapping = stripe_mapping();
+        let unsupported = raw(
+            "st_evt_unknown",
+            json!({"tenant_id": "tenant_1", "legal_entity_id": "US_CO_01"}),
+        );
+        let error = mapping.normalize(unsupported).await.unwrap_err();
+        assert!(
+            matches!(error, ConnectorError::Normalize(message) if message == "unsupported stripe event kind")
+        );
+
+        let zero_amount = raw(
+            "st_evt_zero",
+            json!({
+                "type": "charge.succeeded",
+                "tenant_id": "tenant_1",
+                "legal_entity_id": "US_CO_01",
+                "charge_id": "ch_0",
+                "amount": 0
+            }),
+        );
+        let error = mapping.normalize(zero_amount).await.unwrap_err();
+        assert!(
+            matches!(error, ConnectorError::Normalize(message) if message == "invalid amount for `amount_minor`")
+        );
+
+        let no_tenant = raw("st_evt_anon", json!({"type": "charge.succeeded"}));
+        let error = mapping.normalize(no_tenant).await.unwrap_err();
+        assert!(
+            matches!(error, ConnectorError::Normalize(message) if message == "missing field `tenant_id`")
+        );
+    }
+
+    #[test]
+    fn rejects_mappings_that_cannot_execute() {
+        let mut definition: serde_json::Value = serde_json::from_str(STRIPE_MAPPING).unwrap();
+        definition["detection"][0]["kind"] = json!("chargeback");
+        let error = MappingDefinition::from_json(&definition.to_string()).unwrap_err();
+        assert!(
+            matches!(error, MappingError::Invalid { reason, .. } if reason == "detection rule names unknown kind `chargeback`")
+        );
+
+        let mut definition: serde_json::Value = serde_json::from_str(STRIPE_MAPPING).unwrap();
+        let fields = definition["kinds"][2]["fields"].as_array_mut().unwrap();
+        fields.swap(1, 3);
+        let error = MappingDefinition::from_json(&definition.to_string()).unwrap_err();
+        assert!(
+            matches!(error, MappingError::Invalid { reason, .. } if reason.contains("sums `net_amount_minor`"))
+        );
+    }
+}
@@ -0,0 +1,679 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::adyen::notification_items;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const STRIPE_SIGNATURE_HEADER: &str = "stripe-signature";
+pub const SQUARE_SIGNATURE_HEADER: &str = "x-square-hmacsha256-signature";
+const STRIPE_DEFAULT_TOLERANCE_SECS: i64 = 300;
+
+/// A webhook delivery as received, before its body is parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest {
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+    pub received_at: DateTime<Utc>,
+}
+
+impl WebhookRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// One signing secret. During a rotation the old and new secrets are both active until the
+/// old one's `expires_at` passes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningSecret {
+    pub secret_id: String,
+    pub secret: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SigningSecret {
+    pub fn new(secret_id: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            secret_id: secret_id.into(),
+            secret: secret.into(),
+            expires_at: None,
+        }
+    }
+
+    pub fn expiring_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    fn is_active(&self, at: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| at < expires_at)
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(self.secret.as_bytes()).expect("HMAC accepts keys of any size")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedWebhook {
+    pub secret_id: String,
+    pub signed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("missing `{0}` header")]
+    MissingHeader(&'static str),
+    #[error("malformed `{header}` header: {reason}")]
+    MalformedHeader {
+        header: &'static str,
+        reason: String,
+    },
+    #[error("malformed webhook body: {0}")]
+    MalformedBody(String),
+    #[error("no signing secret is active")]
+    NoActiveSecret,
+    #[error("signature timestamp {signed_at} is more than {tolerance_secs}s from {received_at}")]
+    OutsideTolerance {
+        signed_at: DateTime<Utc>,
+        received_at: DateTime<Utc>,
+        tolerance_secs: i64,
+    },
+    #[error("signature does not match any active secret")]
+    Mismatch,
+    #[error("signature was already accepted at {first_seen}")]
+    Replayed { first_seen: DateTime<Utc> },
+}
+
+pub trait WebhookVerifier: Send + Sync {
+    fn source_system(&self) -> &'static str;
+    fn verify(&self, request: &WebhookRequest) -> Result<VerifiedWebhook, SignatureError>;
+}
+
+fn active_secrets(
+    secrets: &[SigningSecret],
+    at: DateTime<Utc>,
+) -> Result<Vec<&SigningSecret>, SignatureError> {
+    let active = secrets
+        .iter()
+        .filter(|secret| secret.is_active(at))
+        .collect::<Vec<_>>();
+    if active.is_empty() {
+        return Err(SignatureError::NoActiveSecret);
+    }
+    Ok(active)
+}
+
+/// Stripe's `Stripe-Signature` scheme: `t=<unix>,v1=<hex>[,v1=<hex>...]`, where each `v1` is an
+/// HMAC-SHA256 of `<t>.<body>`. Signatures older or newer than the tolerance are rejected, and a
+/// signature accepted once is rejected again for the replay window.
+#[derive(Debug)]
+pub struct StripeSignatureVerifier {
+    secrets: Vec<SigningSecret>,
+    tolerance: Duration,
+    replay_window: Duration,
+    seen: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl StripeSignatureVerifier {
+    pub fn new(secrets: Vec<SigningSecret>) -> Self {
+        let tolerance = Duration::seconds(STRIPE_DEFAULT_TOLERANCE_SECS);
+        Self {
+            secrets,
+            tolerance,
+            replay_window: tolerance,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_replay_window(mut self, replay_window: Duration) -> Self {
+        self.replay_window = replay_window;
+        self
+    }
+
+    fn parse_header(header: &str) -> Result<(i64, Vec<Vec<u8>>), SignatureError> {
+        let malformed = |reason: &str| SignatureError::MalformedHeader {
+            header: STRIPE_SIGNATURE_HEADER,
+            reason: reason.to_string(),
+        };
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+        for item in header.split(',') {
+            let Some((key, value)) = item.trim().split_once('=') else {
+                return Err(malformed("expected comma-separated key=value pairs"));
+            };
+            match key {
+                "t" => {
+                    timestamp = Some(
+                        value
+                            .parse::<i64>()
+                            .map_err(|_| malformed("timestamp is not a unix time"))?,
+                    )
+                }
+                "v1" => signatures
+                    .push(hex::decode(value).map_err(|_| malformed("v1 signature is not hex"))?),
+                _ => {}
+            }
+        }
+        let timestamp = timestamp.ok_or_else(|| malformed("no timestamp"))?;
+        if signatures.is_empty() {
+            return Err(malformed("no v1 signature"));
+        }
+        Ok((timestamp, signatures))
+    }
+}
+
+impl WebhookVerifier for StripeSignatureVerifier {
+    fn source_system(&self) -> &'static str {
+        "stripe"
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> Result<VerifiedWebhook, SignatureError> {
+        let header = request
+            .header(STRIPE_SIGNATURE_HEADER)
+            .ok_or(SignatureError::MissingHeader(STRIPE_SIGNATURE_HEADER))?;
+        let (timestamp, signatures) = Self::parse_header(header)?;
+        let signed_at = DateTime::from_timestamp(timestamp, 0).ok_or_else(|| {
+            SignatureError::MalformedHeader {
+                header: STRIPE_SIGNATURE_HEADER,
+                reason: "timestamp is out of range".to_string(),
+            }
+        })?;
+        if (request.received_at - signed_at).abs() > self.tolerance {
+            return Err(SignatureError::OutsideTolerance {
+                signed_at,
+                received_at: request.received_at,
+                tolerance_secs: self.tolerance.num_seconds(),
+            });
+        }
+
+        let mut signed_payload = format!("{timestamp}.").into_bytes();
+        signed_payload.extend_from_slice(&request.body);
+        let (secret, signature) = active_secrets(&self.secrets, request.received_at)?
+            .into_iter()
+            .find_map(|secret| {
+                signatures.iter().find_map(|signature| {
+                    let mut mac = secret.mac();
+                    mac.update(&signed_payload);
+                    mac.verify_slice(signature)
+                        .ok()
+                        .map(|()| (secret, signature))
+                })
+            })
+            .ok_or(SignatureError::Mismatch)?;
+
+        let mut seen = self.seen.lock().expect("replay guard lock should work");
+        seen.retain(|_, first_seen| request.received_at - *first_seen <= self.replay_window);
+        let replay_key = format!("{timestamp}:{}", hex::encode(signature));
+        if let Some(first_seen) = seen.get(&replay_key) {
+            return Err(SignatureError::Replayed {
+                first_seen: *first_seen,
+            });
+        }
+        seen.insert(replay_key, request.received_at);
+
+        Ok(VerifiedWebhook {
+            secret_id: secret.secret_id.clone(),
+            signed_at: Some(signed_at),
+        })
+    }
+}
+
+/// Square's `x-square-hmacsha256-signature`: a base64 HMAC-SHA256 of the notification URL the
+/// subscription was registered with, followed by the raw body.
+#[derive(Debug, Clone)]
+pub struct SquareSignatureVerifier {
+    secrets: Vec<SigningSecret>,
+    notification_url: String,
+}
+
+impl SquareSignatureVerifier {
+    pub fn new(notification_url: impl Into<String>, secrets: Vec<SigningSecret>) -> Self {
+        Self {
+            secrets,
+            notification_url: notification_url.into(),
+        }
+    }
+}
+
+impl WebhookVerifier for SquareSignatureVerifier {
+    fn source_system(&self) -> &'static str {
+        "square"
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> Result<VerifiedWebhook, SignatureError> {
+        let header = request
+            .header(SQUARE_SIGNATURE_HEADER)
+            .ok_or(SignatureError::MissingHeader(SQUARE_SIGNATURE_HEADER))?;
+        let signature =
+            BASE64
+                .decode(header.trim())
+                .map_err(|_| SignatureError::MalformedHeader {
+                    header: SQUARE_SIGNATURE_HEADER,
+                    reason: "signature is not base64".to_string(),
+                })?;
+        let secret = active_secrets(&self.secrets, request.received_at)?
+            .into_iter()
+            .find(|secret| {
+                let mut mac = secret.mac();
+                mac.update(self.notification_url.as_bytes());
+                mac.update(&request.body);
+                mac.verify_slice(&signature).is_ok()
+            })
+            .ok_or(SignatureError::Mismatch)?;
+        Ok(VerifiedWebhook {
+            secret_id: secret.secret_id.clone(),
+            signed_at: None,
+        })
+    }
+}
+
+/// A hex HMAC-SHA256 of the raw body in a partner-chosen header, for partners without a
+/// signature scheme of their own.
+#[derive(Debug, Clone)]
+pub struct BodyHmacVerifier {
+    source_system: &'static str,
+    header: &'static str,
+    secrets: Vec<SigningSecret>,
+}
+
+impl BodyHmacVerifier {
+    pub fn new(
+        source_system: &'static str,
+        header: &'static str,
+        secrets: Vec<SigningSecret>,
+    ) -> Self {
+        Self {
+            source_system,
+            header,
+            secrets,
+        }
+    }
+}
+
+impl WebhookVerifier for BodyHmacVerifier {
+    fn source_system(&self) -> &'static str {
+        self.source_system
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> Result<VerifiedWebhook, SignatureError> {
+        let header = request
+            .header(self.header)
+            .ok_or(SignatureError::MissingHeader(self.header))?;
+        let signature =
+            hex::decode(header.trim()).map_err(|_| SignatureError::MalformedHeader {
+                header: self.header,
+                reason: "signature is not hex".to_string(),
+            })?;
+        let secret = active_secrets(&self.secrets, request.received_at)?
+            .into_iter()
+            .find(|secret| {
+                let mut mac = secret.mac();
+                mac.update(&request.body);
+                mac.verify_slice(&signature).is_ok()
+            })
+            .ok_or(SignatureError::Mismatch)?;
+        Ok(VerifiedWebhook {
+            secret_id: secret.secret_id.clone(),
+            signed_at: None,
+        })
+    }
+}
+
+/// Adyen signs each notification item rather than the delivery: `additionalData.hmacSignature`
+/// is a base64 HMAC-SHA256, keyed with the hex-decoded HMAC key, of the item's
+/// `pspReference:originalReference:merchantAccountCode:merchantReference:value:currency:eventCode:success`.
+/// Every item in a batch must verify.
+#[derive(Debug, Clone)]
+pub struct AdyenSignatureVerifier {
+    secrets: Vec<SigningSecret>,
+}
+
+impl AdyenSignatureVerifier {
+    pub fn new(secrets: Vec<SigningSecret>) -> Self {
+        Self { secrets }
+    }
+
+    fn signed_payload(item: &Value) -> String {
+        let field = |pointer: &str| match item.pointer(pointer) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Number(value)) => value.to_string(),
+            Some(Value::Bool(value)) => value.to_string(),
+            _ => String::new(),
+        };
+        [
+            "/pspReference",
+            "/originalReference",
+            "/merchantAccountCode",
+            "/merchantReference",
+            "/amount/value",
+            "/amount/currency",
+            "/eventCode",
+            "/success",
+        ]
+        .map(field)
+        .join(":")
+    }
+}
+
+impl WebhookVerifier for AdyenSignatureVerifier {
+    fn source_system(&self) -> &'static str {
+        "adyen"
+    }
+
+    fn verify(&self, request: &WebhookRequest) -> Result<VerifiedWebhook, SignatureError> {
+        let malformed = |reason: &str| SignatureError::MalformedBody(reason.to_string());
+        let payload: Value =
+            serde_json::from_slice(&request.body).map_err(|_| malformed("body is not JSON"))?;
+        let items = notification_items(&payload);
+        if items.is_empty() {
+            return Err(malformed("no notification items"));
+        }
+        let keys = active_secrets(&self.secrets, request.received_at)?
+            .into_iter()
+            .filter_map(|secret| hex::decode(&secret.secret).ok().map(|key| (secret, key)))
+            .collect::<Vec<_>>();
+
+        let mut verified_with = None;
+        for item in &items {
+            let signature = item
+                .pointer("/additionalData/hmacSignature")
+                .and_then(Value::as_str)
+                .ok_or_else(|| malformed("notification item has no hmacSignature"))?;
+            let signature = BASE64
+                .decode(signature.trim())
+                .map_err(|_| malformed("hmacSignature is not base64"))?;
+            let signed_payload = Self::signed_payload(item);
+            let (secret, _) = keys
+                .iter()
+                .find(|(_, key)| {
+                    let mut mac =
+                        HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any size");
+                    mac.update(signed_payload.as_bytes());
+                    mac.verify_slice(&signature).is_ok()
+                })
+                .ok_or(SignatureError::Mismatch)?;
+            verified_with.get_or_insert(*secret);
+        }
+        Ok(VerifiedWebhook {
+            secret_id: verified_with
+                .map(|secret| secret.secret_id.clone())
+                .unwrap_or_default(),
+            signed_at: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(unix: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(unix, 0).unwrap()
+    }
+
+    fn stripe_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{timestamp}.").as_bytes());
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn request(header: &str, value: String, body: &[u8], received_at: i64) -> WebhookRequest {
+        WebhookRequest {
+            headers: BTreeMap::from([(header.to_string(), value)]),
+            body: body.to_vec(),
+            received_at: at(received_at),
+        }
+    }
+
+    #[test]
+    fn stripe_accepts_any_active_secret_and_rejects_stale_or_replayed_signatures() {
+        let body = br#"{"id":"evt_1","type":"charge.captured"}"#;
+        let verifier = StripeSignatureVerifier::new(vec![
+            SigningSecret::new("whsec_old", "old-secret").expiring_at(at(1_800_000_000)),
+            SigningSecret::new("whsec_new", "new-secret"),
+        ]);
+        let signed_at = 1_750_000_000;
+        let header = format!(
+            "t={signed_at},v1={},v1={}",
+            stripe_signature("unrelated", signed_at, body),
+            stripe_signature("old-secret", signed_at, body)
+        );
+
+        let verified = verifier
+            .verify(&request(
+                "Stripe-Signature",
+                header.clone(),
+                body,
+                signed_at + 10,
+            ))
+            .unwrap();
+        assert_eq!(verified.secret_id, "whsec_old");
+        assert_eq!(verified.signed_at, Some(at(signed_at)));
+        assert_eq!(
+            verifier.verify(&request("Stripe-Signature", header, body, signed_at + 20)),
+            Err(SignatureError::Replayed {
+                first_seen: at(signed_at + 10)
+            })
+        );
+
+        let rotated_at = 1_800_000_100;
+        let header = format!(
+            "t={rotated_at},v1={}",
+            stripe_signature("old-secret", rotated_at, body)
+        );
+        assert_eq!(
+            verifier.verify(&request("stripe-signature", header, body, rotated_at)),
+            Err(SignatureError::Mismatch)
+        );
+        let header = format!(
+            "t={rotated_at},v1={}",
+            stripe_signature("new-secret", rotated_at, body)
+        );
+        assert_eq!(
+            verifier
+                .verify(&request(
+                    "stripe-signature",
+                    header.clone(),
+                    body,
+                    rotated_at
+                ))
+                .unwrap()
+                .secret_id,
+            "whsec_new"
+        );
+        assert_eq!(
+            verifier.verify(&request("stripe-signature", header, body, rotated_at + 301)),
+            Err(SignatureError::OutsideTolerance {
+                signed_at: at(rotated_at),
+                received_at: at(rotated_at + 301),
+                tolerance_secs: 300,
+            })
+        );
+    }
+
+    #[test]
+    fn stripe_rejects_missing_and_malformed_headers() {
+        let verifier = StripeSignatureVerifier::new(vec![SigningSecret::new("s", "secret")]);
+        let mut missing = request("content-type", "application/json".to_string(), b"{}", 0);
+        assert_eq!(
+            verifier.verify(&missing),
+            Err(SignatureError::MissingHeader(STRIPE_SIGNATURE_HEADER))
+        );
+        missing
+            .headers
+            .insert("Stripe-Signature".to_string(), "t=1".to_string());
+        assert!(matches!(
+            verifier.verify(&missing),
+            Err(SignatureError::MalformedHeader { .. })
+        ));
+    }
+
+    #[test]
+    fn square_signs_the_notification_url_and_body() {
+        let url = "https://ledger.example.com/v1/webhooks/square";
+        let body = br#"{"event_id":"sq_evt_1","type":"payment.updated"}"#;
+        let sign = |secret: &str, url: &str| {
+            let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+            mac.update(url.as_bytes());
+            mac.update(body);
+            BASE64.encode(mac.finalize().into_bytes())
+        };
+        let verifier = SquareSignatureVerifier::new(
+            url,
+            vec![
+                SigningSecret::new("sq_current", "square-key"),
+                SigningSecret::new("sq_retired", "retired-key").expiring_at(at(100)),
+            ],
+        );
+
+        let verified = verifier
+            .verify(&request(
+                SQUARE_SIGNATURE_HEADER,
+                sign("square-key", url),
+                body,
+                200,
+            ))
+            .unwrap();
+        assert_eq!(verified.secret_id, "sq_current");
+        assert_eq!(
+            verifier.verify(&request(
+                SQUARE_SIGNATURE_HEADER,
+                sign("retired-key", url),
+                body,
+                200
+            )),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(&request(
+                SQUARE_SIGNATURE_HEADER,
+                sign("square-key", "https://attacker.example.com/hook"),
+                body,
+                200
+            )),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            SquareSignatureVerifier::new(url, vec![]).verify(&request(
+                SQUARE_SIGNATURE_HEADER,
+                sign("square-key", url),
+                body,
+                200
+            )),
+            Err(SignatureError::NoActiveSecret)
+        );
+    }
+
+    #[test]
+    fn body_hmac_verifies_the_raw_body() {
+        let body = br#"{"event_id":"inn_evt_1"}"#;
+        let mut mac = HmacSha256::new_from_slice(b"inntopia-key").unwrap();
+        mac.update(body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+        let verifier = BodyHmacVerifier::new(
+            "inntopia",
+            "x-inntopia-signature",
+            vec![SigningSecret::new("inn_1", "inntopia-key")],
+        );
+        assert_eq!(
+            verifier
+                .verify(&request("X-Inntopia-Signature", signature.clone(), body, 0))
+                .unwrap()
+                .secret_id,
+            "inn_1"
+        );
+        assert_eq!(
+            verifier.verify(&request("x-inntopia-signature", signature, b"{}", 0)),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn adyen_verifies_every_item_against_the_hex_hmac_key() {
+        let key = "44782DEF547AAA06C910C43932B1EB0C71FC68D9D0C057550C48EC2ACF6BA056";
+        let body = include_bytes!("../fixtures/adyen/authorisation.json");
+        let verifier = AdyenSignatureVerifier::new(vec![
+            SigningSecret::new("ady_next", "00".repeat(32)),
+            SigningSecret::new("ady_current", key),
+        ]);
+        let verified = verifier
+            .verify(&request(
+                "content-type",
+                "application/json".to_string(),
+                body,
+                0,
+            ))
+            .unwrap();
+        assert_eq!(verified.secret_id, "ady_current");
+
+        let mut payload: Value = serde_json::from_slice(body).unwrap();
+        payload["notificationItems"][0]["NotificationRequestItem"]["amount"]["value"] =
+            serde_json::json!(1);
+        let tampered = serde_json::to_vec(&payload).unwrap();
+        assert_eq!(
+            verifier.verify(&request("content-type", String::new(), &tampered, 0)),
+            Err(SignatureError::Mismatch)
+        );
+
+        let mut batch = payload.clone();
+        let refund: Value =
+            serde_json::from_str(include_str!("../fixtures/adyen/refund.json")).unwrap();
+        batch["notificationItems"] = refund["notificationItems"].clone();
+        assert!(verifier
+            .verify(&request(
+                "content-type",
+                String::new(),
+                &serde_json::to_vec(&batch).unwrap(),
+                0
+            ))
+            .is_ok());
+        batch["notificationItems"]
+            .as_array_mut()
+            .unwrap()
+            .push(payload["notificationItems"][0].clone());
+        assert_eq!(
+            verifier.verify(&request(
+                "content-type",
+                String::new(),
+                &serde_json::to_vec(&batch).unwrap(),
+                0
+            )),
+            Err(SignatureError::Mismatch)
+        );
+
+        payload["notificationItems"][0]["NotificationRequestItem"]["additionalData"]
+            .as_object_mut()
+            .unwrap()
+            .remove("hmacSignature");
+        assert_eq!(
+            verifier.verify(&request(
+                "content-type",
+                String::new(),
+                &serde_json::to_vec(&payload).unwrap(),
+                0
+            )),
+            Err(SignatureError::MalformedBody(
+                "notification item has no hmacSignature".to_string()
+            ))
+        );
+    }
+}
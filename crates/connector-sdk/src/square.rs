@@ -3,6 +3,9 @@ use chrono::{DateTime, Utc};
 use platform_core::payload_hash;
 use serde_json::{json, Value};
 
+use crate::fields::{
+    first_date_string, first_string, has_any, optional_i64, required_amount, required_string,
+};
 use crate::{CanonicalEvent, CanonicalTraceContext, ConnectorAdapter, ConnectorError, RawEvent};
 
 #[derive(Debug, Default, Clone)]
@@ -538,63 +541,6 @@ fn normalize_dispute(
     }))
 }
 
-fn required_string(
-    payload: &Value,
-    pointers: &[&str],
-    field_name: &'static str,
-) -> Result<String, ConnectorError> {
-    first_string(payload, pointers)
-        .ok_or_else(|| ConnectorError::Normalize(format!("missing field `{field_name}`")))
-}
-
-fn required_amount(
-    payload: &Value,
-    pointers: &[&str],
-    field_name: &'static str,
-) -> Result<i64, ConnectorError> {
-    let value = optional_i64(payload, pointers)
-        .ok_or_else(|| ConnectorError::Normalize(format!("missing field `{field_name}`")))?;
-    let normalized = value.abs();
-    if normalized == 0 {
-        return Err(ConnectorError::Normalize(format!(
-            "invalid amount for `{field_name}`"
-        )));
-    }
-    Ok(normalized)
-}
-
-fn first_string(payload: &Value, pointers: &[&str]) -> Option<String> {
-    pointers
-        .iter()
-        .find_map(|pointer| payload.pointer(pointer).and_then(Value::as_str))
-        .map(ToString::to_string)
-}
-
-fn first_date_string(payload: &Value, pointers: &[&str]) -> Option<String> {
-    pointers.iter().find_map(|pointer| {
-        payload.pointer(pointer).and_then(|value| match value {
-            Value::String(string) => string.get(0..10).map(ToString::to_string),
-            _ => None,
-        })
-    })
-}
-
-fn optional_i64(payload: &Value, pointers: &[&str]) -> Option<i64> {
-    pointers.iter().find_map(|pointer| {
-        payload.pointer(pointer).and_then(|value| match value {
-            Value::Number(number) => number.as_i64(),
-            Value::String(string) => string.parse().ok(),
-            _ => None,
-        })
-    })
-}
-
-fn has_any(payload: &Value, pointers: &[&str]) -> bool {
-    pointers
-        .iter()
-        .any(|pointer| payload.pointer(pointer).is_some())
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -602,10 +548,8 @@ mod tests {
     use chrono::Utc;
     use serde_json::json;
 
-    use crate::{
-        evaluate_cutover_rehearsal, run_replay_backfill_resiliency, ConnectorAdapter,
-        CutoverCheckpoint, RawEvent,
-    };
+    use crate::backfill::testing::replay_with_transient_failures;
+    use crate::{evaluate_cutover_rehearsal, ConnectorAdapter, CutoverCheckpoint, RawEvent};
 
     use super::SquareAdapter;
 
@@ -921,9 +865,7 @@ mod tests {
         ];
         let failures = BTreeSet::from([0_usize]);
 
-        let result = run_replay_backfill_resiliency(&adapter, &events, &failures, 1000, 150)
-            .await
-            .unwrap();
+        let result = replay_with_transient_failures(&adapter, &events, &failures, 1000).await;
         assert_eq!(result.hashes.len(), 2);
         assert_eq!(result.telemetry.first_attempt_failures, 1);
         assert_eq!(result.telemetry.recovered_events, 1);
@@ -946,9 +888,8 @@ mod tests {
             }),
         }];
 
-        let replay = run_replay_backfill_resiliency(&adapter, &events, &BTreeSet::new(), 1000, 150)
-            .await
-            .unwrap();
+        let replay =
+            replay_with_transient_failures(&adapter, &events, &BTreeSet::new(), 1000).await;
         let rehearsal = evaluate_cutover_rehearsal(
             &replay,
             true,
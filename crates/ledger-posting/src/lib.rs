@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -165,6 +165,47 @@ pub struct JournalHeader {
     pub fx_rate_set_id: String,
     pub ruleset_version: String,
     pub workflow_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revenue_designation: Option<RevenueDesignation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prior_period_adjustment: Option<PriorPeriodAdjustment>,
+    /// Shared by the journals one event posted into each of its entity's books.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub book_group_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<TraceContext>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RevenueDesignation {
+    pub supplier_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_id: Option<String>,
+    pub designation: String,
+    pub commission_bps: u32,
+    pub terms_version: String,
+}
+
+/// Trace context of the source event a journal was posted from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TraceContext {
+    pub correlation_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub causation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traceparent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracestate: Option<String>,
+}
+
+/// Lineage of a journal posted in an open period to correct an earlier one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PriorPeriodAdjustment {
+    pub affected_period_id: String,
+    pub reason_code: String,
+    pub material: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -176,6 +217,18 @@ pub struct JournalLine {
     pub currency: String,
     pub base_amount_minor: i64,
     pub base_currency: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dimensions: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fx_rate: Option<AppliedFxRate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppliedFxRate {
+    pub rate_type: String,
+    pub rate: String,
+    pub effective_date: NaiveDate,
+    pub source: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -210,20 +263,12 @@ pub enum LedgerError {
     AlreadyReversed,
 }
 
+#[derive(Default)]
 pub struct InMemoryJournalRepository {
     journals: HashMap<Uuid, JournalRecord>,
     persistence: Option<Arc<WriteBehind<HashMap<Uuid, JournalRecord>>>>,
 }
 
-impl Default for InMemoryJournalRepository {
-    fn default() -> Self {
-        Self {
-            journals: HashMap::new(),
-            persistence: None,
-        }
-    }
-}
-
 impl InMemoryJournalRepository {
     pub fn with_persistence_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
         let path = dir.as_ref().join(JOURNAL_STORE_FILENAME);
@@ -246,11 +291,22 @@ impl InMemoryJournalRepository {
     }
 
     pub fn insert_posted(&mut self, record: JournalRecord) -> Result<(), LedgerError> {
-        if self.journals.contains_key(&record.header.journal_id) {
-            return Err(LedgerError::JournalExists);
+        self.insert_posted_batch(vec![record])
+    }
+
+    pub fn insert_posted_batch(&mut self, records: Vec<JournalRecord>) -> Result<(), LedgerError> {
+        let mut journal_ids = HashSet::new();
+        for record in &records {
+            if self.journals.contains_key(&record.header.journal_id)
+                || !journal_ids.insert(record.header.journal_id)
+            {
+                return Err(LedgerError::JournalExists);
+            }
+            validate_balanced(&record.lines)?;
+        }
+        for record in records {
+            self.journals.insert(record.header.journal_id, record);
         }
-        validate_balanced(&record.lines)?;
-        self.journals.insert(record.header.journal_id, record);
         if let Some(persistence) = &self.persistence {
             persistence.persist(self.journals.clone());
         }
@@ -364,6 +420,11 @@ mod tests {
             fx_rate_set_id: "fx_2026_02_21".to_string(),
             ruleset_version: "v1".to_string(),
             workflow_id: Some("wf_1".to_string()),
+            estimate_version: None,
+            revenue_designation: None,
+            prior_period_adjustment: None,
+            book_group_id: None,
+            trace_context: None,
         }
     }
 
@@ -377,6 +438,8 @@ mod tests {
                 currency: "USD".to_string(),
                 base_amount_minor: 10000,
                 base_currency: "USD".to_string(),
+                dimensions: BTreeMap::new(),
+                fx_rate: None,
             },
             JournalLine {
                 line_number: 2,
@@ -386,6 +449,8 @@ mod tests {
                 currency: "USD".to_string(),
                 base_amount_minor: 10000,
                 base_currency: "USD".to_string(),
+                dimensions: BTreeMap::new(),
+                fx_rate: None,
             },
         ]
     }
@@ -404,6 +469,33 @@ mod tests {
         assert_eq!(result, Err(LedgerError::Unbalanced));
     }
 
+    #[test]
+    fn batch_insert_commits_all_or_nothing() {
+        let mut repo = InMemoryJournalRepository::default();
+        let us_gaap = JournalRecord {
+            header: sample_header(),
+            lines: balanced_lines(),
+        };
+        let mut ifrs = JournalRecord {
+            header: sample_header(),
+            lines: balanced_lines(),
+        };
+        ifrs.header.ledger_book = "IFRS".to_string();
+        ifrs.lines[1].amount_minor = 9000;
+
+        let result = repo.insert_posted_batch(vec![us_gaap.clone(), ifrs.clone()]);
+        assert_eq!(result, Err(LedgerError::Unbalanced));
+        assert!(repo.all().is_empty());
+
+        ifrs.lines[1].amount_minor = 10000;
+        let result = repo.insert_posted_batch(vec![us_gaap.clone(), us_gaap.clone()]);
+        assert_eq!(result, Err(LedgerError::JournalExists));
+        assert!(repo.all().is_empty());
+
+        repo.insert_posted_batch(vec![us_gaap, ifrs]).unwrap();
+        assert_eq!(repo.all().len(), 2);
+    }
+
     #[test]
     fn posted_journal_is_immutable() {
         let mut repo = InMemoryJournalRepository::default();
@@ -399,6 +399,10 @@ impl InMemoryAuditSealStore {
             .map_err(|_| AuditSealError::StorePoisoned)?;
         Ok(store.len())
     }
+
+    pub fn is_empty(&self) -> Result<bool, AuditSealError> {
+        Ok(self.len()? == 0)
+    }
 }
 
 fn canonical_entity_scope(entity_scope: &[String]) -> Vec<String> {
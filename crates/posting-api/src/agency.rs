@@ -0,0 +1,629 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use chrono::NaiveDate;
+use ledger_posting::{JournalStatus, RevenueDesignation};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::rule_engine::RuleEngineError;
+use crate::{first_string, rule_engine_error_response, signed_amount, AppState, PostEventRequest};
+
+const SUPPLIER_TERMS_STORE_FILENAME: &str = "supplier_terms_store.json";
+const RESERVATION_EVENT: &str = "inntopia.reservation.captured.v1";
+const SUPPLIER_PAYABLE_ACCOUNT: &str = "2260-SUPPLIER-PAYABLE";
+const BASIS_POINTS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Designation {
+    Principal,
+    Agent,
+}
+
+impl Designation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Principal => "PRINCIPAL",
+            Self::Agent => "AGENT",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProductTerms {
+    pub designation: Designation,
+    #[serde(default)]
+    pub commission_bps: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SupplierTerms {
+    pub tenant_id: String,
+    pub supplier_id: String,
+    pub version: String,
+    pub effective_from: NaiveDate,
+    pub designation: Designation,
+    pub commission_bps: u32,
+    pub products: BTreeMap<String, ProductTerms>,
+    pub approved_by: String,
+}
+
+impl SupplierTerms {
+    fn resolve(&self, product_id: Option<&str>) -> ProductTerms {
+        product_id
+            .and_then(|product_id| self.products.get(product_id))
+            .copied()
+            .unwrap_or(ProductTerms {
+                designation: self.designation,
+                commission_bps: self.commission_bps,
+            })
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AgencyError {
+    #[error("commission rate {0} bps must be below {BASIS_POINTS}")]
+    InvalidCommissionRate(u32),
+    #[error("supplier {supplier_id} already has terms version {version}")]
+    DuplicateTerms {
+        supplier_id: String,
+        version: String,
+    },
+    #[error(
+        "terms version {version} take effect before the current terms ({current_effective_from})"
+    )]
+    TermsOutOfOrder {
+        version: String,
+        current_effective_from: NaiveDate,
+    },
+    #[error("supplier {supplier_id} has no terms in effect on {accounting_date}")]
+    NoTermsInEffect {
+        supplier_id: String,
+        accounting_date: NaiveDate,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SupplierTermsStore {
+    terms: Vec<SupplierTerms>,
+}
+
+#[derive(Default)]
+pub struct InMemorySupplierTermsRepository {
+    terms: Vec<SupplierTerms>,
+    persistence: Option<Arc<WriteBehind<SupplierTermsStore>>>,
+}
+
+impl InMemorySupplierTermsRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(SUPPLIER_TERMS_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: SupplierTermsStore = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "supplier-terms-write-behind")?);
+        Ok(Self {
+            terms: loaded.terms,
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn terms(&self, tenant_id: &str, supplier_id: Option<&str>) -> Vec<SupplierTerms> {
+        self.terms
+            .iter()
+            .filter(|terms| {
+                terms.tenant_id == tenant_id
+                    && supplier_id.is_none_or(|supplier_id| terms.supplier_id == supplier_id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn effective(
+        &self,
+        tenant_id: &str,
+        supplier_id: &str,
+        date: NaiveDate,
+    ) -> Option<&SupplierTerms> {
+        self.terms.iter().rev().find(|terms| {
+            terms.tenant_id == tenant_id
+                && terms.supplier_id == supplier_id
+                && terms.effective_from <= date
+        })
+    }
+
+    pub fn register(&mut self, terms: SupplierTerms) -> Result<(), AgencyError> {
+        for bps in std::iter::once(terms.commission_bps).chain(
+            terms
+                .products
+                .values()
+                .map(|product| product.commission_bps),
+        ) {
+            if bps >= BASIS_POINTS {
+                return Err(AgencyError::InvalidCommissionRate(bps));
+            }
+        }
+        let mut existing = self.terms.iter().filter(|existing| {
+            existing.tenant_id == terms.tenant_id && existing.supplier_id == terms.supplier_id
+        });
+        if existing
+            .clone()
+            .any(|existing| existing.version == terms.version)
+        {
+            return Err(AgencyError::DuplicateTerms {
+                supplier_id: terms.supplier_id,
+                version: terms.version,
+            });
+        }
+        if let Some(current) = existing.next_back() {
+            if terms.effective_from < current.effective_from {
+                return Err(AgencyError::TermsOutOfOrder {
+                    version: terms.version,
+                    current_effective_from: current.effective_from,
+                });
+            }
+        }
+        self.terms.push(terms);
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(SupplierTermsStore {
+                terms: self.terms.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct AgencyEvent {
+    payload: Value,
+    designation: RevenueDesignation,
+}
+
+impl AgencyEvent {
+    pub(crate) fn payload(&self) -> &Value {
+        &self.payload
+    }
+
+    pub(crate) fn designation(&self) -> RevenueDesignation {
+        self.designation.clone()
+    }
+}
+
+/// Splits a reservation sold for a supplier into principal revenue or an agent payable plus
+/// commission, using the supplier terms in effect on the accounting date. Rule sets that do
+/// not read the split keep booking the reservation gross.
+pub(crate) fn prepare_agency_event(
+    state: &AppState,
+    req: &PostEventRequest,
+    accounting_date: NaiveDate,
+) -> Result<Option<AgencyEvent>, ApiError> {
+    if req.event_type != RESERVATION_EVENT {
+        return Ok(None);
+    }
+    let Some(supplier_id) = first_string(&req.payload, &["/supplier_id"]) else {
+        return Ok(None);
+    };
+    let reads_split = state
+        .rule_sets
+        .get(&req.provenance.ruleset_version)
+        .map_err(rule_engine_error_response)?
+        .reads_amount(&req.event_type, "/agent_amount_minor");
+    if !reads_split {
+        return Ok(None);
+    }
+
+    let terms = state
+        .supplier_terms
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::SupplierTermsStoreError))?
+        .effective(&req.tenant_id, supplier_id, accounting_date)
+        .cloned()
+        .ok_or_else(|| {
+            agency_error_response(AgencyError::NoTermsInEffect {
+                supplier_id: supplier_id.to_string(),
+                accounting_date,
+            })
+        })?;
+    let product_id = first_string(&req.payload, &["/product_id"]);
+    let product = terms.resolve(product_id);
+
+    let amount = reservation_amount(&req.payload).map_err(rule_engine_error_response)?;
+    let (principal, agent, commission) = match product.designation {
+        Designation::Principal => (amount, 0, 0),
+        Designation::Agent => (0, amount, commission_minor(amount, product.commission_bps)),
+    };
+    let mut payload = req.payload.clone();
+    if let Some(fields) = payload.as_object_mut() {
+        fields.insert("principal_amount_minor".to_string(), json!(principal));
+        fields.insert("agent_amount_minor".to_string(), json!(agent));
+        fields.insert("commission_minor".to_string(), json!(commission));
+    }
+    Ok(Some(AgencyEvent {
+        payload,
+        designation: RevenueDesignation {
+            supplier_id: supplier_id.to_string(),
+            product_id: product_id.map(ToString::to_string),
+            designation: product.designation.as_str().to_string(),
+            commission_bps: product.commission_bps,
+            terms_version: terms.version,
+        },
+    }))
+}
+
+/// Rounds half away from zero, so a refunded reservation reverses exactly the commission it
+/// earned.
+fn commission_minor(amount: i64, commission_bps: u32) -> i64 {
+    let product = i128::from(amount) * i128::from(commission_bps);
+    let rounded = (product.abs() + i128::from(BASIS_POINTS / 2)) / i128::from(BASIS_POINTS);
+    (rounded * product.signum()) as i64
+}
+
+fn reservation_amount(payload: &Value) -> Result<i64, RuleEngineError> {
+    let value = ["/total_amount_minor", "/amount_minor"]
+        .iter()
+        .find_map(|pointer| payload.pointer(pointer))
+        .ok_or_else(|| RuleEngineError::MissingField("total_amount_minor".to_string()))?;
+    match value {
+        Value::Number(number) => number.as_i64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| RuleEngineError::InvalidNumber("total_amount_minor".to_string()))
+}
+
+pub(crate) fn agency_error_response(error: AgencyError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        AgencyError::InvalidCommissionRate(bps) => {
+            ApiError::bad_request(ErrorCode::InvalidCommissionRate)
+                .with_detail("commission_bps", bps)
+        }
+        AgencyError::DuplicateTerms {
+            supplier_id,
+            version,
+        } => ApiError::conflict(ErrorCode::DuplicateSupplierTerms)
+            .with_detail("supplier_id", supplier_id)
+            .with_detail("version", version),
+        AgencyError::TermsOutOfOrder {
+            version,
+            current_effective_from,
+        } => ApiError::conflict(ErrorCode::SupplierTermsOutOfOrder)
+            .with_detail("version", version)
+            .with_detail("current_effective_from", current_effective_from.to_string()),
+        AgencyError::NoTermsInEffect {
+            supplier_id,
+            accounting_date,
+        } => ApiError::bad_request(ErrorCode::NoSupplierTermsInEffect)
+            .with_detail("supplier_id", supplier_id)
+            .with_detail("accounting_date", accounting_date.to_string()),
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RegisterSupplierTermsRequest {
+    pub tenant_id: String,
+    pub supplier_id: String,
+    pub version: String,
+    pub effective_from: String,
+    pub designation: Designation,
+    #[serde(default)]
+    pub commission_bps: u32,
+    #[serde(default)]
+    pub products: BTreeMap<String, ProductTerms>,
+    pub approved_by: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SupplierTermsQuery {
+    pub tenant_id: String,
+    pub supplier_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SupplierTermsList {
+    pub terms: Vec<SupplierTerms>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SupplierPayableQuery {
+    pub book: String,
+    pub legal_entity_id: Option<String>,
+    pub supplier_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SupplierPayableReport {
+    pub ledger_book: String,
+    pub rows: Vec<SupplierPayableRow>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SupplierPayableRow {
+    pub legal_entity_id: String,
+    pub supplier_id: String,
+    pub currency: String,
+    pub accrued_minor: i64,
+    pub settled_minor: i64,
+    pub outstanding_minor: i64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/suppliers/terms",
+    tag = "suppliers",
+    request_body = RegisterSupplierTermsRequest,
+    responses(
+        (status = 200, description = "Supplier terms registered", body = SupplierTerms),
+        (status = 400, description = "Invalid effective date or commission rate", body = ErrorEnvelope),
+        (status = 409, description = "Duplicate version or out-of-order effective date", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn register_supplier_terms(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<RegisterSupplierTermsRequest>,
+) -> Result<Json<SupplierTerms>, ApiError> {
+    let effective_from =
+        NaiveDate::parse_from_str(&req.effective_from, "%Y-%m-%d").map_err(|_| {
+            ApiError::bad_request(ErrorCode::InvalidAccountingDate)
+                .with_detail("effective_from", req.effective_from.as_str())
+        })?;
+    let terms = SupplierTerms {
+        tenant_id: req.tenant_id,
+        supplier_id: req.supplier_id,
+        version: req.version,
+        effective_from,
+        designation: req.designation,
+        commission_bps: req.commission_bps,
+        products: req.products,
+        approved_by: req.approved_by,
+    };
+    state
+        .supplier_terms
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::SupplierTermsStoreError))?
+        .register(terms.clone())
+        .map_err(agency_error_response)?;
+    Ok(Json(terms))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/suppliers/terms",
+    tag = "suppliers",
+    params(SupplierTermsQuery),
+    responses(
+        (status = 200, description = "Supplier terms in registration order", body = SupplierTermsList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_supplier_terms(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<SupplierTermsQuery>,
+) -> Result<Json<SupplierTermsList>, ApiError> {
+    let terms = state
+        .supplier_terms
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::SupplierTermsStoreError))?
+        .terms(&query.tenant_id, query.supplier_id.as_deref());
+    Ok(Json(SupplierTermsList { terms }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/suppliers/payables",
+    tag = "suppliers",
+    params(SupplierPayableQuery),
+    responses(
+        (status = 200, description = "Amounts owed to suppliers for agent sales, net of settlements", body = SupplierPayableReport),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_supplier_payables(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<SupplierPayableQuery>,
+) -> Result<Json<SupplierPayableReport>, ApiError> {
+    let journals = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?
+        .all();
+    let mut balances = BTreeMap::<_, (i64, i64)>::new();
+    for record in journals.iter().filter(|record| {
+        record.header.ledger_book == query.book
+            && record.header.status == JournalStatus::Posted
+            && query
+                .legal_entity_id
+                .as_ref()
+                .is_none_or(|legal_entity_id| *legal_entity_id == record.header.legal_entity_id)
+    }) {
+        for line in record
+            .lines
+            .iter()
+            .filter(|line| line.account_id == SUPPLIER_PAYABLE_ACCOUNT)
+        {
+            let supplier_id = line
+                .dimensions
+                .get("supplier_id")
+                .cloned()
+                .unwrap_or_default();
+            if query
+                .supplier_id
+                .as_ref()
+                .is_some_and(|wanted| *wanted != supplier_id)
+            {
+                continue;
+            }
+            let entry = balances
+                .entry((
+                    record.header.legal_entity_id.clone(),
+                    supplier_id,
+                    line.currency.clone(),
+                ))
+                .or_default();
+            match signed_amount(line.entry_side.clone(), line.amount_minor) {
+                amount if amount >= 0 => entry.0 += amount,
+                amount => entry.1 -= amount,
+            }
+        }
+    }
+    let rows = balances
+        .into_iter()
+        .map(
+            |((legal_entity_id, supplier_id, currency), (accrued_minor, settled_minor))| {
+                SupplierPayableRow {
+                    legal_entity_id,
+                    supplier_id,
+                    currency,
+                    accrued_minor,
+                    settled_minor,
+                    outstanding_minor: accrued_minor - settled_minor,
+                }
+            },
+        )
+        .collect();
+    Ok(Json(SupplierPayableReport {
+        ledger_book: query.book,
+        rows,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        commission_minor, reservation_amount, AgencyError, Designation,
+        InMemorySupplierTermsRepository, ProductTerms, SupplierTerms,
+    };
+    use crate::rule_engine::RuleEngineError;
+    use chrono::NaiveDate;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn terms(version: &str, effective_from: &str, bps: u32) -> SupplierTerms {
+        SupplierTerms {
+            tenant_id: "tenant_1".to_string(),
+            supplier_id: "lodge_1".to_string(),
+            version: version.to_string(),
+            effective_from: NaiveDate::parse_from_str(effective_from, "%Y-%m-%d").unwrap(),
+            designation: Designation::Agent,
+            commission_bps: bps,
+            products: BTreeMap::from([(
+                "SUITE".to_string(),
+                ProductTerms {
+                    designation: Designation::Principal,
+                    commission_bps: 0,
+                },
+            )]),
+            approved_by: "controller".to_string(),
+        }
+    }
+
+    #[test]
+    fn terms_are_versioned_by_effective_date_with_product_overrides() {
+        let mut repo = InMemorySupplierTermsRepository::default();
+        repo.register(terms("2026.1", "2026-01-01", 1500)).unwrap();
+        repo.register(terms("2026.2", "2026-03-01", 1200)).unwrap();
+
+        assert_eq!(
+            repo.register(terms("2026.2", "2026-04-01", 1200)),
+            Err(AgencyError::DuplicateTerms {
+                supplier_id: "lodge_1".to_string(),
+                version: "2026.2".to_string(),
+            })
+        );
+        assert_eq!(
+            repo.register(terms("2026.0", "2026-02-01", 1200)),
+            Err(AgencyError::TermsOutOfOrder {
+                version: "2026.0".to_string(),
+                current_effective_from: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            })
+        );
+        assert_eq!(
+            repo.register(terms("2026.3", "2026-05-01", 10000)),
+            Err(AgencyError::InvalidCommissionRate(10000))
+        );
+
+        let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+        let february = repo.effective("tenant_1", "lodge_1", date(2, 21)).unwrap();
+        assert_eq!(february.version, "2026.1");
+        assert_eq!(february.resolve(None).commission_bps, 1500);
+        assert_eq!(
+            february.resolve(Some("SUITE")).designation,
+            Designation::Principal
+        );
+        assert_eq!(
+            repo.effective("tenant_1", "lodge_1", date(3, 1))
+                .map(|terms| terms.version.as_str()),
+            Some("2026.2")
+        );
+        assert!(repo.effective("tenant_1", "lodge_1", date(1, 1)).is_some());
+        assert!(repo
+            .effective(
+                "tenant_1",
+                "lodge_1",
+                NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn commission_rounds_half_away_from_zero() {
+        assert_eq!(commission_minor(10_000, 1500), 1500);
+        assert_eq!(commission_minor(15, 1500), 2);
+        assert_eq!(commission_minor(-15, 1500), -2);
+        assert_eq!(commission_minor(1, 5000), 1);
+        assert_eq!(commission_minor(-1, 5000), -1);
+        assert_eq!(commission_minor(1, 4999), 0);
+        assert_eq!(commission_minor(0, 1500), 0);
+        assert_eq!(commission_minor(12_345, 0), 0);
+        assert_eq!(commission_minor(i64::MAX, 9999), 9_222_449_699_651_090_329);
+    }
+
+    #[test]
+    fn reservation_amount_requires_a_whole_number() {
+        assert_eq!(
+            reservation_amount(&json!({"total_amount_minor": 0, "amount_minor": 500})),
+            Ok(0)
+        );
+        assert_eq!(
+            reservation_amount(&json!({"amount_minor": "-2500"})),
+            Ok(-2500)
+        );
+        assert_eq!(
+            reservation_amount(&json!({"product_id": "SUITE"})),
+            Err(RuleEngineError::MissingField(
+                "total_amount_minor".to_string()
+            ))
+        );
+        for invalid in [json!(12.5), json!("12.50"), json!(null)] {
+            assert_eq!(
+                reservation_amount(&json!({"total_amount_minor": invalid})),
+                Err(RuleEngineError::InvalidNumber(
+                    "total_amount_minor".to_string()
+                ))
+            );
+        }
+    }
+}
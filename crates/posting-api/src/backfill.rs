@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use connector_sdk::{
+    BackfillDeadLetter, BackfillPoster, CanonicalEvent, DeadLetterSink, DeadLetterSinkError,
+    PostingError,
+};
+
+use crate::error::{ApiError, ErrorCode};
+use crate::ingest::{post_canonical_event, webhook_source, WebhookSource};
+use crate::AppState;
+
+/// Posts replayed events through the same pipeline as a live delivery from `source`, so a
+/// backfilled event and its webhook share one idempotency key.
+pub struct IngestBackfillPoster {
+    state: AppState,
+    source: String,
+    webhook: Arc<WebhookSource>,
+}
+
+impl IngestBackfillPoster {
+    pub fn new(state: AppState, source: &str) -> Result<Self, ApiError> {
+        let webhook = webhook_source(&state, source)?;
+        Ok(Self {
+            state,
+            source: source.to_string(),
+            webhook,
+        })
+    }
+}
+
+#[async_trait]
+impl BackfillPoster for IngestBackfillPoster {
+    async fn post(&self, event: &CanonicalEvent) -> Result<(), PostingError> {
+        post_canonical_event(&self.state, &self.source, &self.webhook, event)
+            .map(|_| ())
+            .map_err(posting_error)
+    }
+}
+
+/// Parks events the backfill gave up on in the dead-letter store under `source`, where the
+/// retry and discard endpoints pick them up like any failed delivery.
+pub struct DeadLetterStoreSink {
+    state: AppState,
+    source: String,
+    adapter_version: String,
+}
+
+impl DeadLetterStoreSink {
+    pub fn new(state: AppState, source: &str) -> Result<Self, ApiError> {
+        let adapter_version = webhook_source(&state, source)?
+            .adapter
+            .adapter_version()
+            .to_string();
+        Ok(Self {
+            state,
+            source: source.to_string(),
+            adapter_version,
+        })
+    }
+}
+
+impl DeadLetterSink for DeadLetterStoreSink {
+    fn accept(&self, letter: BackfillDeadLetter) -> Result<(), DeadLetterSinkError> {
+        self.state
+            .dead_letters
+            .lock()
+            .map_err(|_| {
+                DeadLetterSinkError::new(
+                    ApiError::internal(ErrorCode::DeadLetterStoreError).message,
+                )
+            })?
+            .record_failure(
+                &self.source,
+                &self.adapter_version,
+                letter.raw_event,
+                letter.error,
+                Utc::now(),
+            );
+        Ok(())
+    }
+}
+
+/// Server errors and unavailable stores are worth retrying; anything else will fail the same
+/// way on every attempt.
+pub fn posting_error(error: ApiError) -> PostingError {
+    if error.status.is_server_error() || error.code.is_store_unavailable() {
+        PostingError::transient(error.message)
+    } else {
+        PostingError::permanent(error.message)
+    }
+}
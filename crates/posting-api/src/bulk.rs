@@ -0,0 +1,335 @@
+use std::convert::Infallible;
+
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, CorrelationId, ErrorCode, ErrorEnvelope};
+use crate::{
+    ensure_supported_event_type, post_event_with_idempotency_key, AppState, BookJournal,
+    PostEventRequest,
+};
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+const BULK_RESULT_BUFFER: usize = 16;
+const MAX_BULK_LINE_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BulkPostEventLine {
+    pub idempotency_key: String,
+    pub event: PostEventRequest,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub struct BulkPostEventResult {
+    pub line_number: u64,
+    pub idempotency_key: Option<String>,
+    pub outcome: BulkPostOutcome,
+    pub http_status: u16,
+    pub journal_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(required = false)]
+    pub book_journals: Vec<BookJournal>,
+    pub error: Option<ErrorEnvelope>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BulkPostOutcome {
+    Posted,
+    Replayed,
+    Rejected,
+    /// Webhook ingestion only: the source reported nothing to book.
+    Skipped,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/posting/events/bulk",
+    tag = "posting",
+    request_body(content = BulkPostEventLine, content_type = "application/x-ndjson"),
+    responses(
+        (status = 200, description = "One result per input line", body = BulkPostEventResult, content_type = "application/x-ndjson")
+    )
+)]
+pub(crate) async fn post_events_bulk(
+    State(state): State<AppState>,
+    CorrelationId(correlation_id): CorrelationId,
+    body: Body,
+) -> Response {
+    let (tx, rx) = mpsc::channel::<Bytes>(BULK_RESULT_BUFFER);
+    tokio::spawn(run_bulk_posting(state, correlation_id, body, tx));
+
+    let results = stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|chunk| (Ok::<_, Infallible>(chunk), rx))
+    });
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(results),
+    )
+        .into_response()
+}
+
+async fn run_bulk_posting(
+    state: AppState,
+    correlation_id: String,
+    body: Body,
+    tx: mpsc::Sender<Bytes>,
+) {
+    let mut chunks = body.into_data_stream();
+    let mut framer = NdjsonFramer::default();
+
+    loop {
+        for frame in framer.drain_complete_lines() {
+            if !emit(&tx, process_bulk_frame(&state, &correlation_id, frame)).await {
+                return;
+            }
+        }
+
+        match chunks.next().await {
+            Some(Ok(chunk)) => framer.push(&chunk),
+            Some(Err(_)) => {
+                let result = rejected(
+                    framer.next_line_number(),
+                    None,
+                    ApiError::bad_request(ErrorCode::NdjsonBodyReadError),
+                    &correlation_id,
+                );
+                emit(&tx, Some(result)).await;
+                return;
+            }
+            None => break,
+        }
+    }
+
+    if let Some(frame) = framer.finish() {
+        emit(&tx, process_bulk_frame(&state, &correlation_id, frame)).await;
+    }
+}
+
+async fn emit(tx: &mpsc::Sender<Bytes>, result: Option<BulkPostEventResult>) -> bool {
+    let Some(result) = result else {
+        return true;
+    };
+    let mut encoded =
+        serde_json::to_vec(&result).expect("bulk result serialization should not fail");
+    encoded.push(b'\n');
+    tx.send(Bytes::from(encoded)).await.is_ok()
+}
+
+fn process_bulk_frame(
+    state: &AppState,
+    correlation_id: &str,
+    frame: NdjsonFrame,
+) -> Option<BulkPostEventResult> {
+    match frame {
+        NdjsonFrame::Line { line_number, bytes } => {
+            process_bulk_line(state, correlation_id, line_number, &bytes)
+        }
+        NdjsonFrame::Oversized { line_number } => Some(rejected(
+            line_number,
+            None,
+            ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, ErrorCode::NdjsonLineTooLarge)
+                .with_detail("max_bytes", MAX_BULK_LINE_BYTES),
+            correlation_id,
+        )),
+    }
+}
+
+fn process_bulk_line(
+    state: &AppState,
+    correlation_id: &str,
+    line_number: u64,
+    bytes: &[u8],
+) -> Option<BulkPostEventResult> {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        return None;
+    }
+
+    let line: BulkPostEventLine = match serde_json::from_slice(bytes) {
+        Ok(line) => line,
+        Err(error) => {
+            return Some(rejected(
+                line_number,
+                None,
+                ApiError::bad_request(ErrorCode::InvalidNdjsonLine)
+                    .with_detail("reason", error.to_string()),
+                correlation_id,
+            ))
+        }
+    };
+    let key = line.idempotency_key.trim().to_string();
+    if key.is_empty() {
+        return Some(rejected(
+            line_number,
+            None,
+            ApiError::bad_request(ErrorCode::MissingIdempotencyKey),
+            correlation_id,
+        ));
+    }
+
+    let outcome = ensure_supported_event_type(state, &line.event)
+        .and_then(|_| post_event_with_idempotency_key(state, &key, line.event));
+    Some(match outcome {
+        Ok(response) => BulkPostEventResult {
+            line_number,
+            idempotency_key: Some(key),
+            outcome: if response.replayed {
+                BulkPostOutcome::Replayed
+            } else {
+                BulkPostOutcome::Posted
+            },
+            http_status: StatusCode::OK.as_u16(),
+            journal_id: Some(response.journal_id),
+            book_journals: response.book_journals,
+            error: None,
+        },
+        Err(error) => rejected(line_number, Some(key), error, correlation_id),
+    })
+}
+
+fn rejected(
+    line_number: u64,
+    idempotency_key: Option<String>,
+    error: ApiError,
+    correlation_id: &str,
+) -> BulkPostEventResult {
+    BulkPostEventResult {
+        line_number,
+        idempotency_key,
+        outcome: BulkPostOutcome::Rejected,
+        http_status: error.status.as_u16(),
+        journal_id: None,
+        book_journals: Vec::new(),
+        error: Some(error.envelope(correlation_id)),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum NdjsonFrame {
+    Line { line_number: u64, bytes: Vec<u8> },
+    Oversized { line_number: u64 },
+}
+
+#[derive(Default)]
+struct NdjsonFramer {
+    buffer: Vec<u8>,
+    lines_seen: u64,
+    discarding_oversized: bool,
+}
+
+impl NdjsonFramer {
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    fn next_line_number(&self) -> u64 {
+        self.lines_seen + 1
+    }
+
+    fn drain_complete_lines(&mut self) -> Vec<NdjsonFrame> {
+        let mut frames = Vec::new();
+        while let Some(position) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let mut line = self.buffer.drain(..=position).collect::<Vec<_>>();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            self.lines_seen += 1;
+            if std::mem::take(&mut self.discarding_oversized) {
+                continue;
+            }
+            frames.push(self.frame(line));
+        }
+
+        if self.buffer.len() > MAX_BULK_LINE_BYTES && !self.discarding_oversized {
+            self.buffer.clear();
+            self.discarding_oversized = true;
+            frames.push(NdjsonFrame::Oversized {
+                line_number: self.next_line_number(),
+            });
+        } else if self.discarding_oversized {
+            self.buffer.clear();
+        }
+        frames
+    }
+
+    fn finish(&mut self) -> Option<NdjsonFrame> {
+        if self.discarding_oversized || self.buffer.is_empty() {
+            return None;
+        }
+        self.lines_seen += 1;
+        let line = std::mem::take(&mut self.buffer);
+        Some(self.frame(line))
+    }
+
+    fn frame(&self, bytes: Vec<u8>) -> NdjsonFrame {
+        if bytes.len() > MAX_BULK_LINE_BYTES {
+            NdjsonFrame::Oversized {
+                line_number: self.lines_seen,
+            }
+        } else {
+            NdjsonFrame::Line {
+                line_number: self.lines_seen,
+                bytes,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NdjsonFrame, NdjsonFramer, MAX_BULK_LINE_BYTES};
+
+    #[test]
+    fn framer_splits_lines_across_chunks() {
+        let mut framer = NdjsonFramer::default();
+        framer.push(b"{\"a\":1}\r\n{\"b\"");
+        let first = framer.drain_complete_lines();
+        assert_eq!(
+            first,
+            vec![NdjsonFrame::Line {
+                line_number: 1,
+                bytes: b"{\"a\":1}".to_vec()
+            }]
+        );
+
+        framer.push(b":2}");
+        assert!(framer.drain_complete_lines().is_empty());
+        assert_eq!(
+            framer.finish(),
+            Some(NdjsonFrame::Line {
+                line_number: 2,
+                bytes: b"{\"b\":2}".to_vec()
+            })
+        );
+    }
+
+    #[test]
+    fn framer_rejects_oversized_line_and_resumes_after_newline() {
+        let mut framer = NdjsonFramer::default();
+        framer.push(&vec![b'x'; MAX_BULK_LINE_BYTES + 1]);
+        assert_eq!(
+            framer.drain_complete_lines(),
+            vec![NdjsonFrame::Oversized { line_number: 1 }]
+        );
+
+        framer.push(b"xxx\n{}\n");
+        assert_eq!(
+            framer.drain_complete_lines(),
+            vec![NdjsonFrame::Line {
+                line_number: 2,
+                bytes: b"{}".to_vec()
+            }]
+        );
+        assert_eq!(framer.finish(), None);
+    }
+}
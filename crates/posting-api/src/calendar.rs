@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::{period_error_response, AppState};
+
+/// How a legal entity divides its fiscal year into periods. Calendar months keep `YYYY-MM`
+/// period ids; every other calendar uses `FY<year>-P<nn>`, where the fiscal year is named for
+/// the calendar year it ends in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FiscalCalendar {
+    #[default]
+    CalendarMonth,
+    FiscalMonth {
+        start_month: u32,
+    },
+    Weeks {
+        pattern: WeekPattern,
+        year_end_month: u32,
+        #[schema(value_type = String, example = "Sat")]
+        year_end_weekday: Weekday,
+        year_end: YearEndRule,
+    },
+}
+
+/// Weeks per period across a 52-week year. A 53rd week goes to the last period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WeekPattern {
+    FourFourFive,
+    FourFiveFour,
+    FiveFourFour,
+    ThirteenFourWeek,
+}
+
+impl WeekPattern {
+    fn weeks(self) -> Vec<u64> {
+        let quarter = match self {
+            Self::FourFourFive => [4, 4, 5],
+            Self::FourFiveFour => [4, 5, 4],
+            Self::FiveFourFour => [5, 4, 4],
+            Self::ThirteenFourWeek => return vec![4; 13],
+        };
+        quarter.repeat(4)
+    }
+}
+
+/// Where a 52/53-week year ends: on the last `year_end_weekday` of `year_end_month`, or on
+/// the `year_end_weekday` nearest the month's last day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum YearEndRule {
+    LastWeekday,
+    NearestMonthEnd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FiscalPeriod {
+    pub period_id: String,
+    pub fiscal_year: i32,
+    pub period_number: u32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+pub enum CalendarError {
+    #[error("month {0} is not between 1 and 12")]
+    InvalidMonth(u32),
+    #[error("fiscal year {0} falls outside the supported date range")]
+    FiscalYearOutOfRange(i32),
+}
+
+impl FiscalCalendar {
+    pub fn validate(&self) -> Result<(), CalendarError> {
+        let month = match *self {
+            Self::CalendarMonth => return Ok(()),
+            Self::FiscalMonth { start_month } => start_month,
+            Self::Weeks { year_end_month, .. } => year_end_month,
+        };
+        if !(1..=12).contains(&month) {
+            return Err(CalendarError::InvalidMonth(month));
+        }
+        Ok(())
+    }
+
+    pub fn period_for(&self, date: NaiveDate) -> Result<FiscalPeriod, CalendarError> {
+        let fiscal_year = match *self {
+            Self::CalendarMonth => date.year(),
+            Self::FiscalMonth { start_month } => {
+                if start_month > 1 && date.month() >= start_month {
+                    offset_year(date.year(), 1)?
+                } else {
+                    date.year()
+                }
+            }
+            Self::Weeks { .. } => {
+                let mut fiscal_year = date.year();
+                while date > self.year_end(fiscal_year)? {
+                    fiscal_year = offset_year(fiscal_year, 1)?;
+                }
+                while date <= self.year_end(offset_year(fiscal_year, -1)?)? {
+                    fiscal_year = offset_year(fiscal_year, -1)?;
+                }
+                fiscal_year
+            }
+        };
+        self.periods(fiscal_year)?
+            .into_iter()
+            .find(|period| period.start_date <= date && date <= period.end_date)
+            .ok_or(CalendarError::FiscalYearOutOfRange(fiscal_year))
+    }
+
+    pub fn periods(&self, fiscal_year: i32) -> Result<Vec<FiscalPeriod>, CalendarError> {
+        self.validate()?;
+        let out_of_range = CalendarError::FiscalYearOutOfRange(fiscal_year);
+        let ranges = match *self {
+            Self::CalendarMonth => month_ranges(fiscal_year, 1),
+            Self::FiscalMonth { start_month } if start_month > 1 => {
+                month_ranges(offset_year(fiscal_year, -1)?, start_month)
+            }
+            Self::FiscalMonth { .. } => month_ranges(fiscal_year, 1),
+            Self::Weeks { pattern, .. } => {
+                let year_start = self
+                    .year_end(offset_year(fiscal_year, -1)?)?
+                    .checked_add_days(Days::new(1))
+                    .ok_or(out_of_range)?;
+                let year_end = self.year_end(fiscal_year)?;
+                let weeks = pattern.weeks();
+                let last = weeks.len() - 1;
+                let mut start = year_start;
+                weeks
+                    .iter()
+                    .enumerate()
+                    .map(|(index, weeks)| {
+                        let end = if index == last {
+                            year_end
+                        } else {
+                            start
+                                .checked_add_days(Days::new(weeks * 7 - 1))
+                                .ok_or(out_of_range)?
+                        };
+                        let range = (start, end);
+                        start = end.checked_add_days(Days::new(1)).ok_or(out_of_range)?;
+                        Ok(range)
+                    })
+                    .collect()
+            }
+        }?;
+        Ok(ranges
+            .into_iter()
+            .enumerate()
+            .map(|(index, (start_date, end_date))| {
+                let period_number = index as u32 + 1;
+                FiscalPeriod {
+                    period_id: self.period_id(fiscal_year, period_number),
+                    fiscal_year,
+                    period_number,
+                    start_date,
+                    end_date,
+                }
+            })
+            .collect())
+    }
+
+    pub fn is_valid_period_id(&self, period_id: &str) -> bool {
+        self.period(period_id).is_some()
+    }
+
+    pub fn period(&self, period_id: &str) -> Option<FiscalPeriod> {
+        let (fiscal_year, period_number) = self.parse_period_id(period_id)?;
+        let index = usize::try_from(period_number).ok()?.checked_sub(1)?;
+        self.periods(fiscal_year).ok()?.into_iter().nth(index)
+    }
+
+    fn parse_period_id(&self, period_id: &str) -> Option<(i32, u32)> {
+        let (year, number) = match self {
+            Self::CalendarMonth => period_id.split_once('-')?,
+            _ => period_id.strip_prefix("FY")?.split_once("-P")?,
+        };
+        if year.len() != 4 || number.len() != 2 {
+            return None;
+        }
+        if !year
+            .chars()
+            .chain(number.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        Some((year.parse().ok()?, number.parse().ok()?))
+    }
+
+    fn period_id(&self, fiscal_year: i32, period_number: u32) -> String {
+        match self {
+            Self::CalendarMonth => format!("{fiscal_year:04}-{period_number:02}"),
+            _ => format!("FY{fiscal_year:04}-P{period_number:02}"),
+        }
+    }
+
+    fn year_end(&self, fiscal_year: i32) -> Result<NaiveDate, CalendarError> {
+        let Self::Weeks {
+            year_end_month,
+            year_end_weekday,
+            year_end,
+            ..
+        } = *self
+        else {
+            unreachable!("only week-based calendars have a floating year end")
+        };
+        let month_end = last_day_of_month(fiscal_year, year_end_month)?;
+        let back = (7 + month_end.weekday().num_days_from_monday()
+            - year_end_weekday.num_days_from_monday())
+            % 7;
+        match year_end {
+            YearEndRule::NearestMonthEnd if back > 3 => {
+                month_end.checked_add_days(Days::new(u64::from(7 - back)))
+            }
+            _ => month_end.checked_sub_days(Days::new(u64::from(back))),
+        }
+        .ok_or(CalendarError::FiscalYearOutOfRange(fiscal_year))
+    }
+}
+
+/// Each legal entity's fiscal calendar. Entities without one use calendar months. Cheap to
+/// clone, so reports can copy it out of the period store instead of holding its lock.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FiscalCalendars {
+    by_legal_entity: HashMap<String, FiscalCalendar>,
+}
+
+impl FiscalCalendars {
+    pub fn new(by_legal_entity: HashMap<String, FiscalCalendar>) -> Self {
+        Self { by_legal_entity }
+    }
+
+    pub fn calendar(&self, legal_entity_id: &str) -> FiscalCalendar {
+        self.by_legal_entity
+            .get(legal_entity_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Whether `period_id` names a period in the entity's calendar or, without an entity, in the
+    /// default calendar or any configured one.
+    pub fn knows_period_id(&self, legal_entity_id: Option<&str>, period_id: &str) -> bool {
+        match legal_entity_id {
+            Some(legal_entity_id) => self.calendar(legal_entity_id).is_valid_period_id(period_id),
+            None => std::iter::once(FiscalCalendar::default())
+                .chain(self.by_legal_entity.values().copied())
+                .any(|calendar| calendar.is_valid_period_id(period_id)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiscalCalendarQuery {
+    pub fiscal_year: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FiscalCalendarResponse {
+    pub legal_entity_id: String,
+    pub calendar: FiscalCalendar,
+    pub fiscal_year: i32,
+    pub periods: Vec<FiscalPeriod>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ledger/fiscal-calendars/{legal_entity_id}",
+    tag = "ledger",
+    params(
+        ("legal_entity_id" = String, Path, description = "Legal entity whose calendar to resolve"),
+        FiscalCalendarQuery
+    ),
+    responses(
+        (status = 200, description = "Periods of the fiscal year with their date ranges", body = FiscalCalendarResponse),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_fiscal_calendar(
+    State(state): State<AppState>,
+    Path(legal_entity_id): Path<String>,
+    ApiQuery(query): ApiQuery<FiscalCalendarQuery>,
+) -> Result<Json<FiscalCalendarResponse>, ApiError> {
+    if !(1..=9999).contains(&query.fiscal_year) {
+        return Err(ApiError::bad_request(ErrorCode::InvalidQuery)
+            .with_detail("fiscal_year", query.fiscal_year));
+    }
+    let calendar = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+        .calendar(&legal_entity_id);
+    Ok(Json(FiscalCalendarResponse {
+        legal_entity_id,
+        calendar,
+        fiscal_year: query.fiscal_year,
+        periods: calendar
+            .periods(query.fiscal_year)
+            .map_err(|error| period_error_response(error.into()))?,
+    }))
+}
+
+/// The fiscal year `delta` years from `fiscal_year`, or out of range instead of overflowing.
+fn offset_year(fiscal_year: i32, delta: i32) -> Result<i32, CalendarError> {
+    fiscal_year
+        .checked_add(delta)
+        .ok_or(CalendarError::FiscalYearOutOfRange(fiscal_year))
+}
+
+fn month_ranges(
+    start_year: i32,
+    start_month: u32,
+) -> Result<Vec<(NaiveDate, NaiveDate)>, CalendarError> {
+    (0..12)
+        .map(|offset| {
+            let month_index = start_month - 1 + offset;
+            let year = offset_year(start_year, (month_index / 12) as i32)?;
+            let month = month_index % 12 + 1;
+            Ok((month_start(year, month)?, last_day_of_month(year, month)?))
+        })
+        .collect()
+}
+
+fn month_start(year: i32, month: u32) -> Result<NaiveDate, CalendarError> {
+    NaiveDate::from_ymd_opt(year, month, 1).ok_or(CalendarError::FiscalYearOutOfRange(year))
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Result<NaiveDate, CalendarError> {
+    let (next_year, next_month) = if month == 12 {
+        (offset_year(year, 1)?, 1)
+    } else {
+        (year, month + 1)
+    };
+    month_start(next_year, next_month)?
+        .pred_opt()
+        .ok_or(CalendarError::FiscalYearOutOfRange(year))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Weekday};
+
+    use super::{CalendarError, FiscalCalendar, WeekPattern, YearEndRule};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn fiscal_month_calendar_names_the_year_it_ends_in() {
+        let calendar = FiscalCalendar::FiscalMonth { start_month: 5 };
+        let may = calendar.period_for(date(2026, 5, 1)).unwrap();
+        assert_eq!(may.period_id, "FY2027-P01");
+        assert_eq!(
+            calendar.period_for(date(2027, 4, 30)).unwrap().period_id,
+            "FY2027-P12"
+        );
+        assert_eq!(
+            calendar.period_for(date(2026, 2, 21)).unwrap().period_id,
+            "FY2026-P10"
+        );
+        assert!(calendar.is_valid_period_id("FY2027-P12"));
+        assert!(!calendar.is_valid_period_id("FY2027-P13"));
+        assert!(!calendar.is_valid_period_id("2026-05"));
+        assert_eq!(
+            FiscalCalendar::CalendarMonth
+                .period_for(date(2026, 12, 31))
+                .unwrap()
+                .period_id,
+            "2026-12"
+        );
+    }
+
+    #[test]
+    fn week_calendars_follow_the_pattern_and_absorb_a_53rd_week() {
+        let calendar = FiscalCalendar::Weeks {
+            pattern: WeekPattern::FourFourFive,
+            year_end_month: 12,
+            year_end_weekday: Weekday::Sat,
+            year_end: YearEndRule::LastWeekday,
+        };
+        let periods = calendar.periods(2026).unwrap();
+        assert_eq!(periods.len(), 12);
+        assert_eq!(periods[0].start_date, date(2025, 12, 28));
+        assert_eq!(periods[0].end_date, date(2026, 1, 24));
+        assert_eq!(periods[2].end_date, date(2026, 3, 28));
+        assert_eq!(periods[11].end_date, date(2026, 12, 26));
+        assert_eq!(
+            (periods[11].end_date - periods[11].start_date).num_days(),
+            34
+        );
+
+        // Under the nearest rule 2025 runs from 2024-12-29 to 2026-01-03, which is 53 weeks.
+        let nearest = FiscalCalendar::Weeks {
+            pattern: WeekPattern::ThirteenFourWeek,
+            year_end_month: 12,
+            year_end_weekday: Weekday::Sat,
+            year_end: YearEndRule::NearestMonthEnd,
+        };
+        let periods = nearest.periods(2025).unwrap();
+        assert_eq!(periods.len(), 13);
+        assert_eq!(periods[0].start_date, date(2024, 12, 29));
+        assert_eq!(periods[12].end_date, date(2026, 1, 3));
+        assert_eq!(
+            (periods[12].end_date - periods[12].start_date).num_days(),
+            34
+        );
+        assert_eq!(
+            nearest.period_for(date(2026, 1, 3)).unwrap().period_id,
+            "FY2025-P13"
+        );
+        assert_eq!(
+            nearest.period_for(date(2026, 1, 4)).unwrap().period_id,
+            "FY2026-P01"
+        );
+    }
+
+    #[test]
+    fn unresolvable_periods_are_errors_not_panics() {
+        assert_eq!(
+            FiscalCalendar::FiscalMonth { start_month: 0 }.periods(2026),
+            Err(CalendarError::InvalidMonth(0))
+        );
+        let weeks = FiscalCalendar::Weeks {
+            pattern: WeekPattern::FourFourFive,
+            year_end_month: 12,
+            year_end_weekday: Weekday::Sat,
+            year_end: YearEndRule::NearestMonthEnd,
+        };
+        assert!(matches!(
+            weeks.period_for(NaiveDate::MAX),
+            Err(CalendarError::FiscalYearOutOfRange(_))
+        ));
+        assert!(matches!(
+            FiscalCalendar::FiscalMonth { start_month: 7 }.period_for(NaiveDate::MAX),
+            Err(CalendarError::FiscalYearOutOfRange(_))
+        ));
+        for fiscal_year in [i32::MIN, i32::MAX] {
+            assert!(matches!(
+                FiscalCalendar::FiscalMonth { start_month: 7 }.periods(fiscal_year),
+                Err(CalendarError::FiscalYearOutOfRange(_))
+            ));
+            assert!(matches!(
+                FiscalCalendar::FiscalMonth { start_month: 1 }.periods(fiscal_year),
+                Err(CalendarError::FiscalYearOutOfRange(_))
+            ));
+            assert!(matches!(
+                weeks.periods(fiscal_year),
+                Err(CalendarError::FiscalYearOutOfRange(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::{HashSet, VecDeque};
+use std::convert::Infallible;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::Json;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::{stream, Stream};
+use ledger_posting::{JournalHeader, JournalRecord, JournalStatus};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::watch;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::AppState;
+
+const CHANGE_FEED_STORE_FILENAME: &str = "ledger_change_feed.json";
+const DEFAULT_PAGE_LIMIT: usize = 100;
+const MAX_PAGE_LIMIT: usize = 1000;
+const MAX_LONG_POLL_WAIT_MS: u64 = 30_000;
+const SSE_BATCH_LIMIT: usize = 100;
+const SSE_KEEP_ALIVE_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LedgerChangeType {
+    JournalPosted,
+    JournalReversed,
+    JournalAdjusted,
+}
+
+impl LedgerChangeType {
+    fn sse_event_name(self) -> &'static str {
+        match self {
+            Self::JournalPosted => "journal.posted",
+            Self::JournalReversed => "journal.reversed",
+            Self::JournalAdjusted => "journal.adjusted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LedgerChangeEvent {
+    pub offset: u64,
+    pub change_type: LedgerChangeType,
+    pub journal_id: String,
+    pub related_journal_id: Option<String>,
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub accounting_date: NaiveDate,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ChangeFeedError {
+    #[error("change feed store lock poisoned")]
+    StorePoisoned,
+    #[error("change feed offset {found} is out of order, expected {expected}")]
+    OffsetGap { expected: u64, found: u64 },
+}
+
+#[derive(Clone)]
+pub struct LedgerChangeFeed {
+    events: Arc<Mutex<Vec<LedgerChangeEvent>>>,
+    latest_offset: Arc<watch::Sender<u64>>,
+    persistence: Option<Arc<WriteBehind<Vec<LedgerChangeEvent>>>>,
+}
+
+impl Default for LedgerChangeFeed {
+    fn default() -> Self {
+        Self::from_events(Vec::new(), None)
+    }
+}
+
+impl LedgerChangeFeed {
+    pub fn with_persistence_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let path = dir.as_ref().join(CHANGE_FEED_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "change-feed-write-behind")?);
+        Ok(Self::from_events(loaded, Some(persistence)))
+    }
+
+    fn from_events(
+        events: Vec<LedgerChangeEvent>,
+        persistence: Option<Arc<WriteBehind<Vec<LedgerChangeEvent>>>>,
+    ) -> Self {
+        let latest = events.last().map(|event| event.offset).unwrap_or_default();
+        let (latest_offset, _) = watch::channel(latest);
+        Self {
+            events: Arc::new(Mutex::new(events)),
+            latest_offset: Arc::new(latest_offset),
+            persistence,
+        }
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn append(
+        &self,
+        change_type: LedgerChangeType,
+        header: &JournalHeader,
+        related_journal_id: Option<String>,
+    ) -> Result<LedgerChangeEvent, ChangeFeedError> {
+        let mut events = self
+            .events
+            .lock()
+            .map_err(|_| ChangeFeedError::StorePoisoned)?;
+        let event = LedgerChangeEvent {
+            offset: events.last().map(|event| event.offset).unwrap_or_default() + 1,
+            change_type,
+            journal_id: header.journal_id.to_string(),
+            related_journal_id,
+            tenant_id: header.tenant_id.clone(),
+            legal_entity_id: header.legal_entity_id.clone(),
+            ledger_book: header.ledger_book.clone(),
+            accounting_date: header.accounting_date,
+            recorded_at: Utc::now(),
+        };
+        events.push(event.clone());
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(events.clone());
+        }
+        drop(events);
+        self.latest_offset.send_replace(event.offset);
+        Ok(event)
+    }
+
+    pub fn read_after(
+        &self,
+        cursor: u64,
+        limit: usize,
+    ) -> Result<Vec<LedgerChangeEvent>, ChangeFeedError> {
+        let events = self
+            .events
+            .lock()
+            .map_err(|_| ChangeFeedError::StorePoisoned)?;
+        let start = events.partition_point(|event| event.offset <= cursor);
+        Ok(events[start..].iter().take(limit).cloned().collect())
+    }
+
+    pub fn latest_offset(&self) -> u64 {
+        *self.latest_offset.borrow()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.latest_offset.subscribe()
+    }
+
+    pub fn backfill_missing(&self, journals: &[JournalRecord]) -> Result<usize, ChangeFeedError> {
+        let (mut recorded, mut reversed) = (HashSet::new(), HashSet::new());
+        for event in self.read_after(0, usize::MAX)? {
+            match event.change_type {
+                LedgerChangeType::JournalReversed => reversed.insert(event.journal_id),
+                _ => recorded.insert(event.journal_id),
+            };
+        }
+
+        let mut journals = journals.iter().collect::<Vec<_>>();
+        journals.sort_by_key(|record| record.header.posted_at);
+        let mut appended = 0;
+        for record in journals {
+            let journal_id = record.header.journal_id.to_string();
+            if !recorded.contains(&journal_id) {
+                let adjusted = record
+                    .header
+                    .source_event_ids
+                    .iter()
+                    .find_map(|id| id.strip_prefix("adjusts:"))
+                    .map(ToString::to_string);
+                let change_type = match adjusted {
+                    Some(_) => LedgerChangeType::JournalAdjusted,
+                    None => LedgerChangeType::JournalPosted,
+                };
+                self.append(change_type, &record.header, adjusted)?;
+                appended += 1;
+            }
+            if record.header.status == JournalStatus::Reversed && !reversed.contains(&journal_id) {
+                self.append(LedgerChangeType::JournalReversed, &record.header, None)?;
+                appended += 1;
+            }
+        }
+        Ok(appended)
+    }
+
+    pub fn verify_offsets(&self) -> Result<(), ChangeFeedError> {
+        let events = self
+            .events
+            .lock()
+            .map_err(|_| ChangeFeedError::StorePoisoned)?;
+        for (index, event) in events.iter().enumerate() {
+            let expected = index as u64 + 1;
+            if event.offset != expected {
+                return Err(ChangeFeedError::OffsetGap {
+                    expected,
+                    found: event.offset,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangeFeedQuery {
+    #[serde(default)]
+    pub after: u64,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub wait_ms: u64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangeStreamQuery {
+    pub after: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct ChangeFeedPage {
+    pub events: Vec<LedgerChangeEvent>,
+    pub next_cursor: u64,
+    pub latest_offset: u64,
+}
+
+pub(crate) fn change_feed_error_response(_error: ChangeFeedError) -> ApiError {
+    ApiError::internal(ErrorCode::ChangeFeedStoreError)
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ledger/changes",
+    tag = "ledger",
+    params(ChangeFeedQuery),
+    responses(
+        (status = 200, description = "Ledger changes after the cursor, waiting up to wait_ms when none are available", body = ChangeFeedPage),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_ledger_changes(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<ChangeFeedQuery>,
+) -> Result<Json<ChangeFeedPage>, ApiError> {
+    let feed = &state.change_feed;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let mut updates = feed.subscribe();
+    let mut events = feed
+        .read_after(query.after, limit)
+        .map_err(change_feed_error_response)?;
+
+    if events.is_empty() && query.wait_ms > 0 {
+        let wait = Duration::from_millis(query.wait_ms.min(MAX_LONG_POLL_WAIT_MS));
+        let _ = tokio::time::timeout(wait, updates.wait_for(|latest| *latest > query.after)).await;
+        events = feed
+            .read_after(query.after, limit)
+            .map_err(change_feed_error_response)?;
+    }
+
+    let next_cursor = events
+        .last()
+        .map(|event| event.offset)
+        .unwrap_or(query.after);
+    Ok(Json(ChangeFeedPage {
+        events,
+        next_cursor,
+        latest_offset: feed.latest_offset(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ledger/changes/stream",
+    tag = "ledger",
+    params(
+        ChangeStreamQuery,
+        ("Last-Event-ID" = Option<u64>, Header, description = "Offset of the last event the client processed; takes precedence over `after`")
+    ),
+    responses(
+        (status = 200, description = "Server-Sent Events; each event id is the change offset", body = LedgerChangeEvent, content_type = "text/event-stream"),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn stream_ledger_changes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ApiQuery(query): ApiQuery<ChangeStreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let cursor = headers
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .or(query.after)
+        .unwrap_or_default();
+
+    Sse::new(change_event_stream(state.change_feed.clone(), cursor))
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(SSE_KEEP_ALIVE_SECS)))
+}
+
+struct StreamCursor {
+    feed: LedgerChangeFeed,
+    updates: watch::Receiver<u64>,
+    cursor: u64,
+    pending: VecDeque<LedgerChangeEvent>,
+}
+
+fn change_event_stream(
+    feed: LedgerChangeFeed,
+    cursor: u64,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let updates = feed.subscribe();
+    let state = StreamCursor {
+        feed,
+        updates,
+        cursor,
+        pending: VecDeque::new(),
+    };
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                state.cursor = event.offset;
+                return Some((Ok(sse_event(&event)), state));
+            }
+            match state.feed.read_after(state.cursor, SSE_BATCH_LIMIT) {
+                Ok(events) if !events.is_empty() => state.pending.extend(events),
+                Ok(_) => {
+                    let cursor = state.cursor;
+                    state
+                        .updates
+                        .wait_for(|latest| *latest > cursor)
+                        .await
+                        .ok()?;
+                }
+                Err(_) => return None,
+            }
+        }
+    })
+}
+
+fn sse_event(event: &LedgerChangeEvent) -> Event {
+    Event::default()
+        .id(event.offset.to_string())
+        .event(event.change_type.sse_event_name())
+        .json_data(event)
+        .expect("change event serialization should not fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use ledger_posting::{JournalHeader, JournalStatus};
+    use uuid::Uuid;
+
+    use ledger_posting::JournalRecord;
+
+    use super::{ChangeFeedError, LedgerChangeFeed, LedgerChangeType};
+
+    fn header() -> JournalHeader {
+        JournalHeader {
+            journal_id: Uuid::new_v4(),
+            journal_number: "S2-test".to_string(),
+            status: JournalStatus::Posted,
+            tenant_id: "tenant_1".to_string(),
+            legal_entity_id: "US_CO_01".to_string(),
+            ledger_book: "US_GAAP".to_string(),
+            accounting_date: NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+            posted_at: chrono::Utc::now(),
+            source_event_ids: vec!["evt_1".to_string()],
+            posting_run_id: "run_1".to_string(),
+            book_policy_id: "policy_dual_book".to_string(),
+            policy_version: "1.0.0".to_string(),
+            fx_rate_set_id: "fx_2026_02_21".to_string(),
+            ruleset_version: "v1".to_string(),
+            workflow_id: None,
+            estimate_version: None,
+            revenue_designation: None,
+            prior_period_adjustment: None,
+            book_group_id: None,
+            trace_context: None,
+        }
+    }
+
+    #[test]
+    fn read_after_resumes_from_cursor() {
+        let feed = LedgerChangeFeed::default();
+        let header = header();
+        for change_type in [
+            LedgerChangeType::JournalPosted,
+            LedgerChangeType::JournalReversed,
+            LedgerChangeType::JournalPosted,
+        ] {
+            feed.append(change_type, &header, None).unwrap();
+        }
+
+        let first = feed.read_after(0, 2).unwrap();
+        assert_eq!(
+            first.iter().map(|event| event.offset).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let rest = feed.read_after(2, 10).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].offset, 3);
+        assert!(feed.read_after(3, 10).unwrap().is_empty());
+        assert_eq!(feed.latest_offset(), 3);
+        assert_eq!(feed.verify_offsets(), Ok(()));
+    }
+
+    #[test]
+    fn backfill_records_journals_missing_from_feed() {
+        let feed = LedgerChangeFeed::default();
+        let posted = header();
+        feed.append(LedgerChangeType::JournalPosted, &posted, None)
+            .unwrap();
+
+        let mut reversed = header();
+        reversed.status = JournalStatus::Reversed;
+        let mut adjusted = header();
+        adjusted.source_event_ids = vec![
+            "adj_evt".to_string(),
+            format!("adjusts:{}", reversed.journal_id),
+        ];
+        let journals = [posted, reversed.clone(), adjusted.clone()]
+            .into_iter()
+            .map(|header| JournalRecord {
+                header,
+                lines: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(feed.backfill_missing(&journals).unwrap(), 3);
+        let events = feed.read_after(1, 10).unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.change_type, event.journal_id.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    LedgerChangeType::JournalPosted,
+                    reversed.journal_id.to_string()
+                ),
+                (
+                    LedgerChangeType::JournalReversed,
+                    reversed.journal_id.to_string()
+                ),
+                (
+                    LedgerChangeType::JournalAdjusted,
+                    adjusted.journal_id.to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            events[2].related_journal_id,
+            Some(reversed.journal_id.to_string())
+        );
+        assert_eq!(feed.backfill_missing(&journals).unwrap(), 0);
+    }
+
+    #[test]
+    fn verify_offsets_detects_gaps() {
+        let feed = LedgerChangeFeed::default();
+        feed.append(LedgerChangeType::JournalPosted, &header(), None)
+            .unwrap();
+        feed.events.lock().unwrap()[0].offset = 5;
+        assert_eq!(
+            feed.verify_offsets(),
+            Err(ChangeFeedError::OffsetGap {
+                expected: 1,
+                found: 5
+            })
+        );
+    }
+}
@@ -0,0 +1,655 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::Utc;
+use reconciliation_model::{
+    evaluate_entity_close_checklist, transition_close_dependency_status, CloseChecklistDependency,
+    CloseChecklistError, CloseChecklistProgression, CloseChecklistStatus, CloseDependencyStatus,
+    EntityCloseChecklist,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::{period_error_response, AppState};
+
+const CLOSE_CHECKLIST_STORE_FILENAME: &str = "close_checklist_store.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredChecklist {
+    tenant_id: String,
+    checklist: EntityCloseChecklist,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CloseError {
+    #[error("close checklist has no dependencies")]
+    EmptyChecklist,
+    #[error("close checklist lists dependency `{0}` more than once")]
+    DuplicateDependency(String),
+    #[error("dependency `{0}` is required for close and still open; it cannot be dropped")]
+    RequiredDependencyOpen(String),
+    #[error("no close checklist for {legal_entity_id} period {period_id}")]
+    ChecklistNotFound {
+        legal_entity_id: String,
+        period_id: String,
+    },
+    #[error("close checklist for {legal_entity_id} period {period_id} is already closed")]
+    ChecklistClosed {
+        legal_entity_id: String,
+        period_id: String,
+    },
+    #[error(transparent)]
+    Dependency(#[from] CloseChecklistError),
+    #[error(
+        "period {period_id} needs a close checklist in ReadyToClose status before it can be closed"
+    )]
+    ChecklistRequired { period_id: String },
+    #[error("close checklist for period {period_id} is {status:?}, not ReadyToClose")]
+    NotReadyToClose {
+        period_id: String,
+        status: CloseChecklistStatus,
+        unresolved_blockers: Vec<String>,
+        outstanding_dependencies: Vec<String>,
+    },
+}
+
+#[derive(Default)]
+pub struct InMemoryCloseChecklistRepository {
+    checklists: BTreeMap<(String, String, String), EntityCloseChecklist>,
+    persistence: Option<Arc<WriteBehind<Vec<StoredChecklist>>>>,
+}
+
+impl InMemoryCloseChecklistRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(CLOSE_CHECKLIST_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: Vec<StoredChecklist> = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "close-checklist-write-behind")?);
+        Ok(Self {
+            checklists: loaded
+                .into_iter()
+                .map(|stored| {
+                    let key = checklist_key(
+                        &stored.tenant_id,
+                        &stored.checklist.legal_entity_id,
+                        &stored.checklist.period_id,
+                    );
+                    (key, stored.checklist)
+                })
+                .collect(),
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        period_id: &str,
+    ) -> Option<&EntityCloseChecklist> {
+        self.checklists
+            .get(&checklist_key(tenant_id, legal_entity_id, period_id))
+    }
+
+    /// Replaces the checklist for its entity and period. Dependencies that are new, or whose
+    /// description or `required_for_close` changed, start `Pending`; unchanged ones keep their
+    /// stored status, which only `transition_dependency` moves. A required dependency that is not
+    /// yet satisfied cannot be removed or made optional. The checklist status is always derived
+    /// from the dependencies, never taken from the caller.
+    pub fn upsert(
+        &mut self,
+        tenant_id: &str,
+        mut checklist: EntityCloseChecklist,
+    ) -> Result<EntityCloseChecklist, CloseError> {
+        if checklist.dependencies.is_empty() {
+            return Err(CloseError::EmptyChecklist);
+        }
+        let mut seen = HashSet::new();
+        for dependency in &checklist.dependencies {
+            if !seen.insert(dependency.dependency_id.as_str()) {
+                return Err(CloseError::DuplicateDependency(
+                    dependency.dependency_id.clone(),
+                ));
+            }
+        }
+        let existing = self.get(tenant_id, &checklist.legal_entity_id, &checklist.period_id);
+        if existing.is_some_and(|existing| existing.status == CloseChecklistStatus::Closed) {
+            return Err(CloseError::ChecklistClosed {
+                legal_entity_id: checklist.legal_entity_id,
+                period_id: checklist.period_id,
+            });
+        }
+        let stored = existing
+            .map(|existing| existing.dependencies.as_slice())
+            .unwrap_or_default();
+        for open in stored.iter().filter(|dependency| {
+            dependency.required_for_close && dependency.status != CloseDependencyStatus::Satisfied
+        }) {
+            if !checklist.dependencies.iter().any(|dependency| {
+                dependency.dependency_id == open.dependency_id && dependency.required_for_close
+            }) {
+                return Err(CloseError::RequiredDependencyOpen(
+                    open.dependency_id.clone(),
+                ));
+            }
+        }
+        for dependency in &mut checklist.dependencies {
+            dependency.status = stored
+                .iter()
+                .find(|existing| {
+                    existing.dependency_id == dependency.dependency_id
+                        && existing.description == dependency.description
+                        && existing.required_for_close == dependency.required_for_close
+                })
+                .map_or(CloseDependencyStatus::Pending, |existing| {
+                    existing.status.clone()
+                });
+        }
+        checklist.status = CloseChecklistStatus::InProgress;
+        checklist.status = evaluate_entity_close_checklist(&checklist).status;
+        self.store(tenant_id, checklist.clone());
+        Ok(checklist)
+    }
+
+    pub fn transition_dependency(
+        &mut self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        period_id: &str,
+        dependency_id: &str,
+        status: CloseDependencyStatus,
+    ) -> Result<EntityCloseChecklist, CloseError> {
+        let existing = self
+            .get(tenant_id, legal_entity_id, period_id)
+            .ok_or_else(|| CloseError::ChecklistNotFound {
+                legal_entity_id: legal_entity_id.to_string(),
+                period_id: period_id.to_string(),
+            })?;
+        let updated =
+            transition_close_dependency_status(existing, dependency_id, status, Utc::now())?;
+        self.store(tenant_id, updated.clone());
+        Ok(updated)
+    }
+
+    /// The checklist a hard close is allowed to proceed on.
+    pub fn ready_to_close(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        period_id: &str,
+    ) -> Result<EntityCloseChecklist, CloseError> {
+        let checklist = self
+            .get(tenant_id, legal_entity_id, period_id)
+            .ok_or_else(|| CloseError::ChecklistRequired {
+                period_id: period_id.to_string(),
+            })?;
+        let progression = evaluate_entity_close_checklist(checklist);
+        if progression.status != CloseChecklistStatus::ReadyToClose {
+            return Err(CloseError::NotReadyToClose {
+                period_id: period_id.to_string(),
+                status: progression.status,
+                unresolved_blockers: progression.unresolved_blockers,
+                outstanding_dependencies: checklist
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| {
+                        dependency.required_for_close
+                            && dependency.status != CloseDependencyStatus::Satisfied
+                    })
+                    .map(|dependency| dependency.dependency_id.clone())
+                    .collect(),
+            });
+        }
+        Ok(checklist.clone())
+    }
+
+    fn store(&mut self, tenant_id: &str, checklist: EntityCloseChecklist) {
+        self.checklists.insert(
+            checklist_key(tenant_id, &checklist.legal_entity_id, &checklist.period_id),
+            checklist,
+        );
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(
+                self.checklists
+                    .iter()
+                    .map(|((tenant_id, _, _), checklist)| StoredChecklist {
+                        tenant_id: tenant_id.clone(),
+                        checklist: checklist.clone(),
+                    })
+                    .collect(),
+            );
+        }
+    }
+}
+
+fn checklist_key(
+    tenant_id: &str,
+    legal_entity_id: &str,
+    period_id: &str,
+) -> (String, String, String) {
+    (
+        tenant_id.to_string(),
+        legal_entity_id.to_string(),
+        period_id.to_string(),
+    )
+}
+
+/// Checks the close checklist before a hard close and seals the snapshot it was granted on.
+pub(crate) fn seal_hard_close(
+    state: &AppState,
+    tenant_id: &str,
+    legal_entity_id: &str,
+    ledger_book: &str,
+    period_id: &str,
+) -> Result<String, ApiError> {
+    let checklist = state
+        .close_checklists
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::CloseChecklistStoreError))?
+        .ready_to_close(tenant_id, legal_entity_id, period_id)
+        .map_err(close_error_response)?;
+    state.append_audit_seal(
+        "period.closed",
+        &[legal_entity_id.to_string()],
+        &json!({
+            "tenant_id": tenant_id,
+            "legal_entity_id": legal_entity_id,
+            "ledger_book": ledger_book,
+            "period_id": period_id,
+            "close_checklist": checklist,
+        }),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )
+}
+
+pub(crate) fn close_error_response(error: CloseError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        CloseError::EmptyChecklist => ApiError::bad_request(ErrorCode::InvalidCloseChecklist),
+        CloseError::DuplicateDependency(dependency_id) => {
+            ApiError::bad_request(ErrorCode::InvalidCloseChecklist)
+                .with_detail("dependency_id", dependency_id)
+        }
+        CloseError::RequiredDependencyOpen(dependency_id) => {
+            ApiError::conflict(ErrorCode::CloseDependencyStillRequired)
+                .with_detail("dependency_id", dependency_id)
+        }
+        CloseError::ChecklistNotFound {
+            legal_entity_id,
+            period_id,
+        } => ApiError::not_found(ErrorCode::CloseChecklistNotFound)
+            .with_detail("legal_entity_id", legal_entity_id)
+            .with_detail("period_id", period_id),
+        CloseError::ChecklistClosed {
+            legal_entity_id,
+            period_id,
+        } => ApiError::conflict(ErrorCode::CloseChecklistClosed)
+            .with_detail("legal_entity_id", legal_entity_id)
+            .with_detail("period_id", period_id),
+        CloseError::Dependency(CloseChecklistError::DependencyNotFound { dependency_id }) => {
+            ApiError::not_found(ErrorCode::CloseDependencyNotFound)
+                .with_detail("dependency_id", dependency_id)
+        }
+        CloseError::Dependency(CloseChecklistError::InvalidDependencyTransition { from, to }) => {
+            ApiError::conflict(ErrorCode::InvalidCloseDependencyTransition)
+                .with_detail("from_status", json!(from))
+                .with_detail("to_status", json!(to))
+        }
+        CloseError::Dependency(CloseChecklistError::UnsupportedEntityCount { entity_count }) => {
+            ApiError::bad_request(ErrorCode::InvalidCloseChecklist)
+                .with_detail("entity_count", entity_count)
+        }
+        CloseError::ChecklistRequired { period_id } => {
+            ApiError::conflict(ErrorCode::CloseChecklistRequired)
+                .with_detail("period_id", period_id)
+        }
+        CloseError::NotReadyToClose {
+            period_id,
+            status,
+            unresolved_blockers,
+            outstanding_dependencies,
+        } => ApiError::conflict(ErrorCode::CloseChecklistNotReady)
+            .with_detail("period_id", period_id)
+            .with_detail("status", json!(status))
+            .with_detail("unresolved_blockers", unresolved_blockers)
+            .with_detail("outstanding_dependencies", outstanding_dependencies),
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpsertCloseChecklistRequest {
+    pub tenant_id: String,
+    pub checklist_id: String,
+    pub legal_entity_id: String,
+    pub period_id: String,
+    pub dependencies: Vec<CloseDependencyDefinition>,
+}
+
+/// A dependency as the caller defines it. Its status starts `Pending` and moves only through
+/// the dependency transition endpoint.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CloseDependencyDefinition {
+    pub dependency_id: String,
+    pub description: String,
+    pub required_for_close: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct TransitionCloseDependencyRequest {
+    pub tenant_id: String,
+    #[schema(value_type = String, example = "Satisfied")]
+    pub status: CloseDependencyStatus,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CloseChecklistQuery {
+    pub tenant_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CloseChecklistResponse {
+    #[schema(value_type = Object)]
+    pub checklist: EntityCloseChecklist,
+    #[schema(value_type = Object)]
+    pub progression: CloseChecklistProgression,
+}
+
+impl CloseChecklistResponse {
+    fn of(checklist: EntityCloseChecklist) -> Self {
+        Self {
+            progression: evaluate_entity_close_checklist(&checklist),
+            checklist,
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/close/checklists",
+    tag = "close",
+    request_body = UpsertCloseChecklistRequest,
+    responses(
+        (status = 200, description = "Checklist stored with its derived status", body = CloseChecklistResponse),
+        (status = 400, description = "Invalid period id or dependency list", body = ErrorEnvelope),
+        (status = 409, description = "Checklist is already closed, or an open required dependency would be dropped", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn upsert_close_checklist(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<UpsertCloseChecklistRequest>,
+) -> Result<Json<CloseChecklistResponse>, ApiError> {
+    state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+        .ensure_valid_period_id(&req.legal_entity_id, &req.period_id)
+        .map_err(period_error_response)?;
+    let checklist = state
+        .close_checklists
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::CloseChecklistStoreError))?
+        .upsert(
+            &req.tenant_id,
+            EntityCloseChecklist {
+                checklist_id: req.checklist_id,
+                legal_entity_id: req.legal_entity_id,
+                period_id: req.period_id,
+                status: CloseChecklistStatus::InProgress,
+                dependencies: req
+                    .dependencies
+                    .into_iter()
+                    .map(|dependency| CloseChecklistDependency {
+                        dependency_id: dependency.dependency_id,
+                        description: dependency.description,
+                        required_for_close: dependency.required_for_close,
+                        status: CloseDependencyStatus::Pending,
+                    })
+                    .collect(),
+                updated_at: Utc::now(),
+            },
+        )
+        .map_err(close_error_response)?;
+    Ok(Json(CloseChecklistResponse::of(checklist)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/close/checklists/{legal_entity_id}/{period_id}",
+    tag = "close",
+    params(
+        ("legal_entity_id" = String, Path, description = "Legal entity being closed"),
+        ("period_id" = String, Path, description = "Period in the entity's fiscal calendar"),
+        CloseChecklistQuery
+    ),
+    responses(
+        (status = 200, description = "Checklist and its progression", body = CloseChecklistResponse),
+        (status = 404, description = "No checklist for the entity and period", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_close_checklist(
+    State(state): State<AppState>,
+    Path((legal_entity_id, period_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<CloseChecklistQuery>,
+) -> Result<Json<CloseChecklistResponse>, ApiError> {
+    let checklist = state
+        .close_checklists
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::CloseChecklistStoreError))?
+        .get(&query.tenant_id, &legal_entity_id, &period_id)
+        .cloned()
+        .ok_or_else(|| {
+            close_error_response(CloseError::ChecklistNotFound {
+                legal_entity_id,
+                period_id,
+            })
+        })?;
+    Ok(Json(CloseChecklistResponse::of(checklist)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/close/checklists/{legal_entity_id}/{period_id}/dependencies/{dependency_id}",
+    tag = "close",
+    params(
+        ("legal_entity_id" = String, Path, description = "Legal entity being closed"),
+        ("period_id" = String, Path, description = "Period in the entity's fiscal calendar"),
+        ("dependency_id" = String, Path, description = "Checklist dependency to update")
+    ),
+    request_body = TransitionCloseDependencyRequest,
+    responses(
+        (status = 200, description = "Dependency updated and checklist status re-derived", body = CloseChecklistResponse),
+        (status = 404, description = "Checklist or dependency not found", body = ErrorEnvelope),
+        (status = 409, description = "Dependency cannot move to the requested status", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn transition_close_dependency(
+    State(state): State<AppState>,
+    Path((legal_entity_id, period_id, dependency_id)): Path<(String, String, String)>,
+    ApiJson(req): ApiJson<TransitionCloseDependencyRequest>,
+) -> Result<Json<CloseChecklistResponse>, ApiError> {
+    let checklist = state
+        .close_checklists
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::CloseChecklistStoreError))?
+        .transition_dependency(
+            &req.tenant_id,
+            &legal_entity_id,
+            &period_id,
+            &dependency_id,
+            req.status,
+        )
+        .map_err(close_error_response)?;
+    Ok(Json(CloseChecklistResponse::of(checklist)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use reconciliation_model::{
+        CloseChecklistDependency, CloseChecklistStatus, CloseDependencyStatus, EntityCloseChecklist,
+    };
+
+    use super::{CloseError, InMemoryCloseChecklistRepository};
+
+    fn checklist(statuses: &[CloseDependencyStatus]) -> EntityCloseChecklist {
+        EntityCloseChecklist {
+            checklist_id: "close_2026_02".to_string(),
+            legal_entity_id: "US_CO_01".to_string(),
+            period_id: "2026-02".to_string(),
+            status: CloseChecklistStatus::ReadyToClose,
+            dependencies: statuses
+                .iter()
+                .enumerate()
+                .map(|(index, status)| CloseChecklistDependency {
+                    dependency_id: format!("dep_{index}"),
+                    description: "reconcile".to_string(),
+                    required_for_close: true,
+                    status: status.clone(),
+                })
+                .collect(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn hard_close_requires_a_ready_checklist() {
+        let mut repo = InMemoryCloseChecklistRepository::default();
+        assert_eq!(
+            repo.ready_to_close("tenant_1", "US_CO_01", "2026-02"),
+            Err(CloseError::ChecklistRequired {
+                period_id: "2026-02".to_string()
+            })
+        );
+
+        repo.upsert(
+            "tenant_1",
+            checklist(&[
+                CloseDependencyStatus::Pending,
+                CloseDependencyStatus::Pending,
+            ]),
+        )
+        .unwrap();
+        let stored = repo
+            .transition_dependency(
+                "tenant_1",
+                "US_CO_01",
+                "2026-02",
+                "dep_0",
+                CloseDependencyStatus::Blocked,
+            )
+            .unwrap();
+        assert_eq!(stored.status, CloseChecklistStatus::Blocked);
+        assert_eq!(
+            repo.ready_to_close("tenant_1", "US_CO_01", "2026-02"),
+            Err(CloseError::NotReadyToClose {
+                period_id: "2026-02".to_string(),
+                status: CloseChecklistStatus::Blocked,
+                unresolved_blockers: vec!["dep_0".to_string()],
+                outstanding_dependencies: vec!["dep_0".to_string(), "dep_1".to_string()],
+            })
+        );
+
+        for dependency_id in ["dep_0", "dep_1"] {
+            repo.transition_dependency(
+                "tenant_1",
+                "US_CO_01",
+                "2026-02",
+                dependency_id,
+                CloseDependencyStatus::Satisfied,
+            )
+            .unwrap();
+        }
+        assert!(repo
+            .ready_to_close("tenant_1", "US_CO_01", "2026-02")
+            .is_ok());
+        assert!(repo
+            .ready_to_close("tenant_2", "US_CO_01", "2026-02")
+            .is_err());
+    }
+
+    #[test]
+    fn upsert_keeps_unchanged_statuses_and_guards_open_required_dependencies() {
+        let mut repo = InMemoryCloseChecklistRepository::default();
+        let stored = repo
+            .upsert(
+                "tenant_1",
+                checklist(&[
+                    CloseDependencyStatus::Satisfied,
+                    CloseDependencyStatus::Satisfied,
+                ]),
+            )
+            .unwrap();
+        assert!(stored
+            .dependencies
+            .iter()
+            .all(|dependency| dependency.status == CloseDependencyStatus::Pending));
+        repo.transition_dependency(
+            "tenant_1",
+            "US_CO_01",
+            "2026-02",
+            "dep_0",
+            CloseDependencyStatus::Satisfied,
+        )
+        .unwrap();
+
+        let mut resubmitted = checklist(&[
+            CloseDependencyStatus::Pending,
+            CloseDependencyStatus::Pending,
+        ]);
+        resubmitted.dependencies[1].description = "reconcile and review".to_string();
+        let stored = repo.upsert("tenant_1", resubmitted.clone()).unwrap();
+        assert_eq!(
+            stored.dependencies[0].status,
+            CloseDependencyStatus::Satisfied
+        );
+        assert_eq!(
+            stored.dependencies[1].status,
+            CloseDependencyStatus::Pending
+        );
+
+        let mut optional = resubmitted.clone();
+        optional.dependencies[1].required_for_close = false;
+        assert_eq!(
+            repo.upsert("tenant_1", optional),
+            Err(CloseError::RequiredDependencyOpen("dep_1".to_string()))
+        );
+        let mut dropped = resubmitted;
+        dropped.dependencies.truncate(1);
+        assert_eq!(
+            repo.upsert("tenant_1", dropped),
+            Err(CloseError::RequiredDependencyOpen("dep_1".to_string()))
+        );
+
+        let mut dropped_satisfied = checklist(&[CloseDependencyStatus::Pending]);
+        dropped_satisfied.dependencies[0].dependency_id = "dep_1".to_string();
+        dropped_satisfied.dependencies[0].description = "reconcile and review".to_string();
+        let stored = repo.upsert("tenant_1", dropped_satisfied).unwrap();
+        assert_eq!(stored.dependencies.len(), 1);
+        assert_eq!(stored.status, CloseChecklistStatus::InProgress);
+    }
+}
@@ -0,0 +1,667 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::calendar::{CalendarError, FiscalCalendar};
+use crate::Provenance;
+
+pub const DEFAULT_BIND_ADDR: &str = "0.0.0.0:3000";
+
+pub const USAGE: &str = "usage: posting-api [--config <file>] [--bind <addr>] \
+[--persistence-dir <dir>] [--entity-registry <file>] [--policy <file>] [--rule-sets <dir>] \
+[--fx-rate-sets <dir>] [--webhooks <file>]";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("invalid bind address `{0}`")]
+    InvalidBindAddr(String),
+    #[error("entity registry lists legal entity `{0}` more than once")]
+    DuplicateLegalEntity(String),
+    #[error("entity registry has no legal entities")]
+    EmptyEntityRegistry,
+    #[error("legal entity `{legal_entity_id}` lists ledger book `{ledger_book}` more than once")]
+    DuplicateLedgerBook {
+        legal_entity_id: String,
+        ledger_book: String,
+    },
+    #[error("legal entity `{legal_entity_id}` has an invalid fiscal calendar: {source}")]
+    InvalidFiscalCalendar {
+        legal_entity_id: String,
+        source: CalendarError,
+    },
+    #[error("webhook source `{source_system}` is invalid: {reason}")]
+    InvalidWebhookSource {
+        source_system: String,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    pub bind_addr: SocketAddr,
+    pub persistence_dir: Option<PathBuf>,
+    pub entity_registry_path: Option<PathBuf>,
+    pub policy_path: Option<PathBuf>,
+    pub rule_set_dir: Option<PathBuf>,
+    pub fx_rate_set_dir: Option<PathBuf>,
+    pub webhook_config_path: Option<PathBuf>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: DEFAULT_BIND_ADDR
+                .parse()
+                .expect("default bind addr is valid"),
+            persistence_dir: None,
+            entity_registry_path: None,
+            policy_path: None,
+            rule_set_dir: None,
+            fx_rate_set_dir: None,
+            webhook_config_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServerConfigFile {
+    bind_addr: Option<String>,
+    persistence_dir: Option<PathBuf>,
+    entity_registry_path: Option<PathBuf>,
+    policy_path: Option<PathBuf>,
+    rule_set_dir: Option<PathBuf>,
+    fx_rate_set_dir: Option<PathBuf>,
+    webhook_config_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+struct CliArgs {
+    config: Option<PathBuf>,
+    bind_addr: Option<String>,
+    persistence_dir: Option<PathBuf>,
+    entity_registry_path: Option<PathBuf>,
+    policy_path: Option<PathBuf>,
+    rule_set_dir: Option<PathBuf>,
+    fx_rate_set_dir: Option<PathBuf>,
+    webhook_config_path: Option<PathBuf>,
+}
+
+impl ServerConfig {
+    pub fn from_args<I>(args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let cli = parse_cli_args(args)?;
+        let mut config = Self::default();
+
+        if let Some(path) = cli.config.as_deref() {
+            let file: ServerConfigFile = read_json(path)?;
+            let base = path.parent().unwrap_or_else(|| Path::new(""));
+            if let Some(bind_addr) = file.bind_addr {
+                config.bind_addr = parse_bind_addr(&bind_addr)?;
+            }
+            config.persistence_dir = file.persistence_dir.map(|p| base.join(p));
+            config.entity_registry_path = file.entity_registry_path.map(|p| base.join(p));
+            config.policy_path = file.policy_path.map(|p| base.join(p));
+            config.rule_set_dir = file.rule_set_dir.map(|p| base.join(p));
+            config.fx_rate_set_dir = file.fx_rate_set_dir.map(|p| base.join(p));
+            config.webhook_config_path = file.webhook_config_path.map(|p| base.join(p));
+        }
+
+        if let Some(bind_addr) = cli.bind_addr {
+            config.bind_addr = parse_bind_addr(&bind_addr)?;
+        }
+        if cli.persistence_dir.is_some() {
+            config.persistence_dir = cli.persistence_dir;
+        }
+        if cli.entity_registry_path.is_some() {
+            config.entity_registry_path = cli.entity_registry_path;
+        }
+        if cli.policy_path.is_some() {
+            config.policy_path = cli.policy_path;
+        }
+        if cli.rule_set_dir.is_some() {
+            config.rule_set_dir = cli.rule_set_dir;
+        }
+        if cli.fx_rate_set_dir.is_some() {
+            config.fx_rate_set_dir = cli.fx_rate_set_dir;
+        }
+        if cli.webhook_config_path.is_some() {
+            config.webhook_config_path = cli.webhook_config_path;
+        }
+        Ok(config)
+    }
+}
+
+fn parse_cli_args<I>(args: I) -> Result<CliArgs, ConfigError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut cli = CliArgs::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.into())),
+            _ => (arg, None),
+        };
+        if flag == "--help" || flag == "-h" {
+            return Err(ConfigError::Usage(USAGE.to_string()));
+        }
+        if !matches!(
+            flag.as_str(),
+            "--config"
+                | "--bind"
+                | "--persistence-dir"
+                | "--entity-registry"
+                | "--policy"
+                | "--rule-sets"
+                | "--fx-rate-sets"
+                | "--webhooks"
+        ) {
+            return Err(ConfigError::Usage(format!(
+                "unknown argument `{flag}`\n{USAGE}"
+            )));
+        }
+        let Some(value) = inline_value.or_else(|| args.next()) else {
+            return Err(ConfigError::Usage(format!("{flag} requires a value")));
+        };
+        match flag.as_str() {
+            "--config" => cli.config = Some(value.into()),
+            "--bind" => cli.bind_addr = Some(value),
+            "--persistence-dir" => cli.persistence_dir = Some(value.into()),
+            "--entity-registry" => cli.entity_registry_path = Some(value.into()),
+            "--policy" => cli.policy_path = Some(value.into()),
+            "--rule-sets" => cli.rule_set_dir = Some(value.into()),
+            "--fx-rate-sets" => cli.fx_rate_set_dir = Some(value.into()),
+            _ => cli.webhook_config_path = Some(value.into()),
+        }
+    }
+    Ok(cli)
+}
+
+fn parse_bind_addr(value: &str) -> Result<SocketAddr, ConfigError> {
+    value
+        .parse()
+        .map_err(|_| ConfigError::InvalidBindAddr(value.to_string()))
+}
+
+fn read_json<T>(path: &Path) -> Result<T, ConfigError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let encoded = fs::read(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_slice(&encoded).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntityRegistry {
+    pub legal_entities: Vec<LegalEntityEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LegalEntityEntry {
+    pub legal_entity_id: String,
+    pub locations: Vec<String>,
+    #[serde(default)]
+    pub books: Vec<BookRequirement>,
+    #[serde(default)]
+    pub base_currency: Option<String>,
+    #[serde(default)]
+    pub fiscal_calendar: Option<FiscalCalendar>,
+    /// Accounts the entity may post to. Entities without one are not checked.
+    #[serde(default)]
+    pub chart_of_accounts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BookRequirement {
+    pub ledger_book: String,
+    pub book_policy_id: String,
+    pub policy_version: String,
+}
+
+impl EntityRegistry {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        read_json(path)
+    }
+
+    pub fn book_requirements(&self) -> Result<HashMap<String, Vec<BookRequirement>>, ConfigError> {
+        let mut requirements = HashMap::new();
+        for entity in &self.legal_entities {
+            let mut ledger_books = HashSet::new();
+            for book in &entity.books {
+                if !ledger_books.insert(book.ledger_book.as_str()) {
+                    return Err(ConfigError::DuplicateLedgerBook {
+                        legal_entity_id: entity.legal_entity_id.clone(),
+                        ledger_book: book.ledger_book.clone(),
+                    });
+                }
+            }
+            if !entity.books.is_empty() {
+                requirements.insert(entity.legal_entity_id.clone(), entity.books.clone());
+            }
+        }
+        Ok(requirements)
+    }
+
+    pub fn base_currencies(&self) -> HashMap<String, String> {
+        self.legal_entities
+            .iter()
+            .filter_map(|entity| {
+                let base_currency = entity.base_currency.clone()?;
+                Some((entity.legal_entity_id.clone(), base_currency))
+            })
+            .collect()
+    }
+
+    pub fn charts_of_accounts(&self) -> HashMap<String, HashSet<String>> {
+        self.legal_entities
+            .iter()
+            .filter_map(|entity| {
+                let accounts = entity.chart_of_accounts.clone()?;
+                Some((
+                    entity.legal_entity_id.clone(),
+                    accounts.into_iter().collect(),
+                ))
+            })
+            .collect()
+    }
+
+    pub fn fiscal_calendars(&self) -> Result<HashMap<String, FiscalCalendar>, ConfigError> {
+        let mut calendars = HashMap::new();
+        for entity in &self.legal_entities {
+            let Some(calendar) = entity.fiscal_calendar else {
+                continue;
+            };
+            calendar
+                .validate()
+                .map_err(|source| ConfigError::InvalidFiscalCalendar {
+                    legal_entity_id: entity.legal_entity_id.clone(),
+                    source,
+                })?;
+            calendars.insert(entity.legal_entity_id.clone(), calendar);
+        }
+        Ok(calendars)
+    }
+
+    pub fn into_location_allowlist(self) -> Result<HashMap<String, HashSet<String>>, ConfigError> {
+        if self.legal_entities.is_empty() {
+            return Err(ConfigError::EmptyEntityRegistry);
+        }
+        let mut allowlist = HashMap::new();
+        for entity in self.legal_entities {
+            let locations = entity.locations.into_iter().collect::<HashSet<_>>();
+            if allowlist
+                .insert(entity.legal_entity_id.clone(), locations)
+                .is_some()
+            {
+                return Err(ConfigError::DuplicateLegalEntity(entity.legal_entity_id));
+            }
+        }
+        Ok(allowlist)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostingPolicySet {
+    pub book_policies: Vec<BookPolicy>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BookPolicy {
+    pub book_policy_id: String,
+    pub policy_versions: Vec<String>,
+    pub ledger_books: Vec<String>,
+}
+
+impl PostingPolicySet {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        read_json(path)
+    }
+
+    pub fn allows(&self, book_policy_id: &str, policy_version: &str, ledger_book: &str) -> bool {
+        self.book_policies.iter().any(|policy| {
+            policy.book_policy_id == book_policy_id
+                && policy.policy_versions.iter().any(|v| v == policy_version)
+                && policy.ledger_books.iter().any(|b| b == ledger_book)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub sources: Vec<WebhookSourceConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSourceConfig {
+    pub source: String,
+    pub secrets: Vec<WebhookSecretConfig>,
+    /// Square signs the notification URL along with the body.
+    #[serde(default)]
+    pub notification_url: Option<String>,
+    #[serde(default)]
+    pub tolerance_secs: Option<i64>,
+    pub provenance: Provenance,
+    /// Empty posts to every book the legal entity requires.
+    #[serde(default)]
+    pub ledger_book: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSecretConfig {
+    pub secret_id: String,
+    pub secret: String,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookConfig {
+    pub const SOURCES: [&'static str; 4] = ["stripe", "square", "inntopia", "adyen"];
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let config: Self = read_json(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut seen = HashSet::new();
+        for source in &self.sources {
+            let invalid = |reason: &str| ConfigError::InvalidWebhookSource {
+                source_system: source.source.clone(),
+                reason: reason.to_string(),
+            };
+            if !Self::SOURCES.contains(&source.source.as_str()) {
+                return Err(invalid("no connector adapter for this source"));
+            }
+            if !seen.insert(source.source.as_str()) {
+                return Err(invalid("configured more than once"));
+            }
+            if source.secrets.is_empty() {
+                return Err(invalid("at least one signing secret is required"));
+            }
+            if source.source == "square" && source.notification_url.is_none() {
+                return Err(invalid("square requires a notification_url"));
+            }
+            if source.source == "adyen"
+                && source
+                    .secrets
+                    .iter()
+                    .any(|secret| hex::decode(&secret.secret).is_err())
+            {
+                return Err(invalid("adyen secrets must be hex-encoded HMAC keys"));
+            }
+            if source.tolerance_secs.is_some_and(|secs| secs <= 0) {
+                return Err(invalid("tolerance_secs must be positive"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be monotonic from epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "posting-api-{prefix}-{}-{nanos}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&path).expect("test temp dir should be creatable");
+        path
+    }
+
+    #[test]
+    fn defaults_without_arguments() {
+        let config = ServerConfig::from_args(Vec::new()).unwrap();
+        assert_eq!(config, ServerConfig::default());
+        assert_eq!(config.bind_addr.to_string(), DEFAULT_BIND_ADDR);
+    }
+
+    #[test]
+    fn cli_arguments_override_config_file() {
+        let dir = temp_dir("config");
+        let config_path = dir.join("posting-api.json");
+        fs::write(
+            &config_path,
+            r#"{
+                "bind_addr": "127.0.0.1:4000",
+                "persistence_dir": "data",
+                "entity_registry_path": "entities.json",
+                "policy_path": "policies.json",
+                "rule_set_dir": "rulesets",
+                "fx_rate_set_dir": "fx",
+                "webhook_config_path": "webhooks.json"
+            }"#,
+        )
+        .unwrap();
+
+        let config = ServerConfig::from_args(args(&[
+            "--config",
+            config_path.to_str().unwrap(),
+            "--bind=127.0.0.1:5000",
+            "--policy",
+            "/etc/posting/policies.json",
+            "--webhooks=/etc/posting/webhooks.json",
+        ]))
+        .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(config.bind_addr.to_string(), "127.0.0.1:5000");
+        assert_eq!(config.persistence_dir, Some(dir.join("data")));
+        assert_eq!(config.entity_registry_path, Some(dir.join("entities.json")));
+        assert_eq!(
+            config.policy_path,
+            Some(PathBuf::from("/etc/posting/policies.json"))
+        );
+        assert_eq!(config.rule_set_dir, Some(dir.join("rulesets")));
+        assert_eq!(config.fx_rate_set_dir, Some(dir.join("fx")));
+        assert_eq!(
+            config.webhook_config_path,
+            Some(PathBuf::from("/etc/posting/webhooks.json"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_arguments_and_bad_bind_addr() {
+        assert!(matches!(
+            ServerConfig::from_args(args(&["--port", "3000"])),
+            Err(ConfigError::Usage(_))
+        ));
+        assert!(matches!(
+            ServerConfig::from_args(args(&["--bind"])),
+            Err(ConfigError::Usage(_))
+        ));
+        assert!(matches!(
+            ServerConfig::from_args(args(&["--bind", "not-an-addr"])),
+            Err(ConfigError::InvalidBindAddr(_))
+        ));
+    }
+
+    #[test]
+    fn webhook_config_rejects_unknown_duplicate_and_unsigned_sources() {
+        let config = |sources: serde_json::Value| -> WebhookConfig {
+            serde_json::from_value(serde_json::json!({ "sources": sources })).unwrap()
+        };
+        let provenance = serde_json::json!({
+            "book_policy_id": "policy_dual_book",
+            "policy_version": "1.0.0",
+            "fx_rate_set_id": "fx_2026_02_21",
+            "ruleset_version": "v1",
+            "workflow_id": null
+        });
+        let source = |name: &str, secrets: serde_json::Value| serde_json::json!({ "source": name, "secrets": secrets, "provenance": provenance });
+        let secret = serde_json::json!([{ "secret_id": "whsec_1", "secret": "key" }]);
+
+        assert!(
+            config(serde_json::json!([source("stripe", secret.clone())]))
+                .validate()
+                .is_ok()
+        );
+        let hex_key = serde_json::json!([{ "secret_id": "ady_1", "secret": "44782DEF" }]);
+        assert!(config(serde_json::json!([source("adyen", hex_key)]))
+            .validate()
+            .is_ok());
+        for (sources, expected) in [
+            (
+                serde_json::json!([source("paypal", secret.clone())]),
+                "paypal",
+            ),
+            (
+                serde_json::json!([
+                    source("stripe", secret.clone()),
+                    source("stripe", secret.clone())
+                ]),
+                "stripe",
+            ),
+            (
+                serde_json::json!([source("inntopia", serde_json::json!([]))]),
+                "inntopia",
+            ),
+            (
+                serde_json::json!([source("square", secret.clone())]),
+                "square",
+            ),
+            (
+                serde_json::json!([source("adyen", secret.clone())]),
+                "adyen",
+            ),
+        ] {
+            assert!(matches!(
+                config(sources).validate(),
+                Err(ConfigError::InvalidWebhookSource { source_system, .. }) if source_system == expected
+            ));
+        }
+    }
+
+    #[test]
+    fn entity_registry_rejects_duplicate_legal_entities() {
+        let registry = EntityRegistry {
+            legal_entities: vec![
+                LegalEntityEntry {
+                    legal_entity_id: "US_CO_01".to_string(),
+                    locations: vec!["BRECK_BASE_AREA".to_string()],
+                    books: Vec::new(),
+                    base_currency: None,
+                    fiscal_calendar: None,
+                    chart_of_accounts: None,
+                },
+                LegalEntityEntry {
+                    legal_entity_id: "US_CO_01".to_string(),
+                    locations: vec!["VAIL_BASE_LODGE".to_string()],
+                    books: Vec::new(),
+                    base_currency: None,
+                    fiscal_calendar: None,
+                    chart_of_accounts: None,
+                },
+            ],
+        };
+        assert!(matches!(
+            registry.into_location_allowlist(),
+            Err(ConfigError::DuplicateLegalEntity(id)) if id == "US_CO_01"
+        ));
+    }
+
+    #[test]
+    fn entity_registry_reads_and_validates_fiscal_calendars() {
+        let registry: EntityRegistry = serde_json::from_str(
+            r#"{"legal_entities": [
+                {"legal_entity_id": "US_CO_01", "locations": ["BRECK_BASE_AREA"],
+                 "chart_of_accounts": ["1105-CASH-CLEARING", "4000-REVENUE"],
+                 "fiscal_calendar": {"kind": "WEEKS", "pattern": "FOUR_FOUR_FIVE",
+                  "year_end_month": 1, "year_end_weekday": "Sat", "year_end": "LAST_WEEKDAY"}},
+                {"legal_entity_id": "CA_BC_01", "locations": ["WHISTLER_VILLAGE"]}
+            ]}"#,
+        )
+        .unwrap();
+        let charts = registry.charts_of_accounts();
+        assert_eq!(charts.len(), 1);
+        assert!(charts["US_CO_01"].contains("4000-REVENUE"));
+        let calendars = registry.fiscal_calendars().unwrap();
+        assert_eq!(calendars.len(), 1);
+        assert!(matches!(
+            calendars["US_CO_01"],
+            FiscalCalendar::Weeks {
+                year_end_month: 1,
+                ..
+            }
+        ));
+
+        let mut invalid = registry;
+        invalid.legal_entities[1].fiscal_calendar =
+            Some(FiscalCalendar::FiscalMonth { start_month: 13 });
+        assert!(matches!(
+            invalid.fiscal_calendars(),
+            Err(ConfigError::InvalidFiscalCalendar { legal_entity_id, .. })
+                if legal_entity_id == "CA_BC_01"
+        ));
+    }
+
+    #[test]
+    fn entity_registry_rejects_duplicate_ledger_books() {
+        let book = BookRequirement {
+            ledger_book: "IFRS".to_string(),
+            book_policy_id: "policy_dual_book".to_string(),
+            policy_version: "1.0.0".to_string(),
+        };
+        let registry = EntityRegistry {
+            legal_entities: vec![LegalEntityEntry {
+                legal_entity_id: "CA_BC_01".to_string(),
+                locations: vec!["WHISTLER_VILLAGE".to_string()],
+                books: vec![book.clone(), book],
+                base_currency: None,
+                fiscal_calendar: None,
+                chart_of_accounts: None,
+            }],
+        };
+        assert!(matches!(
+            registry.book_requirements(),
+            Err(ConfigError::DuplicateLedgerBook { legal_entity_id, ledger_book })
+                if legal_entity_id == "CA_BC_01" && ledger_book == "IFRS"
+        ));
+    }
+}
@@ -0,0 +1,769 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use connector_sdk::{ConnectorError, RawEvent};
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::bulk::BulkPostOutcome;
+use crate::error::{ApiError, ApiJson, ApiQuery, CorrelationId, ErrorCode, ErrorEnvelope};
+use crate::ingest::{normalization_error_response, normalize_and_post, webhook_source};
+use crate::period::PeriodActor;
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::AppState;
+
+const DEAD_LETTER_STORE_FILENAME: &str = "dead_letter_store.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeadLetterStatus {
+    Pending,
+    Replayed,
+    Discarded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeadLetterActionKind {
+    Retried,
+    Discarded,
+}
+
+/// An operator action on a dead letter, sealed into the audit chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetterAction {
+    pub action: DeadLetterActionKind,
+    pub actor: PeriodActor,
+    pub at: DateTime<Utc>,
+    /// The retry normalized an edited payload instead of the stored one.
+    #[serde(default)]
+    pub payload_edited: bool,
+    /// Hash of the edited payload. The stored raw event keeps the payload the source sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_payload_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<BulkPostOutcome>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_event_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub audit_seal: String,
+}
+
+/// A verified webhook whose body the source's adapter could not normalize.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetter {
+    pub dead_letter_id: String,
+    pub source: String,
+    #[schema(value_type = Object)]
+    pub raw_event: RawEvent,
+    pub error: String,
+    /// Failed normalizations, counting redeliveries and retries.
+    pub attempts: u32,
+    pub status: DeadLetterStatus,
+    pub adapter_version: String,
+    pub first_failed_at: DateTime<Utc>,
+    pub last_failed_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub history: Vec<DeadLetterAction>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DeadLetterError {
+    #[error("dead letter `{0}` not found")]
+    NotFound(String),
+    #[error("dead letter `{dead_letter_id}` is {status:?}, not pending")]
+    NotPending {
+        dead_letter_id: String,
+        status: DeadLetterStatus,
+    },
+    #[error("edited payload must be a JSON object")]
+    InvalidPayload,
+}
+
+#[derive(Default)]
+pub struct InMemoryDeadLetterRepository {
+    letters: BTreeMap<String, DeadLetter>,
+    persistence: Option<Arc<WriteBehind<Vec<DeadLetter>>>>,
+}
+
+impl InMemoryDeadLetterRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(DEAD_LETTER_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: Vec<DeadLetter> = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "dead-letter-write-behind")?);
+        Ok(Self {
+            letters: loaded
+                .into_iter()
+                .map(|letter| (letter.dead_letter_id.clone(), letter))
+                .collect(),
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self, dead_letter_id: &str) -> Option<&DeadLetter> {
+        self.letters.get(dead_letter_id)
+    }
+
+    pub fn pending(&self, dead_letter_id: &str) -> Result<&DeadLetter, DeadLetterError> {
+        let letter = self
+            .get(dead_letter_id)
+            .ok_or_else(|| DeadLetterError::NotFound(dead_letter_id.to_string()))?;
+        if letter.status != DeadLetterStatus::Pending {
+            return Err(DeadLetterError::NotPending {
+                dead_letter_id: dead_letter_id.to_string(),
+                status: letter.status,
+            });
+        }
+        Ok(letter)
+    }
+
+    pub fn list(&self, source: Option<&str>, status: Option<DeadLetterStatus>) -> Vec<DeadLetter> {
+        self.letters
+            .values()
+            .filter(|letter| source.is_none_or(|source| letter.source == source))
+            .filter(|letter| status.is_none_or(|status| letter.status == status))
+            .cloned()
+            .collect()
+    }
+
+    /// Redeliveries of the same source event share one dead letter. A pending letter takes the
+    /// latest body; a resolved one only counts the attempt.
+    pub fn record_failure(
+        &mut self,
+        source: &str,
+        adapter_version: &str,
+        raw_event: RawEvent,
+        error: String,
+        failed_at: DateTime<Utc>,
+    ) -> String {
+        let dead_letter_id = dead_letter_id(source, &raw_event.source_event_id);
+        let letter = self
+            .letters
+            .entry(dead_letter_id.clone())
+            .or_insert_with(|| DeadLetter {
+                dead_letter_id: dead_letter_id.clone(),
+                source: source.to_string(),
+                raw_event: raw_event.clone(),
+                error: error.clone(),
+                attempts: 0,
+                status: DeadLetterStatus::Pending,
+                adapter_version: adapter_version.to_string(),
+                first_failed_at: failed_at,
+                last_failed_at: failed_at,
+                resolved_at: None,
+                history: Vec::new(),
+            });
+        letter.attempts += 1;
+        letter.last_failed_at = failed_at;
+        if letter.status == DeadLetterStatus::Pending {
+            letter.raw_event = raw_event;
+            letter.error = error;
+            letter.adapter_version = adapter_version.to_string();
+        }
+        self.persist();
+        dead_letter_id
+    }
+
+    pub fn store(&mut self, letter: DeadLetter) {
+        self.letters.insert(letter.dead_letter_id.clone(), letter);
+        self.persist();
+    }
+
+    fn persist(&self) {
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(self.letters.values().cloned().collect());
+        }
+    }
+}
+
+fn dead_letter_id(source: &str, source_event_id: &str) -> String {
+    let digest = payload_hash(&json!({
+        "source": source,
+        "source_event_id": source_event_id,
+    }));
+    format!("dl_{}", digest[..24].to_ascii_lowercase())
+}
+
+pub(crate) fn record_dead_letter(
+    state: &AppState,
+    source: &str,
+    adapter_version: &str,
+    raw_event: RawEvent,
+    error: &ConnectorError,
+) -> Result<String, ApiError> {
+    Ok(state
+        .dead_letters
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?
+        .record_failure(
+            source,
+            adapter_version,
+            raw_event,
+            error.to_string(),
+            Utc::now(),
+        ))
+}
+
+fn dead_letter_error_response(error: DeadLetterError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        DeadLetterError::NotFound(dead_letter_id) => {
+            ApiError::not_found(ErrorCode::DeadLetterNotFound)
+                .with_detail("dead_letter_id", dead_letter_id)
+        }
+        DeadLetterError::NotPending {
+            dead_letter_id,
+            status,
+        } => ApiError::conflict(ErrorCode::DeadLetterNotPending)
+            .with_detail("dead_letter_id", dead_letter_id)
+            .with_detail("status", json!(status)),
+        DeadLetterError::InvalidPayload => {
+            ApiError::bad_request(ErrorCode::InvalidDeadLetterPayload)
+        }
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeadLetterQuery {
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    #[param(value_type = Option<DeadLetterStatus>)]
+    pub status: Option<DeadLetterStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeadLetterList {
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RetryDeadLetterRequest {
+    pub actor: PeriodActor,
+    /// Normalized instead of the stored raw payload, which is kept as the source sent it.
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub payload: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct DiscardDeadLetterRequest {
+    pub actor: PeriodActor,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReplayDeadLettersRequest {
+    pub actor: PeriodActor,
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Empty replays every pending dead letter, optionally limited to `source`.
+    #[serde(default)]
+    pub dead_letter_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeadLetterReplayResult {
+    pub dead_letter_id: String,
+    pub status: Option<DeadLetterStatus>,
+    pub attempts: Option<u32>,
+    pub outcome: Option<BulkPostOutcome>,
+    pub canonical_event_id: Option<String>,
+    pub journal_id: Option<String>,
+    pub error: Option<ErrorEnvelope>,
+    pub audit_seal: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayDeadLettersResponse {
+    pub results: Vec<DeadLetterReplayResult>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ingest/dead-letters",
+    tag = "ingest",
+    params(DeadLetterQuery),
+    responses(
+        (status = 200, description = "Dead letters, oldest id first", body = DeadLetterList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_dead_letters(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<DeadLetterQuery>,
+) -> Result<Json<DeadLetterList>, ApiError> {
+    let dead_letters = state
+        .dead_letters
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?
+        .list(query.source.as_deref(), query.status);
+    Ok(Json(DeadLetterList { dead_letters }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ingest/dead-letters/{dead_letter_id}",
+    tag = "ingest",
+    params(("dead_letter_id" = String, Path, description = "Dead letter to inspect")),
+    responses(
+        (status = 200, description = "Dead letter with its raw event and action history", body = DeadLetter),
+        (status = 404, description = "Dead letter not found", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_dead_letter(
+    State(state): State<AppState>,
+    Path(dead_letter_id): Path<String>,
+) -> Result<Json<DeadLetter>, ApiError> {
+    state
+        .dead_letters
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?
+        .get(&dead_letter_id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| dead_letter_error_response(DeadLetterError::NotFound(dead_letter_id)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ingest/dead-letters/{dead_letter_id}/retry",
+    tag = "ingest",
+    params(("dead_letter_id" = String, Path, description = "Pending dead letter to retry")),
+    request_body = RetryDeadLetterRequest,
+    responses(
+        (status = 200, description = "Retry recorded; a failed normalization leaves the letter pending", body = DeadLetterReplayResult),
+        (status = 400, description = "Edited payload is not a JSON object", body = ErrorEnvelope),
+        (status = 404, description = "Dead letter or webhook source not found", body = ErrorEnvelope),
+        (status = 409, description = "Dead letter already replayed or discarded", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn retry_dead_letter(
+    State(state): State<AppState>,
+    Path(dead_letter_id): Path<String>,
+    CorrelationId(correlation_id): CorrelationId,
+    ApiJson(req): ApiJson<RetryDeadLetterRequest>,
+) -> Result<Json<DeadLetterReplayResult>, ApiError> {
+    replay_dead_letter(
+        &state,
+        &dead_letter_id,
+        &req.actor,
+        req.payload,
+        &correlation_id,
+    )
+    .await
+    .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ingest/dead-letters/replay",
+    tag = "ingest",
+    request_body = ReplayDeadLettersRequest,
+    responses(
+        (status = 200, description = "One result per dead letter, in request or id order", body = ReplayDeadLettersResponse),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn replay_dead_letters(
+    State(state): State<AppState>,
+    CorrelationId(correlation_id): CorrelationId,
+    ApiJson(req): ApiJson<ReplayDeadLettersRequest>,
+) -> Result<Json<ReplayDeadLettersResponse>, ApiError> {
+    let dead_letter_ids = if req.dead_letter_ids.is_empty() {
+        state
+            .dead_letters
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?
+            .list(req.source.as_deref(), Some(DeadLetterStatus::Pending))
+            .into_iter()
+            .map(|letter| letter.dead_letter_id)
+            .collect()
+    } else {
+        req.dead_letter_ids
+    };
+
+    let mut results = Vec::with_capacity(dead_letter_ids.len());
+    for dead_letter_id in dead_letter_ids {
+        let result =
+            match replay_dead_letter(&state, &dead_letter_id, &req.actor, None, &correlation_id)
+                .await
+            {
+                Ok(result) => result,
+                Err(error) => DeadLetterReplayResult {
+                    dead_letter_id,
+                    status: None,
+                    attempts: None,
+                    outcome: None,
+                    canonical_event_id: None,
+                    journal_id: None,
+                    error: Some(error.envelope(&correlation_id)),
+                    audit_seal: None,
+                },
+            };
+        results.push(result);
+    }
+    Ok(Json(ReplayDeadLettersResponse { results }))
+}
+
+/// Normalizes the stored raw event with the source's current adapter and posts it.
+async fn replay_dead_letter(
+    state: &AppState,
+    dead_letter_id: &str,
+    actor: &PeriodActor,
+    payload: Option<Value>,
+    correlation_id: &str,
+) -> Result<DeadLetterReplayResult, ApiError> {
+    let letter = state
+        .dead_letters
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?
+        .pending(dead_letter_id)
+        .cloned()
+        .map_err(dead_letter_error_response)?;
+    let webhook = webhook_source(state, &letter.source)?;
+    let mut raw_event = letter.raw_event;
+    let mut edited_payload_hash = None;
+    if let Some(payload) = payload {
+        if !payload.is_object() {
+            return Err(dead_letter_error_response(DeadLetterError::InvalidPayload));
+        }
+        edited_payload_hash = Some(payload_hash(&payload));
+        raw_event.payload = payload;
+    }
+    let payload_edited = edited_payload_hash.is_some();
+    let adapter_version = webhook.adapter.adapter_version();
+    let replayed =
+        normalize_and_post(state, &letter.source, &webhook, raw_event, correlation_id).await;
+
+    let mut letters = state
+        .dead_letters
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?;
+    let mut letter = letters
+        .pending(dead_letter_id)
+        .cloned()
+        .map_err(dead_letter_error_response)?;
+    let now = Utc::now();
+    letter.adapter_version = adapter_version.to_string();
+    let mut entity_scope = Vec::new();
+    let mut action = DeadLetterAction {
+        action: DeadLetterActionKind::Retried,
+        actor: actor.clone(),
+        at: now,
+        payload_edited,
+        edited_payload_hash,
+        adapter_version: Some(adapter_version.to_string()),
+        outcome: None,
+        canonical_event_id: None,
+        journal_id: None,
+        error: None,
+        reason: None,
+        audit_seal: String::new(),
+    };
+    let mut result = DeadLetterReplayResult {
+        dead_letter_id: dead_letter_id.to_string(),
+        status: None,
+        attempts: None,
+        outcome: None,
+        canonical_event_id: None,
+        journal_id: None,
+        error: None,
+        audit_seal: None,
+    };
+    match replayed {
+        Ok(response) => {
+            // A rejected posting, such as one into a closed period, leaves the letter pending.
+            if response.outcome == BulkPostOutcome::Rejected {
+                letter.attempts += 1;
+                letter.last_failed_at = now;
+                if let Some(error) = &response.error {
+                    letter.error = error.message.clone();
+                }
+            } else {
+                letter.status = DeadLetterStatus::Replayed;
+                letter.resolved_at = Some(now);
+            }
+            entity_scope.extend(response.legal_entity_id.clone());
+            action.outcome = Some(response.outcome);
+            action.canonical_event_id = response.canonical_event_id.clone();
+            action.journal_id = response.journal_id.clone();
+            action.error = response.error.as_ref().map(|error| error.message.clone());
+            result.outcome = Some(response.outcome);
+            result.canonical_event_id = response.canonical_event_id;
+            result.journal_id = response.journal_id;
+            result.error = response.error;
+        }
+        Err(error) => {
+            letter.attempts += 1;
+            letter.last_failed_at = now;
+            letter.error = error.to_string();
+            action.error = Some(letter.error.clone());
+            result.error = Some(normalization_error_response(error).envelope(correlation_id));
+        }
+    }
+
+    action.audit_seal = state.append_audit_seal(
+        "ingest.dead_letter_retried",
+        &entity_scope,
+        &json!({
+            "dead_letter_id": dead_letter_id,
+            "source": &letter.source,
+            "source_event_id": &letter.raw_event.source_event_id,
+            "actor": actor,
+            "payload_edited": payload_edited,
+            "edited_payload_hash": &action.edited_payload_hash,
+            "adapter_version": adapter_version,
+            "status": letter.status,
+            "canonical_event_id": &action.canonical_event_id,
+            "journal_id": &action.journal_id,
+        }),
+        now.timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+    result.status = Some(letter.status);
+    result.attempts = Some(letter.attempts);
+    result.audit_seal = Some(action.audit_seal.clone());
+    letter.history.push(action);
+    letters.store(letter);
+    Ok(result)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ingest/dead-letters/{dead_letter_id}/discard",
+    tag = "ingest",
+    params(("dead_letter_id" = String, Path, description = "Pending dead letter to discard")),
+    request_body = DiscardDeadLetterRequest,
+    responses(
+        (status = 200, description = "Dead letter discarded", body = DeadLetter),
+        (status = 404, description = "Dead letter not found", body = ErrorEnvelope),
+        (status = 409, description = "Dead letter already replayed or discarded", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn discard_dead_letter(
+    State(state): State<AppState>,
+    Path(dead_letter_id): Path<String>,
+    ApiJson(req): ApiJson<DiscardDeadLetterRequest>,
+) -> Result<Json<DeadLetter>, ApiError> {
+    let mut letters = state
+        .dead_letters
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::DeadLetterStoreError))?;
+    let mut letter = letters
+        .pending(&dead_letter_id)
+        .cloned()
+        .map_err(dead_letter_error_response)?;
+    let now = Utc::now();
+    let audit_seal = state.append_audit_seal(
+        "ingest.dead_letter_discarded",
+        &[],
+        &json!({
+            "dead_letter_id": &dead_letter_id,
+            "source": &letter.source,
+            "source_event_id": &letter.raw_event.source_event_id,
+            "actor": &req.actor,
+            "reason": &req.reason,
+        }),
+        now.timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+    letter.status = DeadLetterStatus::Discarded;
+    letter.resolved_at = Some(now);
+    letter.history.push(DeadLetterAction {
+        action: DeadLetterActionKind::Discarded,
+        actor: req.actor,
+        at: now,
+        payload_edited: false,
+        edited_payload_hash: None,
+        adapter_version: None,
+        outcome: None,
+        canonical_event_id: None,
+        journal_id: None,
+        error: None,
+        reason: Some(req.reason),
+        audit_seal,
+    });
+    letters.store(letter.clone());
+    Ok(Json(letter))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use super::*;
+
+    fn raw(source_event_id: &str, payload: Value) -> RawEvent {
+        RawEvent {
+            source_event_id: source_event_id.to_string(),
+            occurred_at: DateTime::from_timestamp(1_771_632_000, 0).unwrap(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn redeliveries_share_a_dead_letter_until_it_is_resolved() {
+        let mut repo = InMemoryDeadLetterRepository::default();
+        let at = Utc::now();
+        let first = repo.record_failure(
+            "square",
+            "0.1.0",
+            raw("sq_1", json!({"v": 1})),
+            "missing tenant_id".to_string(),
+            at,
+        );
+        let second = repo.record_failure(
+            "square",
+            "0.1.0",
+            raw("sq_1", json!({"v": 2})),
+            "missing legal_entity_id".to_string(),
+            at,
+        );
+        assert_eq!(first, second);
+        assert_ne!(
+            first,
+            dead_letter_id("stripe", "sq_1"),
+            "ids are scoped to the source"
+        );
+        let letter = repo.pending(&first).unwrap();
+        assert_eq!(letter.attempts, 2);
+        assert_eq!(letter.raw_event.payload, json!({"v": 2}));
+        assert_eq!(letter.error, "missing legal_entity_id");
+
+        let mut resolved = letter.clone();
+        resolved.status = DeadLetterStatus::Discarded;
+        repo.store(resolved);
+        repo.record_failure(
+            "square",
+            "0.1.0",
+            raw("sq_1", json!({"v": 3})),
+            "missing tenant_id".to_string(),
+            at,
+        );
+        let letter = repo.get(&first).unwrap();
+        assert_eq!(letter.attempts, 3);
+        assert_eq!(letter.status, DeadLetterStatus::Discarded);
+        assert_eq!(letter.raw_event.payload, json!({"v": 2}));
+        assert!(matches!(
+            repo.pending(&first),
+            Err(DeadLetterError::NotPending { .. })
+        ));
+    }
+
+    #[test]
+    fn attempts_keep_the_first_failure_time_and_lists_filter_by_source_and_status() {
+        let mut repo = InMemoryDeadLetterRepository::default();
+        let first_at = DateTime::from_timestamp(1_771_632_000, 0).unwrap();
+        let later_at = DateTime::from_timestamp(1_771_718_400, 0).unwrap();
+        let square = repo.record_failure(
+            "square",
+            "0.1.0",
+            raw("evt_1", json!({})),
+            "missing tenant_id".to_string(),
+            first_at,
+        );
+        repo.record_failure(
+            "square",
+            "0.2.0",
+            raw("evt_1", json!({})),
+            "missing tenant_id".to_string(),
+            later_at,
+        );
+        let stripe = repo.record_failure(
+            "stripe",
+            "0.1.0",
+            raw("evt_1", json!({})),
+            "unsupported event type".to_string(),
+            first_at,
+        );
+
+        let letter = repo.get(&square).unwrap();
+        assert_eq!(letter.first_failed_at, first_at);
+        assert_eq!(letter.last_failed_at, later_at);
+        assert_eq!(letter.adapter_version, "0.2.0");
+        assert_eq!(letter.resolved_at, None);
+        assert_ne!(square, stripe);
+        assert_ne!(dead_letter_id("square", ""), dead_letter_id("stripe", ""));
+
+        let mut discarded = repo.get(&stripe).unwrap().clone();
+        discarded.status = DeadLetterStatus::Discarded;
+        repo.store(discarded);
+        let ids = |letters: Vec<DeadLetter>| {
+            letters
+                .into_iter()
+                .map(|letter| letter.dead_letter_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(repo.list(None, None).len(), 2);
+        assert_eq!(ids(repo.list(Some("square"), None)), vec![square.clone()]);
+        assert_eq!(
+            ids(repo.list(None, Some(DeadLetterStatus::Pending))),
+            vec![square]
+        );
+        assert!(repo
+            .list(Some("square"), Some(DeadLetterStatus::Discarded))
+            .is_empty());
+        assert!(repo.list(Some("adyen"), None).is_empty());
+    }
+
+    #[test]
+    fn unknown_and_resolved_letters_map_to_client_errors() {
+        let repo = InMemoryDeadLetterRepository::default();
+        assert_eq!(
+            repo.pending("dl_missing"),
+            Err(DeadLetterError::NotFound("dl_missing".to_string()))
+        );
+
+        let not_found =
+            dead_letter_error_response(DeadLetterError::NotFound("dl_missing".to_string()));
+        assert_eq!(not_found.status, StatusCode::NOT_FOUND);
+        assert_eq!(not_found.details["dead_letter_id"], json!("dl_missing"));
+
+        let not_pending = dead_letter_error_response(DeadLetterError::NotPending {
+            dead_letter_id: "dl_1".to_string(),
+            status: DeadLetterStatus::Replayed,
+        });
+        assert_eq!(not_pending.status, StatusCode::CONFLICT);
+        assert_eq!(not_pending.details["status"], json!("REPLAYED"));
+
+        let invalid = dead_letter_error_response(DeadLetterError::InvalidPayload);
+        assert_eq!(invalid.status, StatusCode::BAD_REQUEST);
+        assert_eq!(invalid.code, ErrorCode::InvalidDeadLetterPayload);
+    }
+}
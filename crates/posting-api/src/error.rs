@@ -0,0 +1,488 @@
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+const MAX_CORRELATION_ID_LEN: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequestBody,
+    InvalidQuery,
+    RouteNotFound,
+    UnsupportedEventType,
+    UnknownRulesetVersion,
+    RulesetAccountNotNamed,
+    MissingIdempotencyKey,
+    InvalidPayload,
+    IdempotencyPayloadMismatch,
+    IdempotencyStoreError,
+    IdempotencyResultStoreError,
+    InvalidAccountingDate,
+    InvalidStartDate,
+    InvalidEndDate,
+    InvalidLegalHoldRange,
+    LegalHoldStoreError,
+    LegalHoldActive,
+    AuditSealStoreError,
+    AuditSealChainBroken,
+    AuditSealTampered,
+    InvalidJournalId,
+    MissingAdjustmentLines,
+    AdjustmentScopeMismatch,
+    JournalStoreError,
+    JournalNotFound,
+    JournalExists,
+    JournalUnbalanced,
+    JournalImmutable,
+    JournalAlreadyReversed,
+    JournalHasSubledgerEffects,
+    PeriodStoreError,
+    PeriodClosed,
+    InvalidPeriodId,
+    PeriodPostingNotAllowed,
+    InvalidPeriodTransition,
+    PeriodTransitionForbidden,
+    FiscalPeriodUnresolved,
+    CloseChecklistStoreError,
+    InvalidCloseChecklist,
+    CloseChecklistNotFound,
+    CloseChecklistClosed,
+    CloseDependencyNotFound,
+    CloseDependencyStillRequired,
+    InvalidCloseDependencyTransition,
+    CloseChecklistRequired,
+    CloseChecklistNotReady,
+    YearEndCloseStoreError,
+    InvalidFiscalYear,
+    InvalidRetainedEarningsAccount,
+    YearEndAlreadyClosed,
+    YearEndCloseNotFound,
+    YearEndCloseAlreadyReversed,
+    MissingReasonCode,
+    AffectedPeriodNotPrior,
+    MissingLocationId,
+    UnknownLegalEntityBoundary,
+    LocationNotAllowedForLegalEntity,
+    MissingCounterpartyLegalEntityId,
+    InvalidCounterpartyLegalEntity,
+    UnknownCounterpartyLegalEntity,
+    BookPolicyNotAllowed,
+    NoBookPolicyForLegalEntity,
+    MissingField,
+    InvalidNumber,
+    InvalidSettlementMath,
+    InvalidEntrySide,
+    UnknownTaxType,
+    RuleCheckFailed,
+    ComponentsUnreconciled,
+    InvalidStayDates,
+    RecognitionScheduleStoreError,
+    RecognitionScheduleNotFound,
+    RecognitionScheduleNotActive,
+    InvalidForfeitAmount,
+    PassStoreError,
+    PassNotFound,
+    PassAlreadyExists,
+    PassNotActive,
+    PassRecognizedRatably,
+    InvalidPassTerms,
+    VisitsExceedEntitlement,
+    PassStateChanged,
+    InvalidBreakageRate,
+    DuplicateBreakageAssumptionSet,
+    BreakageAssumptionsOutOfOrder,
+    StoredValueStoreError,
+    GiftCardNotFound,
+    GiftCardAlreadyExists,
+    GiftCardNotActive,
+    GiftCardCurrencyMismatch,
+    InsufficientStoredValue,
+    GiftCardStateChanged,
+    NoStoredValueMovement,
+    FxRateStoreError,
+    InvalidFxRateSet,
+    FxRateSetConflict,
+    FxRateSetNotFound,
+    FxRateNotFound,
+    BaseCurrencyNotConfigured,
+    AccountNotInChart,
+    SupplierTermsStoreError,
+    InvalidCommissionRate,
+    DuplicateSupplierTerms,
+    SupplierTermsOutOfOrder,
+    NoSupplierTermsInEffect,
+    CapacityReadinessUnavailable,
+    ChangeFeedStoreError,
+    InvalidNdjsonLine,
+    NdjsonLineTooLarge,
+    NdjsonBodyReadError,
+    WebhookSourceNotConfigured,
+    WebhookSignatureMissing,
+    WebhookSignatureInvalid,
+    WebhookSignatureExpired,
+    WebhookReplayed,
+    InvalidWebhookBody,
+    WebhookNormalizationFailed,
+    WebhookNormalizationUnavailable,
+    DeadLetterStoreError,
+    DeadLetterNotFound,
+    DeadLetterNotPending,
+    InvalidDeadLetterPayload,
+}
+
+impl ErrorCode {
+    pub fn default_message(self) -> &'static str {
+        match self {
+            Self::InvalidRequestBody => "request body could not be parsed",
+            Self::InvalidQuery => "query string could not be parsed",
+            Self::RouteNotFound => "no route matches the request",
+            Self::UnsupportedEventType => "event type is not supported",
+            Self::UnknownRulesetVersion => "ruleset version is not loaded",
+            Self::RulesetAccountNotNamed => "rule set does not name the account this posting needs",
+            Self::MissingIdempotencyKey => "Idempotency-Key is required",
+            Self::InvalidPayload => "payload could not be encoded",
+            Self::IdempotencyPayloadMismatch => {
+                "idempotency key was already used with a different payload"
+            }
+            Self::IdempotencyStoreError => "idempotency store is unavailable",
+            Self::IdempotencyResultStoreError => "idempotency result store is unavailable",
+            Self::InvalidAccountingDate => "accounting_date must be YYYY-MM-DD",
+            Self::InvalidStartDate => "start_date must be YYYY-MM-DD",
+            Self::InvalidEndDate => "end_date must be YYYY-MM-DD",
+            Self::InvalidLegalHoldRange => "end_date must not precede start_date",
+            Self::LegalHoldStoreError => "legal hold store is unavailable",
+            Self::LegalHoldActive => "a legal hold covers the accounting date",
+            Self::AuditSealStoreError => "audit seal store is unavailable",
+            Self::AuditSealChainBroken => "audit seal chain is broken",
+            Self::AuditSealTampered => "audit seal entry was tampered with",
+            Self::InvalidJournalId => "journal id must be a UUID",
+            Self::MissingAdjustmentLines => "adjustment requires at least one line",
+            Self::AdjustmentScopeMismatch => "adjustment scope does not match the original journal",
+            Self::JournalStoreError => "journal store is unavailable",
+            Self::JournalNotFound => "journal not found",
+            Self::JournalExists => "journal already exists",
+            Self::JournalUnbalanced => "journal is unbalanced",
+            Self::JournalImmutable => "posted journal is immutable",
+            Self::JournalAlreadyReversed => "journal already reversed",
+            Self::JournalHasSubledgerEffects => {
+                "journal moved a subledger balance and cannot be reversed on its own"
+            }
+            Self::PeriodStoreError => "period store is unavailable",
+            Self::PeriodClosed => "accounting period is closed",
+            Self::InvalidPeriodId => "period id is invalid",
+            Self::PeriodPostingNotAllowed => "period state does not accept this posting",
+            Self::InvalidPeriodTransition => "period cannot move to the requested state",
+            Self::PeriodTransitionForbidden => "actor role may not make this period transition",
+            Self::FiscalPeriodUnresolved => "fiscal calendar cannot resolve the period",
+            Self::CloseChecklistStoreError => "close checklist store is unavailable",
+            Self::InvalidCloseChecklist => "close checklist is invalid",
+            Self::CloseChecklistNotFound => "close checklist not found",
+            Self::CloseChecklistClosed => "close checklist is already closed",
+            Self::CloseDependencyNotFound => "close checklist dependency not found",
+            Self::CloseDependencyStillRequired => {
+                "open required close dependency cannot be dropped"
+            }
+            Self::InvalidCloseDependencyTransition => {
+                "close checklist dependency cannot move to the requested status"
+            }
+            Self::CloseChecklistRequired => {
+                "period needs a close checklist before it can be closed"
+            }
+            Self::CloseChecklistNotReady => "close checklist is not ready to close",
+            Self::YearEndCloseStoreError => "year-end close store is unavailable",
+            Self::InvalidFiscalYear => "fiscal year is out of range",
+            Self::InvalidRetainedEarningsAccount => {
+                "retained earnings account must be a balance-sheet account"
+            }
+            Self::YearEndAlreadyClosed => "fiscal year is already closed",
+            Self::YearEndCloseNotFound => "year-end close not found",
+            Self::YearEndCloseAlreadyReversed => "year-end close is already reversed",
+            Self::MissingReasonCode => "prior-period adjustment requires a reason code",
+            Self::AffectedPeriodNotPrior => "affected period must precede the posting period",
+            Self::MissingLocationId => "location_id is required",
+            Self::UnknownLegalEntityBoundary => "legal entity has no location boundary",
+            Self::LocationNotAllowedForLegalEntity => {
+                "location is not allowed for the legal entity"
+            }
+            Self::MissingCounterpartyLegalEntityId => "counterparty_legal_entity_id is required",
+            Self::InvalidCounterpartyLegalEntity => {
+                "counterparty must differ from the posting legal entity"
+            }
+            Self::UnknownCounterpartyLegalEntity => "counterparty legal entity is unknown",
+            Self::BookPolicyNotAllowed => "book policy version is not allowed for the ledger book",
+            Self::NoBookPolicyForLegalEntity => {
+                "legal entity has no book policy to post every book"
+            }
+            Self::MissingField => "required payload field is missing",
+            Self::InvalidNumber => "payload field is not a valid amount",
+            Self::InvalidSettlementMath => "gross amount must equal net plus fee",
+            Self::InvalidEntrySide => "entry side must be debit or credit",
+            Self::UnknownTaxType => "tax type has no tax payable account",
+            Self::RuleCheckFailed => "event failed a posting rule check",
+            Self::ComponentsUnreconciled => "order components do not sum to the tender total",
+            Self::InvalidStayDates => "stay dates are invalid",
+            Self::RecognitionScheduleStoreError => "recognition schedule store is unavailable",
+            Self::RecognitionScheduleNotFound => "recognition schedule not found",
+            Self::RecognitionScheduleNotActive => "recognition schedule is not active",
+            Self::InvalidForfeitAmount => "forfeited amount exceeds the remaining deferred balance",
+            Self::PassStoreError => "pass store is unavailable",
+            Self::PassNotFound => "pass not found",
+            Self::PassAlreadyExists => "pass already exists",
+            Self::PassNotActive => "pass is not active",
+            Self::PassRecognizedRatably => "pass revenue is recognized ratably over its season",
+            Self::InvalidPassTerms => "pass terms are invalid",
+            Self::VisitsExceedEntitlement => "visits exceed the pass entitlement",
+            Self::PassStateChanged => "pass changed while the event was being posted",
+            Self::InvalidBreakageRate => "breakage rate must be below 10000 basis points",
+            Self::DuplicateBreakageAssumptionSet => {
+                "breakage assumption set version already exists"
+            }
+            Self::BreakageAssumptionsOutOfOrder => {
+                "breakage assumption set takes effect before the current set"
+            }
+            Self::StoredValueStoreError => "stored value store is unavailable",
+            Self::GiftCardNotFound => "gift card not found",
+            Self::GiftCardAlreadyExists => "gift card already exists",
+            Self::GiftCardNotActive => "gift card is not active",
+            Self::GiftCardCurrencyMismatch => "gift card is denominated in another currency",
+            Self::InsufficientStoredValue => "amount exceeds the gift card balance",
+            Self::GiftCardStateChanged => "gift card changed while the event was being posted",
+            Self::NoStoredValueMovement => "rule set derived no gift card liability movement",
+            Self::FxRateStoreError => "fx rate store is unavailable",
+            Self::InvalidFxRateSet => "fx rate set is invalid",
+            Self::FxRateSetConflict => "fx rate set is already registered with different rates",
+            Self::FxRateSetNotFound => "fx rate set not found",
+            Self::FxRateNotFound => "no fx rate is effective for the currency pair and date",
+            Self::BaseCurrencyNotConfigured => "legal entity has no base currency configured",
+            Self::AccountNotInChart => "account is not in the legal entity's chart of accounts",
+            Self::SupplierTermsStoreError => "supplier terms store is unavailable",
+            Self::InvalidCommissionRate => "commission rate is out of range",
+            Self::DuplicateSupplierTerms => "supplier terms version is already registered",
+            Self::SupplierTermsOutOfOrder => "supplier terms take effect before the current terms",
+            Self::NoSupplierTermsInEffect => {
+                "supplier has no terms in effect on the accounting date"
+            }
+            Self::CapacityReadinessUnavailable => "capacity readiness is unavailable",
+            Self::ChangeFeedStoreError => "ledger change feed is unavailable",
+            Self::InvalidNdjsonLine => "NDJSON line could not be parsed",
+            Self::NdjsonLineTooLarge => "NDJSON line exceeds the size limit",
+            Self::NdjsonBodyReadError => "request body could not be read",
+            Self::WebhookSourceNotConfigured => "no webhook source is configured for this path",
+            Self::WebhookSignatureMissing => "webhook signature header is missing or malformed",
+            Self::WebhookSignatureInvalid => "webhook signature does not match any active secret",
+            Self::WebhookSignatureExpired => "webhook signature is outside the tolerance window",
+            Self::WebhookReplayed => "webhook signature was already accepted",
+            Self::InvalidWebhookBody => "webhook body is not a JSON object",
+            Self::WebhookNormalizationFailed => {
+                "webhook could not be normalized to a canonical event"
+            }
+            Self::WebhookNormalizationUnavailable => {
+                "webhook normalization is temporarily unavailable"
+            }
+            Self::DeadLetterStoreError => "dead letter store is unavailable",
+            Self::DeadLetterNotFound => "dead letter not found",
+            Self::DeadLetterNotPending => "dead letter was already replayed or discarded",
+            Self::InvalidDeadLetterPayload => "edited payload must be a JSON object",
+        }
+    }
+
+    /// A backing store could not be locked or read; the same request may succeed later.
+    pub fn is_store_unavailable(self) -> bool {
+        matches!(
+            self,
+            Self::IdempotencyStoreError
+                | Self::IdempotencyResultStoreError
+                | Self::LegalHoldStoreError
+                | Self::AuditSealStoreError
+                | Self::JournalStoreError
+                | Self::PeriodStoreError
+                | Self::CloseChecklistStoreError
+                | Self::YearEndCloseStoreError
+                | Self::RecognitionScheduleStoreError
+                | Self::PassStoreError
+                | Self::StoredValueStoreError
+                | Self::FxRateStoreError
+                | Self::SupplierTermsStoreError
+                | Self::ChangeFeedStoreError
+                | Self::DeadLetterStoreError
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ErrorEnvelope {
+    pub code: ErrorCode,
+    pub message: String,
+    #[schema(value_type = Object)]
+    pub details: Map<String, Value>,
+    pub correlation_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Map<String, Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: ErrorCode) -> Self {
+        Self {
+            status,
+            code,
+            message: code.default_message().to_string(),
+            details: Map::new(),
+        }
+    }
+
+    pub fn bad_request(code: ErrorCode) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, code)
+    }
+
+    pub fn conflict(code: ErrorCode) -> Self {
+        Self::new(StatusCode::CONFLICT, code)
+    }
+
+    pub fn forbidden(code: ErrorCode) -> Self {
+        Self::new(StatusCode::FORBIDDEN, code)
+    }
+
+    pub fn not_found(code: ErrorCode) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code)
+    }
+
+    pub fn internal(code: ErrorCode) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, code)
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    pub fn with_detail(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn envelope(&self, correlation_id: &str) -> ErrorEnvelope {
+        ErrorEnvelope {
+            code: self.code,
+            message: self.message.clone(),
+            details: self.details.clone(),
+            correlation_id: correlation_id.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, Json(self.envelope(""))).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrelationId(pub String);
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for CorrelationId
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<CorrelationId>()
+            .cloned()
+            .unwrap_or_else(|| CorrelationId(Uuid::new_v4().to_string())))
+    }
+}
+
+pub async fn correlation_middleware(mut request: Request, next: Next) -> Response {
+    let correlation_id = request
+        .headers()
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| {
+            !value.is_empty()
+                && value.len() <= MAX_CORRELATION_ID_LEN
+                && value.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(ToString::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request
+        .extensions_mut()
+        .insert(CorrelationId(correlation_id.clone()));
+
+    let mut response = next.run(request).await;
+    if let Some(error) = response.extensions_mut().remove::<ApiError>() {
+        response = (error.status, Json(error.envelope(&correlation_id))).into_response();
+    }
+    if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+        response.headers_mut().insert(CORRELATION_ID_HEADER, value);
+    }
+    response
+}
+
+pub async fn route_not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::RouteNotFound)
+}
+
+pub struct ApiJson<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        Json::<T>::from_request(request, state)
+            .await
+            .map(|Json(value)| Self(value))
+            .map_err(|rejection: JsonRejection| {
+                ApiError::new(rejection.status(), ErrorCode::InvalidRequestBody)
+                    .with_detail("reason", rejection.body_text())
+            })
+    }
+}
+
+pub struct ApiQuery<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        axum::extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|axum::extract::Query(value)| Self(value))
+            .map_err(|rejection: QueryRejection| {
+                ApiError::bad_request(ErrorCode::InvalidQuery)
+                    .with_detail("reason", rejection.body_text())
+            })
+    }
+}
@@ -0,0 +1,715 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::NaiveDate;
+use ledger_posting::{AppliedFxRate, JournalLine};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, ApiJson, ErrorCode, ErrorEnvelope};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::{signed_amount, AppState};
+
+const FX_RATE_STORE_FILENAME: &str = "fx_rate_store.json";
+const MAX_RATE_DIGITS: usize = 18;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FxRateType {
+    #[default]
+    Spot,
+    Average,
+    Closing,
+}
+
+impl FxRateType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Spot => "SPOT",
+            Self::Average => "AVERAGE",
+            Self::Closing => "CLOSING",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FxRate {
+    pub from_currency: String,
+    pub to_currency: String,
+    #[serde(default)]
+    pub rate_type: FxRateType,
+    #[schema(value_type = String, format = Date)]
+    pub effective_date: NaiveDate,
+    /// Units of `to_currency` per unit of `from_currency`, as a decimal string.
+    pub rate: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FxRateSet {
+    pub fx_rate_set_id: String,
+    pub rates: Vec<FxRate>,
+}
+
+impl FxRateSet {
+    pub fn load(path: &FsPath) -> Result<Self, FxError> {
+        let encoded = fs::read(path).map_err(|source| FxError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_slice(&encoded).map_err(|source| FxError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn load_dir(dir: &FsPath) -> Result<Vec<Self>, FxError> {
+        let read_error = |source| FxError::Read {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let mut paths = fs::read_dir(dir)
+            .map_err(read_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(read_error)?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+        paths.sort();
+        paths.iter().map(|path| Self::load(path)).collect()
+    }
+
+    fn validate(&self) -> Result<(), FxError> {
+        let invalid = |reason: String| FxError::InvalidRateSet {
+            fx_rate_set_id: self.fx_rate_set_id.clone(),
+            reason,
+        };
+        if self.fx_rate_set_id.trim().is_empty() {
+            return Err(invalid("fx_rate_set_id is empty".to_string()));
+        }
+        if self.rates.is_empty() {
+            return Err(invalid("rate set has no rates".to_string()));
+        }
+        let mut seen = HashSet::new();
+        for rate in &self.rates {
+            let pair = format!("{}/{}", rate.from_currency, rate.to_currency);
+            if !is_currency_code(&rate.from_currency) || !is_currency_code(&rate.to_currency) {
+                return Err(invalid(format!("{pair} is not a pair of ISO 4217 codes")));
+            }
+            if rate.from_currency == rate.to_currency {
+                return Err(invalid(format!("{pair} converts a currency to itself")));
+            }
+            if parse_rate(&rate.rate).is_none() {
+                return Err(invalid(format!(
+                    "{pair} rate `{}` is not a positive decimal with at most {MAX_RATE_DIGITS} digits",
+                    rate.rate
+                )));
+            }
+            if rate.source.trim().is_empty() {
+                return Err(invalid(format!("{pair} rate has no source")));
+            }
+            if !seen.insert((
+                &rate.from_currency,
+                &rate.to_currency,
+                rate.rate_type,
+                rate.effective_date,
+            )) {
+                return Err(invalid(format!(
+                    "{pair} {} rate for {} is listed more than once",
+                    rate.rate_type.as_str(),
+                    rate.effective_date
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn lookup(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+        rate_type: FxRateType,
+        accounting_date: NaiveDate,
+    ) -> Option<&FxRate> {
+        self.rates
+            .iter()
+            .filter(|rate| {
+                rate.from_currency == from_currency
+                    && rate.to_currency == to_currency
+                    && rate.rate_type == rate_type
+                    && rate.effective_date <= accounting_date
+            })
+            .max_by_key(|rate| rate.effective_date)
+    }
+}
+
+/// Minor-unit exponent from ISO 4217; most currencies have two decimals.
+fn currency_exponent(currency: &str) -> u32 {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.bytes().all(|byte| byte.is_ascii_uppercase())
+}
+
+fn parse_rate(rate: &str) -> Option<(i128, u32)> {
+    let (whole, fraction) = rate.split_once('.').unwrap_or((rate, ""));
+    if whole.is_empty()
+        || whole.len() + fraction.len() > MAX_RATE_DIGITS
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let mantissa = format!("{whole}{fraction}").parse::<i128>().ok()?;
+    (mantissa > 0).then_some((mantissa, fraction.len() as u32))
+}
+
+/// Multiplies by the rate and rounds half to even, so repeated translations do not drift.
+fn convert(amount_minor: i64, mantissa: i128, scale: u32) -> Option<i64> {
+    let product = i128::from(amount_minor).checked_mul(mantissa)?;
+    let divisor = 10_i128.checked_pow(scale)?;
+    let (quotient, remainder) = (product.abs() / divisor, product.abs() % divisor);
+    let rounded = match (remainder * 2).cmp(&divisor) {
+        std::cmp::Ordering::Greater => quotient + 1,
+        std::cmp::Ordering::Equal if quotient % 2 == 1 => quotient + 1,
+        _ => quotient,
+    };
+    i64::try_from(rounded * product.signum()).ok()
+}
+
+#[derive(Debug, Error)]
+pub enum FxError {
+    #[error("failed to read fx rate set {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse fx rate set {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("fx rate set `{fx_rate_set_id}` is invalid: {reason}")]
+    InvalidRateSet {
+        fx_rate_set_id: String,
+        reason: String,
+    },
+    #[error("fx rate set `{0}` is already registered with different rates")]
+    RateSetConflict(String),
+    #[error("fx rate set `{0}` not found")]
+    RateSetNotFound(String),
+    #[error(
+        "fx rate set `{fx_rate_set_id}` has no {} {from_currency}/{to_currency} rate effective on or before {accounting_date}",
+        rate_type.as_str()
+    )]
+    RateNotFound {
+        fx_rate_set_id: String,
+        rate_type: FxRateType,
+        from_currency: String,
+        to_currency: String,
+        accounting_date: NaiveDate,
+    },
+    #[error("translated base amount overflows on line {0}")]
+    AmountOverflow(u32),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FxRateStore {
+    rate_sets: BTreeMap<String, FxRateSet>,
+}
+
+#[derive(Default)]
+pub struct InMemoryFxRateRepository {
+    rate_sets: BTreeMap<String, FxRateSet>,
+    persistence: Option<Arc<WriteBehind<FxRateStore>>>,
+}
+
+impl InMemoryFxRateRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(FX_RATE_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: FxRateStore = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "fx-rate-write-behind")?);
+        Ok(Self {
+            rate_sets: loaded.rate_sets,
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self, fx_rate_set_id: &str) -> Option<&FxRateSet> {
+        self.rate_sets.get(fx_rate_set_id)
+    }
+
+    pub fn all(&self) -> Vec<FxRateSet> {
+        self.rate_sets.values().cloned().collect()
+    }
+
+    /// Registers a rate set. Sets are immutable once posted against, so re-registering an
+    /// identical set is a no-op and a changed set under the same id is rejected.
+    pub fn register(&mut self, rate_set: FxRateSet) -> Result<(), FxError> {
+        rate_set.validate()?;
+        match self.rate_sets.get(&rate_set.fx_rate_set_id) {
+            Some(existing) if *existing == rate_set => return Ok(()),
+            Some(_) => return Err(FxError::RateSetConflict(rate_set.fx_rate_set_id)),
+            None => {}
+        }
+        self.rate_sets
+            .insert(rate_set.fx_rate_set_id.clone(), rate_set);
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(FxRateStore {
+                rate_sets: self.rate_sets.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Sets the base amount of every line, replacing any the caller supplied. Lines in
+    /// `base_currency` carry their amount over; the rest are translated between the two
+    /// currencies' minor units. Any residual left by rounding is absorbed by the largest
+    /// translated line so the base amounts stay balanced.
+    pub fn translate(
+        &self,
+        fx_rate_set_id: &str,
+        rate_type: FxRateType,
+        accounting_date: NaiveDate,
+        base_currency: &str,
+        lines: &mut [JournalLine],
+    ) -> Result<(), FxError> {
+        let mut foreign = Vec::new();
+        for (index, line) in lines.iter_mut().enumerate() {
+            if line.currency == base_currency {
+                line.base_amount_minor = line.amount_minor;
+                line.base_currency = base_currency.to_string();
+                line.fx_rate = None;
+            } else {
+                foreign.push(index);
+            }
+        }
+        if foreign.is_empty() {
+            return Ok(());
+        }
+        let rate_set = self
+            .get(fx_rate_set_id)
+            .ok_or_else(|| FxError::RateSetNotFound(fx_rate_set_id.to_string()))?;
+
+        for &index in &foreign {
+            let line = &mut lines[index];
+            let rate = rate_set
+                .lookup(&line.currency, base_currency, rate_type, accounting_date)
+                .ok_or_else(|| FxError::RateNotFound {
+                    fx_rate_set_id: fx_rate_set_id.to_string(),
+                    rate_type,
+                    from_currency: line.currency.clone(),
+                    to_currency: base_currency.to_string(),
+                    accounting_date,
+                })?;
+            let (mantissa, scale) =
+                parse_rate(&rate.rate).ok_or_else(|| FxError::InvalidRateSet {
+                    fx_rate_set_id: fx_rate_set_id.to_string(),
+                    reason: format!("rate `{}` is not a positive decimal", rate.rate),
+                })?;
+            // The rate is quoted per major unit, so shift it by the difference in exponents.
+            let base_amount_minor = mantissa
+                .checked_mul(10_i128.pow(currency_exponent(base_currency)))
+                .and_then(|mantissa| {
+                    convert(
+                        line.amount_minor,
+                        mantissa,
+                        scale + currency_exponent(&line.currency),
+                    )
+                });
+            line.base_amount_minor =
+                base_amount_minor.ok_or(FxError::AmountOverflow(line.line_number))?;
+            line.base_currency = base_currency.to_string();
+            line.fx_rate = Some(AppliedFxRate {
+                rate_type: rate_type.as_str().to_string(),
+                rate: rate.rate.clone(),
+                effective_date: rate.effective_date,
+                source: rate.source.clone(),
+            });
+        }
+
+        let residual = lines
+            .iter()
+            .map(|line| signed_amount(line.entry_side.clone(), line.base_amount_minor))
+            .sum::<i64>();
+        if residual != 0 && residual.unsigned_abs() <= foreign.len() as u64 {
+            let largest = foreign
+                .iter()
+                .copied()
+                .max_by_key(|&index| (lines[index].base_amount_minor, std::cmp::Reverse(index)))
+                .expect("foreign lines are not empty");
+            let line = &mut lines[largest];
+            line.base_amount_minor -= signed_amount(line.entry_side.clone(), residual);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn fx_error_response(error: FxError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        FxError::Read { .. } | FxError::Parse { .. } => {
+            ApiError::internal(ErrorCode::FxRateStoreError)
+        }
+        FxError::InvalidRateSet {
+            fx_rate_set_id,
+            reason,
+        } => ApiError::bad_request(ErrorCode::InvalidFxRateSet)
+            .with_detail("fx_rate_set_id", fx_rate_set_id)
+            .with_detail("reason", reason),
+        FxError::RateSetConflict(fx_rate_set_id) => {
+            ApiError::conflict(ErrorCode::FxRateSetConflict)
+                .with_detail("fx_rate_set_id", fx_rate_set_id)
+        }
+        FxError::RateSetNotFound(fx_rate_set_id) => {
+            ApiError::bad_request(ErrorCode::FxRateSetNotFound)
+                .with_detail("fx_rate_set_id", fx_rate_set_id)
+        }
+        FxError::RateNotFound {
+            fx_rate_set_id,
+            rate_type,
+            from_currency,
+            to_currency,
+            accounting_date,
+        } => ApiError::bad_request(ErrorCode::FxRateNotFound)
+            .with_detail("fx_rate_set_id", fx_rate_set_id)
+            .with_detail("rate_type", rate_type.as_str())
+            .with_detail("from_currency", from_currency)
+            .with_detail("to_currency", to_currency)
+            .with_detail("accounting_date", accounting_date.to_string()),
+        FxError::AmountOverflow(line_number) => {
+            ApiError::bad_request(ErrorCode::InvalidNumber).with_detail("line_number", line_number)
+        }
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FxRateSetList {
+    pub rate_sets: Vec<FxRateSet>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/fx/rate-sets",
+    tag = "fx",
+    request_body = FxRateSet,
+    responses(
+        (status = 200, description = "Rate set registered, or already registered unchanged", body = FxRateSet),
+        (status = 400, description = "Invalid currency pair, rate or source", body = ErrorEnvelope),
+        (status = 409, description = "Rate set id already registered with different rates", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn register_rate_set(
+    State(state): State<AppState>,
+    ApiJson(rate_set): ApiJson<FxRateSet>,
+) -> Result<Json<FxRateSet>, ApiError> {
+    state
+        .fx_rates
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::FxRateStoreError))?
+        .register(rate_set.clone())
+        .map_err(fx_error_response)?;
+    Ok(Json(rate_set))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/fx/rate-sets",
+    tag = "fx",
+    responses(
+        (status = 200, description = "Registered rate sets", body = FxRateSetList),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_rate_sets(
+    State(state): State<AppState>,
+) -> Result<Json<FxRateSetList>, ApiError> {
+    let rate_sets = state
+        .fx_rates
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::FxRateStoreError))?
+        .all();
+    Ok(Json(FxRateSetList { rate_sets }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/fx/rate-sets/{fx_rate_set_id}",
+    tag = "fx",
+    params(("fx_rate_set_id" = String, Path, description = "Rate set referenced by posting provenance")),
+    responses(
+        (status = 200, description = "Rate set", body = FxRateSet),
+        (status = 404, description = "Unknown rate set", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_rate_set(
+    State(state): State<AppState>,
+    Path(fx_rate_set_id): Path<String>,
+) -> Result<Json<FxRateSet>, ApiError> {
+    state
+        .fx_rates
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::FxRateStoreError))?
+        .get(&fx_rate_set_id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::not_found(ErrorCode::FxRateSetNotFound)
+                .with_detail("fx_rate_set_id", fx_rate_set_id)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use ledger_posting::EntrySide;
+
+    use super::*;
+
+    fn rate(from_currency: &str, effective_date: &str, rate: &str) -> FxRate {
+        FxRate {
+            from_currency: from_currency.to_string(),
+            to_currency: "USD".to_string(),
+            rate_type: FxRateType::Spot,
+            effective_date: NaiveDate::parse_from_str(effective_date, "%Y-%m-%d").unwrap(),
+            rate: rate.to_string(),
+            source: "BOC".to_string(),
+        }
+    }
+
+    fn line(line_number: u32, entry_side: EntrySide, amount_minor: i64) -> JournalLine {
+        JournalLine {
+            line_number,
+            account_id: format!("{line_number}000"),
+            entry_side,
+            amount_minor,
+            currency: "CAD".to_string(),
+            base_amount_minor: amount_minor,
+            base_currency: "CAD".to_string(),
+            dimensions: BTreeMap::new(),
+            fx_rate: None,
+        }
+    }
+
+    fn repository() -> InMemoryFxRateRepository {
+        let mut repo = InMemoryFxRateRepository::default();
+        repo.register(FxRateSet {
+            fx_rate_set_id: "fx_2026_02".to_string(),
+            rates: vec![
+                rate("CAD", "2026-02-01", "0.7300"),
+                rate("CAD", "2026-02-20", "0.7355"),
+            ],
+        })
+        .unwrap();
+        repo
+    }
+
+    #[test]
+    fn rounds_half_to_even() {
+        assert_eq!(convert(5, 5, 1), Some(2));
+        assert_eq!(convert(7, 5, 1), Some(4));
+        assert_eq!(convert(-7, 5, 1), Some(-4));
+        assert_eq!(convert(10_001, 7355, 4), Some(7356));
+        assert_eq!(convert(i64::MAX, 10_i128.pow(20), 2), None);
+        assert_eq!(parse_rate("0.7355"), Some((7355, 4)));
+        assert_eq!(parse_rate("-1.2"), None);
+        assert_eq!(parse_rate("0.000"), None);
+        assert_eq!(parse_rate("1e3"), None);
+    }
+
+    #[test]
+    fn translates_with_latest_effective_rate_and_balances_residual() {
+        let repo = repository();
+        let mut lines = vec![
+            line(1, EntrySide::Debit, 10_001),
+            line(2, EntrySide::Credit, 5_001),
+            line(3, EntrySide::Credit, 5_000),
+        ];
+        repo.translate(
+            "fx_2026_02",
+            FxRateType::Spot,
+            NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+            "USD",
+            &mut lines,
+        )
+        .unwrap();
+
+        // 7355.7355 -> 7356, 3678.2355 -> 3678 and 3677.5 -> 3678, so no residual is needed.
+        assert_eq!(
+            lines
+                .iter()
+                .map(|l| l.base_amount_minor)
+                .collect::<Vec<_>>(),
+            vec![7356, 3678, 3678]
+        );
+        assert!(lines.iter().all(|l| l.base_currency == "USD"));
+        let applied = lines[0].fx_rate.as_ref().unwrap();
+        assert_eq!(applied.rate, "0.7355");
+        assert_eq!(applied.effective_date.to_string(), "2026-02-20");
+
+        let mut lines = vec![
+            line(1, EntrySide::Debit, 3),
+            line(2, EntrySide::Credit, 1),
+            line(3, EntrySide::Credit, 1),
+            line(4, EntrySide::Credit, 1),
+        ];
+        repo.translate(
+            "fx_2026_02",
+            FxRateType::Spot,
+            NaiveDate::from_ymd_opt(2026, 2, 10).unwrap(),
+            "USD",
+            &mut lines,
+        )
+        .unwrap();
+        // 3 * 0.73 = 2.19 -> 2 while each 1 * 0.73 -> 1; the largest line absorbs the residual.
+        assert!(ledger_posting::validate_balanced(&lines).is_ok());
+    }
+
+    #[test]
+    fn translates_between_currencies_with_different_minor_units() {
+        let mut repo = InMemoryFxRateRepository::default();
+        let mut usd_jpy = rate("USD", "2026-02-01", "150.25");
+        usd_jpy.to_currency = "JPY".to_string();
+        repo.register(FxRateSet {
+            fx_rate_set_id: "fx_minor_units".to_string(),
+            rates: vec![
+                rate("JPY", "2026-02-01", "0.0067"),
+                rate("BHD", "2026-02-01", "2.6525"),
+                usd_jpy,
+            ],
+        })
+        .unwrap();
+        let on = NaiveDate::from_ymd_opt(2026, 2, 21).unwrap();
+        let in_currency = |currency: &str, entry_side, amount_minor| JournalLine {
+            currency: currency.to_string(),
+            ..line(1, entry_side, amount_minor)
+        };
+
+        // ¥10000 is $67.00 and 1.000 BHD is $2.6525, which rounds to $2.65.
+        let mut lines = vec![
+            in_currency("JPY", EntrySide::Debit, 10_000),
+            in_currency("BHD", EntrySide::Credit, 1_000),
+        ];
+        repo.translate("fx_minor_units", FxRateType::Spot, on, "USD", &mut lines)
+            .unwrap();
+        assert_eq!(lines[0].base_amount_minor, 6_700);
+        assert_eq!(lines[1].base_amount_minor, 265);
+
+        // $1.00 is ¥150.25, which rounds to ¥150.
+        let mut lines = vec![in_currency("USD", EntrySide::Debit, 100)];
+        repo.translate("fx_minor_units", FxRateType::Spot, on, "JPY", &mut lines)
+            .unwrap();
+        assert_eq!(lines[0].base_amount_minor, 150);
+        assert_eq!(lines[0].base_currency, "JPY");
+    }
+
+    #[test]
+    fn replaces_base_amounts_supplied_by_the_caller() {
+        let repo = repository();
+        let mut lines = vec![
+            JournalLine {
+                base_amount_minor: 1,
+                base_currency: "USD".to_string(),
+                ..line(1, EntrySide::Debit, 10_000)
+            },
+            JournalLine {
+                currency: "USD".to_string(),
+                base_amount_minor: 1,
+                base_currency: "USD".to_string(),
+                ..line(2, EntrySide::Credit, 7_355)
+            },
+        ];
+        repo.translate(
+            "fx_2026_02",
+            FxRateType::Spot,
+            NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+            "USD",
+            &mut lines,
+        )
+        .unwrap();
+        assert_eq!(lines[0].base_amount_minor, 7_355);
+        assert!(lines[0].fx_rate.is_some());
+        assert_eq!(lines[1].base_amount_minor, 7_355);
+        assert!(lines[1].fx_rate.is_none());
+    }
+
+    #[test]
+    fn rejects_missing_rates_and_conflicting_sets() {
+        let mut repo = repository();
+        let mut lines = vec![
+            line(1, EntrySide::Debit, 100),
+            line(2, EntrySide::Credit, 100),
+        ];
+        assert!(matches!(
+            repo.translate(
+                "fx_2026_02",
+                FxRateType::Spot,
+                NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(),
+                "USD",
+                &mut lines,
+            ),
+            Err(FxError::RateNotFound { .. })
+        ));
+        assert!(matches!(
+            repo.translate(
+                "fx_unknown",
+                FxRateType::Spot,
+                NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+                "USD",
+                &mut lines,
+            ),
+            Err(FxError::RateSetNotFound(_))
+        ));
+
+        let mut changed = repo.get("fx_2026_02").unwrap().clone();
+        assert!(repo.register(changed.clone()).is_ok());
+        changed.rates[0].rate = "0.7400".to_string();
+        assert!(matches!(
+            repo.register(changed),
+            Err(FxError::RateSetConflict(_))
+        ));
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use connector_sdk::{
+    AdyenAdapter, AdyenSignatureVerifier, BodyHmacVerifier, CanonicalEvent, ConnectorAdapter,
+    ConnectorError, FailureClass, InntopiaAdapter, RawEvent, SignatureError, SigningSecret,
+    SquareAdapter, SquareSignatureVerifier, StripeAdapter, StripeSignatureVerifier, WebhookRequest,
+    WebhookVerifier,
+};
+use ledger_posting::TraceContext;
+use platform_core::payload_hash;
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::bulk::BulkPostOutcome;
+use crate::config::{ConfigError, WebhookConfig, WebhookSourceConfig};
+use crate::dead_letter::record_dead_letter;
+use crate::error::{ApiError, CorrelationId, ErrorCode, ErrorEnvelope};
+use crate::{
+    ensure_supported_event_type, post_event_with_idempotency_key, AppState, BookJournal,
+    PostEventRequest, PostEventResponse, Provenance,
+};
+
+pub const INNTOPIA_SIGNATURE_HEADER: &str = "x-inntopia-signature";
+
+/// A configured webhook path: how to authenticate the delivery, which adapter normalizes it,
+/// and the provenance its events post under.
+pub struct WebhookSource {
+    verifier: Arc<dyn WebhookVerifier>,
+    pub(crate) adapter: Arc<dyn ConnectorAdapter + Send + Sync>,
+    provenance: Provenance,
+    ledger_book: String,
+}
+
+impl WebhookSource {
+    pub fn new(
+        verifier: Arc<dyn WebhookVerifier>,
+        adapter: Arc<dyn ConnectorAdapter + Send + Sync>,
+        provenance: Provenance,
+        ledger_book: impl Into<String>,
+    ) -> Self {
+        Self {
+            verifier,
+            adapter,
+            provenance,
+            ledger_book: ledger_book.into(),
+        }
+    }
+
+    fn from_config(config: WebhookSourceConfig) -> Result<Self, ConfigError> {
+        let secrets = config
+            .secrets
+            .into_iter()
+            .map(|secret| SigningSecret {
+                secret_id: secret.secret_id,
+                secret: secret.secret,
+                expires_at: secret.expires_at,
+            })
+            .collect::<Vec<_>>();
+        let (verifier, adapter): (
+            Arc<dyn WebhookVerifier>,
+            Arc<dyn ConnectorAdapter + Send + Sync>,
+        ) = match config.source.as_str() {
+            "stripe" => {
+                let mut verifier = StripeSignatureVerifier::new(secrets);
+                if let Some(secs) = config.tolerance_secs {
+                    verifier = verifier.with_tolerance(Duration::seconds(secs));
+                }
+                (Arc::new(verifier), Arc::new(StripeAdapter))
+            }
+            "square" => (
+                Arc::new(SquareSignatureVerifier::new(
+                    config.notification_url.unwrap_or_default(),
+                    secrets,
+                )),
+                Arc::new(SquareAdapter),
+            ),
+            "inntopia" => (
+                Arc::new(BodyHmacVerifier::new(
+                    "inntopia",
+                    INNTOPIA_SIGNATURE_HEADER,
+                    secrets,
+                )),
+                Arc::new(InntopiaAdapter),
+            ),
+            "adyen" => (
+                Arc::new(AdyenSignatureVerifier::new(secrets)),
+                Arc::new(AdyenAdapter),
+            ),
+            _ => {
+                return Err(ConfigError::InvalidWebhookSource {
+                    source_system: config.source,
+                    reason: "no connector adapter for this source".to_string(),
+                })
+            }
+        };
+        Ok(Self::new(
+            verifier,
+            adapter,
+            config.provenance,
+            config.ledger_book,
+        ))
+    }
+}
+
+pub fn webhook_sources(
+    config: WebhookConfig,
+) -> Result<HashMap<String, Arc<WebhookSource>>, ConfigError> {
+    config
+        .sources
+        .into_iter()
+        .map(|source| {
+            Ok((
+                source.source.clone(),
+                Arc::new(WebhookSource::from_config(source)?),
+            ))
+        })
+        .collect()
+}
+
+/// One ingested event. A skipped event has no canonical event and says why it was skipped.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IngestWebhookResponse {
+    pub source: String,
+    pub canonical_event_id: Option<String>,
+    pub idempotency_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(required = false)]
+    pub legal_entity_id: Option<String>,
+    pub outcome: BulkPostOutcome,
+    pub journal_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(required = false)]
+    pub book_journals: Vec<BookJournal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(required = false)]
+    pub skipped_reason: Option<String>,
+    pub error: Option<ErrorEnvelope>,
+}
+
+/// A delivery that batched several events gets one result per event, in delivery order. Items
+/// that could not be normalized are dead-lettered one by one and come back rejected, with the
+/// `dead_letter_id` in their error details.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IngestDeliveryResponse {
+    pub source: String,
+    pub items: Vec<IngestWebhookResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum IngestResponse {
+    Event(Box<IngestWebhookResponse>),
+    Delivery(IngestDeliveryResponse),
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ingest/{source}",
+    tag = "ingest",
+    params(("source" = String, Path, description = "Configured webhook source: stripe, square, inntopia or adyen")),
+    request_body(content = Object, description = "Raw webhook body exactly as signed by the source"),
+    responses(
+        (status = 200, description = "Webhook normalized, or skipped as nothing to book; posting outcome included, per item for a batched delivery", body = IngestResponse),
+        (status = 400, description = "Body is not a JSON object, or could not be normalized and was dead-lettered", body = ErrorEnvelope),
+        (status = 401, description = "Signature missing, invalid or outside tolerance", body = ErrorEnvelope),
+        (status = 404, description = "Source not configured", body = ErrorEnvelope),
+        (status = 409, description = "Signature already accepted", body = ErrorEnvelope),
+        (status = 503, description = "Normalization temporarily unavailable; nothing was posted or dead-lettered, redeliver", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn ingest_webhook(
+    State(state): State<AppState>,
+    Path(source): Path<String>,
+    CorrelationId(correlation_id): CorrelationId,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<IngestResponse>, ApiError> {
+    let webhook = webhook_source(&state, &source)?;
+    let request = WebhookRequest {
+        headers: headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.as_str().to_string(), value.to_string()))
+            })
+            .collect::<BTreeMap<_, _>>(),
+        body: body.to_vec(),
+        received_at: Utc::now(),
+    };
+    webhook
+        .verifier
+        .verify(&request)
+        .map_err(signature_error_response)?;
+
+    let payload: Value = serde_json::from_slice(&request.body)
+        .ok()
+        .filter(Value::is_object)
+        .ok_or_else(|| ApiError::bad_request(ErrorCode::InvalidWebhookBody))?;
+    // A transient normalization failure fails the whole delivery before anything is posted or
+    // dead-lettered, so the source redelivers it.
+    let deliveries = webhook.adapter.split_delivery(payload);
+    let mut normalized = Vec::with_capacity(deliveries.len());
+    for delivery in deliveries {
+        let raw = raw_event(delivery, request.received_at);
+        match webhook.adapter.normalize(raw.clone()).await {
+            Err(error) if error.class() == FailureClass::Transient => {
+                return Err(normalization_error_response(error));
+            }
+            canonical => normalized.push((raw, canonical)),
+        }
+    }
+
+    if normalized.len() == 1 {
+        let (raw, canonical) = normalized.remove(0);
+        return match post_normalized(&state, &source, &webhook, canonical, &correlation_id) {
+            Ok(response) => Ok(Json(IngestResponse::Event(Box::new(response)))),
+            Err(error) => {
+                let dead_letter_id = record_dead_letter(
+                    &state,
+                    &source,
+                    webhook.adapter.adapter_version(),
+                    raw,
+                    &error,
+                )?;
+                Err(normalization_error_response(error)
+                    .with_detail("dead_letter_id", dead_letter_id))
+            }
+        };
+    }
+
+    let mut items = Vec::with_capacity(normalized.len());
+    for (raw, canonical) in normalized {
+        let item = match post_normalized(&state, &source, &webhook, canonical, &correlation_id) {
+            Ok(response) => response,
+            Err(error) => {
+                let dead_letter_id = record_dead_letter(
+                    &state,
+                    &source,
+                    webhook.adapter.adapter_version(),
+                    raw,
+                    &error,
+                )?;
+                let mut response = IngestWebhookResponse::new(&source, BulkPostOutcome::Rejected);
+                response.error = Some(
+                    normalization_error_response(error)
+                        .with_detail("dead_letter_id", dead_letter_id)
+                        .envelope(&correlation_id),
+                );
+                response
+            }
+        };
+        items.push(item);
+    }
+    Ok(Json(IngestResponse::Delivery(IngestDeliveryResponse {
+        source,
+        items,
+    })))
+}
+
+impl IngestWebhookResponse {
+    fn new(source: &str, outcome: BulkPostOutcome) -> Self {
+        Self {
+            source: source.to_string(),
+            canonical_event_id: None,
+            idempotency_key: None,
+            legal_entity_id: None,
+            outcome,
+            journal_id: None,
+            book_journals: Vec::new(),
+            skipped_reason: None,
+            error: None,
+        }
+    }
+}
+
+pub(crate) fn webhook_source(
+    state: &AppState,
+    source: &str,
+) -> Result<Arc<WebhookSource>, ApiError> {
+    state.webhook_sources.get(source).cloned().ok_or_else(|| {
+        ApiError::not_found(ErrorCode::WebhookSourceNotConfigured).with_detail("source", source)
+    })
+}
+
+/// Runs a verified event through the source's current adapter and posts it. Posting failures
+/// and events the adapter skips are part of the response; only a normalization failure is an
+/// error.
+pub(crate) async fn normalize_and_post(
+    state: &AppState,
+    source: &str,
+    webhook: &WebhookSource,
+    raw: RawEvent,
+    correlation_id: &str,
+) -> Result<IngestWebhookResponse, ConnectorError> {
+    let canonical = webhook.adapter.normalize(raw).await;
+    post_normalized(state, source, webhook, canonical, correlation_id)
+}
+
+fn post_normalized(
+    state: &AppState,
+    source: &str,
+    webhook: &WebhookSource,
+    canonical: Result<CanonicalEvent, ConnectorError>,
+    correlation_id: &str,
+) -> Result<IngestWebhookResponse, ConnectorError> {
+    let canonical = match canonical {
+        Ok(canonical) => canonical,
+        Err(ConnectorError::Skipped(reason)) => {
+            let mut response = IngestWebhookResponse::new(source, BulkPostOutcome::Skipped);
+            response.skipped_reason = Some(reason);
+            return Ok(response);
+        }
+        Err(error) => return Err(error),
+    };
+
+    let outcome = post_canonical_event(state, source, webhook, &canonical);
+    let mut response = IngestWebhookResponse::new(source, BulkPostOutcome::Rejected);
+    response.canonical_event_id = Some(canonical.event_id);
+    response.idempotency_key = Some(canonical.idempotency_key);
+    response.legal_entity_id = Some(canonical.legal_entity_id);
+    match outcome {
+        Ok(posted) => {
+            response.outcome = if posted.replayed {
+                BulkPostOutcome::Replayed
+            } else {
+                BulkPostOutcome::Posted
+            };
+            response.journal_id = Some(posted.journal_id);
+            response.book_journals = posted.book_journals;
+        }
+        Err(error) => response.error = Some(error.envelope(correlation_id)),
+    }
+    Ok(response)
+}
+
+/// Posts a normalized event under the source's book and provenance, keyed on its idempotency
+/// key.
+pub(crate) fn post_canonical_event(
+    state: &AppState,
+    source: &str,
+    webhook: &WebhookSource,
+    canonical: &CanonicalEvent,
+) -> Result<PostEventResponse, ApiError> {
+    let req = posting_request(webhook, source, canonical);
+    ensure_supported_event_type(state, &req)
+        .and_then(|_| post_event_with_idempotency_key(state, &canonical.idempotency_key, req))
+}
+
+/// Sources disagree on where the event ID and timestamp live. A missing ID falls back to the
+/// body hash so redeliveries still deduplicate; a missing timestamp to the delivery time.
+fn raw_event(payload: Value, received_at: DateTime<Utc>) -> RawEvent {
+    let source_event_id = ["/id", "/event_id", "/source_event_id"]
+        .iter()
+        .find_map(|pointer| payload.pointer(pointer).and_then(Value::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| payload_hash(&payload));
+    let occurred_at = payload
+        .get("created")
+        .and_then(Value::as_i64)
+        .and_then(|unix| DateTime::from_timestamp(unix, 0))
+        .or_else(|| {
+            ["/created_at", "/occurred_at"].iter().find_map(|pointer| {
+                payload
+                    .pointer(pointer)
+                    .and_then(Value::as_str)
+                    .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+                    .map(|value| value.with_timezone(&Utc))
+            })
+        })
+        .unwrap_or(received_at);
+    RawEvent {
+        source_event_id,
+        occurred_at,
+        payload,
+    }
+}
+
+fn posting_request(
+    webhook: &WebhookSource,
+    source: &str,
+    canonical: &CanonicalEvent,
+) -> PostEventRequest {
+    let trace = &canonical.trace_context;
+    PostEventRequest {
+        event_type: canonical.event_type.clone(),
+        tenant_id: canonical.tenant_id.clone(),
+        legal_entity_id: canonical.legal_entity_id.clone(),
+        location_id: canonical
+            .payload
+            .get("location_id")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        ledger_book: webhook.ledger_book.clone(),
+        accounting_date: canonical.business_date.clone(),
+        source_event_id: canonical.source_event_id.clone(),
+        posting_run_id: format!("ingest-{source}"),
+        payload: canonical.payload.clone(),
+        lines: Vec::new(),
+        provenance: webhook.provenance.clone(),
+        actor: None,
+        trace_context: Some(TraceContext {
+            correlation_id: trace.correlation_id.clone(),
+            causation_id: trace.causation_id.clone(),
+            traceparent: trace.traceparent.clone(),
+            tracestate: trace.tracestate.clone(),
+        }),
+    }
+}
+
+fn signature_error_response(error: SignatureError) -> ApiError {
+    let code = match &error {
+        SignatureError::MissingHeader(_)
+        | SignatureError::MalformedHeader { .. }
+        | SignatureError::MalformedBody(_) => ErrorCode::WebhookSignatureMissing,
+        SignatureError::OutsideTolerance { .. } => ErrorCode::WebhookSignatureExpired,
+        SignatureError::Replayed { .. } => {
+            return ApiError::conflict(ErrorCode::WebhookReplayed).with_message(error.to_string())
+        }
+        SignatureError::NoActiveSecret | SignatureError::Mismatch => {
+            ErrorCode::WebhookSignatureInvalid
+        }
+    };
+    ApiError::new(StatusCode::UNAUTHORIZED, code).with_message(error.to_string())
+}
+
+/// Transient failures are 503s the source should redeliver; anything else will fail the same way
+/// again.
+pub(crate) fn normalization_error_response(error: ConnectorError) -> ApiError {
+    let api_error = match error.class() {
+        FailureClass::Transient => ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::WebhookNormalizationUnavailable,
+        ),
+        FailureClass::Permanent => ApiError::bad_request(ErrorCode::WebhookNormalizationFailed),
+    };
+    api_error.with_message(error.to_string())
+}
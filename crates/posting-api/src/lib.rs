@@ -1,15 +1,15 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path as FsPath;
 use std::sync::{Arc, Mutex};
 
-use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
 use axum::routing::{get, post};
-use axum::{Json, Router};
+use axum::{middleware, Json, Router};
 use chrono::NaiveDate;
 use ledger_posting::{
-    EntrySide, InMemoryJournalRepository, JournalHeader, JournalLine, JournalRecord, JournalStatus,
-    LedgerError,
+    validate_balanced, EntrySide, InMemoryJournalRepository, JournalHeader, JournalLine,
+    JournalRecord, JournalStatus, LedgerError, TraceContext,
 };
 use platform_core::{
     evaluate_no_bend_readiness, payload_hash, AuditSealError, IdempotencyError, IdempotencyStatus,
@@ -17,15 +17,64 @@ use platform_core::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::period::{InMemoryPeriodRepository, PeriodError};
-use crate::rule_engine::{derive_lines_v1, RuleEngineError};
-
+use crate::agency::{prepare_agency_event, InMemorySupplierTermsRepository};
+use crate::calendar::{CalendarError, FiscalCalendar};
+use crate::change_feed::{
+    change_feed_error_response, ChangeFeedError, LedgerChangeFeed, LedgerChangeType,
+};
+use crate::close::{seal_hard_close, InMemoryCloseChecklistRepository};
+use crate::config::{
+    BookRequirement, ConfigError, EntityRegistry, PostingPolicySet, ServerConfig, WebhookConfig,
+};
+use crate::dead_letter::InMemoryDeadLetterRepository;
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::fx::{fx_error_response, FxError, FxRateSet, FxRateType, InMemoryFxRateRepository};
+use crate::ingest::{webhook_sources, WebhookSource};
+use crate::passes::{
+    pass_error_response, prepare_pass_event, InMemoryPassRepository, PassEvent, PassUpdate,
+};
+use crate::period::{
+    Clock, InMemoryPeriodRepository, PeriodActor, PeriodError, PeriodState, PostingAuthority,
+    PostingClass,
+};
+use crate::revrec::{
+    revrec_error_response, schedule_from_capture, InMemoryRevRecScheduleRepository,
+    RecognitionSchedule,
+};
+use crate::rule_engine::{DerivedPostingLine, RuleEngineError, RuleSetError, RuleSetRegistry};
+use crate::stored_value::{
+    moves_card_balance, prepare_stored_value_event, stored_value_error_response, CardUpdate,
+    InMemoryStoredValueRepository, StoredValueEvent, CROSS_ENTITY_REDEEMED_EVENT,
+};
+use crate::year_end::{is_closing_journal, InMemoryYearEndCloseRepository};
+
+pub mod agency;
+pub mod backfill;
+pub mod bulk;
+pub mod calendar;
+pub mod change_feed;
+pub mod close;
+pub mod config;
+pub mod dead_letter;
+pub mod error;
+pub mod fx;
+pub mod ingest;
+pub mod openapi;
+pub mod passes;
 pub mod period;
+mod persistence;
+pub mod preview;
+pub mod prior_period;
+pub mod revrec;
 pub mod rule_engine;
+pub mod stored_value;
+pub mod tax;
+pub mod year_end;
 
-type ApiError = (StatusCode, serde_json::Value);
 const CAPACITY_TARGET_ACTIVE_USERS: u32 = 2000;
 const CAPACITY_LINEARITY_RATIO_MIN: f64 = 0.80;
 const CAPACITY_BURST_RPS: u32 = 500;
@@ -58,19 +107,60 @@ pub struct AppState {
     idempotency: InMemoryIdempotencyStore,
     journals: Arc<Mutex<InMemoryJournalRepository>>,
     periods: Arc<Mutex<InMemoryPeriodRepository>>,
+    close_checklists: Arc<Mutex<InMemoryCloseChecklistRepository>>,
+    year_end_closes: Arc<Mutex<InMemoryYearEndCloseRepository>>,
+    dead_letters: Arc<Mutex<InMemoryDeadLetterRepository>>,
+    revrec_schedules: Arc<Mutex<InMemoryRevRecScheduleRepository>>,
+    passes: Arc<Mutex<InMemoryPassRepository>>,
+    stored_value: Arc<Mutex<InMemoryStoredValueRepository>>,
+    fx_rates: Arc<Mutex<InMemoryFxRateRepository>>,
+    supplier_terms: Arc<Mutex<InMemorySupplierTermsRepository>>,
     post_results: Arc<Mutex<HashMap<String, CachedPostResult>>>,
     location_allowlist_by_legal_entity: Arc<HashMap<String, HashSet<String>>>,
+    book_requirements_by_legal_entity: Arc<HashMap<String, Vec<BookRequirement>>>,
+    base_currency_by_legal_entity: Arc<HashMap<String, String>>,
+    chart_of_accounts_by_legal_entity: Arc<HashMap<String, HashSet<String>>>,
     audit_seals: InMemoryAuditSealStore,
     legal_holds: Arc<Mutex<HashMap<String, LegalHoldRule>>>,
+    posting_policies: Option<Arc<PostingPolicySet>>,
+    rule_sets: Arc<RuleSetRegistry>,
+    webhook_sources: Arc<HashMap<String, Arc<WebhookSource>>>,
+    change_feed: LedgerChangeFeed,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum IntegrityError {
+    #[error("{0} store lock poisoned")]
+    StorePoisoned(&'static str),
+    #[error("audit seal verification failed: {0}")]
+    AuditSeal(#[from] AuditSealError),
+    #[error("ledger change feed verification failed: {0}")]
+    ChangeFeed(#[from] ChangeFeedError),
+    #[error("journal {journal_id} failed balance check: {source}")]
+    Journal {
+        journal_id: Uuid,
+        source: LedgerError,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum StartupError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("failed to open persistence directory: {0}")]
+    Persistence(#[from] std::io::Error),
+    #[error("failed to load posting rule sets: {0}")]
+    RuleSet(#[from] RuleSetError),
+    #[error("failed to load fx rate sets: {0}")]
+    FxRateSet(#[from] FxError),
+    #[error("persisted stores failed integrity checks: {0}")]
+    Integrity(#[from] IntegrityError),
 }
 
 #[derive(Clone)]
 enum CachedPostResult {
     Success(PostEventResponse),
-    Failure {
-        status: StatusCode,
-        body: serde_json::Value,
-    },
+    Failure(ApiError),
 }
 
 impl Default for AppState {
@@ -79,10 +169,25 @@ impl Default for AppState {
             idempotency: InMemoryIdempotencyStore::default(),
             journals: Arc::new(Mutex::new(InMemoryJournalRepository::default())),
             periods: Arc::new(Mutex::new(InMemoryPeriodRepository::default())),
+            close_checklists: Arc::new(Mutex::new(InMemoryCloseChecklistRepository::default())),
+            year_end_closes: Arc::new(Mutex::new(InMemoryYearEndCloseRepository::default())),
+            dead_letters: Arc::new(Mutex::new(InMemoryDeadLetterRepository::default())),
+            revrec_schedules: Arc::new(Mutex::new(InMemoryRevRecScheduleRepository::default())),
+            passes: Arc::new(Mutex::new(InMemoryPassRepository::default())),
+            stored_value: Arc::new(Mutex::new(InMemoryStoredValueRepository::default())),
+            fx_rates: Arc::new(Mutex::new(InMemoryFxRateRepository::default())),
+            supplier_terms: Arc::new(Mutex::new(InMemorySupplierTermsRepository::default())),
             post_results: Arc::new(Mutex::new(HashMap::new())),
             location_allowlist_by_legal_entity: Arc::new(default_location_allowlist()),
+            book_requirements_by_legal_entity: Arc::new(default_book_requirements()),
+            base_currency_by_legal_entity: Arc::new(default_base_currencies()),
+            chart_of_accounts_by_legal_entity: Arc::new(HashMap::new()),
             audit_seals: InMemoryAuditSealStore::default(),
             legal_holds: Arc::new(Mutex::new(HashMap::new())),
+            posting_policies: None,
+            rule_sets: Arc::new(RuleSetRegistry::default()),
+            webhook_sources: Arc::new(HashMap::new()),
+            change_feed: LedgerChangeFeed::default(),
         }
     }
 }
@@ -100,33 +205,255 @@ fn default_location_allowlist() -> HashMap<String, HashSet<String>> {
     ])
 }
 
+fn default_base_currencies() -> HashMap<String, String> {
+    HashMap::from([
+        ("US_CO_01".to_string(), "USD".to_string()),
+        ("CA_BC_01".to_string(), "CAD".to_string()),
+    ])
+}
+
+fn default_book_requirements() -> HashMap<String, Vec<BookRequirement>> {
+    let dual_book = ["US_GAAP", "IFRS"]
+        .into_iter()
+        .map(|ledger_book| BookRequirement {
+            ledger_book: ledger_book.to_string(),
+            book_policy_id: "policy_dual_book".to_string(),
+            policy_version: "1.0.0".to_string(),
+        })
+        .collect::<Vec<_>>();
+    HashMap::from([
+        ("US_CO_01".to_string(), dual_book.clone()),
+        ("CA_BC_01".to_string(), dual_book),
+    ])
+}
+
 impl AppState {
     pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> std::io::Result<Self> {
         let dir = dir.as_ref();
+        let journals = InMemoryJournalRepository::with_persistence_dir(dir)?;
+        let change_feed = LedgerChangeFeed::with_persistence_dir(dir)?;
+        change_feed
+            .backfill_missing(&journals.all())
+            .map_err(|error| std::io::Error::other(error.to_string()))?;
         Ok(Self {
             idempotency: InMemoryIdempotencyStore::with_persistence_dir(dir)?,
-            journals: Arc::new(Mutex::new(InMemoryJournalRepository::with_persistence_dir(
+            journals: Arc::new(Mutex::new(journals)),
+            periods: Arc::new(Mutex::new(InMemoryPeriodRepository::with_persistence_dir(
                 dir,
             )?)),
-            periods: Arc::new(Mutex::new(InMemoryPeriodRepository::with_persistence_dir(
+            close_checklists: Arc::new(Mutex::new(
+                InMemoryCloseChecklistRepository::with_persistence_dir(dir)?,
+            )),
+            year_end_closes: Arc::new(Mutex::new(
+                InMemoryYearEndCloseRepository::with_persistence_dir(dir)?,
+            )),
+            dead_letters: Arc::new(Mutex::new(
+                InMemoryDeadLetterRepository::with_persistence_dir(dir)?,
+            )),
+            revrec_schedules: Arc::new(Mutex::new(
+                InMemoryRevRecScheduleRepository::with_persistence_dir(dir)?,
+            )),
+            passes: Arc::new(Mutex::new(InMemoryPassRepository::with_persistence_dir(
                 dir,
             )?)),
+            stored_value: Arc::new(Mutex::new(
+                InMemoryStoredValueRepository::with_persistence_dir(dir)?,
+            )),
+            fx_rates: Arc::new(Mutex::new(InMemoryFxRateRepository::with_persistence_dir(
+                dir,
+            )?)),
+            supplier_terms: Arc::new(Mutex::new(
+                InMemorySupplierTermsRepository::with_persistence_dir(dir)?,
+            )),
             post_results: Arc::new(Mutex::new(HashMap::new())),
             location_allowlist_by_legal_entity: Arc::new(default_location_allowlist()),
+            book_requirements_by_legal_entity: Arc::new(default_book_requirements()),
+            base_currency_by_legal_entity: Arc::new(default_base_currencies()),
+            chart_of_accounts_by_legal_entity: Arc::new(HashMap::new()),
             audit_seals: InMemoryAuditSealStore::with_persistence_dir(dir)?,
             legal_holds: Arc::new(Mutex::new(HashMap::new())),
+            posting_policies: None,
+            rule_sets: Arc::new(RuleSetRegistry::default()),
+            webhook_sources: Arc::new(HashMap::new()),
+            change_feed,
         })
     }
 
+    pub fn from_config(config: &ServerConfig) -> Result<Self, StartupError> {
+        let mut state = match config.persistence_dir.as_deref() {
+            Some(dir) => Self::with_persistence_dir(dir)?,
+            None => Self::default(),
+        };
+        if let Some(path) = config.entity_registry_path.as_deref() {
+            let registry = EntityRegistry::load(path)?;
+            let book_requirements = registry.book_requirements()?;
+            let base_currencies = registry.base_currencies();
+            let charts_of_accounts = registry.charts_of_accounts();
+            let fiscal_calendars = registry.fiscal_calendars()?;
+            state = state
+                .with_location_allowlist(registry.into_location_allowlist()?)
+                .with_book_requirements(book_requirements)
+                .with_base_currencies(base_currencies)
+                .with_charts_of_accounts(charts_of_accounts)
+                .with_fiscal_calendars(fiscal_calendars);
+        }
+        if let Some(path) = config.policy_path.as_deref() {
+            state = state.with_posting_policies(PostingPolicySet::load(path)?);
+        }
+        if let Some(dir) = config.rule_set_dir.as_deref() {
+            state = state.with_rule_sets(RuleSetRegistry::load_dir(dir)?);
+        }
+        if let Some(dir) = config.fx_rate_set_dir.as_deref() {
+            state = state.with_fx_rate_sets(FxRateSet::load_dir(dir)?)?;
+        }
+        if let Some(path) = config.webhook_config_path.as_deref() {
+            state = state.with_webhook_sources(webhook_sources(WebhookConfig::load(path)?)?);
+        }
+        state.verify_integrity()?;
+        Ok(state)
+    }
+
+    pub fn with_location_allowlist(mut self, allowlist: HashMap<String, HashSet<String>>) -> Self {
+        self.location_allowlist_by_legal_entity = Arc::new(allowlist);
+        self
+    }
+
+    pub fn with_book_requirements(
+        mut self,
+        requirements: HashMap<String, Vec<BookRequirement>>,
+    ) -> Self {
+        self.book_requirements_by_legal_entity = Arc::new(requirements);
+        self
+    }
+
+    pub fn with_webhook_sources(mut self, sources: HashMap<String, Arc<WebhookSource>>) -> Self {
+        self.webhook_sources = Arc::new(sources);
+        self
+    }
+
+    pub fn with_base_currencies(mut self, base_currencies: HashMap<String, String>) -> Self {
+        self.base_currency_by_legal_entity = Arc::new(base_currencies);
+        self
+    }
+
+    pub fn with_charts_of_accounts(mut self, charts: HashMap<String, HashSet<String>>) -> Self {
+        self.chart_of_accounts_by_legal_entity = Arc::new(charts);
+        self
+    }
+
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        self.periods
+            .lock()
+            .expect("period store lock should work")
+            .set_clock(clock);
+        self
+    }
+
+    pub fn with_fiscal_calendars(self, calendars: HashMap<String, FiscalCalendar>) -> Self {
+        self.periods
+            .lock()
+            .expect("period store lock should work")
+            .set_fiscal_calendars(calendars);
+        self
+    }
+
+    pub fn with_fx_rate_sets(self, rate_sets: Vec<FxRateSet>) -> Result<Self, FxError> {
+        {
+            let mut fx_rates = self
+                .fx_rates
+                .lock()
+                .expect("fx rate store lock should work");
+            for rate_set in rate_sets {
+                fx_rates.register(rate_set)?;
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn with_posting_policies(mut self, policies: PostingPolicySet) -> Self {
+        self.posting_policies = Some(Arc::new(policies));
+        self
+    }
+
+    pub fn with_rule_sets(mut self, rule_sets: RuleSetRegistry) -> Self {
+        self.rule_sets = Arc::new(rule_sets);
+        self
+    }
+
+    pub fn verify_integrity(&self) -> Result<(), IntegrityError> {
+        self.audit_seals.verify_chain()?;
+        self.change_feed.verify_offsets()?;
+        let journals = self
+            .journals
+            .lock()
+            .map_err(|_| IntegrityError::StorePoisoned("journal"))?;
+        for record in journals.all() {
+            validate_balanced(&record.lines).map_err(|source| IntegrityError::Journal {
+                journal_id: record.header.journal_id,
+                source,
+            })?;
+        }
+        Ok(())
+    }
+
     pub fn flush_persistence(&self) -> std::io::Result<()> {
         self.idempotency.flush_persistence()?;
         self.audit_seals.flush_persistence()?;
+        self.change_feed.flush_persistence()?;
         let journals = self
             .journals
             .lock()
             .map_err(|_| std::io::Error::other("journal store lock poisoned"))?;
         journals.flush_persistence()?;
         drop(journals);
+        let schedules = self
+            .revrec_schedules
+            .lock()
+            .map_err(|_| std::io::Error::other("revrec schedule store lock poisoned"))?;
+        schedules.flush_persistence()?;
+        drop(schedules);
+        let passes = self
+            .passes
+            .lock()
+            .map_err(|_| std::io::Error::other("pass store lock poisoned"))?;
+        passes.flush_persistence()?;
+        drop(passes);
+        let stored_value = self
+            .stored_value
+            .lock()
+            .map_err(|_| std::io::Error::other("stored value store lock poisoned"))?;
+        stored_value.flush_persistence()?;
+        drop(stored_value);
+        let fx_rates = self
+            .fx_rates
+            .lock()
+            .map_err(|_| std::io::Error::other("fx rate store lock poisoned"))?;
+        fx_rates.flush_persistence()?;
+        drop(fx_rates);
+        let supplier_terms = self
+            .supplier_terms
+            .lock()
+            .map_err(|_| std::io::Error::other("supplier terms store lock poisoned"))?;
+        supplier_terms.flush_persistence()?;
+        drop(supplier_terms);
+        let close_checklists = self
+            .close_checklists
+            .lock()
+            .map_err(|_| std::io::Error::other("close checklist store lock poisoned"))?;
+        close_checklists.flush_persistence()?;
+        drop(close_checklists);
+        let year_end_closes = self
+            .year_end_closes
+            .lock()
+            .map_err(|_| std::io::Error::other("year-end close store lock poisoned"))?;
+        year_end_closes.flush_persistence()?;
+        drop(year_end_closes);
+        let dead_letters = self
+            .dead_letters
+            .lock()
+            .map_err(|_| std::io::Error::other("dead letter store lock poisoned"))?;
+        dead_letters.flush_persistence()?;
+        drop(dead_letters);
         let periods = self
             .periods
             .lock()
@@ -134,55 +461,112 @@ impl AppState {
         periods.flush_persistence()
     }
 
-    pub fn lock_period(
+    fn cache_post_result(&self, key: &str, result: CachedPostResult) -> Result<(), ApiError> {
+        let mut cache = self
+            .post_results
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::IdempotencyResultStoreError))?;
+        cache.insert(key.to_string(), result);
+        Ok(())
+    }
+
+    fn get_cached_post_result(&self, key: &str) -> Result<Option<CachedPostResult>, ApiError> {
+        let cache = self
+            .post_results
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::IdempotencyResultStoreError))?;
+        Ok(cache.get(key).cloned())
+    }
+
+    fn record_change(
         &self,
-        tenant_id: &str,
-        legal_entity_id: &str,
-        ledger_book: &str,
-        period_id: &str,
-    ) -> Result<(), PeriodError> {
-        let mut repo = self.periods.lock().expect("period store lock should work");
-        repo.lock_period(tenant_id, legal_entity_id, ledger_book, period_id)
+        change_type: LedgerChangeType,
+        header: &JournalHeader,
+        related_journal_id: Option<String>,
+    ) -> Result<(), ApiError> {
+        self.change_feed
+            .append(change_type, header, related_journal_id)
+            .map(|_| ())
+            .map_err(change_feed_error_response)
     }
 
-    fn cache_post_result(
+    fn required_books(&self, legal_entity_id: &str) -> Result<&[BookRequirement], ApiError> {
+        self.book_requirements_by_legal_entity
+            .get(legal_entity_id)
+            .map(Vec::as_slice)
+            .ok_or_else(|| {
+                ApiError::bad_request(ErrorCode::NoBookPolicyForLegalEntity)
+                    .with_detail("legal_entity_id", legal_entity_id)
+            })
+    }
+
+    fn validate_posting_policy(
         &self,
-        key: &str,
-        result: CachedPostResult,
-    ) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
-        let mut cache = self.post_results.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "idempotency_result_store_error"})),
-            )
-        })?;
-        cache.insert(key.to_string(), result);
+        provenance: &Provenance,
+        ledger_book: &str,
+    ) -> Result<(), ApiError> {
+        let Some(policies) = self.posting_policies.as_deref() else {
+            return Ok(());
+        };
+        if !policies.allows(
+            &provenance.book_policy_id,
+            &provenance.policy_version,
+            ledger_book,
+        ) {
+            return Err(ApiError::bad_request(ErrorCode::BookPolicyNotAllowed)
+                .with_detail("book_policy_id", provenance.book_policy_id.clone())
+                .with_detail("policy_version", provenance.policy_version.clone())
+                .with_detail("ledger_book", ledger_book));
+        }
         Ok(())
     }
 
-    fn get_cached_post_result(
+    fn validate_chart_of_accounts(
         &self,
-        key: &str,
-    ) -> Result<Option<CachedPostResult>, (StatusCode, Json<serde_json::Value>)> {
-        let cache = self.post_results.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "idempotency_result_store_error"})),
-            )
-        })?;
-        Ok(cache.get(key).cloned())
+        legal_entity_id: &str,
+        lines: &[JournalLine],
+    ) -> Result<(), ApiError> {
+        let Some(chart) = self.chart_of_accounts_by_legal_entity.get(legal_entity_id) else {
+            return Ok(());
+        };
+        match lines.iter().find(|line| !chart.contains(&line.account_id)) {
+            Some(line) => Err(ApiError::bad_request(ErrorCode::AccountNotInChart)
+                .with_detail("legal_entity_id", legal_entity_id)
+                .with_detail("account_id", line.account_id.as_str())
+                .with_detail("line_number", line.line_number)),
+            None => Ok(()),
+        }
     }
 
-    fn upsert_legal_hold(
+    fn translate_to_base(
         &self,
-        rule: LegalHoldRule,
-    ) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
-        let mut holds = self.legal_holds.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "legal_hold_store_error"})),
+        provenance: &Provenance,
+        legal_entity_id: &str,
+        accounting_date: NaiveDate,
+        lines: &mut [JournalLine],
+    ) -> Result<(), ApiError> {
+        let Some(base_currency) = self.base_currency_by_legal_entity.get(legal_entity_id) else {
+            return Err(ApiError::bad_request(ErrorCode::BaseCurrencyNotConfigured)
+                .with_detail("legal_entity_id", legal_entity_id));
+        };
+        self.fx_rates
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::FxRateStoreError))?
+            .translate(
+                &provenance.fx_rate_set_id,
+                provenance.fx_rate_type.unwrap_or_default(),
+                accounting_date,
+                base_currency,
+                lines,
             )
-        })?;
+            .map_err(fx_error_response)
+    }
+
+    fn upsert_legal_hold(&self, rule: LegalHoldRule) -> Result<(), ApiError> {
+        let mut holds = self
+            .legal_holds
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::LegalHoldStoreError))?;
         holds.insert(
             legal_hold_key(&rule.tenant_id, &rule.legal_entity_id, &rule.ledger_book),
             rule,
@@ -197,24 +581,17 @@ impl AppState {
         ledger_book: &str,
         accounting_date: NaiveDate,
     ) -> Result<(), ApiError> {
-        let holds = self.legal_holds.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                json!({"error": "legal_hold_store_error"}),
-            )
-        })?;
+        let holds = self
+            .legal_holds
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::LegalHoldStoreError))?;
         let key = legal_hold_key(tenant_id, legal_entity_id, ledger_book);
         if let Some(rule) = holds.get(&key) {
             if rule.applies_to(accounting_date) {
-                return Err((
-                    StatusCode::CONFLICT,
-                    json!({
-                        "error": "legal_hold_active",
-                        "hold_id": rule.hold_id,
-                        "reason": rule.reason,
-                        "retention_days": rule.retention_days,
-                    }),
-                ));
+                return Err(ApiError::conflict(ErrorCode::LegalHoldActive)
+                    .with_detail("hold_id", rule.hold_id.clone())
+                    .with_detail("reason", rule.reason.clone())
+                    .with_detail("retention_days", rule.retention_days));
             }
         }
         Ok(())
@@ -230,20 +607,7 @@ impl AppState {
         self.audit_seals
             .append(event_type, entity_scope, payload, created_at_ns)
             .map(|entry| entry.seal)
-            .map_err(|error| match error {
-                AuditSealError::StorePoisoned => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    json!({"error": "audit_seal_store_error"}),
-                ),
-                AuditSealError::ChainBroken { sequence } => (
-                    StatusCode::CONFLICT,
-                    json!({"error": "audit_seal_chain_broken", "sequence": sequence}),
-                ),
-                AuditSealError::Tampered { sequence } => (
-                    StatusCode::CONFLICT,
-                    json!({"error": "audit_seal_tampered", "sequence": sequence}),
-                ),
-            })
+            .map_err(audit_seal_error_response)
     }
 }
 
@@ -251,25 +615,41 @@ fn legal_hold_key(tenant_id: &str, legal_entity_id: &str, ledger_book: &str) ->
     format!("{tenant_id}::{legal_entity_id}::{ledger_book}")
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct PostEventRequest {
     pub event_type: String,
     pub tenant_id: String,
     pub legal_entity_id: String,
     #[serde(default)]
     pub location_id: Option<String>,
+    #[serde(default)]
     pub ledger_book: String,
     pub accounting_date: String,
     pub source_event_id: String,
     pub posting_run_id: String,
     #[serde(default)]
+    #[schema(value_type = Object)]
     pub payload: serde_json::Value,
     #[serde(default)]
     pub lines: Vec<PostLine>,
     pub provenance: Provenance,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub trace_context: Option<TraceContext>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl PostEventRequest {
+    /// Posted events are operational whether they carry a payload or their own lines: the actor
+    /// is not authenticated, so it cannot lift an event into the adjustment class. Manual
+    /// entries go through the adjustment endpoints.
+    fn posting_authority(&self) -> PostingAuthority {
+        PostingAuthority::new(PostingClass::Operational, self.actor.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct PostLine {
     pub account_id: String,
     pub entry_side: String,
@@ -279,42 +659,61 @@ pub struct PostLine {
     pub base_currency: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct Provenance {
     pub book_policy_id: String,
     pub policy_version: String,
     pub fx_rate_set_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fx_rate_type: Option<FxRateType>,
     pub ruleset_version: String,
     pub workflow_id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct PostEventResponse {
     pub journal_id: String,
     pub status: String,
     pub replayed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(required = false)]
+    pub book_journals: Vec<BookJournal>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct BookJournal {
+    pub ledger_book: String,
+    pub journal_id: String,
+    pub book_policy_id: String,
+    pub policy_version: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ReverseJournalResponse {
     pub journal_id: String,
     pub status: String,
+    /// Every book journal the reversal covered, when the event was posted to several books.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(required = false)]
+    pub book_journals: Vec<BookJournal>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct LockPeriodRequest {
     pub tenant_id: String,
     pub legal_entity_id: String,
     pub ledger_book: String,
+    pub actor: PeriodActor,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
 pub struct LockPeriodResponse {
     pub period_id: String,
     pub status: String,
+    pub audit_seal: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct UpsertLegalHoldRequest {
     pub hold_id: String,
     pub tenant_id: String,
@@ -328,13 +727,13 @@ pub struct UpsertLegalHoldRequest {
     pub retention_days: u32,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
 pub struct UpsertLegalHoldResponse {
     pub hold_id: String,
     pub status: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct AdjustJournalRequest {
     pub tenant_id: String,
     pub legal_entity_id: String,
@@ -347,9 +746,11 @@ pub struct AdjustJournalRequest {
     pub location_id: Option<String>,
     pub lines: Vec<PostLine>,
     pub provenance: Provenance,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
 pub struct AdjustJournalResponse {
     pub reversed_journal_id: String,
     pub replacement_journal_id: String,
@@ -357,20 +758,26 @@ pub struct AdjustJournalResponse {
     pub audit_seal: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RevRecQuery {
     pub book: String,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, PartialEq, Eq, ToSchema)]
 pub struct RevRecRollforwardResponse {
     pub book: String,
+    pub period: Option<String>,
     pub journal_count: u32,
     pub recognized_revenue_minor: i64,
+    pub opening_deferred_minor: i64,
+    pub additions_minor: i64,
+    pub recognized_minor: i64,
+    pub released_minor: i64,
     pub deferred_revenue_ending_minor: i64,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, PartialEq, Eq, ToSchema)]
 pub struct RevRecDisclosureResponse {
     pub book: String,
     pub journal_count: u32,
@@ -379,20 +786,31 @@ pub struct RevRecDisclosureResponse {
     pub fx_rate_sets: Vec<String>,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, PartialEq, Eq, ToSchema)]
 pub struct AuditSealVerifyResponse {
     pub status: String,
     pub entries: usize,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Serialize, PartialEq, ToSchema)]
+pub struct SloResponse {
+    pub availability_target: String,
+    pub read_p95_ms: u32,
+    pub write_p95_ms: u32,
+    pub error_rate_max: f64,
+    pub no_bend_efficiency_min: f64,
+}
+
+#[derive(Debug, Serialize, PartialEq, ToSchema)]
 pub struct CapacityInstrumentationResponse {
     pub target_active_users: u32,
     pub baseline_rps: u32,
     pub peak_rps: u32,
     pub burst_rps: u32,
     pub readiness_status: String,
+    #[schema(value_type = Object)]
     pub no_bend_readiness: NoBendReadiness,
+    #[schema(value_type = Vec<Object>)]
     pub scale_samples: Vec<ScaleSample>,
 }
 
@@ -410,7 +828,31 @@ pub fn router_with_persistence_dir(dir: impl AsRef<FsPath>) -> std::io::Result<R
 
 pub fn router_with_state(state: AppState) -> Router {
     Router::new()
+        .route("/v1/openapi.json", get(openapi::get_openapi_document))
         .route("/v1/posting/events", post(post_event))
+        .route("/v1/posting/events/bulk", post(bulk::post_events_bulk))
+        .route("/v1/ingest/:source", post(ingest::ingest_webhook))
+        .route(
+            "/v1/ingest/dead-letters",
+            get(dead_letter::list_dead_letters),
+        )
+        .route(
+            "/v1/ingest/dead-letters/replay",
+            post(dead_letter::replay_dead_letters),
+        )
+        .route(
+            "/v1/ingest/dead-letters/:dead_letter_id",
+            get(dead_letter::get_dead_letter),
+        )
+        .route(
+            "/v1/ingest/dead-letters/:dead_letter_id/retry",
+            post(dead_letter::retry_dead_letter),
+        )
+        .route(
+            "/v1/ingest/dead-letters/:dead_letter_id/discard",
+            post(dead_letter::discard_dead_letter),
+        )
+        .route("/v1/posting/preview", post(preview::preview_posting))
         .route(
             "/v1/compliance/legal-holds",
             post(upsert_legal_hold_endpoint),
@@ -427,141 +869,215 @@ pub fn router_with_state(state: AppState) -> Router {
             "/v1/ledger/journals/:journal_id/adjust",
             post(adjust_journal),
         )
+        .route("/v1/ledger/changes", get(change_feed::get_ledger_changes))
+        .route(
+            "/v1/ledger/changes/stream",
+            get(change_feed::stream_ledger_changes),
+        )
         .route(
             "/v1/ledger/periods/:period_id/lock",
             post(lock_period_endpoint),
         )
-        .route("/v1/revrec/rollforward", get(get_revrec_rollforward))
+        .route("/v1/ledger/periods", get(period::list_periods))
+        .route(
+            "/v1/ledger/prior-period-adjustments",
+            post(prior_period::post_prior_period_adjustment),
+        )
+        .route(
+            "/v1/ledger/period-results",
+            get(prior_period::get_period_results),
+        )
+        .route(
+            "/v1/ledger/year-end-closes",
+            post(year_end::close_fiscal_year),
+        )
+        .route(
+            "/v1/ledger/year-end-closes/:legal_entity_id/:fiscal_year",
+            get(year_end::get_year_end_close),
+        )
+        .route(
+            "/v1/ledger/year-end-closes/:legal_entity_id/:fiscal_year/reverse",
+            post(year_end::reverse_year_end_close),
+        )
+        .route("/v1/close/checklists", post(close::upsert_close_checklist))
+        .route(
+            "/v1/close/checklists/:legal_entity_id/:period_id",
+            get(close::get_close_checklist),
+        )
+        .route(
+            "/v1/close/checklists/:legal_entity_id/:period_id/dependencies/:dependency_id",
+            post(close::transition_close_dependency),
+        )
+        .route(
+            "/v1/ledger/periods/:period_id/transitions",
+            post(period::transition_period),
+        )
+        .route(
+            "/v1/ledger/fiscal-calendars/:legal_entity_id",
+            get(calendar::get_fiscal_calendar),
+        )
+        .route(
+            "/v1/revrec/rollforward",
+            get(revrec::get_revrec_rollforward),
+        )
         .route("/v1/revrec/disclosures", get(get_revrec_disclosures))
+        .route(
+            "/v1/revrec/schedules",
+            get(revrec::list_recognition_schedules),
+        )
+        .route(
+            "/v1/revrec/schedules/:schedule_id/modify",
+            post(revrec::modify_recognition_schedule),
+        )
+        .route(
+            "/v1/revrec/schedules/:schedule_id/cancel",
+            post(revrec::cancel_recognition_schedule),
+        )
+        .route("/v1/revrec/recognition-runs", post(revrec::run_recognition))
+        .route("/v1/passes", get(passes::list_passes))
+        .route(
+            "/v1/passes/breakage-assumptions",
+            get(passes::list_breakage_assumptions).post(passes::register_breakage_assumptions),
+        )
+        .route("/v1/stored-value/cards", get(stored_value::list_cards))
+        .route(
+            "/v1/stored-value/reconciliation",
+            get(stored_value::get_reconciliation),
+        )
+        .route(
+            "/v1/suppliers/terms",
+            get(agency::list_supplier_terms).post(agency::register_supplier_terms),
+        )
+        .route("/v1/suppliers/payables", get(agency::get_supplier_payables))
+        .route("/v1/tax/liabilities", get(tax::get_tax_liabilities))
+        .route(
+            "/v1/fx/rate-sets",
+            get(fx::list_rate_sets).post(fx::register_rate_set),
+        )
+        .route("/v1/fx/rate-sets/:fx_rate_set_id", get(fx::get_rate_set))
         .route("/v1/ops/slo", get(get_slo))
         .route("/v1/ops/capacity", get(get_capacity))
+        .fallback(error::route_not_found)
+        .layer(middleware::from_fn(error::correlation_middleware))
         .with_state(state)
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/posting/events",
+    tag = "posting",
+    params(("Idempotency-Key" = String, Header, description = "Client idempotency key")),
+    request_body = PostEventRequest,
+    responses(
+        (status = 200, description = "Journal posted or replayed", body = PostEventResponse),
+        (status = 400, description = "Invalid event", body = ErrorEnvelope),
+        (status = 409, description = "Idempotency, period, hold or journal conflict", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn post_event(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Json(req): Json<PostEventRequest>,
-) -> Result<Json<PostEventResponse>, (StatusCode, Json<serde_json::Value>)> {
-    if ![
-        "order.captured.v1",
-        "payment.settled.v1",
-        "refund.v1",
-        "fee.assessed.v1",
-        "chargeback.created.v1",
-        "payout.cleared.v1",
-        "dispute.opened.v1",
-        "dispute.won.v1",
-        "dispute.lost.v1",
-        "inntopia.reservation.captured.v1",
-        "intercompany.due_to_due_from.v1",
-        "consolidation.elimination.v1",
-        "fx.translation.v1",
-    ]
-    .contains(&req.event_type.as_str())
-    {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "unsupported_event_type"})),
-        ));
-    }
+    ApiJson(req): ApiJson<PostEventRequest>,
+) -> Result<Json<PostEventResponse>, ApiError> {
+    ensure_supported_event_type(&state, &req)?;
 
     let key = headers
         .get("Idempotency-Key")
         .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "missing_idempotency_key"})),
-            )
-        })?;
+        .ok_or_else(|| ApiError::bad_request(ErrorCode::MissingIdempotencyKey))?;
 
-    let payload = serde_json::to_value(&req).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "invalid_payload"})),
-        )
-    })?;
+    post_event_with_idempotency_key(&state, key, req).map(Json)
+}
+
+fn ensure_supported_event_type(state: &AppState, req: &PostEventRequest) -> Result<(), ApiError> {
+    let rule_set = state
+        .rule_sets
+        .get(&req.provenance.ruleset_version)
+        .map_err(rule_engine_error_response)?;
+    if !rule_set.supports(&req.event_type) {
+        return Err(ApiError::bad_request(ErrorCode::UnsupportedEventType)
+            .with_detail("event_type", req.event_type.as_str())
+            .with_detail("ruleset_version", rule_set.version()));
+    }
+    Ok(())
+}
+
+fn post_event_with_idempotency_key(
+    state: &AppState,
+    key: &str,
+    req: PostEventRequest,
+) -> Result<PostEventResponse, ApiError> {
+    let payload =
+        serde_json::to_value(&req).map_err(|_| ApiError::bad_request(ErrorCode::InvalidPayload))?;
 
     let idem_status = match state.idempotency.check_or_insert(key, &payload) {
         Ok(status) => status,
         Err(IdempotencyError::PayloadHashMismatch) => {
-            return Err((
-                StatusCode::CONFLICT,
-                Json(json!({"error": "idempotency_payload_mismatch"})),
-            ))
-        }
-        Err(_) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "idempotency_store_error"})),
-            ))
+            return Err(ApiError::conflict(ErrorCode::IdempotencyPayloadMismatch))
         }
+        Err(_) => return Err(ApiError::internal(ErrorCode::IdempotencyStoreError)),
     };
 
-    let journal_uuid = deterministic_journal_id(key, &payload_hash(&payload));
+    let payload_hash = payload_hash(&payload);
     if idem_status == IdempotencyStatus::Replay {
         return match state.get_cached_post_result(key)? {
-            Some(CachedPostResult::Success(previous)) => Ok(Json(PostEventResponse {
-                journal_id: previous.journal_id,
-                status: previous.status,
+            Some(CachedPostResult::Success(previous)) => Ok(PostEventResponse {
                 replayed: true,
-            })),
-            Some(CachedPostResult::Failure { status, body }) => Err((status, Json(body))),
-            None => Ok(Json(PostEventResponse {
-                journal_id: journal_uuid.to_string(),
-                status: "POSTED".to_string(),
-                replayed: true,
-            })),
+                ..previous
+            }),
+            Some(CachedPostResult::Failure(error)) => Err(error),
+            None => uncached_replay_response(state, key, &payload_hash, &req),
         };
     }
 
-    let result = process_first_seen_post(&state, req, journal_uuid).map(Json);
-    match result {
-        Ok(Json(response)) => {
+    let outcome = if req.ledger_book.is_empty() {
+        process_first_seen_multi_book_post(state, key, &payload_hash, req)
+    } else {
+        let journal_uuid = deterministic_journal_id(key, &payload_hash);
+        process_first_seen_post(state, req, journal_uuid)
+    };
+    match outcome {
+        Ok(response) => {
             state.cache_post_result(key, CachedPostResult::Success(response.clone()))?;
-            Ok(Json(response))
-        }
-        Err((status, body)) => {
-            state.cache_post_result(
-                key,
-                CachedPostResult::Failure {
-                    status,
-                    body: body.clone(),
-                },
-            )?;
-            Err((status, Json(body)))
+            Ok(response)
+        }
+        Err(error) => {
+            state.cache_post_result(key, CachedPostResult::Failure(error.clone()))?;
+            Err(error)
         }
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/compliance/legal-holds",
+    tag = "compliance",
+    request_body = UpsertLegalHoldRequest,
+    responses(
+        (status = 200, description = "Legal hold active", body = UpsertLegalHoldResponse),
+        (status = 400, description = "Invalid hold dates", body = ErrorEnvelope),
+        (status = 409, description = "Audit seal chain conflict", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn upsert_legal_hold_endpoint(
     State(state): State<AppState>,
-    Json(req): Json<UpsertLegalHoldRequest>,
-) -> Result<Json<UpsertLegalHoldResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let start_date = NaiveDate::parse_from_str(&req.start_date, "%Y-%m-%d").map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "invalid_start_date"})),
-        )
-    })?;
+    ApiJson(req): ApiJson<UpsertLegalHoldRequest>,
+) -> Result<Json<UpsertLegalHoldResponse>, ApiError> {
+    let start_date = NaiveDate::parse_from_str(&req.start_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidStartDate))?;
     let end_date = req
         .end_date
         .as_deref()
         .map(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
         .transpose()
-        .map_err(|_| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "invalid_end_date"})),
-            )
-        })?;
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidEndDate))?;
     if let Some(value) = end_date.as_ref() {
         if *value < start_date {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "invalid_legal_hold_range"})),
-            ));
+            return Err(ApiError::bad_request(ErrorCode::InvalidLegalHoldRange));
         }
     }
 
@@ -584,23 +1100,21 @@ async fn upsert_legal_hold_endpoint(
         retention_days: req.retention_days,
     };
     state.upsert_legal_hold(rule)?;
-    state
-        .append_audit_seal(
-            "legal_hold.upserted",
-            &[seal_legal_entity_id.clone()],
-            &json!({
-                "hold_id": hold_id,
-                "tenant_id": seal_tenant_id,
-                "legal_entity_id": seal_legal_entity_id,
-                "ledger_book": seal_ledger_book,
-                "start_date": seal_start_date,
-                "end_date": seal_end_date,
-                "retention_days": seal_retention_days,
-                "reason": seal_reason
-            }),
-            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
-        )
-        .map_err(|(status, body)| (status, Json(body)))?;
+    state.append_audit_seal(
+        "legal_hold.upserted",
+        std::slice::from_ref(&seal_legal_entity_id),
+        &json!({
+            "hold_id": hold_id,
+            "tenant_id": seal_tenant_id,
+            "legal_entity_id": seal_legal_entity_id,
+            "ledger_book": seal_ledger_book,
+            "start_date": seal_start_date,
+            "end_date": seal_end_date,
+            "retention_days": seal_retention_days,
+            "reason": seal_reason
+        }),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
 
     Ok(Json(UpsertLegalHoldResponse {
         hold_id,
@@ -608,100 +1122,94 @@ async fn upsert_legal_hold_endpoint(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/compliance/audit-seals/verify",
+    tag = "compliance",
+    responses(
+        (status = 200, description = "Audit seal chain verified", body = AuditSealVerifyResponse),
+        (status = 409, description = "Audit seal chain broken or tampered", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn verify_audit_seals_endpoint(
     State(state): State<AppState>,
-) -> Result<Json<AuditSealVerifyResponse>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<AuditSealVerifyResponse>, ApiError> {
     state
         .audit_seals
         .verify_chain()
-        .map_err(|error| match error {
-            AuditSealError::StorePoisoned => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "audit_seal_store_error"})),
-            ),
-            AuditSealError::ChainBroken { sequence } => (
-                StatusCode::CONFLICT,
-                Json(json!({"error": "audit_seal_chain_broken", "sequence": sequence})),
-            ),
-            AuditSealError::Tampered { sequence } => (
-                StatusCode::CONFLICT,
-                Json(json!({"error": "audit_seal_tampered", "sequence": sequence})),
-            ),
-        })?;
-    let entries = state.audit_seals.len().map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "audit_seal_store_error"})),
-        )
-    })?;
+        .map_err(audit_seal_error_response)?;
+    let entries = state
+        .audit_seals
+        .len()
+        .map_err(|_| ApiError::internal(ErrorCode::AuditSealStoreError))?;
     Ok(Json(AuditSealVerifyResponse {
         status: "VERIFIED".to_string(),
         entries,
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/journals/{journal_id}/adjust",
+    tag = "ledger",
+    params(("journal_id" = String, Path, description = "Journal to reverse and replace")),
+    request_body = AdjustJournalRequest,
+    responses(
+        (status = 200, description = "Journal reversed and replacement posted", body = AdjustJournalResponse),
+        (status = 400, description = "Invalid adjustment", body = ErrorEnvelope),
+        (status = 404, description = "Journal not found", body = ErrorEnvelope),
+        (status = 409, description = "Period, hold or journal conflict", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn adjust_journal(
     State(state): State<AppState>,
     Path(journal_id): Path<String>,
-    Json(req): Json<AdjustJournalRequest>,
-) -> Result<Json<AdjustJournalResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let target_journal_id = Uuid::parse_str(&journal_id).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "invalid_journal_id"})),
-        )
-    })?;
+    ApiJson(req): ApiJson<AdjustJournalRequest>,
+) -> Result<Json<AdjustJournalResponse>, ApiError> {
+    let target_journal_id = Uuid::parse_str(&journal_id)
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidJournalId))?;
     if req.lines.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "missing_adjustment_lines"})),
-        ));
+        return Err(ApiError::bad_request(ErrorCode::MissingAdjustmentLines));
     }
-    let accounting_date =
-        NaiveDate::parse_from_str(&req.accounting_date, "%Y-%m-%d").map_err(|_| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "invalid_accounting_date"})),
-            )
-        })?;
+    let accounting_date = NaiveDate::parse_from_str(&req.accounting_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidAccountingDate))?;
     if let Some(location_id) = req.location_id.as_deref() {
-        validate_location_boundary(&state, &req.legal_entity_id, location_id)
-            .map_err(|(status, body)| (status, Json(body)))?;
+        validate_location_boundary(&state, &req.legal_entity_id, location_id)?;
     }
-    state
-        .validate_legal_hold(
-            &req.tenant_id,
-            &req.legal_entity_id,
-            &req.ledger_book,
-            accounting_date,
-        )
-        .map_err(|(status, body)| (status, Json(body)))?;
+    state.validate_posting_policy(&req.provenance, &req.ledger_book)?;
+    state.validate_legal_hold(
+        &req.tenant_id,
+        &req.legal_entity_id,
+        &req.ledger_book,
+        accounting_date,
+    )?;
     {
-        let periods = state.periods.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "period_store_error"})),
-            )
-        })?;
+        let periods = state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
         periods
             .ensure_open(
                 &req.tenant_id,
                 &req.legal_entity_id,
                 &req.ledger_book,
                 accounting_date,
+                PostingAuthority::new(PostingClass::Adjustment, req.actor.as_ref()),
             )
-            .map_err(|error| {
-                let (status, body) = period_error_response(error);
-                (status, Json(body))
-            })?;
+            .map_err(period_error_response)?;
     }
 
-    let lines = derive_lines_from_post_lines(&req.lines).map_err(|error| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": error.to_string()})),
-        )
-    })?;
+    let mut lines = derive_lines_from_post_lines(&req.lines).map_err(rule_engine_error_response)?;
+    state.translate_to_base(
+        &req.provenance,
+        &req.legal_entity_id,
+        accounting_date,
+        &mut lines,
+    )?;
+    state.validate_chart_of_accounts(&req.legal_entity_id, &lines)?;
 
     let replacement_journal_id = deterministic_journal_id(
         &format!("adjust:{target_journal_id}:{}", req.source_event_id),
@@ -714,43 +1222,23 @@ async fn adjust_journal(
     );
 
     {
-        let mut repo = state.journals.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "journal_store_error"})),
-            )
-        })?;
-        let existing = repo.get(&target_journal_id).cloned().ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "journal_not_found"})),
-            )
-        })?;
+        let mut repo = state
+            .journals
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+        let existing = repo
+            .get(&target_journal_id)
+            .cloned()
+            .ok_or_else(|| ApiError::not_found(ErrorCode::JournalNotFound))?;
         if existing.header.tenant_id != req.tenant_id
             || existing.header.legal_entity_id != req.legal_entity_id
             || existing.header.ledger_book != req.ledger_book
         {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "adjustment_scope_mismatch"})),
-            ));
+            return Err(ApiError::bad_request(ErrorCode::AdjustmentScopeMismatch));
         }
 
         repo.reverse(&target_journal_id)
-            .map_err(|error| match error {
-                LedgerError::AlreadyReversed => (
-                    StatusCode::CONFLICT,
-                    Json(json!({"error": "journal_already_reversed"})),
-                ),
-                LedgerError::NotFound => (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "journal_not_found"})),
-                ),
-                _ => (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": error.to_string()})),
-                ),
-            })?;
+            .map_err(ledger_error_response)?;
 
         let replacement = JournalRecord {
             header: JournalHeader {
@@ -772,27 +1260,35 @@ async fn adjust_journal(
                 fx_rate_set_id: req.provenance.fx_rate_set_id.clone(),
                 ruleset_version: req.provenance.ruleset_version.clone(),
                 workflow_id: req.provenance.workflow_id.clone(),
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: None,
+                book_group_id: None,
+                trace_context: None,
             },
             lines,
         };
-        repo.insert_posted(replacement).map_err(|error| {
-            let (status, body) = ledger_error_response(error);
-            (status, Json(body))
-        })?;
+        let replacement_header = replacement.header.clone();
+        repo.insert_posted(replacement)
+            .map_err(ledger_error_response)?;
+        state.record_change(LedgerChangeType::JournalReversed, &existing.header, None)?;
+        state.record_change(
+            LedgerChangeType::JournalAdjusted,
+            &replacement_header,
+            Some(target_journal_id.to_string()),
+        )?;
     }
 
-    let audit_seal = state
-        .append_audit_seal(
-            "journal.adjusted",
-            &[req.legal_entity_id.clone()],
-            &json!({
-                "reversed_journal_id": target_journal_id,
-                "replacement_journal_id": replacement_journal_id,
-                "reason_code": &req.reason_code
-            }),
-            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
-        )
-        .map_err(|(status, body)| (status, Json(body)))?;
+    let audit_seal = state.append_audit_seal(
+        "journal.adjusted",
+        std::slice::from_ref(&req.legal_entity_id),
+        &json!({
+            "reversed_journal_id": target_journal_id,
+            "replacement_journal_id": replacement_journal_id,
+            "reason_code": &req.reason_code
+        }),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
 
     Ok(Json(AdjustJournalResponse {
         reversed_journal_id: target_journal_id.to_string(),
@@ -802,58 +1298,25 @@ async fn adjust_journal(
     }))
 }
 
-async fn get_revrec_rollforward(
-    State(state): State<AppState>,
-    Query(query): Query<RevRecQuery>,
-) -> Result<Json<RevRecRollforwardResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let repo = state.journals.lock().map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "journal_store_error"})),
-        )
-    })?;
-    let records = repo.all();
-    drop(repo);
-
-    let mut recognized_revenue_minor = 0_i64;
-    let mut deferred_revenue_ending_minor = 0_i64;
-    let mut journal_count = 0_u32;
-    for record in records {
-        if record.header.ledger_book != query.book || record.header.status != JournalStatus::Posted
-        {
-            continue;
-        }
-        journal_count += 1;
-        for line in &record.lines {
-            if line.account_id == "4000-REVENUE" {
-                recognized_revenue_minor +=
-                    signed_amount(line.entry_side.clone(), line.amount_minor);
-            }
-            if line.account_id.contains("DEFERRED") {
-                deferred_revenue_ending_minor +=
-                    signed_amount(line.entry_side.clone(), line.amount_minor);
-            }
-        }
-    }
-
-    Ok(Json(RevRecRollforwardResponse {
-        book: query.book,
-        journal_count,
-        recognized_revenue_minor,
-        deferred_revenue_ending_minor,
-    }))
-}
-
+#[utoipa::path(
+    get,
+    path = "/v1/revrec/disclosures",
+    tag = "revrec",
+    params(RevRecQuery),
+    responses(
+        (status = 200, description = "Book-scoped revenue disclosures", body = RevRecDisclosureResponse),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn get_revrec_disclosures(
     State(state): State<AppState>,
-    Query(query): Query<RevRecQuery>,
-) -> Result<Json<RevRecDisclosureResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let repo = state.journals.lock().map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "journal_store_error"})),
-        )
-    })?;
+    ApiQuery(query): ApiQuery<RevRecQuery>,
+) -> Result<Json<RevRecDisclosureResponse>, ApiError> {
+    let repo = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
     let records = repo.all();
     drop(repo);
 
@@ -891,18 +1354,146 @@ async fn get_revrec_disclosures(
     }))
 }
 
+fn uncached_replay_response(
+    state: &AppState,
+    key: &str,
+    payload_hash: &str,
+    req: &PostEventRequest,
+) -> Result<PostEventResponse, ApiError> {
+    if !req.ledger_book.is_empty() {
+        return Ok(PostEventResponse {
+            journal_id: deterministic_journal_id(key, payload_hash).to_string(),
+            status: "POSTED".to_string(),
+            replayed: true,
+            book_journals: Vec::new(),
+        });
+    }
+    let book_journals = state
+        .required_books(&req.legal_entity_id)?
+        .iter()
+        .map(|book| BookJournal {
+            ledger_book: book.ledger_book.clone(),
+            journal_id: book_journal_id(key, payload_hash, &book.ledger_book).to_string(),
+            book_policy_id: book.book_policy_id.clone(),
+            policy_version: book.policy_version.clone(),
+        })
+        .collect::<Vec<_>>();
+    Ok(PostEventResponse {
+        journal_id: book_journals
+            .first()
+            .map(|journal| journal.journal_id.clone())
+            .unwrap_or_default(),
+        status: "POSTED".to_string(),
+        replayed: true,
+        book_journals,
+    })
+}
+
+fn book_journal_id(key: &str, payload_hash: &str, ledger_book: &str) -> Uuid {
+    deterministic_journal_id(&format!("{key}:{ledger_book}"), payload_hash)
+}
+
+struct PreparedJournal {
+    record: JournalRecord,
+    location_id: String,
+    schedule: Option<RecognitionSchedule>,
+    pass_update: Option<PassUpdate>,
+    counterpart: Option<JournalRecord>,
+    card_update: Option<CardUpdate>,
+}
+
 fn process_first_seen_post(
     state: &AppState,
     req: PostEventRequest,
     journal_uuid: Uuid,
 ) -> Result<PostEventResponse, ApiError> {
-    let accounting_date =
-        NaiveDate::parse_from_str(&req.accounting_date, "%Y-%m-%d").map_err(|_| {
-            (
-                StatusCode::BAD_REQUEST,
-                json!({"error": "invalid_accounting_date"}),
-            )
-        })?;
+    let prepared = prepare_journal(state, &req, journal_uuid)?;
+    commit_journals(state, &req, vec![prepared])?;
+    Ok(PostEventResponse {
+        journal_id: journal_uuid.to_string(),
+        status: "POSTED".to_string(),
+        replayed: false,
+        book_journals: Vec::new(),
+    })
+}
+
+fn book_requests(
+    state: &AppState,
+    req: &PostEventRequest,
+) -> Result<Vec<PostEventRequest>, ApiError> {
+    Ok(state
+        .required_books(&req.legal_entity_id)?
+        .iter()
+        .map(|book| {
+            let mut book_req = req.clone();
+            book_req.ledger_book = book.ledger_book.clone();
+            book_req.provenance.book_policy_id = book.book_policy_id.clone();
+            book_req.provenance.policy_version = book.policy_version.clone();
+            book_req
+        })
+        .collect())
+}
+
+fn process_first_seen_multi_book_post(
+    state: &AppState,
+    key: &str,
+    payload_hash: &str,
+    req: PostEventRequest,
+) -> Result<PostEventResponse, ApiError> {
+    let mut prepared = Vec::new();
+    for book_req in book_requests(state, &req)? {
+        let journal_uuid = book_journal_id(key, payload_hash, &book_req.ledger_book);
+        prepared.push(prepare_journal(state, &book_req, journal_uuid)?);
+    }
+    if prepared.len() > 1 {
+        let book_group_id = prepared[0].record.header.journal_id;
+        for journal in &mut prepared {
+            journal.record.header.book_group_id = Some(book_group_id);
+        }
+    }
+    let book_journals = prepared
+        .iter()
+        .map(|journal| BookJournal {
+            ledger_book: journal.record.header.ledger_book.clone(),
+            journal_id: journal.record.header.journal_id.to_string(),
+            book_policy_id: journal.record.header.book_policy_id.clone(),
+            policy_version: journal.record.header.policy_version.clone(),
+        })
+        .collect::<Vec<_>>();
+    commit_journals(state, &req, prepared)?;
+
+    Ok(PostEventResponse {
+        journal_id: book_journals[0].journal_id.clone(),
+        status: "POSTED".to_string(),
+        replayed: false,
+        book_journals,
+    })
+}
+
+struct DraftJournal {
+    record: JournalRecord,
+    location_id: String,
+    pass_event: Option<PassEvent>,
+    stored_value_event: Option<StoredValueEvent>,
+}
+
+fn prepare_journal(
+    state: &AppState,
+    req: &PostEventRequest,
+    journal_uuid: Uuid,
+) -> Result<PreparedJournal, ApiError> {
+    let draft = draft_journal(state, req, journal_uuid)?;
+    validate_balanced(&draft.record.lines).map_err(ledger_error_response)?;
+    complete_journal(state, req, draft)
+}
+
+fn draft_journal(
+    state: &AppState,
+    req: &PostEventRequest,
+    journal_uuid: Uuid,
+) -> Result<DraftJournal, ApiError> {
+    let accounting_date = NaiveDate::parse_from_str(&req.accounting_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidAccountingDate))?;
     state.validate_legal_hold(
         &req.tenant_id,
         &req.legal_entity_id,
@@ -910,29 +1501,48 @@ fn process_first_seen_post(
         accounting_date,
     )?;
 
-    let location_id = resolve_location_id(&req)?;
+    let location_id = resolve_location_id(req)?;
     validate_location_boundary(state, &req.legal_entity_id, &location_id)?;
-    validate_intercompany_counterparty(state, &req)?;
+    validate_intercompany_counterparty(state, req)?;
+    state.validate_posting_policy(&req.provenance, &req.ledger_book)?;
 
     {
-        let periods = state.periods.lock().map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                json!({"error": "period_store_error"}),
-            )
-        })?;
+        let periods = state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
         periods
             .ensure_open(
                 &req.tenant_id,
                 &req.legal_entity_id,
                 &req.ledger_book,
                 accounting_date,
+                req.posting_authority(),
             )
             .map_err(period_error_response)?;
     }
 
-    let lines = derive_journal_lines(&req)
-        .map_err(|e| (StatusCode::BAD_REQUEST, json!({"error": e.to_string()})))?;
+    let pass_event = prepare_pass_event(state, req, accounting_date)?;
+    let stored_value_event = prepare_stored_value_event(state, req)?;
+    let agency_event = prepare_agency_event(state, req, accounting_date)?;
+    let payload = pass_event
+        .as_ref()
+        .map_or(&req.payload, |event| event.payload(&req.payload));
+    let payload = stored_value_event
+        .as_ref()
+        .map_or(payload, |event| event.payload());
+    let payload = agency_event
+        .as_ref()
+        .map_or(payload, |event| event.payload());
+    let mut lines =
+        derive_journal_lines(state, req, payload).map_err(rule_engine_error_response)?;
+    state.translate_to_base(
+        &req.provenance,
+        &req.legal_entity_id,
+        accounting_date,
+        &mut lines,
+    )?;
+    state.validate_chart_of_accounts(&req.legal_entity_id, &lines)?;
 
     let record = JournalRecord {
         header: JournalHeader {
@@ -951,23 +1561,147 @@ fn process_first_seen_post(
             fx_rate_set_id: req.provenance.fx_rate_set_id.clone(),
             ruleset_version: req.provenance.ruleset_version.clone(),
             workflow_id: req.provenance.workflow_id.clone(),
+            estimate_version: pass_event
+                .as_ref()
+                .and_then(|event| event.estimate_version()),
+            revenue_designation: agency_event.as_ref().map(|event| event.designation()),
+            prior_period_adjustment: None,
+            book_group_id: None,
+            trace_context: req.trace_context.clone(),
         },
         lines,
     };
+    Ok(DraftJournal {
+        record,
+        location_id,
+        pass_event,
+        stored_value_event,
+    })
+}
 
-    let mut repo = state.journals.lock().map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            json!({"error": "journal_store_error"}),
-        )
-    })?;
-    repo.insert_posted(record).map_err(ledger_error_response)?;
+fn complete_journal(
+    state: &AppState,
+    req: &PostEventRequest,
+    draft: DraftJournal,
+) -> Result<PreparedJournal, ApiError> {
+    let DraftJournal {
+        record,
+        location_id,
+        pass_event,
+        stored_value_event,
+    } = draft;
+    let accounting_date = record.header.accounting_date;
+    let mut schedule = schedule_from_capture(&record, &location_id, &req.payload)
+        .map_err(revrec_error_response)?;
+    let pass_update = match pass_event {
+        Some(event) => {
+            let (update, pass_schedule) = event.complete(&record, &location_id)?;
+            schedule = schedule.or(pass_schedule);
+            Some(update)
+        }
+        None => None,
+    };
+    let (counterpart, card_update) = match stored_value_event {
+        Some(event) => {
+            let mut counterpart = event.counterpart_journal(state, req, &record)?;
+            if let Some(counterpart) = &mut counterpart {
+                state.translate_to_base(
+                    &req.provenance,
+                    &counterpart.header.legal_entity_id,
+                    accounting_date,
+                    &mut counterpart.lines,
+                )?;
+                validate_balanced(&counterpart.lines).map_err(ledger_error_response)?;
+                state.validate_chart_of_accounts(
+                    &counterpart.header.legal_entity_id,
+                    &counterpart.lines,
+                )?;
+            }
+            let update = event
+                .complete(&record, counterpart.as_ref())
+                .map_err(stored_value_error_response)?;
+            (counterpart, Some(update))
+        }
+        None => (None, None),
+    };
+    Ok(PreparedJournal {
+        record,
+        location_id,
+        schedule,
+        pass_update,
+        counterpart,
+        card_update,
+    })
+}
+
+fn commit_journals(
+    state: &AppState,
+    req: &PostEventRequest,
+    prepared: Vec<PreparedJournal>,
+) -> Result<(), ApiError> {
+    let mut schedules = Vec::new();
+    let mut pass_updates = Vec::new();
+    let mut card_updates = Vec::new();
+    let (records, location_ids): (Vec<_>, Vec<_>) = prepared
+        .into_iter()
+        .flat_map(|journal| {
+            schedules.extend(journal.schedule);
+            pass_updates.extend(journal.pass_update);
+            card_updates.extend(journal.card_update);
+            let counterpart = journal
+                .counterpart
+                .map(|record| (record, journal.location_id.clone()));
+            std::iter::once((journal.record, journal.location_id)).chain(counterpart)
+        })
+        .unzip();
+    let headers = records
+        .iter()
+        .map(|record| record.header.clone())
+        .collect::<Vec<_>>();
+
+    let mut repo = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+    let mut passes = state
+        .passes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?;
+    passes
+        .ensure_current(&pass_updates)
+        .map_err(pass_error_response)?;
+    let mut stored_value = state
+        .stored_value
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::StoredValueStoreError))?;
+    stored_value
+        .ensure_current(&card_updates)
+        .map_err(stored_value_error_response)?;
+    let mut revrec_schedules = state
+        .revrec_schedules
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?;
+    // Once the journals are in, nothing may fail before their subledger effects are applied, so
+    // the change feed is written last.
+    repo.insert_posted_batch(records)
+        .map_err(ledger_error_response)?;
+    revrec_schedules.insert(schedules);
+    passes.apply(pass_updates);
+    stored_value.apply(card_updates);
+    drop(revrec_schedules);
+    drop(stored_value);
+    drop(passes);
+    for header in &headers {
+        state.record_change(LedgerChangeType::JournalPosted, header, None)?;
+    }
     drop(repo);
 
     let mut audit_entity_scope = vec![req.legal_entity_id.clone()];
     if matches!(
         req.event_type.as_str(),
-        "intercompany.due_to_due_from.v1" | "consolidation.elimination.v1"
+        "intercompany.due_to_due_from.v1"
+            | "consolidation.elimination.v1"
+            | CROSS_ENTITY_REDEEMED_EVENT
     ) {
         if let Some(counterparty) = first_string(
             &req.payload,
@@ -975,118 +1709,234 @@ fn process_first_seen_post(
                 "/counterparty_legal_entity_id",
                 "/intercompany/counterparty_legal_entity_id",
                 "/consolidation/counterparty_legal_entity_id",
+                "/issuing_legal_entity_id",
             ],
         ) {
             audit_entity_scope.push(counterparty.to_string());
         }
     }
 
-    state.append_audit_seal(
-        "posting.posted",
-        &audit_entity_scope,
-        &json!({
-            "event_type": req.event_type,
-            "journal_id": journal_uuid,
-            "tenant_id": req.tenant_id,
-            "ledger_book": req.ledger_book,
-            "source_event_id": req.source_event_id,
-            "location_id": location_id
-        }),
-        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
-    )?;
-
-    Ok(PostEventResponse {
-        journal_id: journal_uuid.to_string(),
-        status: "POSTED".to_string(),
-        replayed: false,
-    })
+    for (header, location_id) in headers.iter().zip(location_ids) {
+        state.append_audit_seal(
+            "posting.posted",
+            &audit_entity_scope,
+            &json!({
+                "event_type": req.event_type,
+                "journal_id": header.journal_id,
+                "tenant_id": req.tenant_id,
+                "ledger_book": header.ledger_book,
+                "source_event_id": req.source_event_id,
+                "location_id": location_id
+            }),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+        )?;
+    }
+    Ok(())
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/journals/{journal_id}/reverse",
+    tag = "ledger",
+    params(("journal_id" = String, Path, description = "Journal to reverse")),
+    responses(
+        (status = 200, description = "Journal reversed", body = ReverseJournalResponse),
+        (status = 400, description = "Invalid journal id", body = ErrorEnvelope),
+        (status = 404, description = "Journal not found", body = ErrorEnvelope),
+        (status = 409, description = "Journal already reversed, or it moved a subledger balance", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn reverse_journal(
     State(state): State<AppState>,
     Path(journal_id): Path<String>,
-) -> Result<Json<ReverseJournalResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let journal_id = Uuid::parse_str(&journal_id).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "invalid_journal_id"})),
-        )
-    })?;
+) -> Result<Json<ReverseJournalResponse>, ApiError> {
+    let journal_id = Uuid::parse_str(&journal_id)
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidJournalId))?;
 
-    let mut repo = state.journals.lock().map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "journal_store_error"})),
-        )
-    })?;
+    let mut repo = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
 
-    repo.reverse(&journal_id).map_err(|error| match error {
-        LedgerError::NotFound => (
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "journal_not_found"})),
-        ),
-        LedgerError::AlreadyReversed => (
-            StatusCode::CONFLICT,
-            Json(json!({"error": "journal_already_reversed"})),
-        ),
-        _ => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": error.to_string()})),
-        ),
-    })?;
-    let entity_scope = repo
+    let record = repo
         .get(&journal_id)
-        .map(|record| vec![record.header.legal_entity_id.clone()])
-        .unwrap_or_default();
-    drop(repo);
-    if !entity_scope.is_empty() {
-        state
-            .append_audit_seal(
-                "journal.reversed",
-                &entity_scope,
-                &json!({"journal_id": journal_id}),
-                chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
-            )
-            .map_err(|(status, body)| (status, Json(body)))?;
+        .cloned()
+        .ok_or_else(|| ledger_error_response(LedgerError::NotFound))?;
+    let mut group = vec![record];
+    if group[0].header.status == JournalStatus::Posted {
+        group.extend(book_siblings(&repo, &group[0]));
+        for record in &group {
+            ensure_reversible(&state, record)?;
+        }
     }
+    for record in &group {
+        repo.reverse(&record.header.journal_id)
+            .map_err(ledger_error_response)?;
+        state.record_change(LedgerChangeType::JournalReversed, &record.header, None)?;
+    }
+    drop(repo);
+
+    let book_journals = if group.len() > 1 {
+        group
+            .iter()
+            .map(|record| BookJournal {
+                ledger_book: record.header.ledger_book.clone(),
+                journal_id: record.header.journal_id.to_string(),
+                book_policy_id: record.header.book_policy_id.clone(),
+                policy_version: record.header.policy_version.clone(),
+            })
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    state.append_audit_seal(
+        "journal.reversed",
+        std::slice::from_ref(&group[0].header.legal_entity_id),
+        &json!({
+            "journal_id": journal_id,
+            "book_journal_ids": book_journals
+                .iter()
+                .map(|book_journal| book_journal.journal_id.as_str())
+                .collect::<Vec<_>>()
+        }),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
 
     Ok(Json(ReverseJournalResponse {
         journal_id: journal_id.to_string(),
         status: "REVERSED".to_string(),
+        book_journals,
     }))
 }
 
+/// The journals the same event posted into the entity's other books. A multi-book post is one
+/// accounting event, so its book journals are reversed together.
+fn book_siblings(repo: &InMemoryJournalRepository, record: &JournalRecord) -> Vec<JournalRecord> {
+    let Some(book_group_id) = record.header.book_group_id else {
+        return Vec::new();
+    };
+    repo.all()
+        .into_iter()
+        .filter(|other| {
+            other.header.book_group_id == Some(book_group_id)
+                && other.header.journal_id != record.header.journal_id
+                && other.header.status == JournalStatus::Posted
+        })
+        .collect()
+}
+
+/// Reversal only flips the journal's status, so a journal whose posting also moved a subledger
+/// is refused rather than leaving the subledger out of step with the GL.
+fn ensure_reversible(state: &AppState, record: &JournalRecord) -> Result<(), ApiError> {
+    let journal_id = record.header.journal_id.to_string();
+    let subledger = if moves_card_balance(record) {
+        Some("stored_value")
+    } else if state
+        .revrec_schedules
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?
+        .references_journal(&journal_id)
+    {
+        Some("revenue_recognition")
+    } else if state
+        .passes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?
+        .moves_pass(record)
+    {
+        Some("passes")
+    } else if is_closing_journal(&record.header) {
+        Some("year_end_close")
+    } else {
+        None
+    };
+    let Some(subledger) = subledger else {
+        return Ok(());
+    };
+    let error = ApiError::conflict(ErrorCode::JournalHasSubledgerEffects)
+        .with_detail("journal_id", journal_id)
+        .with_detail("subledger", subledger);
+    Err(if subledger == "year_end_close" {
+        error.with_message("year-end closing journals are reversed by reversing the year-end close")
+    } else {
+        error
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/periods/{period_id}/lock",
+    tag = "ledger",
+    params(("period_id" = String, Path, description = "Period to lock in the entity's fiscal calendar")),
+    request_body = LockPeriodRequest,
+    responses(
+        (status = 200, description = "Period locked", body = LockPeriodResponse),
+        (status = 400, description = "Invalid period id", body = ErrorEnvelope),
+        (status = 403, description = "Actor's role may not close the period", body = ErrorEnvelope),
+        (status = 409, description = "Period not soft-closed, or close checklist missing or not ready", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
 async fn lock_period_endpoint(
     State(state): State<AppState>,
     Path(period_id): Path<String>,
-    Json(req): Json<LockPeriodRequest>,
-) -> Result<Json<LockPeriodResponse>, (StatusCode, Json<serde_json::Value>)> {
-    state
-        .lock_period(
+    ApiJson(req): ApiJson<LockPeriodRequest>,
+) -> Result<Json<LockPeriodResponse>, ApiError> {
+    let mut periods = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
+    periods
+        .check_transition(
             &req.tenant_id,
             &req.legal_entity_id,
             &req.ledger_book,
             &period_id,
+            PeriodState::Closed,
+            &req.actor,
         )
-        .map_err(|error| {
-            let (status, body) = period_error_response(error);
-            (status, Json(body))
-        })?;
+        .map_err(period_error_response)?;
+    let audit_seal = seal_hard_close(
+        &state,
+        &req.tenant_id,
+        &req.legal_entity_id,
+        &req.ledger_book,
+        &period_id,
+    )?;
+    periods
+        .transition(
+            &req.tenant_id,
+            &req.legal_entity_id,
+            &req.ledger_book,
+            &period_id,
+            PeriodState::Closed,
+            &req.actor,
+        )
+        .map_err(period_error_response)?;
 
     Ok(Json(LockPeriodResponse {
         period_id,
         status: "LOCKED".to_string(),
+        audit_seal,
     }))
 }
 
-async fn get_slo() -> Json<serde_json::Value> {
-    Json(json!({
-        "availability_target": "99.95%",
-        "read_p95_ms": 150,
-        "write_p95_ms": 250,
-        "error_rate_max": 0.001,
-        "no_bend_efficiency_min": 0.80
-    }))
+#[utoipa::path(
+    get,
+    path = "/v1/ops/slo",
+    tag = "ops",
+    responses((status = 200, description = "Service level objectives", body = SloResponse))
+)]
+async fn get_slo() -> Json<SloResponse> {
+    Json(SloResponse {
+        availability_target: "99.95%".to_string(),
+        read_p95_ms: 150,
+        write_p95_ms: 250,
+        error_rate_max: 0.001,
+        no_bend_efficiency_min: 0.80,
+    })
 }
 
 fn production_scale_samples() -> Vec<ScaleSample> {
@@ -1144,13 +1994,19 @@ fn build_capacity_instrumentation_response(
     })
 }
 
-async fn get_capacity(
-) -> Result<Json<CapacityInstrumentationResponse>, (StatusCode, Json<serde_json::Value>)> {
+#[utoipa::path(
+    get,
+    path = "/v1/ops/capacity",
+    tag = "ops",
+    responses(
+        (status = 200, description = "Capacity instrumentation", body = CapacityInstrumentationResponse),
+        (status = 500, description = "Readiness unavailable", body = ErrorEnvelope)
+    )
+)]
+async fn get_capacity() -> Result<Json<CapacityInstrumentationResponse>, ApiError> {
     let samples = production_scale_samples();
-    let response = build_capacity_instrumentation_response(samples).ok_or((
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(json!({"error": "capacity_readiness_unavailable"})),
-    ))?;
+    let response = build_capacity_instrumentation_response(samples)
+        .ok_or_else(|| ApiError::internal(ErrorCode::CapacityReadinessUnavailable))?;
     Ok(Json(response))
 }
 
@@ -1174,10 +2030,7 @@ fn resolve_location_id(req: &PostEventRequest) -> Result<String, ApiError> {
         ],
     )
     .map(ToString::to_string)
-    .ok_or((
-        StatusCode::BAD_REQUEST,
-        json!({"error": "missing_location_id"}),
-    ))
+    .ok_or_else(|| ApiError::bad_request(ErrorCode::MissingLocationId))
 }
 
 fn validate_location_boundary(
@@ -1188,20 +2041,17 @@ fn validate_location_boundary(
     let allowed_locations = state
         .location_allowlist_by_legal_entity
         .get(legal_entity_id)
-        .ok_or((
-            StatusCode::BAD_REQUEST,
-            json!({"error": "unknown_legal_entity_boundary", "legal_entity_id": legal_entity_id}),
-        ))?;
+        .ok_or_else(|| {
+            ApiError::bad_request(ErrorCode::UnknownLegalEntityBoundary)
+                .with_detail("legal_entity_id", legal_entity_id)
+        })?;
 
     if !allowed_locations.contains(location_id) {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            json!({
-                "error": "location_not_allowed_for_legal_entity",
-                "legal_entity_id": legal_entity_id,
-                "location_id": location_id
-            }),
-        ));
+        return Err(
+            ApiError::bad_request(ErrorCode::LocationNotAllowedForLegalEntity)
+                .with_detail("legal_entity_id", legal_entity_id)
+                .with_detail("location_id", location_id),
+        );
     }
 
     Ok(())
@@ -1213,7 +2063,9 @@ fn validate_intercompany_counterparty(
 ) -> Result<(), ApiError> {
     if !matches!(
         req.event_type.as_str(),
-        "intercompany.due_to_due_from.v1" | "consolidation.elimination.v1"
+        "intercompany.due_to_due_from.v1"
+            | "consolidation.elimination.v1"
+            | CROSS_ENTITY_REDEEMED_EVENT
     ) {
         return Ok(());
     }
@@ -1224,28 +2076,26 @@ fn validate_intercompany_counterparty(
             "/counterparty_legal_entity_id",
             "/intercompany/counterparty_legal_entity_id",
             "/consolidation/counterparty_legal_entity_id",
+            "/issuing_legal_entity_id",
         ],
     )
-    .ok_or((
-        StatusCode::BAD_REQUEST,
-        json!({"error": "missing_counterparty_legal_entity_id"}),
-    ))?;
+    .ok_or_else(|| ApiError::bad_request(ErrorCode::MissingCounterpartyLegalEntityId))?;
 
     if counterparty == req.legal_entity_id {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            json!({"error": "invalid_counterparty_legal_entity"}),
-        ));
+        return Err(
+            ApiError::bad_request(ErrorCode::InvalidCounterpartyLegalEntity)
+                .with_detail("counterparty_legal_entity_id", counterparty),
+        );
     }
 
     if !state
         .location_allowlist_by_legal_entity
         .contains_key(counterparty)
     {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            json!({"error": "unknown_counterparty_legal_entity"}),
-        ));
+        return Err(
+            ApiError::bad_request(ErrorCode::UnknownCounterpartyLegalEntity)
+                .with_detail("counterparty_legal_entity_id", counterparty),
+        );
     }
 
     Ok(())
@@ -1257,31 +2107,44 @@ fn first_string<'a>(payload: &'a Value, pointers: &[&str]) -> Option<&'a str> {
         .find_map(|pointer| payload.pointer(pointer).and_then(Value::as_str))
 }
 
-fn derive_journal_lines(req: &PostEventRequest) -> Result<Vec<JournalLine>, RuleEngineError> {
-    if req.payload.is_object() {
-        let derived = derive_lines_v1(&req.event_type, &req.payload)?;
-        return Ok(derived
-            .into_iter()
-            .enumerate()
-            .map(|(index, line)| JournalLine {
-                line_number: (index + 1) as u32,
-                account_id: line.account_id,
-                entry_side: line.entry_side,
-                amount_minor: line.amount_minor,
-                currency: line.currency,
-                base_amount_minor: line.base_amount_minor,
-                base_currency: line.base_currency,
-            })
-            .collect());
+fn derive_journal_lines(
+    state: &AppState,
+    req: &PostEventRequest,
+    payload: &Value,
+) -> Result<Vec<JournalLine>, RuleEngineError> {
+    if payload.is_object() {
+        let derived = state
+            .rule_sets
+            .get(&req.provenance.ruleset_version)?
+            .derive(&req.event_type, Some(&req.ledger_book), payload)?;
+        return Ok(journal_lines(derived));
     }
 
     if req.lines.is_empty() {
-        return Err(RuleEngineError::MissingField("payload"));
+        return Err(RuleEngineError::MissingField("payload".to_string()));
     }
 
     derive_lines_from_post_lines(&req.lines)
 }
 
+fn journal_lines(derived: Vec<DerivedPostingLine>) -> Vec<JournalLine> {
+    derived
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| JournalLine {
+            line_number: (index + 1) as u32,
+            account_id: line.account_id,
+            entry_side: line.entry_side,
+            amount_minor: line.amount_minor,
+            currency: line.currency,
+            base_amount_minor: line.base_amount_minor,
+            base_currency: line.base_currency,
+            dimensions: line.dimensions,
+            fx_rate: None,
+        })
+        .collect()
+}
+
 fn derive_lines_from_post_lines(lines: &[PostLine]) -> Result<Vec<JournalLine>, RuleEngineError> {
     lines
         .iter()
@@ -1295,6 +2158,8 @@ fn derive_lines_from_post_lines(lines: &[PostLine]) -> Result<Vec<JournalLine>,
                 currency: line.currency.clone(),
                 base_amount_minor: line.base_amount_minor,
                 base_currency: line.base_currency.clone(),
+                dimensions: BTreeMap::new(),
+                fx_rate: None,
             })
         })
         .collect()
@@ -1317,26 +2182,125 @@ fn parse_entry_side(entry_side: &str) -> Result<EntrySide, RuleEngineError> {
     }
 }
 
-fn period_error_response(error: PeriodError) -> ApiError {
-    match error {
-        PeriodError::PeriodClosed(period_id) => (
-            StatusCode::CONFLICT,
-            json!({"error": format!("period_closed:{period_id}")}),
-        ),
-        PeriodError::InvalidPeriodId(_) => {
-            (StatusCode::BAD_REQUEST, json!({"error": error.to_string()}))
+pub(crate) fn rule_engine_error_response(error: RuleEngineError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        RuleEngineError::UnsupportedEventType(event_type) => {
+            ApiError::bad_request(ErrorCode::UnsupportedEventType)
+                .with_detail("event_type", event_type)
+        }
+        RuleEngineError::MissingField(field) => {
+            ApiError::bad_request(ErrorCode::MissingField).with_detail("field", field)
+        }
+        RuleEngineError::InvalidNumber(field) | RuleEngineError::AmountOverflow(field) => {
+            ApiError::bad_request(ErrorCode::InvalidNumber).with_detail("field", field)
+        }
+        RuleEngineError::UnknownRulesetVersion(version) => {
+            ApiError::bad_request(ErrorCode::UnknownRulesetVersion)
+                .with_detail("ruleset_version", version)
+        }
+        RuleEngineError::CheckFailed(check) => {
+            ApiError::bad_request(ErrorCode::RuleCheckFailed).with_detail("check", check)
+        }
+        RuleEngineError::NoLinesDerived(event_type) => {
+            ApiError::bad_request(ErrorCode::RuleCheckFailed).with_detail("event_type", event_type)
+        }
+        RuleEngineError::InvalidSettlementMath => {
+            ApiError::bad_request(ErrorCode::InvalidSettlementMath)
+        }
+        RuleEngineError::InvalidEntrySide(entry_side) => {
+            ApiError::bad_request(ErrorCode::InvalidEntrySide).with_detail("entry_side", entry_side)
+        }
+        RuleEngineError::UnknownTaxType(tax_type) => {
+            ApiError::bad_request(ErrorCode::UnknownTaxType).with_detail("tax_type", tax_type)
+        }
+        RuleEngineError::AccountNotNamed {
+            ruleset_version,
+            role,
+        } => ApiError::bad_request(ErrorCode::RulesetAccountNotNamed)
+            .with_detail("ruleset_version", ruleset_version)
+            .with_detail("role", role),
+        RuleEngineError::ComponentsUnreconciled { check, amounts } => {
+            ApiError::bad_request(ErrorCode::ComponentsUnreconciled)
+                .with_detail("check", check)
+                .with_detail("amounts", serde_json::json!(amounts))
+        }
+    };
+    api_error.with_message(message)
+}
+
+fn period_error_response(error: PeriodError) -> ApiError {
+    let message = error.to_string();
+    match error {
+        PeriodError::PeriodClosed(period_id) => ApiError::conflict(ErrorCode::PeriodClosed)
+            .with_message(message)
+            .with_detail("period_id", period_id),
+        PeriodError::InvalidPeriodId(period_id) => {
+            ApiError::bad_request(ErrorCode::InvalidPeriodId)
+                .with_message(message)
+                .with_detail("period_id", period_id)
+        }
+        PeriodError::PostingNotAllowed {
+            period_id,
+            state,
+            class,
+            role,
+        } => ApiError::conflict(ErrorCode::PeriodPostingNotAllowed)
+            .with_message(message)
+            .with_detail("period_id", period_id)
+            .with_detail("state", json!(state))
+            .with_detail("posting_class", json!(class))
+            .with_detail("role", json!(role)),
+        PeriodError::InvalidTransition {
+            period_id,
+            from,
+            to,
+        } => ApiError::conflict(ErrorCode::InvalidPeriodTransition)
+            .with_message(message)
+            .with_detail("period_id", period_id)
+            .with_detail("from_state", json!(from))
+            .with_detail("to_state", json!(to)),
+        PeriodError::TransitionForbidden {
+            period_id,
+            from,
+            to,
+            role,
+        } => ApiError::forbidden(ErrorCode::PeriodTransitionForbidden)
+            .with_message(message)
+            .with_detail("period_id", period_id)
+            .with_detail("from_state", json!(from))
+            .with_detail("to_state", json!(to))
+            .with_detail("role", json!(role)),
+        PeriodError::Calendar(CalendarError::FiscalYearOutOfRange(fiscal_year)) => {
+            ApiError::bad_request(ErrorCode::FiscalPeriodUnresolved)
+                .with_message(message)
+                .with_detail("fiscal_year", fiscal_year)
+        }
+        PeriodError::Calendar(CalendarError::InvalidMonth(_)) => {
+            ApiError::internal(ErrorCode::FiscalPeriodUnresolved).with_message(message)
+        }
+    }
+}
+
+fn ledger_error_response(error: LedgerError) -> ApiError {
+    match error {
+        LedgerError::JournalExists => ApiError::conflict(ErrorCode::JournalExists),
+        LedgerError::Unbalanced => ApiError::bad_request(ErrorCode::JournalUnbalanced),
+        LedgerError::Immutable => ApiError::conflict(ErrorCode::JournalImmutable),
+        LedgerError::NotFound => ApiError::not_found(ErrorCode::JournalNotFound),
+        LedgerError::AlreadyReversed => ApiError::conflict(ErrorCode::JournalAlreadyReversed),
+    }
+}
+
+fn audit_seal_error_response(error: AuditSealError) -> ApiError {
+    match error {
+        AuditSealError::StorePoisoned => ApiError::internal(ErrorCode::AuditSealStoreError),
+        AuditSealError::ChainBroken { sequence } => {
+            ApiError::conflict(ErrorCode::AuditSealChainBroken).with_detail("sequence", sequence)
+        }
+        AuditSealError::Tampered { sequence } => {
+            ApiError::conflict(ErrorCode::AuditSealTampered).with_detail("sequence", sequence)
         }
-    }
-}
-
-fn ledger_error_response(error: LedgerError) -> ApiError {
-    match error {
-        LedgerError::JournalExists => (StatusCode::CONFLICT, json!({"error": "journal_exists"})),
-        LedgerError::Unbalanced => (
-            StatusCode::BAD_REQUEST,
-            json!({"error": "journal_unbalanced"}),
-        ),
-        _ => (StatusCode::BAD_REQUEST, json!({"error": error.to_string()})),
     }
 }
 
@@ -1359,6 +2323,9 @@ mod tests {
     use tower::ServiceExt;
 
     use super::*;
+    use crate::period::{ActorRole, FixedClock};
+    use crate::rule_engine::RuleSet;
+    use crate::year_end::YearEndCloseStatus;
 
     struct TempDirGuard {
         path: std::path::PathBuf,
@@ -1542,6 +2509,14 @@ mod tests {
             .unwrap()
     }
 
+    fn reverse_request(journal_id: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/v1/ledger/journals/{journal_id}/reverse"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
     fn period_lock_request(period_id: &str, payload: &serde_json::Value) -> Request<Body> {
         Request::builder()
             .method("POST")
@@ -1551,6 +2526,73 @@ mod tests {
             .unwrap()
     }
 
+    fn soft_close_request(period_id: &str) -> Request<Body> {
+        post_json_request(
+            &format!("/v1/ledger/periods/{period_id}/transitions"),
+            &json!({
+                "tenant_id": "tenant_1",
+                "legal_entity_id": "US_CO_01",
+                "ledger_book": "US_GAAP",
+                "to_state": "SOFT_CLOSED",
+                "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+            }),
+        )
+    }
+
+    fn close_period(state: &AppState, ledger_book: &str, period_id: &str) {
+        let controller = PeriodActor {
+            actor_id: "u-ctl".to_string(),
+            role: ActorRole::Controller,
+        };
+        let mut periods = state.periods.lock().unwrap();
+        for to in [PeriodState::SoftClosed, PeriodState::Closed] {
+            periods
+                .transition(
+                    "tenant_1",
+                    "US_CO_01",
+                    ledger_book,
+                    period_id,
+                    to,
+                    &controller,
+                )
+                .unwrap();
+        }
+    }
+
+    fn close_checklist_request(period_id: &str) -> Request<Body> {
+        let payload = json!({
+            "tenant_id": "tenant_1",
+            "checklist_id": format!("close-{period_id}"),
+            "legal_entity_id": "US_CO_01",
+            "period_id": period_id,
+            "dependencies": [
+                {
+                    "dependency_id": "bank-rec",
+                    "description": "Bank reconciliation signed off",
+                    "required_for_close": true
+                },
+                {
+                    "dependency_id": "flux-review",
+                    "description": "Flux review",
+                    "required_for_close": false
+                }
+            ]
+        });
+        Request::builder()
+            .method("POST")
+            .uri("/v1/close/checklists")
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    fn close_dependency_request(period_id: &str, status: &str) -> Request<Body> {
+        post_json_request(
+            &format!("/v1/close/checklists/US_CO_01/{period_id}/dependencies/bank-rec"),
+            &json!({"tenant_id": "tenant_1", "status": status}),
+        )
+    }
+
     fn legal_hold_request(payload: &serde_json::Value) -> Request<Body> {
         Request::builder()
             .method("POST")
@@ -1569,11 +2611,53 @@ mod tests {
             .unwrap()
     }
 
+    fn bulk_post_request(lines: &[serde_json::Value]) -> Request<Body> {
+        let body = lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        Request::builder()
+            .method("POST")
+            .uri("/v1/posting/events/bulk")
+            .header("content-type", bulk::NDJSON_CONTENT_TYPE)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    fn stripe_webhook_request(
+        body: &serde_json::Value,
+        secret: &str,
+        timestamp: i64,
+    ) -> Request<Body> {
+        use hmac::{Hmac, Mac};
+
+        let body = body.to_string();
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{timestamp}.{body}").as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+        Request::builder()
+            .method("POST")
+            .uri("/v1/ingest/stripe")
+            .header("content-type", "application/json")
+            .header("stripe-signature", format!("t={timestamp},v1={signature}"))
+            .body(Body::from(body))
+            .unwrap()
+    }
+
     async fn json_body(response: axum::response::Response) -> serde_json::Value {
         let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
         serde_json::from_slice(&bytes).unwrap()
     }
 
+    async fn ndjson_body(response: axum::response::Response) -> Vec<serde_json::Value> {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        bytes
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect()
+    }
+
     #[tokio::test]
     async fn duplicate_same_payload_replays() {
         let app = router();
@@ -1617,486 +2701,3942 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn inntopia_reservation_posts_with_rule_engine_v1() {
+    async fn bulk_endpoint_streams_per_line_results() {
         let app = router();
+        let mut second = order_payload(2500);
+        second["source_event_id"] = json!("evt_bulk_2");
+        let mut unsupported = order_payload(100);
+        unsupported["event_type"] = json!("inventory.adjusted.v1");
 
         let response = app
-            .oneshot(post_request("inntopia-key", &inntopia_payload(41250)))
+            .oneshot(bulk_post_request(&[
+                json!({"idempotency_key": "bulk-1", "event": order_payload(10000)}),
+                json!({"idempotency_key": "bulk-2", "event": second}),
+                json!({"idempotency_key": "bulk-1", "event": order_payload(10000)}),
+                json!({"idempotency_key": "bulk-3", "event": unsupported}),
+                json!({"event": order_payload(10000)}),
+            ]))
             .await
             .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            bulk::NDJSON_CONTENT_TYPE
+        );
+        let results = ndjson_body(response).await;
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0]["outcome"], json!("POSTED"));
+        assert_eq!(results[1]["outcome"], json!("POSTED"));
+        assert_eq!(results[2]["outcome"], json!("REPLAYED"));
+        assert_eq!(results[2]["journal_id"], results[0]["journal_id"]);
+        assert_eq!(results[3]["outcome"], json!("REJECTED"));
+        assert_eq!(results[3]["http_status"], json!(400));
+        assert_eq!(results[3]["error"]["code"], json!("unsupported_event_type"));
+        assert_eq!(results[4]["outcome"], json!("REJECTED"));
+        assert_eq!(results[4]["error"]["code"], json!("invalid_ndjson_line"));
+        let line_numbers = results
+            .iter()
+            .map(|result| result["line_number"].as_u64().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(line_numbers, vec![1, 2, 3, 4, 5]);
     }
 
     #[tokio::test]
-    async fn sprint3_fee_event_posts_with_rule_engine_v1() {
-        let app = router();
+    async fn bulk_endpoint_keeps_earlier_lines_committed_after_failure() {
+        let state = AppState::default();
+        close_period(&state, "US_GAAP", "2026-01");
+        let app = router_with_state(state.clone());
+        let mut closed = order_payload(500);
+        closed["accounting_date"] = json!("2026-01-31");
+        closed["source_event_id"] = json!("evt_bulk_closed");
 
         let response = app
-            .oneshot(post_request(
-                "fee-key",
-                &sprint3_payload("fee.assessed.v1", 325),
-            ))
+            .clone()
+            .oneshot(bulk_post_request(&[
+                json!({"idempotency_key": "bulk-ok", "event": order_payload(10000)}),
+                json!({"idempotency_key": "bulk-closed", "event": closed}),
+            ]))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
-    }
-
-    #[tokio::test]
-    async fn sprint4_intercompany_event_posts_with_rule_engine_v1() {
-        let app = router();
-
-        let response = app
-            .oneshot(post_request(
-                "intercompany-key",
-                &sprint4_payload("intercompany.due_to_due_from.v1", 1200, Some("CA_BC_01")),
-            ))
+        let results = ndjson_body(response).await;
+        assert_eq!(results[0]["outcome"], json!("POSTED"));
+        assert_eq!(results[1]["outcome"], json!("REJECTED"));
+        assert_eq!(results[1]["http_status"], json!(409));
+        assert_eq!(state.journals.lock().unwrap().all().len(), 1);
+
+        let replay = app
+            .oneshot(post_request("bulk-ok", &order_payload(10000)))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(replay.status(), StatusCode::OK);
+        let body = json_body(replay).await;
+        assert_eq!(body["replayed"], json!(true));
+        assert_eq!(body["journal_id"], results[0]["journal_id"]);
     }
 
     #[tokio::test]
-    async fn sprint4_consolidation_event_posts_with_rule_engine_v1() {
-        let app = router();
-
-        let response = app
-            .oneshot(post_request(
-                "consolidation-key",
-                &sprint4_payload("consolidation.elimination.v1", 900, Some("CA_BC_01")),
-            ))
+    async fn bulk_endpoint_reports_every_book_journal() {
+        let mut dual_book = order_payload(10000);
+        dual_book["ledger_book"] = json!("");
+
+        let response = router()
+            .oneshot(bulk_post_request(&[
+                json!({"idempotency_key": "bulk-dual", "event": dual_book}),
+                json!({"idempotency_key": "bulk-dual", "event": dual_book}),
+                json!({"idempotency_key": "bulk-single", "event": order_payload(500)}),
+            ]))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
+        let results = ndjson_body(response).await;
+        assert_eq!(results[0]["outcome"], json!("POSTED"));
+        let book_journals = results[0]["book_journals"].as_array().unwrap();
+        assert_eq!(book_journals.len(), 2);
+        assert_eq!(book_journals[0]["ledger_book"], json!("US_GAAP"));
+        assert_eq!(book_journals[1]["ledger_book"], json!("IFRS"));
+        assert_eq!(results[0]["journal_id"], book_journals[0]["journal_id"]);
+        assert_eq!(results[1]["outcome"], json!("REPLAYED"));
+        assert_eq!(results[1]["book_journals"], results[0]["book_journals"]);
+        assert!(results[2].get("book_journals").is_none());
     }
 
-    #[tokio::test]
-    async fn sprint4_fx_translation_event_posts_with_rule_engine_v1() {
-        let app = router();
-
-        let response = app
-            .oneshot(post_request(
-                "fx-translation-key",
-                &sprint4_payload("fx.translation.v1", -500, None),
-            ))
-            .await
-            .unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
+    fn stripe_webhook_state() -> AppState {
+        let webhooks: WebhookConfig = serde_json::from_value(json!({
+            "sources": [{
+                "source": "stripe",
+                "secrets": [{ "secret_id": "whsec_1", "secret": "stripe-key" }],
+                "ledger_book": "US_GAAP",
+                "provenance": order_payload(0)["provenance"]
+            }]
+        }))
+        .unwrap();
+        AppState::default().with_webhook_sources(webhook_sources(webhooks).unwrap())
     }
 
     #[tokio::test]
-    async fn unsupported_event_type_is_rejected() {
-        let app = router();
+    async fn transient_normalization_failures_are_retryable_and_not_dead_lettered() {
+        use connector_sdk::{
+            CanonicalEvent, ConnectorAdapter, ConnectorError, RawEvent, SigningSecret,
+            StripeSignatureVerifier,
+        };
+
+        use crate::ingest::WebhookSource;
+
+        struct UnavailableAdapter;
+
+        #[async_trait::async_trait]
+        impl ConnectorAdapter for UnavailableAdapter {
+            fn source_system(&self) -> &'static str {
+                "stripe"
+            }
+
+            async fn normalize(&self, _raw: RawEvent) -> Result<CanonicalEvent, ConnectorError> {
+                Err(ConnectorError::Transient("fx lookup timed out".to_string()))
+            }
+        }
+
+        let provenance = serde_json::from_value(order_payload(0)["provenance"].clone()).unwrap();
+        let source = WebhookSource::new(
+            Arc::new(StripeSignatureVerifier::new(vec![SigningSecret {
+                secret_id: "whsec_1".to_string(),
+                secret: "stripe-key".to_string(),
+                expires_at: None,
+            }])),
+            Arc::new(UnavailableAdapter),
+            provenance,
+            "US_GAAP",
+        );
+        let state = AppState::default()
+            .with_webhook_sources(HashMap::from([("stripe".to_string(), Arc::new(source))]));
+        let app = router_with_state(state.clone());
 
         let response = app
-            .oneshot(post_request(
-                "unsupported-key",
-                &sprint3_payload("inventory.adjusted.v1", 100),
+            .oneshot(stripe_webhook_request(
+                &json!({"id": "evt_unavailable", "type": "charge.succeeded"}),
+                "stripe-key",
+                chrono::Utc::now().timestamp(),
             ))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
         let body = json_body(response).await;
-        assert_eq!(body["error"], json!("unsupported_event_type"));
+        assert_eq!(body["code"], json!("webhook_normalization_unavailable"));
+        assert!(body["details"].get("dead_letter_id").is_none());
+        assert!(state
+            .dead_letters
+            .lock()
+            .unwrap()
+            .list(None, None)
+            .is_empty());
+        assert!(state.journals.lock().unwrap().all().is_empty());
     }
 
     #[tokio::test]
-    async fn closed_period_rejects_first_seen_posting() {
-        let state = AppState::default();
-        state
-            .lock_period("tenant_1", "US_CO_01", "US_GAAP", "2026-02")
+    async fn backfill_posts_through_ingest_and_dead_letters_rejections() {
+        use connector_sdk::{BackfillError, FailureClass, RawEvent, ReplayBackfill, StripeAdapter};
+
+        use crate::backfill::{posting_error, DeadLetterStoreSink, IngestBackfillPoster};
+        use crate::dead_letter::DeadLetterStatus;
+
+        let state = stripe_webhook_state();
+        close_period(&state, "US_GAAP", "2026-02");
+        let charge = |id: &str, business_date: &str| RawEvent {
+            source_event_id: id.to_string(),
+            occurred_at: chrono::Utc::now(),
+            payload: json!({
+                "id": id,
+                "type": "charge.succeeded",
+                "business_date": business_date,
+                "data": { "object": {
+                    "id": format!("ch_{id}"),
+                    "amount": 12500,
+                    "currency": "usd",
+                    "tenant_id": "tenant_1",
+                    "legal_entity_id": "US_CO_01",
+                    "location_id": "BRECK_BASE_AREA"
+                }}
+            }),
+        };
+        let events = vec![
+            charge("evt_backfill_1", "2026-03-02"),
+            charge("evt_backfill_2", "2026-02-21"),
+        ];
+
+        let poster = IngestBackfillPoster::new(state.clone(), "stripe").unwrap();
+        let sink = DeadLetterStoreSink::new(state.clone(), "stripe").unwrap();
+        let result = ReplayBackfill::new(&StripeAdapter, &poster, &sink)
+            .run(&events, None, |_| {})
+            .await
             .unwrap();
-        let app = router_with_state(state);
+        assert_eq!(result.hashes.len(), 1);
+        assert_eq!(result.telemetry.failed_events, 1);
+        assert_eq!(result.telemetry.total_attempts, 2);
 
-        let response = app
-            .oneshot(post_request("period-key", &order_payload(10000)))
+        let app = router_with_state(state.clone());
+        let now = chrono::Utc::now().timestamp();
+        let live = app
+            .oneshot(stripe_webhook_request(
+                &events[0].payload,
+                "stripe-key",
+                now,
+            ))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::CONFLICT);
-        let body = json_body(response).await;
-        assert_eq!(body["error"], json!("period_closed:2026-02"));
+        assert_eq!(json_body(live).await["outcome"], json!("REPLAYED"));
+
+        let letters = state.dead_letters.lock().unwrap().list(None, None);
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].source, "stripe");
+        assert_eq!(letters[0].raw_event.source_event_id, "evt_backfill_2");
+        assert_eq!(letters[0].status, DeadLetterStatus::Pending);
+
+        let dead_letters = state.dead_letters.clone();
+        std::thread::spawn(move || {
+            let _guard = dead_letters.lock().unwrap();
+            panic!("poison the dead letter store");
+        })
+        .join()
+        .unwrap_err();
+        let mut checkpoints = Vec::new();
+        let error = ReplayBackfill::new(&StripeAdapter, &poster, &sink)
+            .run(&events[1..], None, |checkpoint| {
+                checkpoints.push(checkpoint.clone())
+            })
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, BackfillError::DeadLetter { index: 0, source }
+            if source.message == "dead letter store is unavailable")
+        );
+        assert!(checkpoints.is_empty());
+
+        let transient = [
+            ApiError::internal(ErrorCode::JournalStoreError),
+            ApiError::new(StatusCode::SERVICE_UNAVAILABLE, ErrorCode::InvalidPayload),
+            ApiError::conflict(ErrorCode::PeriodStoreError),
+        ];
+        for error in transient {
+            assert_eq!(posting_error(error).class, FailureClass::Transient);
+        }
+        assert_eq!(
+            posting_error(ApiError::bad_request(ErrorCode::UnsupportedEventType)).class,
+            FailureClass::Permanent
+        );
     }
 
     #[tokio::test]
-    async fn closed_period_replay_returns_same_error() {
-        let state = AppState::default();
-        state
-            .lock_period("tenant_1", "US_CO_01", "US_GAAP", "2026-02")
-            .unwrap();
-        let app = router_with_state(state);
-        let payload = order_payload(10000);
+    async fn adyen_notification_is_verified_normalized_and_posted_once() {
+        let state = adyen_webhook_state();
+        let app = router_with_state(state.clone());
+        let capture: serde_json::Value = serde_json::from_str(include_str!(
+            "../../connector-sdk/fixtures/adyen/capture.json"
+        ))
+        .unwrap();
 
-        let first = app
+        let response = app
             .clone()
-            .oneshot(post_request("closed-period-key", &payload))
+            .oneshot(post_json_request("/v1/ingest/adyen", &capture))
             .await
             .unwrap();
-        assert_eq!(first.status(), StatusCode::CONFLICT);
-        let first_body = json_body(first).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let posted = json_body(response).await;
+        assert_eq!(posted["source"], json!("adyen"));
+        assert_eq!(posted["outcome"], json!("POSTED"));
+        let journal_id: Uuid = posted["journal_id"].as_str().unwrap().parse().unwrap();
+        let header = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&journal_id)
+            .map(|record| record.header.clone())
+            .unwrap();
+        assert_eq!(header.accounting_date.to_string(), "2026-03-15");
 
-        let second = app
-            .oneshot(post_request("closed-period-key", &payload))
+        let redelivered = app
+            .clone()
+            .oneshot(post_json_request("/v1/ingest/adyen", &capture))
             .await
             .unwrap();
-        assert_eq!(second.status(), StatusCode::CONFLICT);
-        let second_body = json_body(second).await;
-
-        assert_eq!(second_body, first_body);
-    }
-
-    #[tokio::test]
-    async fn missing_location_is_rejected() {
-        let app = router();
-        let mut payload = order_payload(10000);
-        payload.as_object_mut().unwrap().remove("location_id");
+        let redelivered = json_body(redelivered).await;
+        assert_eq!(redelivered["outcome"], json!("REPLAYED"));
+        assert_eq!(redelivered["journal_id"], posted["journal_id"]);
 
+        let mut tampered = capture.clone();
+        tampered["notificationItems"][0]["NotificationRequestItem"]["amount"]["value"] = json!(1);
         let response = app
-            .oneshot(post_request("missing-location-key", &payload))
+            .clone()
+            .oneshot(post_json_request("/v1/ingest/adyen", &tampered))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        let body = json_body(response).await;
-        assert_eq!(body["error"], json!("missing_location_id"));
-    }
-
-    #[tokio::test]
-    async fn location_not_in_legal_entity_allowlist_is_rejected() {
-        let app = router();
-        let mut payload = order_payload(10000);
-        payload["location_id"] = json!("WHISTLER_VILLAGE");
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
+        let authorisation: serde_json::Value = serde_json::from_str(include_str!(
+            "../../connector-sdk/fixtures/adyen/authorisation.json"
+        ))
+        .unwrap();
         let response = app
-            .oneshot(post_request("boundary-key", &payload))
+            .oneshot(post_json_request("/v1/ingest/adyen", &authorisation))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        let body = json_body(response).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let skipped = json_body(response).await;
+        assert_eq!(skipped["outcome"], json!("SKIPPED"));
         assert_eq!(
-            body["error"],
-            json!("location_not_allowed_for_legal_entity")
+            skipped["skipped_reason"],
+            json!("adyen AUTHORISATION notifications are not booked")
         );
+        assert!(skipped["canonical_event_id"].is_null());
+        assert!(skipped["journal_id"].is_null());
+        assert!(state
+            .dead_letters
+            .lock()
+            .unwrap()
+            .list(None, None)
+            .is_empty());
     }
 
     #[tokio::test]
-    async fn intercompany_event_requires_counterparty() {
-        let app = router();
+    async fn batched_adyen_delivery_gets_one_result_per_item() {
+        let state = adyen_webhook_state();
+        let app = router_with_state(state.clone());
+        let fixture = |raw: &str| serde_json::from_str::<serde_json::Value>(raw).unwrap();
+        let mut delivery = fixture(include_str!(
+            "../../connector-sdk/fixtures/adyen/authorisation.json"
+        ));
+        let capture = fixture(include_str!(
+            "../../connector-sdk/fixtures/adyen/capture.json"
+        ));
+        delivery["notificationItems"]
+            .as_array_mut()
+            .unwrap()
+            .push(capture["notificationItems"][0].clone());
+
         let response = app
-            .oneshot(post_request(
-                "missing-counterparty-key",
-                &sprint4_payload("intercompany.due_to_due_from.v1", 500, None),
-            ))
+            .oneshot(post_json_request("/v1/ingest/adyen", &delivery))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.status(), StatusCode::OK);
         let body = json_body(response).await;
-        assert_eq!(body["error"], json!("missing_counterparty_legal_entity_id"));
+        assert_eq!(body["source"], json!("adyen"));
+        let items = body["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["outcome"], json!("SKIPPED"));
+        assert_eq!(items[1]["outcome"], json!("POSTED"));
+        assert!(items[1]["journal_id"].is_string());
+        assert_eq!(state.journals.lock().unwrap().all().len(), 1);
+        assert!(state
+            .dead_letters
+            .lock()
+            .unwrap()
+            .list(None, None)
+            .is_empty());
     }
 
-    #[tokio::test]
-    async fn intercompany_event_rejects_same_entity_counterparty() {
-        let app = router();
-        let response = app
-            .oneshot(post_request(
-                "same-counterparty-key",
-                &sprint4_payload("intercompany.due_to_due_from.v1", 500, Some("US_CO_01")),
-            ))
-            .await
-            .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        let body = json_body(response).await;
-        assert_eq!(body["error"], json!("invalid_counterparty_legal_entity"));
+    fn adyen_webhook_state() -> AppState {
+        let webhooks: WebhookConfig = serde_json::from_value(json!({
+            "sources": [{
+                "source": "adyen",
+                "secrets": [{
+                    "secret_id": "ady_1",
+                    "secret": "44782DEF547AAA06C910C43932B1EB0C71FC68D9D0C057550C48EC2ACF6BA056"
+                }],
+                "ledger_book": "US_GAAP",
+                "provenance": order_payload(0)["provenance"]
+            }]
+        }))
+        .unwrap();
+        AppState::default().with_webhook_sources(webhook_sources(webhooks).unwrap())
+    }
+
+    #[test]
+    fn webhook_sources_reject_sources_without_an_adapter() {
+        let webhooks: WebhookConfig = serde_json::from_value(json!({
+            "sources": [{
+                "source": "paypal",
+                "secrets": [{ "secret_id": "pp_1", "secret": "paypal-key" }],
+                "provenance": order_payload(0)["provenance"]
+            }]
+        }))
+        .unwrap();
+        assert!(matches!(
+            webhook_sources(webhooks),
+            Err(ConfigError::InvalidWebhookSource { source_system, .. }) if source_system == "paypal"
+        ));
     }
 
     #[tokio::test]
-    async fn consolidation_event_rejects_unknown_counterparty() {
-        let app = router();
+    async fn stripe_webhook_is_verified_normalized_and_posted_once() {
+        let state = stripe_webhook_state();
+        let app = router_with_state(state.clone());
+        let event = json!({
+            "id": "evt_ingest_1",
+            "type": "charge.succeeded",
+            "business_date": "2026-02-21",
+            "correlation_id": "corr_ingest_1",
+            "traceparent": "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "data": { "object": {
+                "id": "ch_ingest_1",
+                "amount": 12500,
+                "currency": "usd",
+                "tenant_id": "tenant_1",
+                "legal_entity_id": "US_CO_01",
+                "location_id": "BRECK_BASE_AREA"
+            }}
+        });
+        let now = chrono::Utc::now().timestamp();
+
         let response = app
-            .oneshot(post_request(
-                "unknown-counterparty-key",
-                &sprint4_payload("consolidation.elimination.v1", 500, Some("MX_NL_01")),
-            ))
+            .clone()
+            .oneshot(stripe_webhook_request(&event, "stripe-key", now))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        let body = json_body(response).await;
-        assert_eq!(body["error"], json!("unknown_counterparty_legal_entity"));
+        assert_eq!(response.status(), StatusCode::OK);
+        let posted = json_body(response).await;
+        assert_eq!(posted["outcome"], json!("POSTED"));
+        assert_eq!(
+            posted["idempotency_key"],
+            json!("stripe:evt_ingest_1:charge_captured")
+        );
+        assert!(posted["canonical_event_id"]
+            .as_str()
+            .unwrap()
+            .starts_with("stripe-evt_ingest_1-"));
+        let journal_id: Uuid = posted["journal_id"].as_str().unwrap().parse().unwrap();
+        let trace = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&journal_id)
+            .and_then(|record| record.header.trace_context.clone())
+            .unwrap();
+        assert_eq!(trace.correlation_id, "corr_ingest_1");
+        assert_eq!(
+            trace.traceparent.as_deref(),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+
+        let replayed_signature = app
+            .clone()
+            .oneshot(stripe_webhook_request(&event, "stripe-key", now))
+            .await
+            .unwrap();
+        assert_eq!(replayed_signature.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(replayed_signature).await["code"],
+            json!("webhook_replayed")
+        );
+
+        let redelivered = app
+            .clone()
+            .oneshot(stripe_webhook_request(&event, "stripe-key", now + 1))
+            .await
+            .unwrap();
+        let redelivered = json_body(redelivered).await;
+        assert_eq!(redelivered["outcome"], json!("REPLAYED"));
+        assert_eq!(redelivered["journal_id"], posted["journal_id"]);
+        assert_eq!(state.journals.lock().unwrap().all().len(), 1);
+
+        let forged = app
+            .clone()
+            .oneshot(stripe_webhook_request(&event, "wrong-key", now + 2))
+            .await
+            .unwrap();
+        assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            json_body(forged).await["code"],
+            json!("webhook_signature_invalid")
+        );
+
+        let mut unconfigured = stripe_webhook_request(&event, "stripe-key", now + 3);
+        *unconfigured.uri_mut() = "/v1/ingest/square".parse().unwrap();
+        let unconfigured = app.oneshot(unconfigured).await.unwrap();
+        assert_eq!(unconfigured.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn period_lock_endpoint_locks_period_and_blocks_posts() {
-        let app = router();
-        let lock_payload = json!({
-            "tenant_id": "tenant_1",
-            "legal_entity_id": "US_CO_01",
-            "ledger_book": "US_GAAP"
+    async fn dead_letter_retry_into_a_closed_period_stays_pending() {
+        let state = stripe_webhook_state();
+        let app = router_with_state(state.clone());
+        let mut event = json!({
+            "id": "evt_dl_closed",
+            "type": "charge.succeeded",
+            "business_date": "2026-02-21",
+            "data": { "object": {
+                "amount": 4200,
+                "currency": "usd",
+                "tenant_id": "tenant_1",
+                "location_id": "BRECK_BASE_AREA"
+            }}
         });
+        let actor = json!({ "actor_id": "ops_1", "role": "ACCOUNTANT" });
+        let now = chrono::Utc::now().timestamp();
+        let rejected = json_body(
+            app.clone()
+                .oneshot(stripe_webhook_request(&event, "stripe-key", now))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let dead_letter_id = rejected["details"]["dead_letter_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        close_period(&state, "US_GAAP", "2026-02");
+
+        event["data"]["object"]["legal_entity_id"] = json!("US_CO_01");
+        let retry_uri = format!("/v1/ingest/dead-letters/{dead_letter_id}/retry");
+        let retried = json_body(
+            app.clone()
+                .oneshot(post_json_request(
+                    &retry_uri,
+                    &json!({ "actor": actor, "payload": event }),
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(retried["outcome"], json!("REJECTED"));
+        assert_eq!(retried["status"], json!("PENDING"));
+        assert_eq!(retried["attempts"], json!(2));
+        assert_eq!(retried["error"]["code"], json!("period_closed"));
+        let inspected = json_body(
+            app.clone()
+                .oneshot(get_request(&format!(
+                    "/v1/ingest/dead-letters/{dead_letter_id}"
+                )))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(inspected["status"], json!("PENDING"));
+        assert_eq!(inspected["resolved_at"], json!(null));
+        assert_eq!(inspected["history"][0]["outcome"], json!("REJECTED"));
+        assert!(state.journals.lock().unwrap().all().is_empty());
+    }
 
-        let lock_response = app
+    #[tokio::test]
+    async fn unnormalizable_webhooks_are_dead_lettered_for_edit_and_replay() {
+        let state = stripe_webhook_state();
+        let app = router_with_state(state.clone());
+        let charge = |id: &str, legal_entity_id: Option<&str>| {
+            let mut event = json!({
+                "id": id,
+                "type": "charge.succeeded",
+                "business_date": "2026-02-21",
+                "data": { "object": {
+                    "amount": 4200,
+                    "currency": "usd",
+                    "tenant_id": "tenant_1",
+                    "location_id": "BRECK_BASE_AREA"
+                }}
+            });
+            if let Some(legal_entity_id) = legal_entity_id {
+                event["data"]["object"]["legal_entity_id"] = json!(legal_entity_id);
+            }
+            event
+        };
+        let actor = json!({ "actor_id": "ops_1", "role": "ACCOUNTANT" });
+        let now = chrono::Utc::now().timestamp();
+
+        let rejected = app
             .clone()
-            .oneshot(period_lock_request("2026-02", &lock_payload))
+            .oneshot(stripe_webhook_request(
+                &charge("evt_dl_1", None),
+                "stripe-key",
+                now,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+        let rejected = json_body(rejected).await;
+        assert_eq!(rejected["code"], json!("webhook_normalization_failed"));
+        let dead_letter_id = rejected["details"]["dead_letter_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        app.clone()
+            .oneshot(stripe_webhook_request(
+                &charge("evt_dl_2", None),
+                "stripe-key",
+                now,
+            ))
             .await
             .unwrap();
-        assert_eq!(lock_response.status(), StatusCode::OK);
-        let lock_body = json_body(lock_response).await;
-        assert_eq!(lock_body["status"], json!("LOCKED"));
-        assert_eq!(lock_body["period_id"], json!("2026-02"));
 
-        let post_response = app
-            .oneshot(post_request("lock-route-key", &order_payload(10000)))
+        let listed = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/ingest/dead-letters?source=stripe&status=PENDING",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(listed["dead_letters"].as_array().unwrap().len(), 2);
+
+        let retry_uri = format!("/v1/ingest/dead-letters/{dead_letter_id}/retry");
+        let unchanged = json_body(
+            app.clone()
+                .oneshot(post_json_request(&retry_uri, &json!({ "actor": actor })))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(unchanged["status"], json!("PENDING"));
+        assert_eq!(unchanged["attempts"], json!(2));
+        assert_eq!(
+            unchanged["error"]["code"],
+            json!("webhook_normalization_failed")
+        );
+
+        let edited = json_body(
+            app.clone()
+                .oneshot(post_json_request(
+                    &retry_uri,
+                    &json!({ "actor": actor, "payload": charge("evt_dl_1", Some("US_CO_01")) }),
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(edited["status"], json!("REPLAYED"));
+        assert_eq!(edited["outcome"], json!("POSTED"));
+        assert!(edited["journal_id"].is_string());
+        let again = app
+            .clone()
+            .oneshot(post_json_request(&retry_uri, &json!({ "actor": actor })))
             .await
             .unwrap();
-        assert_eq!(post_response.status(), StatusCode::CONFLICT);
-        let body = json_body(post_response).await;
-        assert_eq!(body["error"], json!("period_closed:2026-02"));
+        assert_eq!(again.status(), StatusCode::CONFLICT);
+
+        let inspected = json_body(
+            app.clone()
+                .oneshot(get_request(&format!(
+                    "/v1/ingest/dead-letters/{dead_letter_id}"
+                )))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let history = inspected["history"].as_array().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1]["payload_edited"], json!(true));
+        assert_eq!(
+            history[1]["edited_payload_hash"],
+            json!(payload_hash(&charge("evt_dl_1", Some("US_CO_01"))))
+        );
+        assert_eq!(
+            inspected["raw_event"]["payload"],
+            charge("evt_dl_1", None),
+            "the stored raw event keeps the payload the source sent"
+        );
+        assert_eq!(history[1]["actor"]["actor_id"], json!("ops_1"));
+        assert_eq!(
+            history[1]["adapter_version"],
+            json!(env!("CARGO_PKG_VERSION"))
+        );
+
+        let replayed = json_body(
+            app.clone()
+                .oneshot(post_json_request(
+                    "/v1/ingest/dead-letters/replay",
+                    &json!({ "actor": actor, "source": "stripe" }),
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let results = replayed["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["status"], json!("PENDING"));
+        let pending_id = results[0]["dead_letter_id"].as_str().unwrap().to_string();
+
+        let discarded = json_body(
+            app.clone()
+                .oneshot(post_json_request(
+                    &format!("/v1/ingest/dead-letters/{pending_id}/discard"),
+                    &json!({ "actor": actor, "reason": "test charge" }),
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(discarded["status"], json!("DISCARDED"));
+        assert!(discarded["history"][1]["audit_seal"].is_string());
+        let seals = state.audit_seals.len().unwrap();
+        let replayed = json_body(
+            app.oneshot(post_json_request(
+                "/v1/ingest/dead-letters/replay",
+                &json!({ "actor": actor, "dead_letter_ids": [pending_id, "dl_missing"] }),
+            ))
+            .await
+            .unwrap(),
+        )
+        .await;
+        assert_eq!(
+            replayed["results"][0]["error"]["code"],
+            json!("dead_letter_not_pending")
+        );
+        assert_eq!(
+            replayed["results"][1]["error"]["code"],
+            json!("dead_letter_not_found")
+        );
+        assert_eq!(state.audit_seals.len(), Ok(seals));
     }
 
     #[tokio::test]
-    async fn persistent_state_reloads_locked_periods_after_restart() {
-        let temp_dir = TempDirGuard::new("period-reload");
-        let state = AppState::with_persistence_dir(&temp_dir.path).unwrap();
-        state
-            .lock_period("tenant_1", "US_CO_01", "US_GAAP", "2026-02")
+    async fn inntopia_reservation_posts_with_rule_engine_v1() {
+        let app = router();
+
+        let response = app
+            .oneshot(post_request("inntopia-key", &inntopia_payload(41250)))
+            .await
             .unwrap();
-        state.flush_persistence().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 
-        let reloaded = AppState::with_persistence_dir(&temp_dir.path).unwrap();
-        let err = reloaded
-            .periods
-            .lock()
-            .unwrap()
-            .ensure_open(
-                "tenant_1",
-                "US_CO_01",
-                "US_GAAP",
-                chrono::NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
-            )
-            .unwrap_err();
-        assert_eq!(err.to_string(), "period is closed: 2026-02");
+    #[tokio::test]
+    async fn sprint3_fee_event_posts_with_rule_engine_v1() {
+        let app = router();
+
+        let response = app
+            .oneshot(post_request(
+                "fee-key",
+                &sprint3_payload("fee.assessed.v1", 325),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn persistent_state_reloads_journals_after_restart() {
-        let temp_dir = TempDirGuard::new("journal-reload");
-        let state = AppState::with_persistence_dir(&temp_dir.path).unwrap();
-        let app = router_with_state(state.clone());
+    async fn sprint4_intercompany_event_posts_with_rule_engine_v1() {
+        let app = router();
+
         let response = app
-            .oneshot(post_request("persisted-journal-key", &order_payload(10000)))
+            .oneshot(post_request(
+                "intercompany-key",
+                &sprint4_payload("intercompany.due_to_due_from.v1", 1200, Some("CA_BC_01")),
+            ))
             .await
             .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
-        let body = json_body(response).await;
-        let journal_id = body["journal_id"].as_str().unwrap().to_string();
-        state.flush_persistence().unwrap();
+    }
 
-        let reloaded = AppState::with_persistence_dir(&temp_dir.path).unwrap();
-        let repo = reloaded.journals.lock().unwrap();
-        let parsed_id = Uuid::parse_str(&journal_id).unwrap();
-        assert!(repo.get(&parsed_id).is_some());
+    #[tokio::test]
+    async fn sprint4_consolidation_event_posts_with_rule_engine_v1() {
+        let app = router();
+
+        let response = app
+            .oneshot(post_request(
+                "consolidation-key",
+                &sprint4_payload("consolidation.elimination.v1", 900, Some("CA_BC_01")),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn period_lock_endpoint_rejects_invalid_period_id() {
+    async fn sprint4_fx_translation_event_posts_with_rule_engine_v1() {
         let app = router();
-        let lock_payload = json!({
-            "tenant_id": "tenant_1",
-            "legal_entity_id": "US_CO_01",
-            "ledger_book": "US_GAAP"
-        });
 
         let response = app
-            .oneshot(period_lock_request("202602", &lock_payload))
+            .oneshot(post_request(
+                "fx-translation-key",
+                &sprint4_payload("fx.translation.v1", -500, None),
+            ))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-        let body = json_body(response).await;
-        assert!(body["error"]
-            .as_str()
-            .unwrap_or_default()
-            .contains("invalid period id"));
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn reversal_endpoint_reverses_once() {
+    async fn unsupported_event_type_is_rejected() {
         let app = router();
-        let payload = order_payload(10000);
-        let key = "reverse-key";
 
-        let post = app
-            .clone()
-            .oneshot(post_request(key, &payload))
+        let response = app
+            .oneshot(post_request(
+                "unsupported-key",
+                &sprint3_payload("inventory.adjusted.v1", 100),
+            ))
             .await
             .unwrap();
-        assert_eq!(post.status(), StatusCode::OK);
-        let post_body = json_body(post).await;
-        let journal_id = post_body["journal_id"].as_str().unwrap().to_string();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("unsupported_event_type"));
+    }
 
-        let reverse = Request::builder()
-            .method("POST")
-            .uri(format!("/v1/ledger/journals/{journal_id}/reverse"))
-            .body(Body::empty())
+    #[tokio::test]
+    async fn closed_period_rejects_first_seen_posting() {
+        let state = AppState::default();
+        close_period(&state, "US_GAAP", "2026-02");
+        let app = router_with_state(state);
+
+        let response = app
+            .oneshot(post_request("period-key", &order_payload(10000)))
+            .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("period_closed"));
+        assert_eq!(body["details"]["period_id"], json!("2026-02"));
+    }
 
-        let first_reverse = app.clone().oneshot(reverse).await.unwrap();
-        assert_eq!(first_reverse.status(), StatusCode::OK);
-        let first_body = json_body(first_reverse).await;
-        assert_eq!(first_body["status"], json!("REVERSED"));
+    #[tokio::test]
+    async fn future_period_rejects_postings_until_opened() {
+        let state = AppState::default();
+        state.periods.lock().unwrap().set_clock(Arc::new(FixedClock(
+            NaiveDate::from_ymd_opt(2026, 1, 20).unwrap(),
+        )));
+        let app = router_with_state(state);
 
-        let duplicate_reverse = Request::builder()
-            .method("POST")
-            .uri(format!("/v1/ledger/journals/{journal_id}/reverse"))
-            .body(Body::empty())
+        let response = app
+            .clone()
+            .oneshot(post_request("future-period-key", &order_payload(10000)))
+            .await
             .unwrap();
-        let second_reverse = app.oneshot(duplicate_reverse).await.unwrap();
-        assert_eq!(second_reverse.status(), StatusCode::CONFLICT);
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("period_posting_not_allowed"));
+        assert_eq!(body["details"]["period_id"], json!("2026-02"));
+        assert_eq!(body["details"]["state"], json!("FUTURE"));
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/ledger/periods?tenant_id=tenant_1&legal_entity_id=US_CO_01&ledger_book=US_GAAP&fiscal_year=2026",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["periods"][0]["state"], json!("OPEN"));
+        assert_eq!(body["periods"][1]["state"], json!("FUTURE"));
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/periods/2026-02/transitions",
+                &json!({
+                    "tenant_id": "tenant_1",
+                    "legal_entity_id": "US_CO_01",
+                    "ledger_book": "US_GAAP",
+                    "to_state": "OPEN",
+                    "actor": {"actor_id": "u-acct", "role": "ACCOUNTANT"}
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["from_state"], json!("FUTURE"));
+        let response = app
+            .oneshot(post_request("opened-period-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 
     #[tokio::test]
-    async fn legal_hold_blocks_posting_when_active() {
-        let app = router();
-        let hold_payload = json!({
-            "hold_id": "LH-2026-0001",
-            "tenant_id": "tenant_1",
-            "legal_entity_id": "US_CO_01",
-            "ledger_book": "US_GAAP",
-            "start_date": "2026-02-01",
-            "end_date": "2026-03-01",
-            "reason": "Regulatory audit",
-            "retention_days": 2555
-        });
+    async fn closed_period_replay_returns_same_error() {
+        let state = AppState::default();
+        close_period(&state, "US_GAAP", "2026-02");
+        let app = router_with_state(state);
+        let payload = order_payload(10000);
 
-        let hold_response = app
+        let first = app
             .clone()
-            .oneshot(legal_hold_request(&hold_payload))
+            .oneshot(post_request("closed-period-key", &payload))
             .await
             .unwrap();
-        assert_eq!(hold_response.status(), StatusCode::OK);
+        assert_eq!(first.status(), StatusCode::CONFLICT);
+        let mut first_body = json_body(first).await;
 
+        let second = app
+            .oneshot(post_request("closed-period-key", &payload))
+            .await
+            .unwrap();
+        assert_eq!(second.status(), StatusCode::CONFLICT);
+        let mut second_body = json_body(second).await;
+
+        assert_ne!(first_body["correlation_id"], second_body["correlation_id"]);
+        first_body.as_object_mut().unwrap().remove("correlation_id");
+        second_body
+            .as_object_mut()
+            .unwrap()
+            .remove("correlation_id");
+        assert_eq!(second_body, first_body);
+    }
+
+    #[tokio::test]
+    async fn missing_location_is_rejected() {
+        let app = router();
+        let mut payload = order_payload(10000);
+        payload.as_object_mut().unwrap().remove("location_id");
+
+        let response = app
+            .oneshot(post_request("missing-location-key", &payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("missing_location_id"));
+    }
+
+    #[tokio::test]
+    async fn location_not_in_legal_entity_allowlist_is_rejected() {
+        let app = router();
+        let mut payload = order_payload(10000);
+        payload["location_id"] = json!("WHISTLER_VILLAGE");
+
+        let response = app
+            .oneshot(post_request("boundary-key", &payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("location_not_allowed_for_legal_entity"));
+    }
+
+    #[tokio::test]
+    async fn intercompany_event_requires_counterparty() {
+        let app = router();
+        let response = app
+            .oneshot(post_request(
+                "missing-counterparty-key",
+                &sprint4_payload("intercompany.due_to_due_from.v1", 500, None),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("missing_counterparty_legal_entity_id"));
+    }
+
+    #[tokio::test]
+    async fn intercompany_event_rejects_same_entity_counterparty() {
+        let app = router();
+        let response = app
+            .oneshot(post_request(
+                "same-counterparty-key",
+                &sprint4_payload("intercompany.due_to_due_from.v1", 500, Some("US_CO_01")),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("invalid_counterparty_legal_entity"));
+    }
+
+    #[tokio::test]
+    async fn consolidation_event_rejects_unknown_counterparty() {
+        let app = router();
+        let response = app
+            .oneshot(post_request(
+                "unknown-counterparty-key",
+                &sprint4_payload("consolidation.elimination.v1", 500, Some("MX_NL_01")),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("unknown_counterparty_legal_entity"));
+    }
+
+    #[tokio::test]
+    async fn period_lock_endpoint_locks_period_and_blocks_posts() {
+        let app = router();
+        let lock_payload = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+        });
+        let response = app
+            .clone()
+            .oneshot(close_checklist_request("2026-02"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(close_dependency_request("2026-02", "Satisfied"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(soft_close_request("2026-02"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut accountant_lock = lock_payload.clone();
+        accountant_lock["actor"]["role"] = json!("ACCOUNTANT");
+        let response = app
+            .clone()
+            .oneshot(period_lock_request("2026-02", &accountant_lock))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("period_transition_forbidden")
+        );
+
+        let lock_response = app
+            .clone()
+            .oneshot(period_lock_request("2026-02", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(lock_response.status(), StatusCode::OK);
+        let lock_body = json_body(lock_response).await;
+        assert_eq!(lock_body["status"], json!("LOCKED"));
+        assert_eq!(lock_body["period_id"], json!("2026-02"));
+
+        let post_response = app
+            .oneshot(post_request("lock-route-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(post_response.status(), StatusCode::CONFLICT);
+        let body = json_body(post_response).await;
+        assert_eq!(body["code"], json!("period_closed"));
+        assert_eq!(body["details"]["period_id"], json!("2026-02"));
+    }
+
+    #[tokio::test]
+    async fn period_lock_resolves_through_the_entity_fiscal_calendar() {
+        let state = AppState::default().with_fiscal_calendars(HashMap::from([(
+            "US_CO_01".to_string(),
+            FiscalCalendar::FiscalMonth { start_month: 5 },
+        )]));
+        let app = router_with_state(state);
+        let lock_payload = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+        });
+
+        let response = app
+            .clone()
+            .oneshot(period_lock_request("2026-02", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("invalid_period_id")
+        );
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/ledger/fiscal-calendars/US_CO_01?fiscal_year=2026",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["calendar"]["kind"], json!("FISCAL_MONTH"));
+        assert_eq!(body["periods"][9]["period_id"], json!("FY2026-P10"));
+        assert_eq!(body["periods"][9]["start_date"], json!("2026-02-01"));
+        assert_eq!(body["periods"][9]["end_date"], json!("2026-02-28"));
+
+        let response = app
+            .clone()
+            .oneshot(close_checklist_request("FY2026-P10"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(close_dependency_request("FY2026-P10", "Satisfied"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(soft_close_request("FY2026-P10"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(period_lock_request("FY2026-P10", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .oneshot(post_request("fiscal-lock-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("period_closed"));
+        assert_eq!(body["details"]["period_id"], json!("FY2026-P10"));
+    }
+
+    #[tokio::test]
+    async fn hard_close_is_gated_on_a_ready_close_checklist() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+        let lock_payload = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+        });
+
+        let response = app
+            .clone()
+            .oneshot(period_lock_request("2026-02", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("invalid_period_transition")
+        );
+        let response = app
+            .clone()
+            .oneshot(soft_close_request("2026-02"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(period_lock_request("2026-02", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("close_checklist_required")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(close_checklist_request("2026-02"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(close_dependency_request("2026-02", "Blocked"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await["checklist"]["status"],
+            json!("Blocked")
+        );
+        let response = app
+            .clone()
+            .oneshot(period_lock_request("2026-02", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("close_checklist_not_ready"));
+        assert_eq!(body["details"]["status"], json!("Blocked"));
+        assert_eq!(body["details"]["unresolved_blockers"], json!(["bank-rec"]));
+        assert_eq!(
+            body["details"]["outstanding_dependencies"],
+            json!(["bank-rec"])
+        );
+
+        let close_transition = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "to_state": "CLOSED",
+            "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+        });
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/periods/2026-02/transitions",
+                &close_transition,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("close_checklist_not_ready")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(close_dependency_request("2026-02", "Satisfied"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["checklist"]["status"], json!("ReadyToClose"));
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/periods/2026-02/transitions",
+                &close_transition,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["state"], json!("CLOSED"));
+        assert!(body["audit_seal"]
+            .as_str()
+            .is_some_and(|seal| !seal.is_empty()));
+        assert_eq!(state.audit_seals.len().unwrap(), 1);
+
+        let response = app
+            .oneshot(get_request(
+                "/v1/close/checklists/US_CO_01/2026-02?tenant_id=tenant_1",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await["checklist"]["status"],
+            json!("ReadyToClose")
+        );
+    }
+
+    #[tokio::test]
+    async fn reversal_refuses_year_end_closing_journals() {
+        let state = AppState::default();
+        state.periods.lock().unwrap().set_clock(Arc::new(FixedClock(
+            NaiveDate::from_ymd_opt(2027, 1, 15).unwrap(),
+        )));
+        let app = router_with_state(state.clone());
+        let response = app
+            .clone()
+            .oneshot(post_request("year-end-order", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/year-end-closes",
+                &json!({
+                    "tenant_id": "tenant_1",
+                    "legal_entity_id": "US_CO_01",
+                    "ledger_book": "US_GAAP",
+                    "fiscal_year": 2026,
+                    "posting_run_id": "year-end-2026",
+                    "provenance": {
+                        "book_policy_id": "policy_dual_book",
+                        "policy_version": "1.0.0",
+                        "fx_rate_set_id": "fx_2026_12_31",
+                        "ruleset_version": "v1",
+                        "workflow_id": null
+                    }
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let journal_id = json_body(response).await["closing_journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = app.oneshot(reverse_request(&journal_id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("journal_has_subledger_effects"));
+        assert_eq!(body["details"]["subledger"], json!("year_end_close"));
+
+        let close = state
+            .year_end_closes
+            .lock()
+            .unwrap()
+            .get("tenant_1", "US_CO_01", "US_GAAP", 2026)
+            .cloned()
+            .unwrap();
+        assert_eq!(close.status, YearEndCloseStatus::Closed);
+        let journal_id = Uuid::parse_str(&journal_id).unwrap();
+        assert_eq!(
+            state
+                .journals
+                .lock()
+                .unwrap()
+                .get(&journal_id)
+                .unwrap()
+                .header
+                .status,
+            JournalStatus::Posted
+        );
+    }
+
+    #[tokio::test]
+    async fn year_end_close_rolls_income_into_retained_earnings_once() {
+        let state = AppState::default();
+        state.periods.lock().unwrap().set_clock(Arc::new(FixedClock(
+            NaiveDate::from_ymd_opt(2027, 1, 15).unwrap(),
+        )));
+        let app = router_with_state(state.clone());
+        let response = app
+            .clone()
+            .oneshot(post_request("year-end-order", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let close_request = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "fiscal_year": 2026,
+            "posting_run_id": "year-end-2026",
+            "provenance": {
+                "book_policy_id": "policy_dual_book",
+                "policy_version": "1.0.0",
+                "fx_rate_set_id": "fx_2026_12_31",
+                "ruleset_version": "v1",
+                "workflow_id": null
+            }
+        });
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/year-end-closes",
+                &close_request,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["status"], json!("CLOSED"));
+        assert_eq!(body["year_end_date"], json!("2026-12-31"));
+        assert_eq!(body["opening_date"], json!("2027-01-01"));
+        assert_eq!(body["net_income"], json!({"USD": 10000}));
+        let first_journal_id =
+            Uuid::parse_str(body["closing_journal_id"].as_str().unwrap()).unwrap();
+        let closing = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&first_journal_id)
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            closing.header.accounting_date,
+            NaiveDate::from_ymd_opt(2026, 12, 31).unwrap()
+        );
+        assert!(closing.lines.iter().any(|line| {
+            line.account_id == "3200-RETAINED-EARNINGS"
+                && line.entry_side == EntrySide::Credit
+                && line.amount_minor == 10000
+        }));
+        let carry_forward = body["carry_forward"].as_array().unwrap();
+        assert!(carry_forward.iter().any(|balance| {
+            balance["account_id"] == json!("3200-RETAINED-EARNINGS")
+                && balance["balance_minor"] == json!(10000)
+        }));
+        assert!(carry_forward
+            .iter()
+            .all(|balance| !balance["account_id"].as_str().unwrap().starts_with('4')));
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/year-end-closes",
+                &close_request,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("year_end_already_closed")
+        );
+
+        let reverse = json!({"tenant_id": "tenant_1", "ledger_book": "US_GAAP"});
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/year-end-closes/US_CO_01/2026/reverse",
+                &reverse,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["status"], json!("REVERSED"));
+        assert_eq!(
+            state
+                .journals
+                .lock()
+                .unwrap()
+                .get(&first_journal_id)
+                .unwrap()
+                .header
+                .status,
+            JournalStatus::Reversed
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/year-end-closes",
+                &close_request,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["revision"], json!(2));
+        assert_eq!(body["net_income"], json!({"USD": 10000}));
+        assert_ne!(
+            body["closing_journal_id"],
+            json!(first_journal_id.to_string())
+        );
+
+        let response = app
+            .oneshot(get_request(
+                "/v1/ledger/year-end-closes/US_CO_01/2026?tenant_id=tenant_1&ledger_book=US_GAAP",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["status"], json!("CLOSED"));
+    }
+
+    #[tokio::test]
+    async fn prior_period_adjustments_restate_the_period_they_correct() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+        let response = app
+            .clone()
+            .oneshot(post_request("ppa-order", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        close_period(&state, "US_GAAP", "2026-02");
+
+        let adjustment = |source_event_id: &str,
+                          accounting_date: &str,
+                          affected_period_id: &str,
+                          amount: i64,
+                          material: bool| {
+            json!({
+                "tenant_id": "tenant_1",
+                "legal_entity_id": "US_CO_01",
+                "ledger_book": "US_GAAP",
+                "accounting_date": accounting_date,
+                "affected_period_id": affected_period_id,
+                "reason_code": "REVENUE_OVERSTATED",
+                "material": material,
+                "source_event_id": source_event_id,
+                "posting_run_id": "ppa-run",
+                "lines": [
+                    {"account_id": "4000-REVENUE", "entry_side": "debit", "amount_minor": amount, "currency": "USD", "base_amount_minor": amount, "base_currency": "USD"},
+                    {"account_id": "1105-CASH-CLEARING", "entry_side": "credit", "amount_minor": amount, "currency": "USD", "base_amount_minor": amount, "base_currency": "USD"}
+                ],
+                "provenance": {
+                    "book_policy_id": "policy_dual_book",
+                    "policy_version": "1.0.0",
+                    "fx_rate_set_id": "fx_2026_03_15",
+                    "ruleset_version": "v1",
+                    "workflow_id": null
+                }
+            })
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/prior-period-adjustments",
+                &adjustment("ppa-locked", "2026-02-27", "2026-01", 1500, true),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(json_body(response).await["code"], json!("period_closed"));
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/prior-period-adjustments",
+                &adjustment("ppa-same", "2026-03-15", "2026-03", 1500, true),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("affected_period_not_prior")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/prior-period-adjustments",
+                &adjustment("ppa-material", "2026-03-15", "2026-02", 1500, true),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["period_id"], json!("2026-03"));
+        assert_eq!(body["affected_period_id"], json!("2026-02"));
+        let journal_id = Uuid::parse_str(body["journal_id"].as_str().unwrap()).unwrap();
+        let lineage = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&journal_id)
+            .unwrap()
+            .header
+            .prior_period_adjustment
+            .clone()
+            .unwrap();
+        assert_eq!(lineage.affected_period_id, "2026-02");
+        assert_eq!(lineage.reason_code, "REVENUE_OVERSTATED");
+        assert!(lineage.material);
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/prior-period-adjustments",
+                &adjustment("ppa-immaterial", "2026-03-16", "2026-02", 200, false),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let revenue = |body: &serde_json::Value| {
+            body["balances"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|balance| balance["account_id"] == json!("4000-REVENUE"))
+                .map(|balance| balance["balance_minor"].as_i64().unwrap())
+                .unwrap_or_default()
+        };
+        let results = |period_id: &str, basis: &str| {
+            get_request(&format!(
+                "/v1/ledger/period-results?tenant_id=tenant_1&legal_entity_id=US_CO_01&ledger_book=US_GAAP&period_id={period_id}&basis={basis}"
+            ))
+        };
+        let reported = json_body(
+            app.clone()
+                .oneshot(results("2026-02", "AS_ORIGINALLY_REPORTED"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(revenue(&reported), 10000);
+        assert_eq!(reported["corrections"].as_array().unwrap().len(), 2);
+        let restated = json_body(
+            app.clone()
+                .oneshot(results("2026-02", "AS_RESTATED"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(restated["basis"], json!("AS_RESTATED"));
+        assert_eq!(revenue(&restated), 8500);
+        let march_reported = json_body(
+            app.clone()
+                .oneshot(results("2026-03", "AS_ORIGINALLY_REPORTED"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(revenue(&march_reported), -1700);
+        let march_restated = json_body(
+            app.oneshot(results("2026-03", "AS_RESTATED"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(revenue(&march_restated), -200);
+    }
+
+    #[tokio::test]
+    async fn soft_closed_period_rejects_operational_events_but_takes_adjustments() {
+        let app = router();
+        let transition = |to_state: &str, role: &str| {
+            json!({
+                "tenant_id": "tenant_1",
+                "legal_entity_id": "US_CO_01",
+                "ledger_book": "US_GAAP",
+                "to_state": to_state,
+                "actor": {"actor_id": "u-close", "role": role}
+            })
+        };
+        let response = app
+            .clone()
+            .oneshot(post_request("soft-close-order-1", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let journal_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/periods/2026-02/transitions",
+                &transition("SOFT_CLOSED", "ACCOUNTANT"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["from_state"], json!("OPEN"));
+        assert_eq!(body["state"], json!("SOFT_CLOSED"));
+
+        let mut second = order_payload(5000);
+        second["source_event_id"] = json!("evt_soft_close_2");
+        let response = app
+            .clone()
+            .oneshot(post_request("soft-close-order-2", &second))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("period_posting_not_allowed"));
+        assert_eq!(body["details"]["state"], json!("SOFT_CLOSED"));
+
+        // An event carrying its own lines is still operational, whatever role it claims.
+        let mut lines_only = order_payload(5000);
+        lines_only["source_event_id"] = json!("evt_soft_close_lines");
+        lines_only.as_object_mut().unwrap().remove("payload");
+        lines_only["lines"] = adjustment_payload("adj_soft_close_lines", 5000)["lines"].clone();
+        lines_only["actor"] = json!({"actor_id": "u-close", "role": "ACCOUNTANT"});
+        let response = app
+            .clone()
+            .oneshot(post_request("soft-close-lines-only", &lines_only))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["details"]["posting_class"],
+            json!("OPERATIONAL")
+        );
+
+        let mut adjustment = adjustment_payload("adj_soft_close", 10000);
+        adjustment["actor"] = json!({"actor_id": "u-close", "role": "ACCOUNTANT"});
+        let response = app
+            .clone()
+            .oneshot(adjust_request(&journal_id, &adjustment))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/periods/2026-02/transitions",
+                &transition("CLOSED", "ACCOUNTANT"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("period_transition_forbidden")
+        );
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/ledger/periods/2026-02/transitions",
+                &transition("FUTURE", "CONTROLLER"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("invalid_period_transition")
+        );
+
+        let body = json_body(
+            app.oneshot(get_request(
+                "/v1/ledger/periods?tenant_id=tenant_1&legal_entity_id=US_CO_01&ledger_book=US_GAAP&fiscal_year=2026",
+            ))
+            .await
+            .unwrap(),
+        )
+        .await;
+        assert_eq!(body["periods"][0]["state"], json!("OPEN"));
+        assert_eq!(body["periods"][1]["state"], json!("SOFT_CLOSED"));
+    }
+
+    #[tokio::test]
+    async fn persistent_state_reloads_locked_periods_after_restart() {
+        let temp_dir = TempDirGuard::new("period-reload");
+        let state = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        close_period(&state, "US_GAAP", "2026-02");
+        state.flush_persistence().unwrap();
+
+        let reloaded = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        let err = reloaded
+            .periods
+            .lock()
+            .unwrap()
+            .ensure_open(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                chrono::NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+                PostingAuthority::new(PostingClass::Operational, None),
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "period is closed: 2026-02");
+    }
+
+    #[tokio::test]
+    async fn persistent_state_reloads_journals_after_restart() {
+        let temp_dir = TempDirGuard::new("journal-reload");
+        let state = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        let app = router_with_state(state.clone());
+        let response = app
+            .oneshot(post_request("persisted-journal-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        let journal_id = body["journal_id"].as_str().unwrap().to_string();
+        state.flush_persistence().unwrap();
+
+        let reloaded = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        let repo = reloaded.journals.lock().unwrap();
+        let parsed_id = Uuid::parse_str(&journal_id).unwrap();
+        assert!(repo.get(&parsed_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn startup_refuses_tampered_audit_seal_store() {
+        let temp_dir = TempDirGuard::new("startup-integrity");
+        let state = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        let response = router_with_state(state.clone())
+            .oneshot(post_request("integrity-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        state.flush_persistence().unwrap();
+        drop(state);
+
+        let config = ServerConfig {
+            persistence_dir: Some(temp_dir.path.clone()),
+            ..ServerConfig::default()
+        };
+        assert!(AppState::from_config(&config).is_ok());
+
+        let seal_path = temp_dir.path.join("audit_seal_store.json");
+        let mut entries: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&seal_path).unwrap()).unwrap();
+        entries[0]["payload_hash"] = json!("0000");
+        std::fs::write(&seal_path, entries.to_string()).unwrap();
+
+        let result = AppState::from_config(&config);
+        assert!(matches!(
+            result,
+            Err(StartupError::Integrity(IntegrityError::AuditSeal(
+                AuditSealError::Tampered { sequence: 1 }
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn single_event_posts_every_book_required_by_entity_policy() {
+        let rule_set = RuleSet::from_json(
+            FsPath::new("tenant-v1.json"),
+            json!({
+                "ruleset_version": "tenant-v1",
+                "rules": {
+                    "order.captured.v1": {
+                        "amounts": [{"name": "amount", "field": "amount_minor", "from": ["/amount_minor"]}],
+                        "strings": [
+                            {"name": "currency", "from": ["/currency"], "default": "USD"},
+                            {"name": "base_currency", "default": "$currency"}
+                        ],
+                        "lines": [
+                            {"account": "1105-CASH-CLEARING", "side": "debit", "amount": "amount"},
+                            {"books": ["US_GAAP"], "account": "4000-REVENUE", "side": "credit", "amount": "amount"},
+                            {"books": ["IFRS"], "account": "4000-REVENUE-IFRS15", "side": "credit", "amount": "amount"}
+                        ]
+                    }
+                }
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+        let mut rule_sets = RuleSetRegistry::default();
+        rule_sets.insert(rule_set);
+        let state = AppState::default().with_rule_sets(rule_sets);
+        let app = router_with_state(state.clone());
+
+        let mut dual_book = order_payload(10000);
+        dual_book.as_object_mut().unwrap().remove("ledger_book");
+        dual_book["provenance"]["ruleset_version"] = json!("tenant-v1");
+        let response = app
+            .clone()
+            .oneshot(post_request("dual-book-key", &dual_book))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        let book_journals = body["book_journals"].as_array().unwrap().clone();
+        assert_eq!(book_journals.len(), 2);
+        assert_eq!(book_journals[0]["ledger_book"], json!("US_GAAP"));
+        assert_eq!(book_journals[1]["ledger_book"], json!("IFRS"));
+        assert_eq!(body["journal_id"], book_journals[0]["journal_id"]);
+
+        {
+            let journals = state.journals.lock().unwrap();
+            for (book_journal, revenue_account) in book_journals
+                .iter()
+                .zip(["4000-REVENUE", "4000-REVENUE-IFRS15"])
+            {
+                let journal_id =
+                    Uuid::parse_str(book_journal["journal_id"].as_str().unwrap()).unwrap();
+                let record = journals.get(&journal_id).unwrap();
+                assert_eq!(
+                    json!(record.header.ledger_book),
+                    book_journal["ledger_book"]
+                );
+                assert_eq!(record.header.book_policy_id, "policy_dual_book");
+                assert_eq!(record.header.policy_version, "1.0.0");
+                assert_eq!(record.header.source_event_ids, vec!["evt_1"]);
+                assert_eq!(record.lines[1].account_id, revenue_account);
+            }
+        }
+        assert_eq!(state.change_feed.read_after(0, 10).unwrap().len(), 2);
+
+        let response = app
+            .oneshot(post_request("dual-book-key", &dual_book))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let replay = json_body(response).await;
+        assert_eq!(replay["replayed"], json!(true));
+        assert_eq!(replay["book_journals"], json!(book_journals));
+    }
+
+    #[tokio::test]
+    async fn reversing_one_book_journal_reverses_every_book() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+        let mut dual_book = order_payload(10000);
+        dual_book["ledger_book"] = json!("");
+        let response = app
+            .clone()
+            .oneshot(post_request("dual-book-reverse", &dual_book))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let book_journals = json_body(response).await["book_journals"].clone();
+        assert_eq!(book_journals.as_array().unwrap().len(), 2);
+        let response = app
+            .clone()
+            .oneshot(post_request("single-book-order", &order_payload(5000)))
+            .await
+            .unwrap();
+        let unrelated_id =
+            Uuid::parse_str(json_body(response).await["journal_id"].as_str().unwrap()).unwrap();
+
+        let ifrs_journal_id = book_journals[1]["journal_id"].as_str().unwrap();
+        let response = app
+            .clone()
+            .oneshot(reverse_request(ifrs_journal_id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["journal_id"], json!(ifrs_journal_id));
+        let reversed = body["book_journals"].as_array().unwrap();
+        assert_eq!(reversed.len(), 2);
+        assert_eq!(reversed[0]["ledger_book"], json!("IFRS"));
+        assert_eq!(reversed[1]["ledger_book"], json!("US_GAAP"));
+        {
+            let journals = state.journals.lock().unwrap();
+            for book_journal in book_journals.as_array().unwrap() {
+                let journal_id =
+                    Uuid::parse_str(book_journal["journal_id"].as_str().unwrap()).unwrap();
+                assert_eq!(
+                    journals.get(&journal_id).unwrap().header.status,
+                    JournalStatus::Reversed
+                );
+            }
+            assert_eq!(
+                journals.get(&unrelated_id).unwrap().header.status,
+                JournalStatus::Posted
+            );
+        }
+
+        let response = app
+            .oneshot(reverse_request(
+                book_journals[0]["journal_id"].as_str().unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("journal_already_reversed")
+        );
+    }
+
+    #[tokio::test]
+    async fn dual_book_post_commits_no_book_when_one_book_is_blocked() {
+        let state = AppState::default();
+        close_period(&state, "IFRS", "2026-02");
+        let app = router_with_state(state.clone());
+
+        let mut dual_book = order_payload(10000);
+        dual_book["ledger_book"] = json!("");
+        let response = app
+            .clone()
+            .oneshot(post_request("dual-book-locked-key", &dual_book))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("period_closed"));
+        assert!(state.journals.lock().unwrap().all().is_empty());
+        assert_eq!(state.change_feed.latest_offset(), 0);
+
+        let state = AppState::default().with_book_requirements(HashMap::new());
+        let response = router_with_state(state)
+            .oneshot(post_request("dual-book-no-policy-key", &dual_book))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("no_book_policy_for_legal_entity"));
+        assert_eq!(body["details"]["legal_entity_id"], json!("US_CO_01"));
+    }
+
+    #[tokio::test]
+    async fn tax_liability_report_nets_order_tax_and_refunds_by_jurisdiction() {
+        let app = router();
+        let tax_lines = json!([
+            {"tax_type": "CA_GST", "jurisdiction": "CA", "amount_minor": 500},
+            {"tax_type": "CA_PST_BC", "jurisdiction": "CA-BC", "amount_minor": 700}
+        ]);
+        let mut order = order_payload(11200);
+        order["legal_entity_id"] = json!("CA_BC_01");
+        order["location_id"] = json!("WHISTLER_VILLAGE");
+        order["ledger_book"] = json!("IFRS");
+        order["payload"]["currency"] = json!("CAD");
+        order["payload"]["tax_lines"] = tax_lines.clone();
+        order["provenance"]["ruleset_version"] = json!("v2");
+        let response = app
+            .clone()
+            .oneshot(post_request("tax-order-key", &order))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut refund = order.clone();
+        refund["event_type"] = json!("refund.v1");
+        refund["source_event_id"] = json!("evt_tax_refund");
+        refund["payload"] = json!({
+            "refund_amount_minor": 5600,
+            "order_total_minor": 11200,
+            "currency": "CAD",
+            "tax_lines": tax_lines
+        });
+        let response = app
+            .clone()
+            .oneshot(post_request("tax-refund-key", &refund))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(get_request(
+                "/v1/tax/liabilities?book=IFRS&legal_entity_id=CA_BC_01&period=2026-02",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        let rows = body["rows"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["jurisdiction"], json!("CA"));
+        assert_eq!(rows[0]["account_id"], json!("2110-GST-HST-PAYABLE"));
+        assert_eq!(rows[0]["collected_minor"], json!(500));
+        assert_eq!(rows[0]["refunded_minor"], json!(250));
+        assert_eq!(rows[0]["net_liability_minor"], json!(250));
+        assert_eq!(rows[1]["jurisdiction"], json!("CA-BC"));
+        assert_eq!(rows[1]["net_liability_minor"], json!(350));
+
+        let response = app
+            .oneshot(get_request("/v1/tax/liabilities?book=IFRS&period=Feb"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("invalid_period_id")
+        );
+    }
+
+    #[tokio::test]
+    async fn v3_order_posts_decomposed_lines_and_rejects_unreconciled_components() {
+        let app = router();
+        let mut order = order_payload(10800);
+        order["provenance"]["ruleset_version"] = json!("v3");
+        order["payload"]["channel"] = json!("pos");
+        order["payload"]["line_items"] = json!([
+            {"product_id": "LIFT-1DAY", "line_subtotal_minor": 9000},
+            {"product_id": "RENTAL-SKI", "line_subtotal_minor": 2000}
+        ]);
+        order["payload"]["discounts"] =
+            json!([{"discount_id": "PASSHOLDER", "amount_minor": 1000}]);
+        order["payload"]["tips"] = json!([{"staff_id": "emp_7", "amount_minor": 800}]);
+        let response = app
+            .clone()
+            .oneshot(post_request("decomposed-order-key", &order))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut unreconciled = order.clone();
+        unreconciled["source_event_id"] = json!("evt_unreconciled");
+        unreconciled["payload"]["amount_minor"] = json!(11000);
+        let response = app
+            .oneshot(post_request("unreconciled-order-key", &unreconciled))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("components_unreconciled"));
+        assert_eq!(
+            body["details"]["check"],
+            json!("order_components_unreconciled")
+        );
+        assert_eq!(body["details"]["amounts"]["amount"], json!(11000));
+        assert_eq!(body["details"]["amounts"]["line_items"], json!(11000));
+        assert_eq!(body["details"]["amounts"]["unallocated"], json!(200));
+    }
+
+    #[tokio::test]
+    async fn rule_set_versions_coexist_and_select_by_provenance() {
+        let temp_dir = TempDirGuard::new("rule-sets");
+        std::fs::write(
+            temp_dir.path.join("tenant-v1.json"),
+            json!({
+                "ruleset_version": "tenant-v1",
+                "rules": {
+                    "order.captured.v1": {
+                        "amounts": [{
+                            "name": "amount",
+                            "field": "amount_minor",
+                            "from": ["/amount_minor"],
+                            "constraint": "positive"
+                        }],
+                        "strings": [
+                            {"name": "currency", "from": ["/currency"], "default": "USD"},
+                            {"name": "base_currency", "default": "$currency"}
+                        ],
+                        "lines": [
+                            {"account": "1110-CASH-CLEARING-V3", "side": "debit", "amount": "amount"},
+                            {"account": "4010-REVENUE-V3", "side": "credit", "amount": "amount"}
+                        ]
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let config = ServerConfig {
+            rule_set_dir: Some(temp_dir.path.clone()),
+            ..ServerConfig::default()
+        };
+        let state = AppState::from_config(&config).unwrap();
+        let app = router_with_state(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(post_request("ruleset-v1-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let v1_journal = json_body(response).await["journal_id"].clone();
+
+        let mut tenant = order_payload(10000);
+        tenant["source_event_id"] = json!("evt_order_tenant");
+        tenant["provenance"]["ruleset_version"] = json!("tenant-v1");
+        let response = app
+            .clone()
+            .oneshot(post_request("ruleset-tenant-key", &tenant))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let tenant_journal = json_body(response).await["journal_id"].clone();
+
+        let accounts = |journal_id: &serde_json::Value| {
+            let journal_id = Uuid::parse_str(journal_id.as_str().unwrap()).unwrap();
+            let journals = state.journals.lock().unwrap();
+            journals
+                .get(&journal_id)
+                .unwrap()
+                .lines
+                .iter()
+                .map(|line| line.account_id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            accounts(&v1_journal),
+            vec!["1105-CASH-CLEARING", "4000-REVENUE"]
+        );
+        assert_eq!(
+            accounts(&tenant_journal),
+            vec!["1110-CASH-CLEARING-V3", "4010-REVENUE-V3"]
+        );
+
+        let mut unsupported = inntopia_payload(41250);
+        unsupported["provenance"]["ruleset_version"] = json!("tenant-v1");
+        let response = app
+            .clone()
+            .oneshot(post_request("ruleset-tenant-inntopia-key", &unsupported))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("unsupported_event_type"));
+        assert_eq!(body["details"]["ruleset_version"], json!("tenant-v1"));
+
+        let mut unknown = order_payload(10000);
+        unknown["provenance"]["ruleset_version"] = json!("v9");
+        let response = app
+            .oneshot(post_request("ruleset-v9-key", &unknown))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("unknown_ruleset_version"));
+        assert_eq!(body["details"]["ruleset_version"], json!("v9"));
+    }
+
+    #[test]
+    fn startup_rejects_invalid_rule_sets() {
+        let temp_dir = TempDirGuard::new("bad-rule-sets");
+        std::fs::write(
+            temp_dir.path.join("tenant-v1.json"),
+            json!({
+                "ruleset_version": "tenant-v1",
+                "rules": {
+                    "order.captured.v1": {
+                        "amounts": [{"name": "amount", "from": ["/amount_minor"]}],
+                        "lines": [{"account": "4000-REVENUE", "side": "credit", "amount": "gross"}]
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let config = ServerConfig {
+            rule_set_dir: Some(temp_dir.path.clone()),
+            ..ServerConfig::default()
+        };
+        assert!(matches!(
+            AppState::from_config(&config),
+            Err(StartupError::RuleSet(RuleSetError::Invalid { version, event_type, .. }))
+                if version == "tenant-v1" && event_type == "order.captured.v1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn startup_applies_entity_registry_and_posting_policies() {
+        let temp_dir = TempDirGuard::new("startup-config");
+        let registry_path = temp_dir.path.join("entities.json");
+        std::fs::write(
+            &registry_path,
+            json!({
+                "legal_entities": [
+                    {"legal_entity_id": "US_CO_01", "locations": ["KEYSTONE_BASE"], "base_currency": "USD"},
+                    {"legal_entity_id": "CA_BC_01", "locations": ["WHISTLER_VILLAGE"]}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let policy_path = temp_dir.path.join("policies.json");
+        std::fs::write(
+            &policy_path,
+            json!({
+                "book_policies": [{
+                    "book_policy_id": "policy_dual_book",
+                    "policy_versions": ["1.0.0"],
+                    "ledger_books": ["US_GAAP"]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let config = ServerConfig {
+            entity_registry_path: Some(registry_path),
+            policy_path: Some(policy_path),
+            ..ServerConfig::default()
+        };
+        let app = router_with_state(AppState::from_config(&config).unwrap());
+
+        let response = app
+            .clone()
+            .oneshot(post_request("registry-breck-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("location_not_allowed_for_legal_entity"));
+
+        let mut keystone = order_payload(10000);
+        keystone["location_id"] = json!("KEYSTONE_BASE");
+        let response = app
+            .clone()
+            .oneshot(post_request("registry-keystone-key", &keystone))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut old_policy = keystone.clone();
+        old_policy["source_event_id"] = json!("evt_old_policy");
+        old_policy["provenance"]["policy_version"] = json!("0.9.0");
+        let response = app
+            .oneshot(post_request("registry-old-policy-key", &old_policy))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("book_policy_not_allowed"));
+        assert_eq!(body["details"]["policy_version"], json!("0.9.0"));
+    }
+
+    #[tokio::test]
+    async fn period_lock_endpoint_rejects_invalid_period_id() {
+        let app = router();
+        let lock_payload = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+        });
+
+        let response = app
+            .oneshot(period_lock_request("202602", &lock_payload))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("invalid_period_id"));
+        assert_eq!(body["details"]["period_id"], json!("202602"));
+    }
+
+    #[tokio::test]
+    async fn reversal_endpoint_reverses_once() {
+        let app = router();
+        let payload = order_payload(10000);
+        let key = "reverse-key";
+
+        let post = app
+            .clone()
+            .oneshot(post_request(key, &payload))
+            .await
+            .unwrap();
+        assert_eq!(post.status(), StatusCode::OK);
+        let post_body = json_body(post).await;
+        let journal_id = post_body["journal_id"].as_str().unwrap().to_string();
+
+        let reverse = Request::builder()
+            .method("POST")
+            .uri(format!("/v1/ledger/journals/{journal_id}/reverse"))
+            .body(Body::empty())
+            .unwrap();
+
+        let first_reverse = app.clone().oneshot(reverse).await.unwrap();
+        assert_eq!(first_reverse.status(), StatusCode::OK);
+        let first_body = json_body(first_reverse).await;
+        assert_eq!(first_body["status"], json!("REVERSED"));
+
+        let duplicate_reverse = Request::builder()
+            .method("POST")
+            .uri(format!("/v1/ledger/journals/{journal_id}/reverse"))
+            .body(Body::empty())
+            .unwrap();
+        let second_reverse = app.oneshot(duplicate_reverse).await.unwrap();
+        assert_eq!(second_reverse.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn legal_hold_blocks_posting_when_active() {
+        let app = router();
+        let hold_payload = json!({
+            "hold_id": "LH-2026-0001",
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "start_date": "2026-02-01",
+            "end_date": "2026-03-01",
+            "reason": "Regulatory audit",
+            "retention_days": 2555
+        });
+
+        let hold_response = app
+            .clone()
+            .oneshot(legal_hold_request(&hold_payload))
+            .await
+            .unwrap();
+        assert_eq!(hold_response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(post_request("held-post-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("legal_hold_active"));
+        assert_eq!(body["details"]["hold_id"], json!("LH-2026-0001"));
+    }
+
+    #[tokio::test]
+    async fn legal_hold_upsert_emits_audit_seal() {
+        let app = router();
+        let hold_payload = json!({
+            "hold_id": "LH-2026-0002",
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "start_date": "2026-02-01",
+            "end_date": "2026-03-01",
+            "reason": "Audit hold",
+            "retention_days": 2555
+        });
+
+        let hold_response = app
+            .clone()
+            .oneshot(legal_hold_request(&hold_payload))
+            .await
+            .unwrap();
+        assert_eq!(hold_response.status(), StatusCode::OK);
+
+        let verify = Request::builder()
+            .method("GET")
+            .uri("/v1/compliance/audit-seals/verify")
+            .body(Body::empty())
+            .unwrap();
+        let verify_response = app.oneshot(verify).await.unwrap();
+        assert_eq!(verify_response.status(), StatusCode::OK);
+        let body = json_body(verify_response).await;
+        assert!(body["entries"].as_u64().unwrap_or_default() >= 1);
+    }
+
+    #[tokio::test]
+    async fn audit_seal_verify_endpoint_reports_entries() {
+        let app = router();
+
+        let post = app
+            .clone()
+            .oneshot(post_request("audit-seal-post", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(post.status(), StatusCode::OK);
+
+        let verify = Request::builder()
+            .method("GET")
+            .uri("/v1/compliance/audit-seals/verify")
+            .body(Body::empty())
+            .unwrap();
+        let verify_response = app.oneshot(verify).await.unwrap();
+        assert_eq!(verify_response.status(), StatusCode::OK);
+        let body = json_body(verify_response).await;
+        assert_eq!(body["status"], json!("VERIFIED"));
+        assert!(body["entries"].as_u64().unwrap_or_default() >= 1);
+    }
+
+    #[tokio::test]
+    async fn adjustment_endpoint_reverses_original_and_posts_replacement() {
+        let app = router();
+
+        let post = app
+            .clone()
+            .oneshot(post_request("adjust-source-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(post.status(), StatusCode::OK);
+        let posted_body = json_body(post).await;
+        let journal_id = posted_body["journal_id"].as_str().unwrap().to_string();
+
+        let adjust_payload = adjustment_payload("adj_evt_1", 9000);
+        let adjust_response = app
+            .clone()
+            .oneshot(adjust_request(&journal_id, &adjust_payload))
+            .await
+            .unwrap();
+        assert_eq!(adjust_response.status(), StatusCode::OK);
+        let adjust_body = json_body(adjust_response).await;
+        assert_eq!(adjust_body["status"], json!("ADJUSTED"));
+        assert_eq!(adjust_body["reversed_journal_id"], json!(journal_id));
+        assert_ne!(
+            adjust_body["replacement_journal_id"],
+            adjust_body["reversed_journal_id"]
+        );
+        assert!(adjust_body["audit_seal"].as_str().unwrap_or_default().len() > 8);
+    }
+
+    fn get_request(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn change_feed_orders_posted_reversed_and_adjusted_journals() {
+        let app = router();
+        let posted = json_body(
+            app.clone()
+                .oneshot(post_request("feed-post-key", &order_payload(10000)))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let journal_id = posted["journal_id"].as_str().unwrap().to_string();
+        let replay = app
+            .clone()
+            .oneshot(post_request("feed-post-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(replay.status(), StatusCode::OK);
+        let adjusted = json_body(
+            app.clone()
+                .oneshot(adjust_request(
+                    &journal_id,
+                    &adjustment_payload("feed_adj_1", 9000),
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let replacement_id = adjusted["replacement_journal_id"].as_str().unwrap();
+
+        let page = json_body(
+            app.clone()
+                .oneshot(get_request("/v1/ledger/changes?after=0&limit=2"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(page["next_cursor"], json!(2));
+        assert_eq!(page["latest_offset"], json!(3));
+        assert_eq!(page["events"][0]["change_type"], json!("JOURNAL_POSTED"));
+        assert_eq!(page["events"][0]["journal_id"], json!(journal_id));
+        assert_eq!(page["events"][1]["change_type"], json!("JOURNAL_REVERSED"));
+        assert_eq!(page["events"][1]["journal_id"], json!(journal_id));
+
+        let page = json_body(
+            app.oneshot(get_request("/v1/ledger/changes?after=2"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(page["events"].as_array().unwrap().len(), 1);
+        assert_eq!(page["events"][0]["offset"], json!(3));
+        assert_eq!(page["events"][0]["change_type"], json!("JOURNAL_ADJUSTED"));
+        assert_eq!(page["events"][0]["journal_id"], json!(replacement_id));
+        assert_eq!(page["events"][0]["related_journal_id"], json!(journal_id));
+        assert_eq!(page["next_cursor"], json!(3));
+    }
+
+    #[tokio::test]
+    async fn change_feed_long_poll_wakes_on_new_journal() {
+        let app = router();
+        let waiter = tokio::spawn(
+            app.clone()
+                .oneshot(get_request("/v1/ledger/changes?after=0&wait_ms=5000")),
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let response = app
+            .oneshot(post_request("feed-long-poll-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page = json_body(waiter.await.unwrap().unwrap()).await;
+        assert_eq!(page["events"].as_array().unwrap().len(), 1);
+        assert_eq!(page["next_cursor"], json!(1));
+
+        let empty = json_body(
+            router()
+                .oneshot(get_request("/v1/ledger/changes?after=0&wait_ms=10"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(empty["events"], json!([]));
+        assert_eq!(empty["next_cursor"], json!(0));
+    }
+
+    #[tokio::test]
+    async fn change_feed_stream_resumes_from_last_event_id() {
+        use futures_util::StreamExt;
+
+        let app = router();
+        for (key, source_event_id) in [("sse-1", "evt_sse_1"), ("sse-2", "evt_sse_2")] {
+            let mut payload = order_payload(10000);
+            payload["source_event_id"] = json!(source_event_id);
+            let response = app
+                .clone()
+                .oneshot(post_request(key, &payload))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/ledger/changes/stream")
+                    .header("Last-Event-ID", "1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut frames = response.into_body().into_data_stream();
+
+        let mut payload = order_payload(10000);
+        payload["source_event_id"] = json!("evt_sse_3");
+        let live = app.oneshot(post_request("sse-3", &payload)).await.unwrap();
+        assert_eq!(live.status(), StatusCode::OK);
+
+        let mut received = String::new();
+        while received.matches("\n\n").count() < 2 {
+            let frame = tokio::time::timeout(std::time::Duration::from_secs(5), frames.next())
+                .await
+                .expect("stream should yield")
+                .unwrap()
+                .unwrap();
+            received.push_str(std::str::from_utf8(&frame).unwrap());
+        }
+        let ids = received
+            .lines()
+            .filter_map(|line| line.strip_prefix("id: "))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["2", "3"]);
+        assert!(received.contains("event: journal.posted"));
+    }
+
+    #[tokio::test]
+    async fn change_feed_survives_restart() {
+        let temp_dir = TempDirGuard::new("change-feed-reload");
+        let state = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        let response = router_with_state(state.clone())
+            .oneshot(post_request("feed-persist-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        state.flush_persistence().unwrap();
+        drop(state);
+
+        let reloaded = AppState::with_persistence_dir(&temp_dir.path).unwrap();
+        assert_eq!(reloaded.change_feed.latest_offset(), 1);
+        let page = json_body(
+            router_with_state(reloaded)
+                .oneshot(get_request("/v1/ledger/changes?after=1"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(page["events"], json!([]));
+        assert_eq!(page["latest_offset"], json!(1));
+    }
+
+    #[tokio::test]
+    async fn revrec_rollforward_is_book_scoped() {
+        let app = router();
+        let mut ifrs_payload = order_payload(7000);
+        ifrs_payload["ledger_book"] = json!("IFRS");
+        ifrs_payload["source_event_id"] = json!("evt_ifrs_1");
+
+        let us_post = app
+            .clone()
+            .oneshot(post_request("rollforward-us", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(us_post.status(), StatusCode::OK);
+
+        let ifrs_post = app
+            .clone()
+            .oneshot(post_request("rollforward-ifrs", &ifrs_payload))
+            .await
+            .unwrap();
+        assert_eq!(ifrs_post.status(), StatusCode::OK);
+
+        let rollforward = Request::builder()
+            .method("GET")
+            .uri("/v1/revrec/rollforward?book=US_GAAP")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(rollforward).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["book"], json!("US_GAAP"));
+        assert_eq!(body["journal_count"], json!(1));
+        assert_eq!(body["recognized_revenue_minor"], json!(10000));
+    }
+
+    fn post_json_request(uri: &str, payload: &serde_json::Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap()
+    }
+
+    fn stay_payload(
+        source_event_id: &str,
+        amount: i64,
+        arrival_date: &str,
+        departure_date: &str,
+    ) -> serde_json::Value {
+        let mut payload = inntopia_payload(amount);
+        payload["source_event_id"] = json!(source_event_id);
+        payload["payload"]["reservation_id"] = json!(source_event_id);
+        payload["payload"]["arrival_date"] = json!(arrival_date);
+        payload["payload"]["departure_date"] = json!(departure_date);
+        payload
+    }
+
+    #[tokio::test]
+    async fn reservation_schedule_recognizes_nightly_and_replans_on_modification() {
+        let app = router();
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "stay-key",
+                &stay_payload("resv_stay", 30000, "2026-02-27", "2026-03-02"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let schedule_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/revrec/schedules?book=US_GAAP&reference_id=resv_stay",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let schedule = &body["schedules"][0];
+        assert_eq!(schedule["schedule_id"], json!(schedule_id));
+        assert_eq!(schedule["status"], json!("ACTIVE"));
+        assert_eq!(schedule["entries"].as_array().unwrap().len(), 3);
+
+        let run = json!({
+            "tenant_id": "tenant_1",
+            "through_date": "2026-02-28",
+            "posting_run_id": "revrec_run_1"
+        });
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/revrec/recognition-runs", &run))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["journals"].as_array().unwrap().len(), 2);
+        assert_eq!(body["journals"][0]["accounting_date"], json!("2026-02-27"));
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/revrec/recognition-runs", &run))
+            .await
+            .unwrap();
+        assert_eq!(json_body(response).await["journals"], json!([]));
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                &format!("/v1/revrec/schedules/{schedule_id}/modify"),
+                &json!({"arrival_date": "2026-02-27", "departure_date": "2026-03-03"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        let entries = body["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[2]["recognition_date"], json!("2026-03-01"));
+        assert_eq!(entries[2]["amount_minor"], json!(5000));
+        assert_eq!(entries[3]["amount_minor"], json!(5000));
+
+        let mut run = run;
+        run["through_date"] = json!("2026-03-31");
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/revrec/recognition-runs", &run))
+            .await
+            .unwrap();
+        assert_eq!(
+            json_body(response).await["journals"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/revrec/rollforward?book=US_GAAP&period=2026-03",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["opening_deferred_minor"], json!(10000));
+        assert_eq!(body["additions_minor"], json!(0));
+        assert_eq!(body["recognized_minor"], json!(10000));
+        assert_eq!(body["deferred_revenue_ending_minor"], json!(0));
+        assert_eq!(body["recognized_revenue_minor"], json!(10000));
+
+        let response = app
+            .oneshot(post_json_request(
+                &format!("/v1/revrec/schedules/{schedule_id}/modify"),
+                &json!({"arrival_date": "2026-02-27", "departure_date": "2026-03-05"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("recognition_schedule_not_active")
+        );
+    }
+
+    #[tokio::test]
+    async fn recognition_run_skips_entries_in_closed_periods() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "stay-closed-key",
+                &stay_payload("resv_closed", 30000, "2026-02-27", "2026-03-02"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let schedule_id = json_body(response).await["journal_id"].clone();
+        close_period(&state, "US_GAAP", "2026-02");
+
+        let run = json!({
+            "tenant_id": "tenant_1",
+            "ledger_book": "US_GAAP",
+            "through_date": "2026-03-01",
+            "posting_run_id": "revrec_run_closed"
+        });
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/revrec/recognition-runs", &run))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["journals"].as_array().unwrap().len(), 1);
+        assert_eq!(body["journals"][0]["accounting_date"], json!("2026-03-01"));
+        let skipped = body["skipped"].as_array().unwrap();
+        assert_eq!(
+            skipped
+                .iter()
+                .map(|entry| entry["recognition_date"].clone())
+                .collect::<Vec<_>>(),
+            [json!("2026-02-27"), json!("2026-02-28")]
+        );
+        assert!(skipped
+            .iter()
+            .all(|entry| entry["schedule_id"] == schedule_id
+                && entry["code"] == json!("period_closed")));
+
+        // The skipped nights stay due, and the recognized one is not booked twice.
+        let response = app
+            .oneshot(post_json_request("/v1/revrec/recognition-runs", &run))
+            .await
+            .unwrap();
+        let body = json_body(response).await;
+        assert_eq!(body["journals"], json!([]));
+        assert_eq!(body["skipped"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn recognition_books_to_the_accounts_the_rule_set_names() {
+        let rule_set = RuleSet::from_json(
+            FsPath::new("tenant-stays.json"),
+            json!({
+                "ruleset_version": "tenant-stays",
+                "accounts": {"revenue": "4100-LODGING-REVENUE"},
+                "rules": {
+                    "inntopia.reservation.captured.v1": {
+                        "amounts": [{"name": "amount", "field": "total_amount_minor", "from": ["/total_amount_minor"]}],
+                        "strings": [
+                            {"name": "currency", "from": ["/currency"], "default": "USD"},
+                            {"name": "base_currency", "default": "$currency"}
+                        ],
+                        "lines": [
+                            {"account": "1105-CASH-CLEARING", "side": "debit", "amount": "amount"},
+                            {"account": "2200-DEFERRED-REVENUE-RESERVATIONS", "side": "credit", "amount": "amount"}
+                        ]
+                    }
+                }
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+        let mut rule_sets = RuleSetRegistry::default();
+        rule_sets.insert(rule_set);
+        let state = AppState::default().with_rule_sets(rule_sets);
+        let app = router_with_state(state.clone());
+
+        let mut stay = stay_payload("resv_accounts", 30000, "2026-02-27", "2026-03-02");
+        stay["provenance"]["ruleset_version"] = json!("tenant-stays");
+        let response = app
+            .clone()
+            .oneshot(post_request("stay-accounts-key", &stay))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let schedule_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/revrec/recognition-runs",
+                &json!({"tenant_id": "tenant_1", "through_date": "2026-02-27", "posting_run_id": "revrec_run_1"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let recognition_id: Uuid = json_body(response).await["journals"][0]["journal_id"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let credited = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&recognition_id)
+            .unwrap()
+            .lines
+            .iter()
+            .filter(|line| line.entry_side == EntrySide::Credit)
+            .map(|line| line.account_id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(credited, ["4100-LODGING-REVENUE"]);
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/revrec/rollforward?book=US_GAAP&period=2026-02",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["recognized_revenue_minor"], json!(10000));
+
+        // The rule set names no refunds account, so a cancellation cannot be booked.
+        let response = app
+            .oneshot(post_json_request(
+                &format!("/v1/revrec/schedules/{schedule_id}/cancel"),
+                &json!({"cancellation_date": "2026-02-28", "posting_run_id": "cancel_run"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("ruleset_account_not_named"));
+        assert_eq!(
+            body["details"]["role"],
+            json!("reservation_refunds_payable")
+        );
+    }
+
+    #[tokio::test]
+    async fn reversal_refuses_journals_tied_to_a_recognition_schedule() {
+        let app = router();
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "reverse-stay-key",
+                &stay_payload("resv_reverse", 30000, "2026-02-27", "2026-03-02"),
+            ))
+            .await
+            .unwrap();
+        let schedule_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/revrec/recognition-runs",
+                &json!({"tenant_id": "tenant_1", "through_date": "2026-02-27", "posting_run_id": "revrec_run_1"}),
+            ))
+            .await
+            .unwrap();
+        let recognition_id = json_body(response).await["journals"][0]["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                &format!("/v1/revrec/schedules/{schedule_id}/cancel"),
+                &json!({"cancellation_date": "2026-02-28", "posting_run_id": "cancel_run"}),
+            ))
+            .await
+            .unwrap();
+        let cancellation_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        for journal_id in [&schedule_id, &recognition_id, &cancellation_id] {
+            let response = app
+                .clone()
+                .oneshot(reverse_request(journal_id))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CONFLICT, "{journal_id}");
+            let body = json_body(response).await;
+            assert_eq!(body["code"], json!("journal_has_subledger_effects"));
+            assert_eq!(body["details"]["subledger"], json!("revenue_recognition"));
+        }
+
+        let body = json_body(
+            app.oneshot(get_request(
+                "/v1/revrec/schedules?book=US_GAAP&reference_id=resv_reverse",
+            ))
+            .await
+            .unwrap(),
+        )
+        .await;
+        let schedule = &body["schedules"][0];
+        assert_eq!(schedule["status"], json!("CANCELLED"));
+        assert_eq!(schedule["cancellation_journal_id"], json!(cancellation_id));
+    }
+
+    #[tokio::test]
+    async fn cancelled_reservation_releases_remaining_deferred_revenue() {
+        let app = router();
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "cancel-stay-key",
+                &stay_payload("resv_cancel", 40000, "2026-03-10", "2026-03-14"),
+            ))
+            .await
+            .unwrap();
+        let schedule_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let cancel_uri = format!("/v1/revrec/schedules/{schedule_id}/cancel");
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                &cancel_uri,
+                &json!({"cancellation_date": "2026-03-01", "posting_run_id": "cancel_run", "forfeited_minor": 50000}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("invalid_forfeit_amount")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                &cancel_uri,
+                &json!({"cancellation_date": "2026-03-01", "posting_run_id": "cancel_run", "forfeited_minor": 10000}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["status"], json!("CANCELLED"));
+        assert_eq!(body["refundable_minor"], json!(30000));
+
+        let body = json_body(
+            app.oneshot(get_request("/v1/revrec/rollforward?book=US_GAAP"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["additions_minor"], json!(40000));
+        assert_eq!(body["recognized_minor"], json!(10000));
+        assert_eq!(body["released_minor"], json!(30000));
+        assert_eq!(body["deferred_revenue_ending_minor"], json!(0));
+    }
+
+    #[tokio::test]
+    async fn reservation_with_invalid_stay_dates_is_rejected() {
+        let response = router()
+            .oneshot(post_request(
+                "bad-stay-key",
+                &stay_payload("resv_bad", 10000, "2026-03-02", "2026-03-01"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("invalid_stay_dates"));
+        assert_eq!(body["details"]["reservation_id"], json!("resv_bad"));
+    }
+
+    fn pass_payload(
+        event_type: &str,
+        source_event_id: &str,
+        payload: serde_json::Value,
+    ) -> serde_json::Value {
+        let mut request = inntopia_payload(0);
+        request["event_type"] = json!(event_type);
+        request["source_event_id"] = json!(source_event_id);
+        request["payload"] = payload;
+        request["provenance"]["ruleset_version"] = json!("v4");
+        request
+    }
+
+    #[tokio::test]
+    async fn reversal_refuses_journals_that_moved_a_pass() {
+        let app = router();
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/passes/breakage-assumptions",
+                &json!({
+                    "tenant_id": "tenant_1",
+                    "version": "2026.1",
+                    "effective_from": "2026-01-01",
+                    "default_breakage_bps": 0,
+                    "approved_by": "controller",
+                    "rationale": "no breakage"
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut journal_ids = Vec::new();
+        for (key, event_type, payload) in [
+            (
+                "pass_sale_1",
+                "pass.sold.v1",
+                json!({
+                    "pass_id": "pass_1",
+                    "pass_type": "LIFT_10_PACK",
+                    "amount_minor": 9000,
+                    "entitled_visits": 10,
+                    "season_start": "2025-11-15",
+                    "season_end": "2026-04-15"
+                }),
+            ),
+            (
+                "pass_visit_1",
+                "pass.visit_redeemed.v1",
+                json!({"pass_id": "pass_1", "visits": 3}),
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(post_request(key, &pass_payload(event_type, key, payload)))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{key}");
+            journal_ids.push(
+                json_body(response).await["journal_id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+
+        for journal_id in &journal_ids {
+            let response = app
+                .clone()
+                .oneshot(reverse_request(journal_id))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CONFLICT, "{journal_id}");
+            let body = json_body(response).await;
+            assert_eq!(body["code"], json!("journal_has_subledger_effects"));
+            assert_eq!(body["details"]["subledger"], json!("passes"));
+        }
+
+        let body = json_body(
+            app.oneshot(get_request("/v1/passes?pass_id=pass_1"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["passes"][0]["redeemed_visits"], json!(3));
+        assert_eq!(body["passes"][0]["recognized_minor"], json!(2700));
+    }
+
+    #[tokio::test]
+    async fn per_use_pass_recognizes_on_visits_and_trues_up_breakage() {
+        let app = router();
+        let assumptions = json!({
+            "tenant_id": "tenant_1",
+            "version": "2026.1",
+            "effective_from": "2026-01-01",
+            "default_breakage_bps": 1000,
+            "breakage_bps_by_pass_type": {"LIFT_10_PACK": 2000},
+            "approved_by": "controller",
+            "rationale": "2025 season redemption study"
+        });
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/passes/breakage-assumptions",
+                &assumptions,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["pass_count"], json!(0));
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "pass-sale",
+                &pass_payload(
+                    "pass.sold.v1",
+                    "pass_sale_1",
+                    json!({
+                        "pass_id": "pass_1",
+                        "pass_type": "LIFT_10_PACK",
+                        "amount_minor": 9000,
+                        "currency": "USD",
+                        "entitled_visits": 10,
+                        "season_start": "2025-11-15",
+                        "season_end": "2026-04-15"
+                    }),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "pass-visits",
+                &pass_payload(
+                    "pass.visit_redeemed.v1",
+                    "pass_visit_1",
+                    json!({"pass_id": "pass_1", "visits": 3}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "pass-overdraw",
+                &pass_payload(
+                    "pass.visit_redeemed.v1",
+                    "pass_visit_2",
+                    json!({"pass_id": "pass_1", "visits": 8}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("visits_exceed_entitlement")
+        );
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request("/v1/passes?pass_id=pass_1"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let pass = &body["passes"][0];
+        assert_eq!(pass["redeemed_visits"], json!(3));
+        assert_eq!(pass["breakage_bps"], json!(2000));
+        assert_eq!(pass["recognized_minor"], json!(3375));
+        assert_eq!(pass["estimate_version"], json!("2026.1"));
+
+        let mut revised = assumptions;
+        revised["version"] = json!("2026.2");
+        revised["effective_from"] = json!("2026-02-21");
+        revised["breakage_bps_by_pass_type"] = json!({"LIFT_10_PACK": 0});
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/passes/breakage-assumptions",
+                &revised,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["pass_count"], json!(1));
+        assert_eq!(body["journals"][0]["recognized_delta_minor"], json!(-675));
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/passes/breakage-assumptions",
+                &revised,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "pass-expiry",
+                &pass_payload(
+                    "pass.expired.v1",
+                    "pass_expired_1",
+                    json!({"pass_id": "pass_1"}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request("/v1/passes?pass_id=pass_1"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let pass = &body["passes"][0];
+        assert_eq!(pass["status"], json!("EXPIRED"));
+        assert_eq!(pass["recognized_minor"], json!(9000));
+        assert_eq!(pass["estimate_version"], json!("2026.2"));
+
+        let body = json_body(
+            app.oneshot(get_request("/v1/revrec/rollforward?book=US_GAAP"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["additions_minor"], json!(9000));
+        assert_eq!(body["recognized_minor"], json!(9000));
+        assert_eq!(body["deferred_revenue_ending_minor"], json!(0));
+    }
+
+    fn gift_card_payload(
+        event_type: &str,
+        source_event_id: &str,
+        payload: serde_json::Value,
+    ) -> serde_json::Value {
+        let mut request = pass_payload(event_type, source_event_id, payload);
+        request["provenance"]["ruleset_version"] = json!("v5");
+        request
+    }
+
+    #[tokio::test]
+    async fn reversal_refuses_journals_that_moved_a_gift_card_balance() {
+        let app = router();
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "gc_issue",
+                &gift_card_payload(
+                    "giftcard.issued.v1",
+                    "gc_issue",
+                    json!({"card_id": "gc_1", "program_id": "RESORT_GIFT", "amount_minor": 10000}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let journal_id = json_body(response).await["journal_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = app
+            .clone()
+            .oneshot(reverse_request(&journal_id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("journal_has_subledger_effects"));
+        assert_eq!(body["details"]["subledger"], json!("stored_value"));
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/stored-value/cards?book=US_GAAP&card_id=gc_1",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["cards"][0]["balance_minor"], json!(10000));
+        let body = json_body(
+            app.oneshot(get_request("/v1/stored-value/reconciliation?book=US_GAAP"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["reconciled"], json!(true));
+    }
+
+    fn fx_rate_set_payload() -> serde_json::Value {
+        json!({
+            "fx_rate_set_id": "fx_2026_02_21",
+            "rates": [
+                {"from_currency": "CAD", "to_currency": "USD", "effective_date": "2026-02-01", "rate": "0.7300", "source": "BOC"},
+                {"from_currency": "CAD", "to_currency": "USD", "effective_date": "2026-02-20", "rate": "0.7330", "source": "BOC"},
+                {"from_currency": "USD", "to_currency": "CAD", "effective_date": "2026-02-20", "rate": "1.3643", "source": "BOC"}
+            ]
+        })
+    }
+
+    #[tokio::test]
+    async fn gift_card_subledger_tracks_balances_and_reconciles_to_liability() {
+        let app = router();
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/fx/rate-sets",
+                &fx_rate_set_payload(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let events = [
+            (
+                "giftcard.issued.v1",
+                "gc_issue",
+                json!({"card_id": "gc_1", "program_id": "RESORT_GIFT", "amount_minor": 10000, "channel": "ECOMMERCE"}),
+            ),
+            (
+                "giftcard.reloaded.v1",
+                "gc_reload",
+                json!({"card_id": "gc_1", "amount_minor": 5000}),
+            ),
+            (
+                "giftcard.redeemed.v1",
+                "gc_redeem_pos",
+                json!({"card_id": "gc_1", "amount_minor": 4000, "channel": "POS"}),
+            ),
+            (
+                "giftcard.redeemed.v1",
+                "gc_redeem_web",
+                json!({"card_id": "gc_1", "amount_minor": 1500, "channel": "ECOMMERCE"}),
+            ),
+        ];
+        for (event_type, source_event_id, payload) in events {
+            let response = app
+                .clone()
+                .oneshot(post_request(
+                    source_event_id,
+                    &gift_card_payload(event_type, source_event_id, payload),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{source_event_id}");
+        }
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "gc_overdraw",
+                &gift_card_payload(
+                    "giftcard.redeemed.v1",
+                    "gc_overdraw",
+                    json!({"card_id": "gc_1", "amount_minor": 9600}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("insufficient_stored_value"));
+        assert_eq!(body["details"]["balance_minor"], json!(9500));
+
+        let mut cross_entity = gift_card_payload(
+            "giftcard.redeemed_cross_entity.v1",
+            "gc_redeem_whistler",
+            json!({"card_id": "gc_1", "amount_minor": 2500, "issuing_legal_entity_id": "US_CO_01", "channel": "POS"}),
+        );
+        cross_entity["legal_entity_id"] = json!("CA_BC_01");
+        cross_entity["location_id"] = json!("WHISTLER_VILLAGE");
+        let response = app
+            .clone()
+            .oneshot(post_request("gc_redeem_whistler", &cross_entity))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "gc_breakage",
+                &gift_card_payload(
+                    "giftcard.breakage_recognized.v1",
+                    "gc_breakage",
+                    json!({"card_id": "gc_1", "amount_minor": 2000}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/stored-value/cards?book=US_GAAP&card_id=gc_1",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let card = &body["cards"][0];
+        assert_eq!(card["legal_entity_id"], json!("US_CO_01"));
+        assert_eq!(card["status"], json!("ACTIVE"));
+        assert_eq!(card["balance_minor"], json!(5000));
+        assert_eq!(card["redeemed_minor"], json!(8000));
+        assert_eq!(card["cross_entity_redeemed_minor"], json!(2500));
+        assert_eq!(
+            card["redeemed_by_channel"],
+            json!({"ECOMMERCE": 1500, "POS": 6500})
+        );
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request("/v1/stored-value/reconciliation?book=US_GAAP"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["reconciled"], json!(true));
+        assert_eq!(body["rows"].as_array().unwrap().len(), 1);
+        assert_eq!(body["rows"][0]["subledger_balance_minor"], json!(5000));
+        assert_eq!(body["rows"][0]["gl_balance_minor"], json!(5000));
+
+        let response = app
+            .clone()
+            .oneshot(post_request(
+                "gc_escheat",
+                &gift_card_payload(
+                    "giftcard.escheated.v1",
+                    "gc_escheat",
+                    json!({"card_id": "gc_1", "jurisdiction": "US-CO"}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/stored-value/cards?book=US_GAAP&card_id=gc_1",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["cards"][0]["status"], json!("CLOSED"));
+        assert_eq!(body["cards"][0]["escheated_minor"], json!(5000));
+
+        let response = app
+            .oneshot(post_request(
+                "gc_reload_closed",
+                &gift_card_payload(
+                    "giftcard.reloaded.v1",
+                    "gc_reload_closed",
+                    json!({"card_id": "gc_1", "amount_minor": 100}),
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("gift_card_not_active")
+        );
+    }
+
+    #[tokio::test]
+    async fn posting_preview_derives_lines_without_touching_stores() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request(
+                "/v1/posting/preview",
+                &order_payload(10000),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["balanced"], json!(true));
+        assert_eq!(body["warnings"], json!([]));
+        let journal = &body["journals"][0];
+        assert_eq!(journal["ledger_book"], json!("US_GAAP"));
+        assert_eq!(journal["lines"].as_array().unwrap().len(), 2);
+        assert_eq!(journal["lines"][1]["account_id"], json!("4000-REVENUE"));
+        assert_eq!(journal["balance"]["debit_total_minor"], json!(10000));
+        assert_eq!(journal["balance"]["credit_total_minor"], json!(10000));
+
+        let mut dual_book = order_payload(10000);
+        dual_book.as_object_mut().unwrap().remove("ledger_book");
+        let body = json_body(
+            app.clone()
+                .oneshot(post_json_request("/v1/posting/preview", &dual_book))
+                .await
+                .unwrap(),
+        )
+        .await;
+        let books = body["journals"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|journal| journal["ledger_book"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(books, vec![json!("US_GAAP"), json!("IFRS")]);
+
+        let mut unbalanced = order_payload(10000);
+        unbalanced.as_object_mut().unwrap().remove("payload");
+        unbalanced["lines"] = json!([
+            {"account_id": "1105-CASH-CLEARING", "entry_side": "debit", "amount_minor": 100, "currency": "USD", "base_amount_minor": 100, "base_currency": "USD"},
+            {"account_id": "4000-REVENUE", "entry_side": "credit", "amount_minor": 90, "currency": "USD", "base_amount_minor": 90, "base_currency": "USD"}
+        ]);
+        let body = json_body(
+            app.clone()
+                .oneshot(post_json_request("/v1/posting/preview", &unbalanced))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["balanced"], json!(false));
+        assert_eq!(body["warnings"][0]["code"], json!("journal_unbalanced"));
+
+        let mut wrong_location = order_payload(10000);
+        wrong_location["location_id"] = json!("WHISTLER_VILLAGE");
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/posting/preview", &wrong_location))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("location_not_allowed_for_legal_entity")
+        );
+
+        assert!(state.journals.lock().unwrap().all().is_empty());
+        assert!(state.change_feed.read_after(0, 10).unwrap().is_empty());
+        assert_eq!(state.audit_seals.len().unwrap(), 0);
+
+        let response = app
+            .clone()
+            .oneshot(post_request("preview-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["replayed"], json!(false));
+        let journal_id = body["journal_id"].clone();
+
+        let body = json_body(
+            app.oneshot(post_json_request(
+                "/v1/posting/preview",
+                &order_payload(10000),
+            ))
+            .await
+            .unwrap(),
+        )
+        .await;
+        assert_eq!(
+            body["warnings"][0]["code"],
+            json!("source_event_already_posted")
+        );
+        assert_eq!(body["warnings"][0]["journal_id"], journal_id);
+    }
+
+    #[tokio::test]
+    async fn preview_and_posting_reject_accounts_outside_the_chart() {
+        let chart = |accounts: &[&str]| {
+            HashMap::from([(
+                "US_CO_01".to_string(),
+                accounts.iter().map(|account| account.to_string()).collect(),
+            )])
+        };
+        let state = AppState::default().with_charts_of_accounts(chart(&["1105-CASH-CLEARING"]));
+        let app = router_with_state(state.clone());
+
+        for request in [
+            post_json_request("/v1/posting/preview", &order_payload(10000)),
+            post_request("chart-key", &order_payload(10000)),
+        ] {
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = json_body(response).await;
+            assert_eq!(body["code"], json!("account_not_in_chart"));
+            assert_eq!(body["details"]["account_id"], json!("4000-REVENUE"));
+            assert_eq!(body["details"]["line_number"], json!(2));
+        }
+        assert!(state.journals.lock().unwrap().all().is_empty());
+
+        let app = router_with_state(
+            AppState::default()
+                .with_charts_of_accounts(chart(&["1105-CASH-CLEARING", "4000-REVENUE"])),
+        );
+        let response = app
+            .oneshot(post_request("chart-key", &order_payload(10000)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn agent_reservations_book_supplier_payable_and_commission() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+        let terms = json!({
+            "tenant_id": "tenant_1",
+            "supplier_id": "lodge_1",
+            "version": "2026.1",
+            "effective_from": "2026-01-01",
+            "designation": "AGENT",
+            "commission_bps": 1500,
+            "products": {"SUITE": {"designation": "PRINCIPAL"}},
+            "approved_by": "controller"
+        });
+        let mut reservation = inntopia_payload(20000);
+        reservation["provenance"]["ruleset_version"] = json!("v6");
+        reservation["payload"]["supplier_id"] = json!("lodge_1");
+
+        let response = app
+            .clone()
+            .oneshot(post_request("agency-no-terms", &reservation))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("no_supplier_terms_in_effect")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/suppliers/terms", &terms))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .clone()
+            .oneshot(post_json_request("/v1/suppliers/terms", &terms))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("duplicate_supplier_terms")
+        );
+
+        let response = app
+            .clone()
+            .oneshot(post_request("agency-agent", &reservation))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let agent_journal_id =
+            Uuid::parse_str(json_body(response).await["journal_id"].as_str().unwrap()).unwrap();
+        let record = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&agent_journal_id)
+            .cloned()
+            .unwrap();
+        let agent_book = record.header.ledger_book.clone();
+        let designation = record.header.revenue_designation.as_ref().unwrap();
+        assert_eq!(designation.designation, "AGENT");
+        assert_eq!(designation.commission_bps, 1500);
+        assert_eq!(designation.terms_version, "2026.1");
+        let credits: Vec<_> = record
+            .lines
+            .iter()
+            .filter(|line| line.entry_side == EntrySide::Credit)
+            .map(|line| (line.account_id.as_str(), line.amount_minor))
+            .collect();
+        assert_eq!(
+            credits,
+            vec![
+                ("2260-SUPPLIER-PAYABLE", 17000),
+                ("4250-COMMISSION-REVENUE", 3000)
+            ]
+        );
+
+        let mut suite = reservation.clone();
+        suite["source_event_id"] = json!("inntopia_evt_suite");
+        suite["payload"]["product_id"] = json!("SUITE");
+        let response = app
+            .clone()
+            .oneshot(post_request("agency-principal", &suite))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let journal_id =
+            Uuid::parse_str(json_body(response).await["journal_id"].as_str().unwrap()).unwrap();
+        let record = state
+            .journals
+            .lock()
+            .unwrap()
+            .get(&journal_id)
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            record
+                .header
+                .revenue_designation
+                .as_ref()
+                .unwrap()
+                .designation,
+            "PRINCIPAL"
+        );
+        assert!(record.lines.iter().any(|line| line.account_id
+            == "2200-DEFERRED-REVENUE-RESERVATIONS"
+            && line.amount_minor == 20000));
+
+        let mut settlement = reservation.clone();
+        settlement["event_type"] = json!("supplier.settlement.v1");
+        settlement["source_event_id"] = json!("supplier_settlement_1");
+        settlement["payload"] = json!({
+            "settlement_id": "stl_1",
+            "supplier_id": "lodge_1",
+            "amount_minor": 12000,
+            "currency": "USD"
+        });
         let response = app
-            .oneshot(post_request("held-post-key", &order_payload(10000)))
+            .clone()
+            .oneshot(post_request("agency-settlement", &settlement))
             .await
             .unwrap();
-        assert_eq!(response.status(), StatusCode::CONFLICT);
-        let body = json_body(response).await;
-        assert_eq!(body["error"], json!("legal_hold_active"));
-        assert_eq!(body["hold_id"], json!("LH-2026-0001"));
-    }
+        assert_eq!(response.status(), StatusCode::OK);
 
-    #[tokio::test]
-    async fn legal_hold_upsert_emits_audit_seal() {
-        let app = router();
-        let hold_payload = json!({
-            "hold_id": "LH-2026-0002",
-            "tenant_id": "tenant_1",
-            "legal_entity_id": "US_CO_01",
-            "ledger_book": "US_GAAP",
-            "start_date": "2026-02-01",
-            "end_date": "2026-03-01",
-            "reason": "Audit hold",
-            "retention_days": 2555
-        });
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request(
+                    "/v1/suppliers/payables?book=US_GAAP&supplier_id=lodge_1",
+                ))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["rows"].as_array().unwrap().len(), 1);
+        assert_eq!(body["rows"][0]["accrued_minor"], json!(17000));
+        assert_eq!(body["rows"][0]["settled_minor"], json!(12000));
+        assert_eq!(body["rows"][0]["outstanding_minor"], json!(5000));
 
-        let hold_response = app
+        let response = app
             .clone()
-            .oneshot(legal_hold_request(&hold_payload))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/v1/ledger/journals/{agent_journal_id}/reverse"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
             .await
             .unwrap();
-        assert_eq!(hold_response.status(), StatusCode::OK);
-
-        let verify = Request::builder()
-            .method("GET")
-            .uri("/v1/compliance/audit-seals/verify")
-            .body(Body::empty())
-            .unwrap();
-        let verify_response = app.oneshot(verify).await.unwrap();
-        assert_eq!(verify_response.status(), StatusCode::OK);
-        let body = json_body(verify_response).await;
-        assert!(body["entries"].as_u64().unwrap_or_default() >= 1);
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(
+            app.oneshot(get_request(&format!(
+                "/v1/suppliers/payables?book={agent_book}&supplier_id=lodge_1"
+            )))
+            .await
+            .unwrap(),
+        )
+        .await;
+        assert_eq!(body["rows"][0]["accrued_minor"], json!(0));
+        assert_eq!(body["rows"][0]["outstanding_minor"], json!(-12000));
     }
 
     #[tokio::test]
-    async fn audit_seal_verify_endpoint_reports_entries() {
-        let app = router();
+    async fn foreign_currency_lines_translate_at_the_referenced_rate_set() {
+        let state = AppState::default();
+        let app = router_with_state(state.clone());
+        let mut order = order_payload(11111);
+        order["payload"]["currency"] = json!("CAD");
 
-        let post = app
+        let response = app
             .clone()
-            .oneshot(post_request("audit-seal-post", &order_payload(10000)))
+            .oneshot(post_request("fx-no-set", &order))
             .await
             .unwrap();
-        assert_eq!(post.status(), StatusCode::OK);
-
-        let verify = Request::builder()
-            .method("GET")
-            .uri("/v1/compliance/audit-seals/verify")
-            .body(Body::empty())
-            .unwrap();
-        let verify_response = app.oneshot(verify).await.unwrap();
-        assert_eq!(verify_response.status(), StatusCode::OK);
-        let body = json_body(verify_response).await;
-        assert_eq!(body["status"], json!("VERIFIED"));
-        assert!(body["entries"].as_u64().unwrap_or_default() >= 1);
-    }
-
-    #[tokio::test]
-    async fn adjustment_endpoint_reverses_original_and_posts_replacement() {
-        let app = router();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("fx_rate_set_not_found"));
+        assert_eq!(body["details"]["fx_rate_set_id"], json!("fx_2026_02_21"));
 
-        let post = app
+        let response = app
             .clone()
-            .oneshot(post_request("adjust-source-key", &order_payload(10000)))
+            .oneshot(post_json_request(
+                "/v1/fx/rate-sets",
+                &fx_rate_set_payload(),
+            ))
             .await
             .unwrap();
-        assert_eq!(post.status(), StatusCode::OK);
-        let posted_body = json_body(post).await;
-        let journal_id = posted_body["journal_id"].as_str().unwrap().to_string();
-
-        let adjust_payload = adjustment_payload("adj_evt_1", 9000);
-        let adjust_response = app
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut changed = fx_rate_set_payload();
+        changed["rates"][1]["rate"] = json!("0.7400");
+        let response = app
             .clone()
-            .oneshot(adjust_request(&journal_id, &adjust_payload))
+            .oneshot(post_json_request("/v1/fx/rate-sets", &changed))
             .await
             .unwrap();
-        assert_eq!(adjust_response.status(), StatusCode::OK);
-        let adjust_body = json_body(adjust_response).await;
-        assert_eq!(adjust_body["status"], json!("ADJUSTED"));
-        assert_eq!(adjust_body["reversed_journal_id"], json!(journal_id));
-        assert_ne!(
-            adjust_body["replacement_journal_id"],
-            adjust_body["reversed_journal_id"]
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            json_body(response).await["code"],
+            json!("fx_rate_set_conflict")
         );
-        assert!(adjust_body["audit_seal"].as_str().unwrap_or_default().len() > 8);
-    }
-
-    #[tokio::test]
-    async fn revrec_rollforward_is_book_scoped() {
-        let app = router();
-        let mut ifrs_payload = order_payload(7000);
-        ifrs_payload["ledger_book"] = json!("IFRS");
-        ifrs_payload["source_event_id"] = json!("evt_ifrs_1");
+        let body = json_body(
+            app.clone()
+                .oneshot(get_request("/v1/fx/rate-sets/fx_2026_02_21"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(body["rates"][1]["rate"], json!("0.7330"));
+        assert_eq!(body["rates"][1]["rate_type"], json!("SPOT"));
 
-        let us_post = app
+        let mut average = order.clone();
+        average["source_event_id"] = json!("evt_fx_average");
+        average["provenance"]["fx_rate_type"] = json!("AVERAGE");
+        let response = app
             .clone()
-            .oneshot(post_request("rollforward-us", &order_payload(10000)))
+            .oneshot(post_request("fx-average", &average))
             .await
             .unwrap();
-        assert_eq!(us_post.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert_eq!(body["code"], json!("fx_rate_not_found"));
+        assert_eq!(body["details"]["rate_type"], json!("AVERAGE"));
+        assert_eq!(body["details"]["from_currency"], json!("CAD"));
+        assert_eq!(body["details"]["to_currency"], json!("USD"));
 
-        let ifrs_post = app
+        let response = app
             .clone()
-            .oneshot(post_request("rollforward-ifrs", &ifrs_payload))
+            .oneshot(post_request("fx-spot", &order))
             .await
             .unwrap();
-        assert_eq!(ifrs_post.status(), StatusCode::OK);
+        assert_eq!(response.status(), StatusCode::OK);
+        let journal_id =
+            Uuid::parse_str(json_body(response).await["journal_id"].as_str().unwrap()).unwrap();
 
-        let rollforward = Request::builder()
-            .method("GET")
-            .uri("/v1/revrec/rollforward?book=US_GAAP")
-            .body(Body::empty())
+        let mut explicit = order.clone();
+        explicit["source_event_id"] = json!("evt_fx_explicit");
+        explicit["payload"]["base_amount_minor"] = json!(8000);
+        explicit["payload"]["base_currency"] = json!("USD");
+        let response = app
+            .clone()
+            .oneshot(post_request("fx-explicit", &explicit))
+            .await
             .unwrap();
-        let response = app.oneshot(rollforward).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
-        let body = json_body(response).await;
-        assert_eq!(body["book"], json!("US_GAAP"));
-        assert_eq!(body["journal_count"], json!(1));
-        assert_eq!(body["recognized_revenue_minor"], json!(10000));
+        let explicit_id =
+            Uuid::parse_str(json_body(response).await["journal_id"].as_str().unwrap()).unwrap();
+
+        let journals = state.journals.lock().unwrap();
+        let translated = journals.get(&journal_id).unwrap();
+        for line in &translated.lines {
+            assert_eq!(line.amount_minor, 11111);
+            assert_eq!(line.currency, "CAD");
+            assert_eq!(line.base_amount_minor, 8144);
+            assert_eq!(line.base_currency, "USD");
+            let applied = line.fx_rate.as_ref().unwrap();
+            assert_eq!(applied.rate_type, "SPOT");
+            assert_eq!(applied.rate, "0.7330");
+            assert_eq!(applied.effective_date.to_string(), "2026-02-20");
+            assert_eq!(applied.source, "BOC");
+        }
+        let explicit = journals.get(&explicit_id).unwrap();
+        assert!(
+            explicit
+                .lines
+                .iter()
+                .all(|line| line.base_amount_minor == 8144 && line.fx_rate.is_some()),
+            "base amounts sent by the client are recomputed"
+        );
     }
 
     #[tokio::test]
@@ -2205,4 +6745,531 @@ mod tests {
         let response = build_capacity_instrumentation_response(samples);
         assert!(response.is_none());
     }
+
+    #[tokio::test]
+    async fn error_envelope_echoes_correlation_id() {
+        let app = router();
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/ledger/journals/not-a-uuid/reverse")
+                    .header(error::CORRELATION_ID_HEADER, "corr-123")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[error::CORRELATION_ID_HEADER], "corr-123");
+        let body = json_body(response).await;
+        assert_eq!(
+            body,
+            json!({
+                "code": "invalid_journal_id",
+                "message": "journal id must be a UUID",
+                "details": {},
+                "correlation_id": "corr-123"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_route_and_malformed_body_use_error_envelope() {
+        let app = router();
+        let missing = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/does-not-exist")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let correlation_id = missing.headers()[error::CORRELATION_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = json_body(missing).await;
+        assert_eq!(body["code"], json!("route_not_found"));
+        assert_eq!(body["correlation_id"], json!(correlation_id));
+
+        let malformed = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/posting/events")
+                    .header("content-type", "application/json")
+                    .header("Idempotency-Key", "malformed-key")
+                    .body(Body::from("{\"event_type\":"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(malformed.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(malformed).await;
+        assert_eq!(body["code"], json!("invalid_request_body"));
+        assert!(body["details"]["reason"].is_string());
+    }
+
+    fn contract_snapshot_path() -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../contracts/posting_api_openapi_v1.json")
+    }
+
+    #[test]
+    fn openapi_document_matches_published_contract() {
+        let generated = serde_json::to_value(openapi::openapi_document()).unwrap();
+        let path = contract_snapshot_path();
+        if std::env::var_os("UPDATE_OPENAPI_CONTRACT").is_some() {
+            let mut encoded = serde_json::to_string_pretty(&generated).unwrap();
+            encoded.push('\n');
+            std::fs::write(&path, encoded).unwrap();
+        }
+        let published: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            generated, published,
+            "OpenAPI contract drifted; rerun with UPDATE_OPENAPI_CONTRACT=1 and review the diff"
+        );
+    }
+
+    fn resolve_schema<'a>(
+        doc: &'a serde_json::Value,
+        schema: &'a serde_json::Value,
+    ) -> &'a serde_json::Value {
+        match schema.get("$ref").and_then(serde_json::Value::as_str) {
+            Some(reference) => {
+                let pointer = reference.trim_start_matches('#');
+                resolve_schema(
+                    doc,
+                    doc.pointer(pointer).expect("schema ref should resolve"),
+                )
+            }
+            None => schema,
+        }
+    }
+
+    fn schema_violations(
+        doc: &serde_json::Value,
+        schema: &serde_json::Value,
+        value: &serde_json::Value,
+        at: &str,
+    ) -> Vec<String> {
+        let schema = resolve_schema(doc, schema);
+        let mut violations = Vec::new();
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(options) = schema.get(keyword).and_then(serde_json::Value::as_array) {
+                let matching = options
+                    .iter()
+                    .filter(|option| schema_violations(doc, option, value, at).is_empty())
+                    .count();
+                let ok = if keyword == "oneOf" {
+                    matching == 1
+                } else {
+                    matching >= 1
+                };
+                if !ok {
+                    violations.push(format!("{at}: {matching} {keyword} branches matched"));
+                }
+            }
+        }
+        if let Some(all) = schema.get("allOf").and_then(serde_json::Value::as_array) {
+            for option in all {
+                violations.extend(schema_violations(doc, option, value, at));
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed = match types {
+                serde_json::Value::Array(items) => {
+                    items.iter().filter_map(|t| t.as_str()).collect()
+                }
+                other => vec![other.as_str().unwrap_or_default()],
+            };
+            let matches = allowed.iter().any(|expected| match *expected {
+                "null" => value.is_null(),
+                "boolean" => value.is_boolean(),
+                "string" => value.is_string(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "array" => value.is_array(),
+                "object" => value.is_object(),
+                _ => false,
+            });
+            if !matches {
+                violations.push(format!("{at}: expected {allowed:?}, got {value}"));
+                return violations;
+            }
+        }
+
+        if let Some(variants) = schema.get("enum").and_then(serde_json::Value::as_array) {
+            if !variants.contains(value) {
+                violations.push(format!("{at}: {value} is not an allowed enum value"));
+            }
+        }
+
+        if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+            for (index, item) in values.iter().enumerate() {
+                violations.extend(schema_violations(
+                    doc,
+                    items,
+                    item,
+                    &format!("{at}/{index}"),
+                ));
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            if let Some(required) = schema.get("required").and_then(serde_json::Value::as_array) {
+                for field in required.iter().filter_map(serde_json::Value::as_str) {
+                    if !object.contains_key(field) {
+                        violations.push(format!("{at}: missing required field `{field}`"));
+                    }
+                }
+            }
+            if let Some(properties) = schema
+                .get("properties")
+                .and_then(serde_json::Value::as_object)
+            {
+                for (field, field_value) in object {
+                    match properties.get(field) {
+                        Some(field_schema) => violations.extend(schema_violations(
+                            doc,
+                            field_schema,
+                            field_value,
+                            &format!("{at}/{field}"),
+                        )),
+                        None => violations.push(format!("{at}: undeclared field `{field}`")),
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+
+    async fn assert_matches_contract(
+        doc: &serde_json::Value,
+        app: &Router,
+        method: &str,
+        path_template: &str,
+        request: Request<Body>,
+    ) -> serde_json::Value {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status().as_u16().to_string();
+        let operation = &doc["paths"][path_template][method];
+        assert!(
+            operation.is_object(),
+            "{method} {path_template} is missing from the OpenAPI document"
+        );
+        let declared = &operation["responses"][&status];
+        assert!(
+            declared.is_object(),
+            "{method} {path_template} returned undeclared status {status}"
+        );
+        let (content_type, body) = match declared["content"].get(bulk::NDJSON_CONTENT_TYPE) {
+            Some(_) => (
+                bulk::NDJSON_CONTENT_TYPE,
+                serde_json::Value::Array(ndjson_body(response).await),
+            ),
+            None => ("application/json", json_body(response).await),
+        };
+        let schema = &declared["content"][content_type]["schema"];
+        let values = match (content_type, &body) {
+            (bulk::NDJSON_CONTENT_TYPE, serde_json::Value::Array(lines)) => lines.clone(),
+            _ => vec![body.clone()],
+        };
+        for value in &values {
+            let violations = schema_violations(doc, schema, value, "");
+            assert!(
+                violations.is_empty(),
+                "{method} {path_template} {status} drifted from contract: {violations:?}"
+            );
+        }
+        body
+    }
+
+    #[tokio::test]
+    async fn handler_responses_match_openapi_contract() {
+        let doc = serde_json::to_value(openapi::openapi_document()).unwrap();
+        let app = router();
+        let posting = "/v1/posting/events";
+
+        let posted = assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-post", &order_payload(10000)),
+        )
+        .await;
+        let journal_id = posted["journal_id"].as_str().unwrap().to_string();
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-post", &order_payload(10000)),
+        )
+        .await;
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-post", &order_payload(12000)),
+        )
+        .await;
+        let mut unsupported = order_payload(10000);
+        unsupported["event_type"] = json!("unknown.v1");
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-unsupported", &unsupported),
+        )
+        .await;
+        let mut incomplete = order_payload(10000);
+        incomplete.as_object_mut().unwrap().remove("provenance");
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-incomplete", &incomplete),
+        )
+        .await;
+        let mut dual_book = order_payload(7000);
+        dual_book.as_object_mut().unwrap().remove("ledger_book");
+        dual_book["source_event_id"] = json!("evt_contract_dual_book");
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-dual-book", &dual_book),
+        )
+        .await;
+
+        let stay = assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request(
+                "contract-stay",
+                &stay_payload("resv_contract", 20000, "2026-03-01", "2026-03-03"),
+            ),
+        )
+        .await;
+        let schedule_id = stay["journal_id"].as_str().unwrap().to_string();
+        for through_date in ["2026-03-01", "March"] {
+            assert_matches_contract(
+                &doc,
+                &app,
+                "post",
+                "/v1/revrec/recognition-runs",
+                post_json_request(
+                    "/v1/revrec/recognition-runs",
+                    &json!({"tenant_id": "tenant_1", "through_date": through_date, "posting_run_id": "contract_run"}),
+                ),
+            )
+            .await;
+        }
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/revrec/schedules/{schedule_id}/modify",
+            post_json_request(
+                &format!("/v1/revrec/schedules/{schedule_id}/modify"),
+                &json!({"arrival_date": "2026-03-01", "departure_date": "2026-03-04"}),
+            ),
+        )
+        .await;
+        for forfeited_minor in [0, 1] {
+            assert_matches_contract(
+                &doc,
+                &app,
+                "post",
+                "/v1/revrec/schedules/{schedule_id}/cancel",
+                post_json_request(
+                    &format!("/v1/revrec/schedules/{schedule_id}/cancel"),
+                    &json!({"cancellation_date": "2026-03-02", "posting_run_id": "contract_run", "forfeited_minor": forfeited_minor}),
+                ),
+            )
+            .await;
+        }
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/revrec/schedules/{schedule_id}/modify",
+            post_json_request(
+                &format!("/v1/revrec/schedules/{}/modify", Uuid::nil()),
+                &json!({"arrival_date": "2026-03-01", "departure_date": "2026-03-04"}),
+            ),
+        )
+        .await;
+
+        let mut second = order_payload(5000);
+        second["source_event_id"] = json!("evt_contract_bulk");
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/posting/events/bulk",
+            bulk_post_request(&[
+                json!({"idempotency_key": "contract-bulk-1", "event": second}),
+                json!({"idempotency_key": "contract-bulk-2", "event": unsupported}),
+                json!({"not": "a bulk line"}),
+            ]),
+        )
+        .await;
+
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/ledger/journals/{journal_id}/adjust",
+            adjust_request(&journal_id, &adjustment_payload("evt_contract_adj", 9000)),
+        )
+        .await;
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/ledger/journals/{journal_id}/reverse",
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/ledger/journals/{journal_id}/reverse"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/ledger/journals/{journal_id}/reverse",
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/ledger/journals/{}/reverse", Uuid::nil()))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        let lock_payload = json!({
+            "tenant_id": "tenant_1",
+            "legal_entity_id": "US_CO_01",
+            "ledger_book": "US_GAAP",
+            "actor": {"actor_id": "u-ctl", "role": "CONTROLLER"}
+        });
+        app.clone()
+            .oneshot(close_checklist_request("2026-02"))
+            .await
+            .unwrap();
+        app.clone()
+            .oneshot(close_dependency_request("2026-02", "Satisfied"))
+            .await
+            .unwrap();
+        app.clone()
+            .oneshot(soft_close_request("2026-02"))
+            .await
+            .unwrap();
+        for period_id in ["2026-02", "202602"] {
+            assert_matches_contract(
+                &doc,
+                &app,
+                "post",
+                "/v1/ledger/periods/{period_id}/lock",
+                period_lock_request(period_id, &lock_payload),
+            )
+            .await;
+        }
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            posting,
+            post_request("contract-closed", &order_payload(10000)),
+        )
+        .await;
+
+        assert_matches_contract(
+            &doc,
+            &app,
+            "post",
+            "/v1/compliance/legal-holds",
+            Request::builder()
+                .method("POST")
+                .uri("/v1/compliance/legal-holds")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({
+                        "hold_id": "LH-CONTRACT",
+                        "tenant_id": "tenant_1",
+                        "legal_entity_id": "CA_BC_01",
+                        "ledger_book": "IFRS",
+                        "start_date": "2026-02-01",
+                        "reason": "contract test"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await;
+
+        for uri in [
+            "/v1/compliance/audit-seals/verify",
+            "/v1/revrec/rollforward?book=US_GAAP",
+            "/v1/revrec/rollforward",
+            "/v1/revrec/rollforward?book=US_GAAP&period=2026-03",
+            "/v1/revrec/rollforward?book=US_GAAP&period=March",
+            "/v1/revrec/schedules?book=US_GAAP",
+            "/v1/revrec/disclosures?book=US_GAAP",
+            "/v1/tax/liabilities?book=US_GAAP",
+            "/v1/tax/liabilities?book=US_GAAP&period=2026-2",
+            "/v1/ledger/changes?after=0",
+            "/v1/ledger/changes?after=nope",
+            "/v1/ops/slo",
+            "/v1/ops/capacity",
+        ] {
+            let path_template = uri.split('?').next().unwrap();
+            assert_matches_contract(
+                &doc,
+                &app,
+                "get",
+                path_template,
+                Request::builder().uri(uri).body(Body::empty()).unwrap(),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn openapi_route_serves_generated_document() {
+        let response = router()
+            .oneshot(
+                Request::builder()
+                    .uri("/v1/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(
+            body,
+            serde_json::to_value(openapi::openapi_document()).unwrap()
+        );
+        assert!(body["openapi"].as_str().unwrap().starts_with("3."));
+    }
 }
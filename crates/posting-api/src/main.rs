@@ -1,11 +1,76 @@
-use posting_api::router;
+use std::process::ExitCode;
+
+use posting_api::config::{ConfigError, ServerConfig};
+use posting_api::{router_with_state, AppState};
 
 #[tokio::main]
-async fn main() {
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
-        .await
-        .expect("bind should work");
-    axum::serve(listener, router())
-        .await
-        .expect("server should run");
+async fn main() -> ExitCode {
+    let config = match ServerConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(ConfigError::Usage(message)) => {
+            eprintln!("{message}");
+            return ExitCode::from(2);
+        }
+        Err(error) => {
+            eprintln!("posting-api: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let state = match AppState::from_config(&config) {
+        Ok(state) => state,
+        Err(error) => {
+            eprintln!("posting-api: refusing to start: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let listener = match tokio::net::TcpListener::bind(config.bind_addr).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("posting-api: failed to bind {}: {error}", config.bind_addr);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("posting-api: listening on {}", config.bind_addr);
+
+    let served = axum::serve(listener, router_with_state(state.clone()))
+        .with_graceful_shutdown(shutdown_signal())
+        .await;
+
+    let flushed = state.flush_persistence();
+    if let Err(error) = served {
+        eprintln!("posting-api: server error: {error}");
+        return ExitCode::FAILURE;
+    }
+    if let Err(error) = flushed {
+        eprintln!("posting-api: failed to flush stores on shutdown: {error}");
+        return ExitCode::FAILURE;
+    }
+    eprintln!("posting-api: stores flushed, shut down cleanly");
+    ExitCode::SUCCESS
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
@@ -0,0 +1,234 @@
+use axum::Json;
+use utoipa::OpenApi;
+
+use crate::agency::{
+    Designation, ProductTerms, RegisterSupplierTermsRequest, SupplierPayableReport,
+    SupplierPayableRow, SupplierTerms, SupplierTermsList,
+};
+use crate::bulk::{BulkPostEventLine, BulkPostEventResult, BulkPostOutcome};
+use crate::calendar::{
+    FiscalCalendar, FiscalCalendarResponse, FiscalPeriod, WeekPattern, YearEndRule,
+};
+use crate::change_feed::{ChangeFeedPage, LedgerChangeEvent, LedgerChangeType};
+use crate::close::{
+    CloseChecklistResponse, CloseDependencyDefinition, TransitionCloseDependencyRequest,
+    UpsertCloseChecklistRequest,
+};
+use crate::dead_letter::{
+    DeadLetter, DeadLetterAction, DeadLetterActionKind, DeadLetterList, DeadLetterReplayResult,
+    DeadLetterStatus, DiscardDeadLetterRequest, ReplayDeadLettersRequest,
+    ReplayDeadLettersResponse, RetryDeadLetterRequest,
+};
+use crate::error::{ErrorCode, ErrorEnvelope};
+use crate::fx::{FxRate, FxRateSet, FxRateSetList, FxRateType};
+use crate::ingest::{IngestDeliveryResponse, IngestResponse, IngestWebhookResponse};
+use crate::passes::{
+    BreakageAssumptionSet, BreakageAssumptionSetList, BreakageTrueUpResponse, PassEntitlement,
+    PassList, PassStatus, RecognitionPattern, RegisterBreakageAssumptionsRequest, TrueUpJournal,
+};
+use crate::period::{
+    ActorRole, PeriodActor, PeriodList, PeriodState, PeriodStatus, PeriodTransitionRequest,
+    PeriodTransitionResponse, PostingClass,
+};
+use crate::preview::{
+    BalanceCheck, PostingPreviewResponse, PreviewJournal, PreviewWarning, PreviewWarningCode,
+};
+use crate::prior_period::{
+    PeriodAdjustmentLineage, PeriodResultsResponse, PriorPeriodAdjustmentRequest,
+    PriorPeriodAdjustmentResponse, ReportingBasis,
+};
+use crate::revrec::{
+    CancelScheduleRequest, CancelScheduleResponse, ModifyScheduleRequest, RecognitionJournal,
+    RecognitionRunRequest, RecognitionRunResponse, RecognitionSchedule, RecognitionScheduleList,
+    ScheduleEntry, ScheduleKind, ScheduleStatus, SkippedRecognitionEntry,
+};
+use crate::stored_value::{
+    CardStatus, StoredValueCard, StoredValueCardList, StoredValueReconciliation,
+    StoredValueReconciliationRow,
+};
+use crate::tax::{TaxLiabilityReport, TaxLiabilityRow};
+use crate::year_end::{
+    AccountBalance, ReverseYearEndCloseRequest, YearEndClose, YearEndCloseRequest,
+    YearEndCloseResponse, YearEndCloseStatus,
+};
+use crate::{
+    AdjustJournalRequest, AdjustJournalResponse, AuditSealVerifyResponse, BookJournal,
+    CapacityInstrumentationResponse, LockPeriodRequest, LockPeriodResponse, PostEventRequest,
+    PostEventResponse, PostLine, Provenance, RevRecDisclosureResponse, RevRecRollforwardResponse,
+    ReverseJournalResponse, SloResponse, UpsertLegalHoldRequest, UpsertLegalHoldResponse,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Posting API", version = "v1"),
+    paths(
+        crate::post_event,
+        crate::bulk::post_events_bulk,
+        crate::ingest::ingest_webhook,
+        crate::dead_letter::list_dead_letters,
+        crate::dead_letter::get_dead_letter,
+        crate::dead_letter::retry_dead_letter,
+        crate::dead_letter::discard_dead_letter,
+        crate::dead_letter::replay_dead_letters,
+        crate::preview::preview_posting,
+        crate::upsert_legal_hold_endpoint,
+        crate::verify_audit_seals_endpoint,
+        crate::reverse_journal,
+        crate::adjust_journal,
+        crate::change_feed::get_ledger_changes,
+        crate::change_feed::stream_ledger_changes,
+        crate::lock_period_endpoint,
+        crate::period::transition_period,
+        crate::period::list_periods,
+        crate::close::upsert_close_checklist,
+        crate::close::get_close_checklist,
+        crate::close::transition_close_dependency,
+        crate::prior_period::post_prior_period_adjustment,
+        crate::prior_period::get_period_results,
+        crate::year_end::close_fiscal_year,
+        crate::year_end::get_year_end_close,
+        crate::year_end::reverse_year_end_close,
+        crate::calendar::get_fiscal_calendar,
+        crate::revrec::get_revrec_rollforward,
+        crate::get_revrec_disclosures,
+        crate::revrec::list_recognition_schedules,
+        crate::revrec::run_recognition,
+        crate::revrec::modify_recognition_schedule,
+        crate::revrec::cancel_recognition_schedule,
+        crate::passes::list_passes,
+        crate::passes::list_breakage_assumptions,
+        crate::passes::register_breakage_assumptions,
+        crate::stored_value::list_cards,
+        crate::stored_value::get_reconciliation,
+        crate::agency::register_supplier_terms,
+        crate::agency::list_supplier_terms,
+        crate::agency::get_supplier_payables,
+        crate::tax::get_tax_liabilities,
+        crate::fx::register_rate_set,
+        crate::fx::list_rate_sets,
+        crate::fx::get_rate_set,
+        crate::get_slo,
+        crate::get_capacity,
+    ),
+    components(schemas(
+        ErrorCode,
+        ErrorEnvelope,
+        PostEventRequest,
+        PostLine,
+        Provenance,
+        PostEventResponse,
+        BookJournal,
+        BulkPostEventLine,
+        BulkPostEventResult,
+        BulkPostOutcome,
+        IngestWebhookResponse,
+        IngestDeliveryResponse,
+        IngestResponse,
+        DeadLetter,
+        DeadLetterAction,
+        DeadLetterActionKind,
+        DeadLetterStatus,
+        DeadLetterList,
+        RetryDeadLetterRequest,
+        DiscardDeadLetterRequest,
+        ReplayDeadLettersRequest,
+        DeadLetterReplayResult,
+        ReplayDeadLettersResponse,
+        PostingPreviewResponse,
+        PreviewJournal,
+        BalanceCheck,
+        PreviewWarningCode,
+        PreviewWarning,
+        UpsertLegalHoldRequest,
+        UpsertLegalHoldResponse,
+        AuditSealVerifyResponse,
+        ReverseJournalResponse,
+        AdjustJournalRequest,
+        AdjustJournalResponse,
+        LedgerChangeType,
+        LedgerChangeEvent,
+        ChangeFeedPage,
+        LockPeriodRequest,
+        LockPeriodResponse,
+        PeriodState,
+        ActorRole,
+        PeriodActor,
+        PostingClass,
+        PeriodTransitionRequest,
+        PeriodTransitionResponse,
+        PeriodStatus,
+        PeriodList,
+        UpsertCloseChecklistRequest,
+        CloseDependencyDefinition,
+        TransitionCloseDependencyRequest,
+        CloseChecklistResponse,
+        PriorPeriodAdjustmentRequest,
+        PriorPeriodAdjustmentResponse,
+        PeriodResultsResponse,
+        PeriodAdjustmentLineage,
+        ReportingBasis,
+        YearEndCloseRequest,
+        ReverseYearEndCloseRequest,
+        YearEndCloseResponse,
+        YearEndClose,
+        YearEndCloseStatus,
+        AccountBalance,
+        FiscalCalendar,
+        WeekPattern,
+        YearEndRule,
+        FiscalPeriod,
+        FiscalCalendarResponse,
+        RevRecRollforwardResponse,
+        RevRecDisclosureResponse,
+        ScheduleKind,
+        ScheduleStatus,
+        ScheduleEntry,
+        RecognitionSchedule,
+        RecognitionScheduleList,
+        RecognitionRunRequest,
+        RecognitionRunResponse,
+        RecognitionJournal,
+        SkippedRecognitionEntry,
+        ModifyScheduleRequest,
+        CancelScheduleRequest,
+        CancelScheduleResponse,
+        RecognitionPattern,
+        PassStatus,
+        PassEntitlement,
+        PassList,
+        BreakageAssumptionSet,
+        BreakageAssumptionSetList,
+        RegisterBreakageAssumptionsRequest,
+        BreakageTrueUpResponse,
+        TrueUpJournal,
+        CardStatus,
+        StoredValueCard,
+        StoredValueCardList,
+        StoredValueReconciliation,
+        StoredValueReconciliationRow,
+        Designation,
+        ProductTerms,
+        SupplierTerms,
+        SupplierTermsList,
+        RegisterSupplierTermsRequest,
+        SupplierPayableReport,
+        SupplierPayableRow,
+        TaxLiabilityReport,
+        TaxLiabilityRow,
+        FxRateType,
+        FxRate,
+        FxRateSet,
+        FxRateSetList,
+        SloResponse,
+        CapacityInstrumentationResponse,
+    ))
+)]
+pub struct ApiDoc;
+
+pub fn openapi_document() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub(crate) async fn get_openapi_document() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi_document())
+}
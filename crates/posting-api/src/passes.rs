@@ -0,0 +1,1141 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use chrono::{Duration, NaiveDate};
+use ledger_posting::{EntrySide, JournalHeader, JournalLine, JournalRecord, JournalStatus};
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::change_feed::LedgerChangeType;
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::period::{PeriodActor, PostingAuthority, PostingClass};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::revrec::{schedule_over, RecognitionSchedule, ScheduleKind, REVENUE_ROLE};
+use crate::rule_engine::RuleEngineError;
+use crate::{
+    deterministic_journal_id, first_string, ledger_error_response, period_error_response,
+    rule_engine_error_response, AppState, PostEventRequest,
+};
+
+const PASS_STORE_FILENAME: &str = "pass_store.json";
+const PASS_SOLD_EVENT: &str = "pass.sold.v1";
+const PASS_VISIT_REDEEMED_EVENT: &str = "pass.visit_redeemed.v1";
+const PASS_EXPIRED_EVENT: &str = "pass.expired.v1";
+const BASIS_POINTS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecognitionPattern {
+    #[default]
+    PerUse,
+    Ratable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PassStatus {
+    Active,
+    Expired,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PassEntitlement {
+    pub pass_id: String,
+    pub pass_type: String,
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub recognition: RecognitionPattern,
+    pub status: PassStatus,
+    pub season_start: NaiveDate,
+    pub season_end: NaiveDate,
+    pub entitled_visits: u32,
+    pub redeemed_visits: u32,
+    pub deferred_account_id: String,
+    pub currency: String,
+    pub base_currency: String,
+    pub price_minor: i64,
+    pub base_price_minor: i64,
+    pub recognized_minor: i64,
+    pub recognized_base_minor: i64,
+    pub breakage_bps: u32,
+    pub estimate_version: Option<String>,
+    pub sale_journal_id: String,
+    pub book_policy_id: String,
+    pub policy_version: String,
+    pub fx_rate_set_id: String,
+    pub ruleset_version: String,
+}
+
+impl PassEntitlement {
+    fn key(&self) -> String {
+        pass_key(
+            &self.tenant_id,
+            &self.legal_entity_id,
+            &self.ledger_book,
+            &self.pass_id,
+        )
+    }
+
+    fn recognize_through(&mut self, redeemed_visits: u32, breakage_bps: u32) -> (i64, i64) {
+        let (recognized, recognized_base) = match self.status {
+            PassStatus::Expired => (self.price_minor, self.base_price_minor),
+            PassStatus::Active => (
+                recognized_target(
+                    self.price_minor,
+                    redeemed_visits,
+                    self.entitled_visits,
+                    breakage_bps,
+                ),
+                recognized_target(
+                    self.base_price_minor,
+                    redeemed_visits,
+                    self.entitled_visits,
+                    breakage_bps,
+                ),
+            ),
+        };
+        let delta = (
+            recognized - self.recognized_minor,
+            recognized_base - self.recognized_base_minor,
+        );
+        self.redeemed_visits = redeemed_visits;
+        self.breakage_bps = breakage_bps;
+        self.recognized_minor = recognized;
+        self.recognized_base_minor = recognized_base;
+        delta
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BreakageAssumptionSet {
+    pub tenant_id: String,
+    pub version: String,
+    pub effective_from: NaiveDate,
+    pub default_breakage_bps: u32,
+    pub breakage_bps_by_pass_type: BTreeMap<String, u32>,
+    pub approved_by: String,
+    pub rationale: String,
+}
+
+impl BreakageAssumptionSet {
+    fn breakage_bps(&self, pass_type: &str) -> u32 {
+        self.breakage_bps_by_pass_type
+            .get(pass_type)
+            .copied()
+            .unwrap_or(self.default_breakage_bps)
+    }
+}
+
+fn recognized_target(total: i64, redeemed_visits: u32, entitled_visits: u32, bps: u32) -> i64 {
+    let expected_visits = i128::from(entitled_visits) * i128::from(BASIS_POINTS - bps);
+    if expected_visits == 0 {
+        return total;
+    }
+    let earned = i128::from(total) * i128::from(redeemed_visits) * i128::from(BASIS_POINTS)
+        / expected_visits;
+    earned.min(i128::from(total)) as i64
+}
+
+fn pass_key(tenant_id: &str, legal_entity_id: &str, ledger_book: &str, pass_id: &str) -> String {
+    format!("{tenant_id}:{legal_entity_id}:{ledger_book}:{pass_id}")
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PassError {
+    #[error("pass {0} not found")]
+    PassNotFound(String),
+    #[error("pass {0} already exists")]
+    PassAlreadyExists(String),
+    #[error("pass {0} is not active")]
+    PassNotActive(String),
+    #[error("pass {0} is recognized ratably over its season")]
+    PassRecognizedRatably(String),
+    #[error("pass {pass_id} has invalid terms: {reason}")]
+    InvalidPassTerms { pass_id: String, reason: String },
+    #[error(
+        "redeeming {visits} visits exceeds the {remaining_visits} remaining on pass {pass_id}"
+    )]
+    VisitsExceedEntitlement {
+        pass_id: String,
+        visits: u32,
+        remaining_visits: u32,
+    },
+    #[error("pass {0} changed while the event was being posted")]
+    PassStateChanged(String),
+    #[error("breakage rate of {0} bps must be below 10000")]
+    InvalidBreakageRate(u32),
+    #[error("breakage assumption set {0} already exists")]
+    DuplicateAssumptionSet(String),
+    #[error("breakage assumption set {version} takes effect before {current_effective_from}")]
+    AssumptionsOutOfOrder {
+        version: String,
+        current_effective_from: NaiveDate,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PassStore {
+    passes: BTreeMap<String, PassEntitlement>,
+    breakage_assumptions: Vec<BreakageAssumptionSet>,
+}
+
+#[derive(Default)]
+pub struct InMemoryPassRepository {
+    passes: BTreeMap<String, PassEntitlement>,
+    breakage_assumptions: Vec<BreakageAssumptionSet>,
+    persistence: Option<Arc<WriteBehind<PassStore>>>,
+}
+
+impl InMemoryPassRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(PASS_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: PassStore = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "pass-write-behind")?);
+        Ok(Self {
+            passes: loaded.passes,
+            breakage_assumptions: loaded.breakage_assumptions,
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        pass_id: &str,
+    ) -> Option<&PassEntitlement> {
+        self.passes
+            .get(&pass_key(tenant_id, legal_entity_id, ledger_book, pass_id))
+    }
+
+    pub fn all(&self) -> Vec<PassEntitlement> {
+        self.passes.values().cloned().collect()
+    }
+
+    /// Whether the journal sold, recognized or trued up a pass this store tracks.
+    pub(crate) fn moves_pass(&self, record: &JournalRecord) -> bool {
+        let header = &record.header;
+        record.lines.iter().any(|line| {
+            line.dimensions.get("pass_id").is_some_and(|pass_id| {
+                self.get(
+                    &header.tenant_id,
+                    &header.legal_entity_id,
+                    &header.ledger_book,
+                    pass_id,
+                )
+                .is_some()
+            })
+        })
+    }
+
+    pub fn breakage_assumptions(&self, tenant_id: &str) -> Vec<BreakageAssumptionSet> {
+        self.breakage_assumptions
+            .iter()
+            .filter(|set| set.tenant_id == tenant_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn effective_breakage_assumptions(
+        &self,
+        tenant_id: &str,
+        date: NaiveDate,
+    ) -> Option<&BreakageAssumptionSet> {
+        self.breakage_assumptions
+            .iter()
+            .rev()
+            .find(|set| set.tenant_id == tenant_id && set.effective_from <= date)
+    }
+
+    fn ensure_registrable(&self, set: &BreakageAssumptionSet) -> Result<(), PassError> {
+        for bps in
+            std::iter::once(&set.default_breakage_bps).chain(set.breakage_bps_by_pass_type.values())
+        {
+            if *bps >= BASIS_POINTS {
+                return Err(PassError::InvalidBreakageRate(*bps));
+            }
+        }
+        let mut existing = self
+            .breakage_assumptions
+            .iter()
+            .filter(|existing| existing.tenant_id == set.tenant_id);
+        if existing
+            .clone()
+            .any(|existing| existing.version == set.version)
+        {
+            return Err(PassError::DuplicateAssumptionSet(set.version.clone()));
+        }
+        if let Some(current) = existing.next_back() {
+            if set.effective_from < current.effective_from {
+                return Err(PassError::AssumptionsOutOfOrder {
+                    version: set.version.clone(),
+                    current_effective_from: current.effective_from,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Works out the per-pass re-estimates and the journal batches a new assumption set
+    /// implies, without applying either.
+    fn true_up_plan(
+        &self,
+        set: &BreakageAssumptionSet,
+    ) -> (Vec<PassUpdate>, BTreeMap<TrueUpBatchKey, Vec<TrueUpEntry>>) {
+        let mut updates = Vec::new();
+        let mut batches = BTreeMap::<_, Vec<_>>::new();
+        for pass in self.passes.values() {
+            if pass.tenant_id != set.tenant_id
+                || pass.status != PassStatus::Active
+                || pass.recognition != RecognitionPattern::PerUse
+            {
+                continue;
+            }
+            let mut next = pass.clone();
+            let (delta_minor, base_delta_minor) =
+                next.recognize_through(pass.redeemed_visits, set.breakage_bps(&pass.pass_type));
+            next.estimate_version = Some(set.version.clone());
+            if delta_minor != 0 || base_delta_minor != 0 {
+                let key = TrueUpBatchKey {
+                    legal_entity_id: pass.legal_entity_id.clone(),
+                    ledger_book: pass.ledger_book.clone(),
+                    book_policy_id: pass.book_policy_id.clone(),
+                    policy_version: pass.policy_version.clone(),
+                    fx_rate_set_id: pass.fx_rate_set_id.clone(),
+                    ruleset_version: pass.ruleset_version.clone(),
+                };
+                batches.entry(key).or_default().push(TrueUpEntry {
+                    pass_id: pass.pass_id.clone(),
+                    deferred_account_id: pass.deferred_account_id.clone(),
+                    currency: pass.currency.clone(),
+                    base_currency: pass.base_currency.clone(),
+                    delta_minor,
+                    base_delta_minor,
+                });
+            }
+            updates.push(PassUpdate {
+                previous: Some(pass.clone()),
+                next,
+            });
+        }
+        (updates, batches)
+    }
+
+    /// Rejects a registration whose plan no longer matches the store, either because a planned
+    /// pass moved on or because a pass the new set would re-estimate appeared since.
+    fn ensure_planned(
+        &self,
+        set: &BreakageAssumptionSet,
+        planned: &[PassUpdate],
+    ) -> Result<(), PassError> {
+        self.ensure_current(planned)?;
+        let (updates, _) = self.true_up_plan(set);
+        match updates.iter().find(|update| {
+            !planned
+                .iter()
+                .any(|planned| planned.next.key() == update.next.key())
+        }) {
+            Some(update) => Err(PassError::PassStateChanged(update.next.pass_id.clone())),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn ensure_current(&self, updates: &[PassUpdate]) -> Result<(), PassError> {
+        for update in updates {
+            if self.passes.get(&update.next.key()) != update.previous.as_ref() {
+                return Err(match update.previous {
+                    None => PassError::PassAlreadyExists(update.next.pass_id.clone()),
+                    Some(_) => PassError::PassStateChanged(update.next.pass_id.clone()),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn register(&mut self, set: BreakageAssumptionSet, updates: Vec<PassUpdate>) {
+        self.breakage_assumptions.push(set);
+        for update in updates {
+            self.passes.insert(update.next.key(), update.next);
+        }
+        self.persist();
+    }
+
+    pub(crate) fn apply(&mut self, updates: Vec<PassUpdate>) {
+        if updates.is_empty() {
+            return;
+        }
+        for update in updates {
+            self.passes.insert(update.next.key(), update.next);
+        }
+        self.persist();
+    }
+
+    fn persist(&self) {
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(PassStore {
+                passes: self.passes.clone(),
+                breakage_assumptions: self.breakage_assumptions.clone(),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PassUpdate {
+    previous: Option<PassEntitlement>,
+    next: PassEntitlement,
+}
+
+struct SaleTerms {
+    pass_id: String,
+    pass_type: String,
+    recognition: RecognitionPattern,
+    entitled_visits: u32,
+    season_start: NaiveDate,
+    season_end: NaiveDate,
+    breakage_bps: u32,
+}
+
+enum PendingPass {
+    Sale(SaleTerms),
+    Update(Box<PassUpdate>),
+}
+
+pub(crate) struct PassEvent {
+    payload: Option<Value>,
+    estimate_version: Option<String>,
+    pending: PendingPass,
+}
+
+impl PassEvent {
+    pub(crate) fn payload<'a>(&'a self, original: &'a Value) -> &'a Value {
+        self.payload.as_ref().unwrap_or(original)
+    }
+
+    pub(crate) fn estimate_version(&self) -> Option<String> {
+        self.estimate_version.clone()
+    }
+
+    pub(crate) fn complete(
+        self,
+        record: &JournalRecord,
+        location_id: &str,
+    ) -> Result<(PassUpdate, Option<RecognitionSchedule>), ApiError> {
+        let terms = match self.pending {
+            PendingPass::Update(update) => return Ok((*update, None)),
+            PendingPass::Sale(terms) => terms,
+        };
+        let header = &record.header;
+        let deferred = record
+            .lines
+            .iter()
+            .find(|line| {
+                line.entry_side == EntrySide::Credit && line.account_id.contains("DEFERRED")
+            })
+            .ok_or_else(|| {
+                pass_error_response(PassError::InvalidPassTerms {
+                    pass_id: terms.pass_id.clone(),
+                    reason: "rule set derived no deferred revenue line".to_string(),
+                })
+            })?;
+        let schedule = match terms.recognition {
+            RecognitionPattern::PerUse => None,
+            RecognitionPattern::Ratable => schedule_over(
+                record,
+                location_id,
+                ScheduleKind::SeasonPass,
+                terms.pass_id.clone(),
+                terms.season_start,
+                terms.season_end + Duration::days(1),
+            ),
+        };
+        let pass = PassEntitlement {
+            pass_id: terms.pass_id,
+            pass_type: terms.pass_type,
+            tenant_id: header.tenant_id.clone(),
+            legal_entity_id: header.legal_entity_id.clone(),
+            ledger_book: header.ledger_book.clone(),
+            recognition: terms.recognition,
+            status: PassStatus::Active,
+            season_start: terms.season_start,
+            season_end: terms.season_end,
+            entitled_visits: terms.entitled_visits,
+            redeemed_visits: 0,
+            deferred_account_id: deferred.account_id.clone(),
+            currency: deferred.currency.clone(),
+            base_currency: deferred.base_currency.clone(),
+            price_minor: deferred.amount_minor,
+            base_price_minor: deferred.base_amount_minor,
+            recognized_minor: 0,
+            recognized_base_minor: 0,
+            breakage_bps: terms.breakage_bps,
+            estimate_version: self.estimate_version,
+            sale_journal_id: header.journal_id.to_string(),
+            book_policy_id: header.book_policy_id.clone(),
+            policy_version: header.policy_version.clone(),
+            fx_rate_set_id: header.fx_rate_set_id.clone(),
+            ruleset_version: header.ruleset_version.clone(),
+        };
+        Ok((
+            PassUpdate {
+                previous: None,
+                next: pass,
+            },
+            schedule,
+        ))
+    }
+}
+
+pub(crate) fn prepare_pass_event(
+    state: &AppState,
+    req: &PostEventRequest,
+    accounting_date: NaiveDate,
+) -> Result<Option<PassEvent>, ApiError> {
+    if !matches!(
+        req.event_type.as_str(),
+        PASS_SOLD_EVENT | PASS_VISIT_REDEEMED_EVENT | PASS_EXPIRED_EVENT
+    ) {
+        return Ok(None);
+    }
+    let pass_id = first_string(&req.payload, &["/pass_id"])
+        .ok_or_else(|| {
+            rule_engine_error_response(RuleEngineError::MissingField("pass_id".to_string()))
+        })?
+        .to_string();
+    let passes = state
+        .passes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?;
+    let existing = passes.get(
+        &req.tenant_id,
+        &req.legal_entity_id,
+        &req.ledger_book,
+        &pass_id,
+    );
+
+    if req.event_type == PASS_SOLD_EVENT {
+        if existing.is_some() {
+            return Err(pass_error_response(PassError::PassAlreadyExists(pass_id)));
+        }
+        let mut terms = sale_terms(pass_id, &req.payload).map_err(pass_error_response)?;
+        let assumptions = match terms.recognition {
+            RecognitionPattern::PerUse => {
+                passes.effective_breakage_assumptions(&req.tenant_id, accounting_date)
+            }
+            RecognitionPattern::Ratable => None,
+        };
+        terms.breakage_bps = assumptions.map_or(0, |set| set.breakage_bps(&terms.pass_type));
+        return Ok(Some(PassEvent {
+            payload: None,
+            estimate_version: assumptions.map(|set| set.version.clone()),
+            pending: PendingPass::Sale(terms),
+        }));
+    }
+
+    let previous = existing
+        .cloned()
+        .ok_or_else(|| pass_error_response(PassError::PassNotFound(pass_id.clone())))?;
+    drop(passes);
+    if previous.status != PassStatus::Active {
+        return Err(pass_error_response(PassError::PassNotActive(pass_id)));
+    }
+    if previous.recognition == RecognitionPattern::Ratable {
+        return Err(pass_error_response(PassError::PassRecognizedRatably(
+            pass_id,
+        )));
+    }
+
+    let mut next = previous.clone();
+    let redeemed_visits = if req.event_type == PASS_EXPIRED_EVENT {
+        next.status = PassStatus::Expired;
+        previous.redeemed_visits
+    } else {
+        let visits = match req.payload.pointer("/visits") {
+            None | Some(Value::Null) => 1,
+            Some(value) => value
+                .as_u64()
+                .and_then(|visits| u32::try_from(visits).ok())
+                .filter(|visits| *visits > 0)
+                .ok_or_else(|| {
+                    rule_engine_error_response(RuleEngineError::InvalidNumber("visits".to_string()))
+                })?,
+        };
+        let remaining_visits = previous.entitled_visits - previous.redeemed_visits;
+        if visits > remaining_visits {
+            return Err(pass_error_response(PassError::VisitsExceedEntitlement {
+                pass_id,
+                visits,
+                remaining_visits,
+            }));
+        }
+        previous.redeemed_visits + visits
+    };
+    let (recognized_minor, recognized_base_minor) =
+        next.recognize_through(redeemed_visits, previous.breakage_bps);
+
+    let mut payload = req.payload.as_object().cloned().unwrap_or_default();
+    payload.extend([
+        ("pass_id".to_string(), json!(next.pass_id)),
+        (
+            "recognized_amount_minor".to_string(),
+            json!(recognized_minor),
+        ),
+        (
+            "recognized_base_amount_minor".to_string(),
+            json!(recognized_base_minor),
+        ),
+        ("currency".to_string(), json!(next.currency)),
+        ("base_currency".to_string(), json!(next.base_currency)),
+        (
+            "deferred_account_id".to_string(),
+            json!(next.deferred_account_id),
+        ),
+    ]);
+    Ok(Some(PassEvent {
+        payload: Some(Value::Object(payload)),
+        estimate_version: next.estimate_version.clone(),
+        pending: PendingPass::Update(Box::new(PassUpdate {
+            previous: Some(previous),
+            next,
+        })),
+    }))
+}
+
+fn sale_terms(pass_id: String, payload: &Value) -> Result<SaleTerms, PassError> {
+    let invalid = |reason: &str| PassError::InvalidPassTerms {
+        pass_id: pass_id.clone(),
+        reason: reason.to_string(),
+    };
+    let recognition = match payload.pointer("/recognition") {
+        None | Some(Value::Null) => RecognitionPattern::default(),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|_| invalid("recognition must be PER_USE or RATABLE"))?,
+    };
+    let season_date = |pointer: &str| {
+        payload
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    };
+    let (Some(season_start), Some(season_end)) =
+        (season_date("/season_start"), season_date("/season_end"))
+    else {
+        return Err(invalid("season_start and season_end are required dates"));
+    };
+    if season_end < season_start {
+        return Err(invalid("season_end precedes season_start"));
+    }
+    let entitled_visits = payload
+        .pointer("/entitled_visits")
+        .and_then(Value::as_u64)
+        .and_then(|visits| u32::try_from(visits).ok())
+        .unwrap_or(0);
+    if recognition == RecognitionPattern::PerUse && entitled_visits == 0 {
+        return Err(invalid("per-use passes require entitled_visits"));
+    }
+    Ok(SaleTerms {
+        pass_type: first_string(payload, &["/pass_type"])
+            .unwrap_or("UNSPECIFIED")
+            .to_string(),
+        pass_id,
+        recognition,
+        entitled_visits,
+        season_start,
+        season_end,
+        breakage_bps: 0,
+    })
+}
+
+pub(crate) fn pass_error_response(error: PassError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        PassError::PassNotFound(pass_id) => {
+            ApiError::not_found(ErrorCode::PassNotFound).with_detail("pass_id", pass_id)
+        }
+        PassError::PassAlreadyExists(pass_id) => {
+            ApiError::conflict(ErrorCode::PassAlreadyExists).with_detail("pass_id", pass_id)
+        }
+        PassError::PassNotActive(pass_id) => {
+            ApiError::conflict(ErrorCode::PassNotActive).with_detail("pass_id", pass_id)
+        }
+        PassError::PassRecognizedRatably(pass_id) => {
+            ApiError::bad_request(ErrorCode::PassRecognizedRatably).with_detail("pass_id", pass_id)
+        }
+        PassError::InvalidPassTerms { pass_id, reason } => {
+            ApiError::bad_request(ErrorCode::InvalidPassTerms)
+                .with_detail("pass_id", pass_id)
+                .with_detail("reason", reason)
+        }
+        PassError::VisitsExceedEntitlement {
+            pass_id,
+            visits,
+            remaining_visits,
+        } => ApiError::bad_request(ErrorCode::VisitsExceedEntitlement)
+            .with_detail("pass_id", pass_id)
+            .with_detail("visits", visits)
+            .with_detail("remaining_visits", remaining_visits),
+        PassError::PassStateChanged(pass_id) => {
+            ApiError::conflict(ErrorCode::PassStateChanged).with_detail("pass_id", pass_id)
+        }
+        PassError::InvalidBreakageRate(bps) => {
+            ApiError::bad_request(ErrorCode::InvalidBreakageRate).with_detail("breakage_bps", bps)
+        }
+        PassError::DuplicateAssumptionSet(version) => {
+            ApiError::conflict(ErrorCode::DuplicateBreakageAssumptionSet)
+                .with_detail("version", version)
+        }
+        PassError::AssumptionsOutOfOrder {
+            version,
+            current_effective_from,
+        } => ApiError::conflict(ErrorCode::BreakageAssumptionsOutOfOrder)
+            .with_detail("version", version)
+            .with_detail("current_effective_from", current_effective_from.to_string()),
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct TrueUpBatchKey {
+    legal_entity_id: String,
+    ledger_book: String,
+    book_policy_id: String,
+    policy_version: String,
+    fx_rate_set_id: String,
+    ruleset_version: String,
+}
+
+struct TrueUpEntry {
+    pass_id: String,
+    deferred_account_id: String,
+    currency: String,
+    base_currency: String,
+    delta_minor: i64,
+    base_delta_minor: i64,
+}
+
+fn true_up_record(
+    set: &BreakageAssumptionSet,
+    key: &TrueUpBatchKey,
+    entries: &[TrueUpEntry],
+    revenue_account: &str,
+) -> JournalRecord {
+    let journal_id = deterministic_journal_id(
+        &format!(
+            "breakage-true-up:{}:{}:{}:{}",
+            set.tenant_id, key.legal_entity_id, key.ledger_book, set.version
+        ),
+        &payload_hash(&json!(entries
+            .iter()
+            .map(|entry| json!([entry.pass_id, entry.delta_minor, entry.base_delta_minor]))
+            .collect::<Vec<_>>())),
+    );
+    let mut lines = Vec::with_capacity(entries.len() * 2);
+    for entry in entries {
+        let recognizes =
+            entry.delta_minor > 0 || entry.delta_minor == 0 && entry.base_delta_minor > 0;
+        let (debit_account, credit_account) = if recognizes {
+            (entry.deferred_account_id.as_str(), revenue_account)
+        } else {
+            (revenue_account, entry.deferred_account_id.as_str())
+        };
+        let dimensions = BTreeMap::from([
+            ("revrec_movement".to_string(), "recognized".to_string()),
+            ("pass_id".to_string(), entry.pass_id.clone()),
+        ]);
+        for (account_id, entry_side) in [
+            (debit_account, EntrySide::Debit),
+            (credit_account, EntrySide::Credit),
+        ] {
+            lines.push(JournalLine {
+                line_number: lines.len() as u32 + 1,
+                account_id: account_id.to_string(),
+                entry_side,
+                amount_minor: entry.delta_minor.abs(),
+                currency: entry.currency.clone(),
+                base_amount_minor: entry.base_delta_minor.abs(),
+                base_currency: entry.base_currency.clone(),
+                dimensions: dimensions.clone(),
+                fx_rate: None,
+            });
+        }
+    }
+    JournalRecord {
+        header: JournalHeader {
+            journal_id,
+            journal_number: format!("BT-{}", &journal_id.to_string()[..8]),
+            status: JournalStatus::Posted,
+            tenant_id: set.tenant_id.clone(),
+            legal_entity_id: key.legal_entity_id.clone(),
+            ledger_book: key.ledger_book.clone(),
+            accounting_date: set.effective_from,
+            posted_at: chrono::Utc::now(),
+            source_event_ids: entries
+                .iter()
+                .map(|entry| format!("breakage-true-up:{}:{}", set.version, entry.pass_id))
+                .collect(),
+            posting_run_id: format!("breakage-true-up:{}", set.version),
+            book_policy_id: key.book_policy_id.clone(),
+            policy_version: key.policy_version.clone(),
+            fx_rate_set_id: key.fx_rate_set_id.clone(),
+            ruleset_version: key.ruleset_version.clone(),
+            workflow_id: None,
+            estimate_version: Some(set.version.clone()),
+            revenue_designation: None,
+            prior_period_adjustment: None,
+            book_group_id: None,
+            trace_context: None,
+        },
+        lines,
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PassQuery {
+    pub book: Option<String>,
+    pub legal_entity_id: Option<String>,
+    pub pass_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PassList {
+    pub passes: Vec<PassEntitlement>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BreakageAssumptionQuery {
+    pub tenant_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BreakageAssumptionSetList {
+    pub assumption_sets: Vec<BreakageAssumptionSet>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RegisterBreakageAssumptionsRequest {
+    pub tenant_id: String,
+    pub version: String,
+    pub effective_from: String,
+    pub default_breakage_bps: u32,
+    #[serde(default)]
+    pub breakage_bps_by_pass_type: BTreeMap<String, u32>,
+    pub approved_by: String,
+    pub rationale: String,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BreakageTrueUpResponse {
+    pub version: String,
+    pub effective_from: NaiveDate,
+    pub pass_count: usize,
+    pub journals: Vec<TrueUpJournal>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrueUpJournal {
+    pub journal_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub pass_count: usize,
+    pub recognized_delta_minor: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/passes",
+    tag = "passes",
+    params(PassQuery),
+    responses(
+        (status = 200, description = "Pass entitlements and recognized revenue", body = PassList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_passes(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<PassQuery>,
+) -> Result<Json<PassList>, ApiError> {
+    let passes = state
+        .passes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?
+        .all()
+        .into_iter()
+        .filter(|pass| {
+            query
+                .book
+                .as_ref()
+                .is_none_or(|book| *book == pass.ledger_book)
+                && query
+                    .legal_entity_id
+                    .as_ref()
+                    .is_none_or(|legal_entity_id| *legal_entity_id == pass.legal_entity_id)
+                && query
+                    .pass_id
+                    .as_ref()
+                    .is_none_or(|pass_id| *pass_id == pass.pass_id)
+        })
+        .collect();
+    Ok(Json(PassList { passes }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/passes/breakage-assumptions",
+    tag = "passes",
+    params(BreakageAssumptionQuery),
+    responses(
+        (status = 200, description = "Breakage assumption sets in registration order", body = BreakageAssumptionSetList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_breakage_assumptions(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<BreakageAssumptionQuery>,
+) -> Result<Json<BreakageAssumptionSetList>, ApiError> {
+    let assumption_sets = state
+        .passes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?
+        .breakage_assumptions(&query.tenant_id);
+    Ok(Json(BreakageAssumptionSetList { assumption_sets }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/passes/breakage-assumptions",
+    tag = "passes",
+    request_body = RegisterBreakageAssumptionsRequest,
+    responses(
+        (status = 200, description = "Assumption set registered and active per-use passes trued up", body = BreakageTrueUpResponse),
+        (status = 400, description = "Invalid effective date or breakage rate", body = ErrorEnvelope),
+        (status = 409, description = "Duplicate version, ordering, period or hold conflict", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn register_breakage_assumptions(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<RegisterBreakageAssumptionsRequest>,
+) -> Result<Json<BreakageTrueUpResponse>, ApiError> {
+    let effective_from =
+        NaiveDate::parse_from_str(&req.effective_from, "%Y-%m-%d").map_err(|_| {
+            ApiError::bad_request(ErrorCode::InvalidAccountingDate)
+                .with_detail("effective_from", req.effective_from.as_str())
+        })?;
+    let set = BreakageAssumptionSet {
+        tenant_id: req.tenant_id,
+        version: req.version,
+        effective_from,
+        default_breakage_bps: req.default_breakage_bps,
+        breakage_bps_by_pass_type: req.breakage_bps_by_pass_type,
+        approved_by: req.approved_by,
+        rationale: req.rationale,
+    };
+
+    // Holds and periods are checked before the journal store is locked, so registration never
+    // holds the period store and the journal store at once.
+    let (updates, batches) = {
+        let passes = state
+            .passes
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?;
+        passes
+            .ensure_registrable(&set)
+            .map_err(pass_error_response)?;
+        passes.true_up_plan(&set)
+    };
+    let mut revenue_accounts = Vec::with_capacity(batches.len());
+    for key in batches.keys() {
+        revenue_accounts.push(
+            state
+                .rule_sets
+                .account(&key.ruleset_version, REVENUE_ROLE)
+                .map_err(rule_engine_error_response)?,
+        );
+        state.validate_legal_hold(
+            &set.tenant_id,
+            &key.legal_entity_id,
+            &key.ledger_book,
+            effective_from,
+        )?;
+        state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+            .ensure_open(
+                &set.tenant_id,
+                &key.legal_entity_id,
+                &key.ledger_book,
+                effective_from,
+                PostingAuthority::new(PostingClass::Close, req.actor.as_ref()),
+            )
+            .map_err(period_error_response)?;
+    }
+
+    let mut journals = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+    let mut passes = state
+        .passes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PassStoreError))?;
+    passes
+        .ensure_registrable(&set)
+        .map_err(pass_error_response)?;
+    passes
+        .ensure_planned(&set, &updates)
+        .map_err(pass_error_response)?;
+    let records = batches
+        .iter()
+        .zip(&revenue_accounts)
+        .map(|((key, entries), revenue_account)| {
+            true_up_record(&set, key, entries, revenue_account)
+        })
+        .collect::<Vec<_>>();
+
+    let headers = records
+        .iter()
+        .map(|record| record.header.clone())
+        .collect::<Vec<_>>();
+    journals
+        .insert_posted_batch(records)
+        .map_err(ledger_error_response)?;
+    for header in &headers {
+        state.record_change(LedgerChangeType::JournalPosted, header, None)?;
+    }
+    let pass_count = updates.len();
+    passes.register(set.clone(), updates);
+    drop(passes);
+    drop(journals);
+
+    let entity_scope = headers
+        .iter()
+        .map(|header| header.legal_entity_id.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    state.append_audit_seal(
+        "passes.breakage_assumptions_registered",
+        &entity_scope,
+        &json!({
+            "tenant_id": set.tenant_id,
+            "version": set.version,
+            "effective_from": set.effective_from,
+            "default_breakage_bps": set.default_breakage_bps,
+            "breakage_bps_by_pass_type": set.breakage_bps_by_pass_type,
+            "approved_by": set.approved_by,
+            "rationale": set.rationale,
+            "journal_ids": headers
+                .iter()
+                .map(|header| header.journal_id.to_string())
+                .collect::<Vec<_>>()
+        }),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+
+    let journals = batches
+        .values()
+        .zip(headers)
+        .map(|(entries, header)| TrueUpJournal {
+            journal_id: header.journal_id.to_string(),
+            legal_entity_id: header.legal_entity_id,
+            ledger_book: header.ledger_book,
+            pass_count: entries.len(),
+            recognized_delta_minor: entries.iter().map(|entry| entry.delta_minor).sum(),
+        })
+        .collect();
+    Ok(Json(BreakageTrueUpResponse {
+        version: set.version,
+        effective_from,
+        pass_count,
+        journals,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recognized_target, BreakageAssumptionSet, InMemoryPassRepository, PassError};
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    fn assumption_set(version: &str, effective_from: &str, bps: u32) -> BreakageAssumptionSet {
+        BreakageAssumptionSet {
+            tenant_id: "tenant_1".to_string(),
+            version: version.to_string(),
+            effective_from: NaiveDate::parse_from_str(effective_from, "%Y-%m-%d").unwrap(),
+            default_breakage_bps: bps,
+            breakage_bps_by_pass_type: BTreeMap::from([("SEASON_10_PACK".to_string(), 2500)]),
+            approved_by: "controller".to_string(),
+            rationale: "prior season redemption".to_string(),
+        }
+    }
+
+    #[test]
+    fn per_use_target_spreads_price_over_expected_visits() {
+        assert_eq!(recognized_target(10000, 2, 10, 0), 2000);
+        assert_eq!(recognized_target(10000, 3, 10, 2500), 4000);
+        assert_eq!(recognized_target(10000, 8, 10, 2500), 10000);
+        assert_eq!(recognized_target(10000, 1, 3, 0), 3333);
+    }
+
+    #[test]
+    fn assumption_sets_are_immutable_ordered_and_bounded() {
+        let mut repo = InMemoryPassRepository::default();
+        repo.ensure_registrable(&assumption_set("2026.1", "2026-01-01", 1000))
+            .unwrap();
+        repo.breakage_assumptions
+            .push(assumption_set("2026.1", "2026-01-01", 1000));
+
+        assert_eq!(
+            repo.ensure_registrable(&assumption_set("2026.1", "2026-02-01", 1000)),
+            Err(PassError::DuplicateAssumptionSet("2026.1".to_string()))
+        );
+        assert_eq!(
+            repo.ensure_registrable(&assumption_set("2025.9", "2025-12-01", 1000)),
+            Err(PassError::AssumptionsOutOfOrder {
+                version: "2025.9".to_string(),
+                current_effective_from: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            })
+        );
+        assert_eq!(
+            repo.ensure_registrable(&assumption_set("2026.2", "2026-02-01", 10000)),
+            Err(PassError::InvalidBreakageRate(10000))
+        );
+
+        let date = |day| NaiveDate::from_ymd_opt(2026, 1, day).unwrap();
+        assert!(repo
+            .effective_breakage_assumptions("tenant_1", date(1))
+            .is_some_and(|set| set.breakage_bps("SEASON_10_PACK") == 2500
+                && set.breakage_bps("DAY_4_PACK") == 1000));
+        assert!(repo
+            .effective_breakage_assumptions("tenant_2", date(1))
+            .is_none());
+    }
+}
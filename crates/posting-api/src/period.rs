@@ -1,180 +1,176 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fs;
 use std::io;
-use std::path::{Path, PathBuf};
-use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::path::Path as FsPath;
 use std::sync::Arc;
-use std::thread;
 
-use chrono::{Datelike, NaiveDate};
-use serde::de::DeserializeOwned;
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::{Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::calendar::{CalendarError, FiscalCalendar, FiscalCalendars};
+use crate::close::seal_hard_close;
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::{period_error_response, AppState};
 
 const PERIOD_STORE_FILENAME: &str = "period_store.json";
 
-enum WriteBehindCommand<T> {
-    Persist(T),
-    Flush(Sender<io::Result<()>>),
-    Shutdown,
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+struct PeriodKey {
+    tenant_id: String,
+    legal_entity_id: String,
+    ledger_book: String,
+    period_id: String,
+}
+
+impl PeriodKey {
+    fn new(tenant_id: &str, legal_entity_id: &str, ledger_book: &str, period_id: &str) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            legal_entity_id: legal_entity_id.to_string(),
+            ledger_book: ledger_book.to_string(),
+            period_id: period_id.to_string(),
+        }
+    }
+}
+
+/// Periods without a recorded state are `Future` when they start after the period holding the
+/// repository clock's today, and `Open` otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PeriodState {
+    Future,
+    #[default]
+    Open,
+    SoftClosed,
+    Closed,
+    Archived,
 }
 
-struct WriteBehind<T> {
-    tx: Sender<WriteBehindCommand<T>>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ActorRole {
+    #[default]
+    System,
+    Accountant,
+    Controller,
 }
 
-impl<T> Drop for WriteBehind<T> {
-    fn drop(&mut self) {
-        let _ = self.tx.send(WriteBehindCommand::Shutdown);
-    }
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PeriodActor {
+    pub actor_id: String,
+    pub role: ActorRole,
 }
 
-impl<T> WriteBehind<T>
-where
-    T: Serialize + Send + 'static,
-{
-    fn new(path: PathBuf, worker_name: &str) -> io::Result<Self> {
-        let (tx, rx) = mpsc::channel();
-        thread::Builder::new()
-            .name(worker_name.to_string())
-            .spawn(move || run_write_behind_worker(path, rx))?;
-        Ok(Self { tx })
-    }
+/// Operational postings come from source events. Adjustments are manual or corrective
+/// entries, and close entries are period-end runs such as revenue recognition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PostingClass {
+    Operational,
+    Adjustment,
+    Close,
+}
 
-    fn persist(&self, snapshot: T) {
-        let _ = self.tx.send(WriteBehindCommand::Persist(snapshot));
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostingAuthority {
+    pub class: PostingClass,
+    pub role: ActorRole,
+}
 
-    fn flush(&self) -> io::Result<()> {
-        let (ack_tx, ack_rx) = mpsc::channel();
-        self.tx
-            .send(WriteBehindCommand::Flush(ack_tx))
-            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "persistence worker stopped"))?;
-        ack_rx
-            .recv()
-            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "persistence worker stopped"))?
+impl PostingAuthority {
+    pub fn new(class: PostingClass, actor: Option<&PeriodActor>) -> Self {
+        Self {
+            class,
+            role: actor.map(|actor| actor.role).unwrap_or_default(),
+        }
     }
 }
 
-fn run_write_behind_worker<T>(path: PathBuf, rx: Receiver<WriteBehindCommand<T>>)
-where
-    T: Serialize,
-{
-    let mut latest_snapshot = None;
-    loop {
-        match rx.recv() {
-            Ok(WriteBehindCommand::Persist(snapshot)) => {
-                latest_snapshot = Some(snapshot);
-            }
-            Ok(WriteBehindCommand::Flush(ack)) => {
-                let _ = ack.send(persist_latest_snapshot(&path, &mut latest_snapshot));
-                continue;
-            }
-            Ok(WriteBehindCommand::Shutdown) => {
-                let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
-                return;
-            }
-            Err(_) => {
-                let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
-                return;
+impl PeriodState {
+    /// Open periods take everything. A soft-closed period only takes adjustments and close
+    /// entries from accountants and controllers; every other state takes nothing.
+    pub fn allows(self, authority: PostingAuthority) -> bool {
+        match self {
+            Self::Open => true,
+            Self::SoftClosed => {
+                authority.class != PostingClass::Operational && authority.role != ActorRole::System
             }
+            Self::Future | Self::Closed | Self::Archived => false,
         }
+    }
 
-        loop {
-            match rx.try_recv() {
-                Ok(WriteBehindCommand::Persist(snapshot)) => {
-                    latest_snapshot = Some(snapshot);
-                }
-                Ok(WriteBehindCommand::Flush(ack)) => {
-                    let _ = ack.send(persist_latest_snapshot(&path, &mut latest_snapshot));
-                }
-                Ok(WriteBehindCommand::Shutdown) => {
-                    let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
-                    return;
-                }
-                Err(TryRecvError::Empty) => {
-                    let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
-                    break;
-                }
-                Err(TryRecvError::Disconnected) => {
-                    let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
-                    return;
-                }
+    fn transition_roles(self, to: Self) -> &'static [ActorRole] {
+        match (self, to) {
+            (Self::Future, Self::Open) | (Self::Open, Self::SoftClosed) => {
+                &[ActorRole::Accountant, ActorRole::Controller]
             }
+            (Self::SoftClosed, Self::Open)
+            | (Self::SoftClosed, Self::Closed)
+            | (Self::Closed, Self::SoftClosed)
+            | (Self::Closed, Self::Archived) => &[ActorRole::Controller],
+            _ => &[],
         }
     }
 }
 
-fn persist_latest_snapshot<T>(path: &Path, snapshot: &mut Option<T>) -> io::Result<()>
-where
-    T: Serialize,
-{
-    if let Some(value) = snapshot.take() {
-        persist_snapshot(path, &value)?;
-    }
-    Ok(())
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PeriodRecord {
+    #[serde(flatten)]
+    key: PeriodKey,
+    #[serde(default = "closed_state")]
+    state: PeriodState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    changed_by: Option<String>,
 }
 
-fn persist_snapshot<T>(path: &Path, snapshot: &T) -> io::Result<()>
-where
-    T: Serialize,
-{
-    let encoded = serde_json::to_vec(snapshot).map_err(|error| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("failed to serialize persistence snapshot: {error}"),
-        )
-    })?;
-    let temp_path = path.with_extension("tmp");
-    fs::write(&temp_path, encoded)?;
-    fs::rename(&temp_path, path)?;
-    Ok(())
+// Snapshots written before periods had states only listed closed periods.
+fn closed_state() -> PeriodState {
+    PeriodState::Closed
 }
 
-fn load_snapshot_or_default<T>(path: &Path) -> io::Result<T>
-where
-    T: DeserializeOwned + Default,
-{
-    match fs::read(path) {
-        Ok(encoded) => serde_json::from_slice(&encoded).map_err(|error| {
-            io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("failed to deserialize persistence snapshot: {error}"),
-            )
-        }),
-        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(T::default()),
-        Err(error) => Err(error),
-    }
+/// Tells the period repository what today is, which decides the periods still in the future.
+pub trait Clock: Send + Sync {
+    fn today(&self) -> NaiveDate;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-struct PeriodKey {
-    tenant_id: String,
-    legal_entity_id: String,
-    ledger_book: String,
-    period_id: String,
+/// Today in UTC.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Utc::now().date_naive()
+    }
 }
 
-impl PeriodKey {
-    fn new(tenant_id: &str, legal_entity_id: &str, ledger_book: &str, period_id: &str) -> Self {
-        Self {
-            tenant_id: tenant_id.to_string(),
-            legal_entity_id: legal_entity_id.to_string(),
-            ledger_book: ledger_book.to_string(),
-            period_id: period_id.to_string(),
-        }
+/// Always the same day.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
     }
 }
 
 pub struct InMemoryPeriodRepository {
-    closed: HashSet<PeriodKey>,
-    persistence: Option<Arc<WriteBehind<HashSet<PeriodKey>>>>,
+    periods: HashMap<PeriodKey, PeriodRecord>,
+    calendars: FiscalCalendars,
+    clock: Arc<dyn Clock>,
+    persistence: Option<Arc<WriteBehind<Vec<PeriodRecord>>>>,
 }
 
 impl Default for InMemoryPeriodRepository {
     fn default() -> Self {
         Self {
-            closed: HashSet::new(),
+            periods: HashMap::new(),
+            calendars: FiscalCalendars::default(),
+            clock: Arc::new(SystemClock),
             persistence: None,
         }
     }
@@ -186,22 +182,74 @@ pub enum PeriodError {
     InvalidPeriodId(String),
     #[error("period is closed: {0}")]
     PeriodClosed(String),
+    #[error(
+        "period {period_id} is {state:?} and does not accept {class:?} postings from {role:?}"
+    )]
+    PostingNotAllowed {
+        period_id: String,
+        state: PeriodState,
+        class: PostingClass,
+        role: ActorRole,
+    },
+    #[error("period {period_id} cannot move from {from:?} to {to:?}")]
+    InvalidTransition {
+        period_id: String,
+        from: PeriodState,
+        to: PeriodState,
+    },
+    #[error("{role:?} may not move period {period_id} from {from:?} to {to:?}")]
+    TransitionForbidden {
+        period_id: String,
+        from: PeriodState,
+        to: PeriodState,
+        role: ActorRole,
+    },
+    #[error(transparent)]
+    Calendar(#[from] CalendarError),
 }
 
 impl InMemoryPeriodRepository {
-    pub fn with_persistence_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
         let path = dir.as_ref().join(PERIOD_STORE_FILENAME);
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
-        let loaded = load_snapshot_or_default(&path)?;
+        let loaded: Vec<PeriodRecord> = load_snapshot_or_default(&path)?;
         let persistence = Arc::new(WriteBehind::new(path, "period-write-behind")?);
         Ok(Self {
-            closed: loaded,
+            periods: loaded
+                .into_iter()
+                .map(|record| (record.key.clone(), record))
+                .collect(),
+            calendars: FiscalCalendars::default(),
+            clock: Arc::new(SystemClock),
             persistence: Some(persistence),
         })
     }
 
+    /// Entities without a calendar here use calendar months.
+    pub fn set_fiscal_calendars(&mut self, calendars: HashMap<String, FiscalCalendar>) {
+        self.calendars = FiscalCalendars::new(calendars);
+    }
+
+    pub fn calendar(&self, legal_entity_id: &str) -> FiscalCalendar {
+        self.calendars.calendar(legal_entity_id)
+    }
+
+    pub fn fiscal_calendars(&self) -> FiscalCalendars {
+        self.calendars.clone()
+    }
+
+    /// Replaces the clock that decides which periods are still in the future. Defaults to
+    /// [`SystemClock`].
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn knows_period_id(&self, legal_entity_id: Option<&str>, period_id: &str) -> bool {
+        self.calendars.knows_period_id(legal_entity_id, period_id)
+    }
+
     pub fn flush_persistence(&self) -> io::Result<()> {
         match &self.persistence {
             Some(persistence) => persistence.flush(),
@@ -209,26 +257,86 @@ impl InMemoryPeriodRepository {
         }
     }
 
-    pub fn lock_period(
-        &mut self,
+    pub fn state(
+        &self,
         tenant_id: &str,
         legal_entity_id: &str,
         ledger_book: &str,
         period_id: &str,
-    ) -> Result<(), PeriodError> {
-        if !is_valid_period_id(period_id) {
-            return Err(PeriodError::InvalidPeriodId(period_id.to_string()));
+    ) -> PeriodState {
+        self.periods
+            .get(&PeriodKey::new(
+                tenant_id,
+                legal_entity_id,
+                ledger_book,
+                period_id,
+            ))
+            .map(|record| record.state)
+            .unwrap_or_else(|| self.unrecorded_state(legal_entity_id, period_id))
+    }
+
+    fn unrecorded_state(&self, legal_entity_id: &str, period_id: &str) -> PeriodState {
+        let calendar = self.calendar(legal_entity_id);
+        let today = self.clock.today();
+        match (calendar.period(period_id), calendar.period_for(today)) {
+            (Some(period), Ok(current)) if period.start_date > current.end_date => {
+                PeriodState::Future
+            }
+            _ => PeriodState::Open,
         }
-        self.closed.insert(PeriodKey::new(
+    }
+
+    pub fn transition(
+        &mut self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        period_id: &str,
+        to: PeriodState,
+        actor: &PeriodActor,
+    ) -> Result<PeriodState, PeriodError> {
+        let from = self.check_transition(
             tenant_id,
             legal_entity_id,
             ledger_book,
             period_id,
-        ));
-        if let Some(persistence) = &self.persistence {
-            persistence.persist(self.closed.clone());
+            to,
+            actor,
+        )?;
+        let key = PeriodKey::new(tenant_id, legal_entity_id, ledger_book, period_id);
+        self.set_state(key, to, Some(actor.actor_id.clone()));
+        Ok(from)
+    }
+
+    /// Returns the period's current state if `actor` may move it to `to`.
+    pub fn check_transition(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        period_id: &str,
+        to: PeriodState,
+        actor: &PeriodActor,
+    ) -> Result<PeriodState, PeriodError> {
+        self.ensure_valid_period_id(legal_entity_id, period_id)?;
+        let from = self.state(tenant_id, legal_entity_id, ledger_book, period_id);
+        let roles = from.transition_roles(to);
+        if roles.is_empty() {
+            return Err(PeriodError::InvalidTransition {
+                period_id: period_id.to_string(),
+                from,
+                to,
+            });
         }
-        Ok(())
+        if !roles.contains(&actor.role) {
+            return Err(PeriodError::TransitionForbidden {
+                period_id: period_id.to_string(),
+                from,
+                to,
+                role: actor.role,
+            });
+        }
+        Ok(from)
     }
 
     pub fn ensure_open(
@@ -237,33 +345,219 @@ impl InMemoryPeriodRepository {
         legal_entity_id: &str,
         ledger_book: &str,
         accounting_date: NaiveDate,
+        authority: PostingAuthority,
     ) -> Result<(), PeriodError> {
-        let period_id = period_id_from_date(accounting_date);
-        let period = PeriodKey::new(tenant_id, legal_entity_id, ledger_book, &period_id);
-        if self.closed.contains(&period) {
-            return Err(PeriodError::PeriodClosed(period_id));
+        let period_id = self
+            .calendar(legal_entity_id)
+            .period_for(accounting_date)?
+            .period_id;
+        let state = self.state(tenant_id, legal_entity_id, ledger_book, &period_id);
+        if state.allows(authority) {
+            return Ok(());
+        }
+        match state {
+            PeriodState::Closed | PeriodState::Archived => {
+                Err(PeriodError::PeriodClosed(period_id))
+            }
+            _ => Err(PeriodError::PostingNotAllowed {
+                period_id,
+                state,
+                class: authority.class,
+                role: authority.role,
+            }),
+        }
+    }
+
+    pub fn ensure_valid_period_id(
+        &self,
+        legal_entity_id: &str,
+        period_id: &str,
+    ) -> Result<(), PeriodError> {
+        if !self.calendar(legal_entity_id).is_valid_period_id(period_id) {
+            return Err(PeriodError::InvalidPeriodId(period_id.to_string()));
         }
         Ok(())
     }
+
+    fn set_state(&mut self, key: PeriodKey, state: PeriodState, changed_by: Option<String>) {
+        self.periods.insert(
+            key.clone(),
+            PeriodRecord {
+                key,
+                state,
+                changed_by,
+            },
+        );
+        if let Some(persistence) = &self.persistence {
+            let mut snapshot = self.periods.values().cloned().collect::<Vec<_>>();
+            snapshot.sort_by(|left, right| left.key.cmp(&right.key));
+            persistence.persist(snapshot);
+        }
+    }
 }
 
 pub fn period_id_from_date(date: NaiveDate) -> String {
     format!("{:04}-{:02}", date.year(), date.month())
 }
 
-fn is_valid_period_id(period_id: &str) -> bool {
-    period_id.len() == 7
-        && period_id.chars().nth(4) == Some('-')
-        && period_id[..4].chars().all(|c| c.is_ascii_digit())
-        && period_id[5..].chars().all(|c| c.is_ascii_digit())
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PeriodTransitionRequest {
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub to_state: PeriodState,
+    pub actor: PeriodActor,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PeriodTransitionResponse {
+    pub period_id: String,
+    pub from_state: PeriodState,
+    pub state: PeriodState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_seal: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PeriodListQuery {
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub fiscal_year: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PeriodStatus {
+    pub period_id: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub state: PeriodState,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PeriodList {
+    pub periods: Vec<PeriodStatus>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/periods/{period_id}/transitions",
+    tag = "ledger",
+    params(("period_id" = String, Path, description = "Period in the entity's fiscal calendar")),
+    request_body = PeriodTransitionRequest,
+    responses(
+        (status = 200, description = "Period moved to the requested state", body = PeriodTransitionResponse),
+        (status = 400, description = "Invalid period id", body = ErrorEnvelope),
+        (status = 403, description = "Actor's role may not make this transition", body = ErrorEnvelope),
+        (status = 409, description = "Transition not allowed from the current state, or close checklist not ready", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn transition_period(
+    State(state): State<AppState>,
+    Path(period_id): Path<String>,
+    ApiJson(req): ApiJson<PeriodTransitionRequest>,
+) -> Result<Json<PeriodTransitionResponse>, ApiError> {
+    let mut periods = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
+    periods
+        .check_transition(
+            &req.tenant_id,
+            &req.legal_entity_id,
+            &req.ledger_book,
+            &period_id,
+            req.to_state,
+            &req.actor,
+        )
+        .map_err(period_error_response)?;
+    let audit_seal = if req.to_state == PeriodState::Closed {
+        Some(seal_hard_close(
+            &state,
+            &req.tenant_id,
+            &req.legal_entity_id,
+            &req.ledger_book,
+            &period_id,
+        )?)
+    } else {
+        None
+    };
+    let from_state = periods
+        .transition(
+            &req.tenant_id,
+            &req.legal_entity_id,
+            &req.ledger_book,
+            &period_id,
+            req.to_state,
+            &req.actor,
+        )
+        .map_err(period_error_response)?;
+    Ok(Json(PeriodTransitionResponse {
+        period_id,
+        from_state,
+        state: req.to_state,
+        audit_seal,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ledger/periods",
+    tag = "ledger",
+    params(PeriodListQuery),
+    responses(
+        (status = 200, description = "Periods of the fiscal year with their lifecycle state", body = PeriodList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_periods(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<PeriodListQuery>,
+) -> Result<Json<PeriodList>, ApiError> {
+    if !(1..=9999).contains(&query.fiscal_year) {
+        return Err(ApiError::bad_request(ErrorCode::InvalidQuery)
+            .with_detail("fiscal_year", query.fiscal_year));
+    }
+    let repo = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
+    let periods = repo
+        .calendar(&query.legal_entity_id)
+        .periods(query.fiscal_year)
+        .map_err(|error| period_error_response(error.into()))?
+        .into_iter()
+        .map(|period| PeriodStatus {
+            state: repo.state(
+                &query.tenant_id,
+                &query.legal_entity_id,
+                &query.ledger_book,
+                &period.period_id,
+            ),
+            period_id: period.period_id,
+            start_date: period.start_date,
+            end_date: period.end_date,
+        })
+        .collect();
+    Ok(Json(PeriodList { periods }))
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDate;
+    use std::collections::HashMap;
+    use std::sync::Arc;
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    use super::{period_id_from_date, InMemoryPeriodRepository, PeriodError};
+    use super::{
+        period_id_from_date, ActorRole, FixedClock, InMemoryPeriodRepository, PeriodActor,
+        PeriodError, PeriodState, PostingAuthority, PostingClass,
+    };
+    use crate::calendar::FiscalCalendar;
 
     struct TempDirGuard {
         path: std::path::PathBuf,
@@ -290,20 +584,44 @@ mod tests {
         }
     }
 
+    fn operational() -> PostingAuthority {
+        PostingAuthority::new(PostingClass::Operational, None)
+    }
+
+    fn close_period(
+        repo: &mut InMemoryPeriodRepository,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        period_id: &str,
+    ) -> Result<(), PeriodError> {
+        let controller = PeriodActor {
+            actor_id: "controller_1".to_string(),
+            role: ActorRole::Controller,
+        };
+        for to in [PeriodState::SoftClosed, PeriodState::Closed] {
+            repo.transition(
+                "tenant_1",
+                legal_entity_id,
+                ledger_book,
+                period_id,
+                to,
+                &controller,
+            )?;
+        }
+        Ok(())
+    }
+
     #[test]
-    fn lock_period_rejects_invalid_period_id() {
+    fn close_rejects_invalid_period_id() {
         let mut repo = InMemoryPeriodRepository::default();
-        let err = repo
-            .lock_period("tenant_1", "US_CO_01", "US_GAAP", "202602")
-            .unwrap_err();
+        let err = close_period(&mut repo, "US_CO_01", "US_GAAP", "202602").unwrap_err();
         assert_eq!(err, PeriodError::InvalidPeriodId("202602".to_string()));
     }
 
     #[test]
     fn locked_period_rejects_posting_date() {
         let mut repo = InMemoryPeriodRepository::default();
-        repo.lock_period("tenant_1", "US_CO_01", "US_GAAP", "2026-02")
-            .unwrap();
+        close_period(&mut repo, "US_CO_01", "US_GAAP", "2026-02").unwrap();
 
         let err = repo
             .ensure_open(
@@ -311,6 +629,7 @@ mod tests {
                 "US_CO_01",
                 "US_GAAP",
                 NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+                operational(),
             )
             .unwrap_err();
         assert_eq!(err, PeriodError::PeriodClosed("2026-02".to_string()));
@@ -324,10 +643,177 @@ mod tests {
             "US_CO_01",
             "US_GAAP",
             NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+            operational(),
         );
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn entity_calendar_resolves_locked_periods() {
+        let mut repo = InMemoryPeriodRepository::default();
+        repo.set_fiscal_calendars(HashMap::from([(
+            "US_CO_01".to_string(),
+            FiscalCalendar::FiscalMonth { start_month: 5 },
+        )]));
+        assert_eq!(
+            close_period(&mut repo, "US_CO_01", "US_GAAP", "2026-02"),
+            Err(PeriodError::InvalidPeriodId("2026-02".to_string()))
+        );
+        close_period(&mut repo, "US_CO_01", "US_GAAP", "FY2026-P10").unwrap();
+
+        let err = repo
+            .ensure_open(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+                operational(),
+            )
+            .unwrap_err();
+        assert_eq!(err, PeriodError::PeriodClosed("FY2026-P10".to_string()));
+        assert!(repo
+            .ensure_open(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+                operational(),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn periods_after_the_as_of_period_start_in_the_future() {
+        let mut repo = InMemoryPeriodRepository::default();
+        repo.set_clock(Arc::new(FixedClock(
+            NaiveDate::from_ymd_opt(2026, 2, 10).unwrap(),
+        )));
+        let march = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+
+        assert_eq!(
+            repo.state("tenant_1", "US_CO_01", "US_GAAP", "2026-02"),
+            PeriodState::Open
+        );
+        assert_eq!(
+            repo.ensure_open("tenant_1", "US_CO_01", "US_GAAP", march, operational()),
+            Err(PeriodError::PostingNotAllowed {
+                period_id: "2026-03".to_string(),
+                state: PeriodState::Future,
+                class: PostingClass::Operational,
+                role: ActorRole::System,
+            })
+        );
+
+        let accountant = PeriodActor {
+            actor_id: "u-1".to_string(),
+            role: ActorRole::Accountant,
+        };
+        assert_eq!(
+            repo.transition(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                "2026-03",
+                PeriodState::Open,
+                &accountant,
+            ),
+            Ok(PeriodState::Future)
+        );
+        assert!(repo
+            .ensure_open("tenant_1", "US_CO_01", "US_GAAP", march, operational())
+            .is_ok());
+    }
+
+    #[test]
+    fn soft_close_admits_only_authorized_adjustments_and_close_entries() {
+        let mut repo = InMemoryPeriodRepository::default();
+        let actor = |role| PeriodActor {
+            actor_id: "u-1".to_string(),
+            role,
+        };
+        let accountant = actor(ActorRole::Accountant);
+        let controller = actor(ActorRole::Controller);
+        let date = NaiveDate::from_ymd_opt(2026, 2, 21).unwrap();
+        let posting = |repo: &InMemoryPeriodRepository, class, actor| {
+            repo.ensure_open(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                date,
+                PostingAuthority::new(class, actor),
+            )
+        };
+
+        repo.transition(
+            "tenant_1",
+            "US_CO_01",
+            "US_GAAP",
+            "2026-02",
+            PeriodState::SoftClosed,
+            &accountant,
+        )
+        .unwrap();
+        assert_eq!(
+            posting(&repo, PostingClass::Operational, Some(&controller)),
+            Err(PeriodError::PostingNotAllowed {
+                period_id: "2026-02".to_string(),
+                state: PeriodState::SoftClosed,
+                class: PostingClass::Operational,
+                role: ActorRole::Controller,
+            })
+        );
+        assert!(posting(&repo, PostingClass::Adjustment, None).is_err());
+        assert!(posting(&repo, PostingClass::Adjustment, Some(&accountant)).is_ok());
+        assert!(posting(&repo, PostingClass::Close, Some(&controller)).is_ok());
+
+        assert_eq!(
+            repo.transition(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                "2026-02",
+                PeriodState::Closed,
+                &accountant,
+            ),
+            Err(PeriodError::TransitionForbidden {
+                period_id: "2026-02".to_string(),
+                from: PeriodState::SoftClosed,
+                to: PeriodState::Closed,
+                role: ActorRole::Accountant,
+            })
+        );
+        for to in [PeriodState::Closed, PeriodState::Archived] {
+            repo.transition(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                "2026-02",
+                to,
+                &controller,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            posting(&repo, PostingClass::Close, Some(&controller)),
+            Err(PeriodError::PeriodClosed("2026-02".to_string()))
+        );
+        assert_eq!(
+            repo.transition(
+                "tenant_1",
+                "US_CO_01",
+                "US_GAAP",
+                "2026-02",
+                PeriodState::Open,
+                &controller,
+            ),
+            Err(PeriodError::InvalidTransition {
+                period_id: "2026-02".to_string(),
+                from: PeriodState::Archived,
+                to: PeriodState::Open,
+            })
+        );
+    }
+
     #[test]
     fn derives_period_id_from_date() {
         let period = period_id_from_date(NaiveDate::from_ymd_opt(2026, 12, 31).unwrap());
@@ -338,8 +824,7 @@ mod tests {
     fn flush_persists_locked_periods_to_disk() {
         let temp_dir = TempDirGuard::new("period-flush");
         let mut repo = InMemoryPeriodRepository::with_persistence_dir(&temp_dir.path).unwrap();
-        repo.lock_period("tenant_1", "US_CO_01", "US_GAAP", "2026-02")
-            .unwrap();
+        close_period(&mut repo, "US_CO_01", "US_GAAP", "2026-02").unwrap();
         repo.flush_persistence().unwrap();
 
         let reloaded = InMemoryPeriodRepository::with_persistence_dir(&temp_dir.path).unwrap();
@@ -349,6 +834,7 @@ mod tests {
                 "US_CO_01",
                 "US_GAAP",
                 NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+                operational(),
             )
             .unwrap_err();
         assert_eq!(err, PeriodError::PeriodClosed("2026-02".to_string()));
@@ -359,10 +845,8 @@ mod tests {
         let temp_dir = TempDirGuard::new("period-restart");
         {
             let mut repo = InMemoryPeriodRepository::with_persistence_dir(&temp_dir.path).unwrap();
-            repo.lock_period("tenant_1", "US_CO_01", "US_GAAP", "2026-01")
-                .unwrap();
-            repo.lock_period("tenant_1", "CA_BC_01", "IFRS", "2026-02")
-                .unwrap();
+            close_period(&mut repo, "US_CO_01", "US_GAAP", "2026-01").unwrap();
+            close_period(&mut repo, "CA_BC_01", "IFRS", "2026-02").unwrap();
             repo.flush_persistence().unwrap();
         }
 
@@ -373,6 +857,7 @@ mod tests {
                 "US_CO_01",
                 "US_GAAP",
                 NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+                operational(),
             )
             .unwrap_err();
         assert_eq!(us_err, PeriodError::PeriodClosed("2026-01".to_string()));
@@ -382,6 +867,7 @@ mod tests {
                 "CA_BC_01",
                 "IFRS",
                 NaiveDate::from_ymd_opt(2026, 2, 2).unwrap(),
+                operational(),
             )
             .unwrap_err();
         assert_eq!(ca_err, PeriodError::PeriodClosed("2026-02".to_string()));
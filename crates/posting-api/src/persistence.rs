@@ -0,0 +1,142 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+enum WriteBehindCommand<T> {
+    Persist(T),
+    Flush(Sender<io::Result<()>>),
+    Shutdown,
+}
+
+pub(crate) struct WriteBehind<T> {
+    tx: Sender<WriteBehindCommand<T>>,
+}
+
+impl<T> Drop for WriteBehind<T> {
+    fn drop(&mut self) {
+        let _ = self.tx.send(WriteBehindCommand::Shutdown);
+    }
+}
+
+impl<T> WriteBehind<T>
+where
+    T: Serialize + Send + 'static,
+{
+    pub(crate) fn new(path: PathBuf, worker_name: &str) -> io::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name(worker_name.to_string())
+            .spawn(move || run_write_behind_worker(path, rx))?;
+        Ok(Self { tx })
+    }
+
+    pub(crate) fn persist(&self, snapshot: T) {
+        let _ = self.tx.send(WriteBehindCommand::Persist(snapshot));
+    }
+
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        self.tx
+            .send(WriteBehindCommand::Flush(ack_tx))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "persistence worker stopped"))?;
+        ack_rx
+            .recv()
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "persistence worker stopped"))?
+    }
+}
+
+fn run_write_behind_worker<T>(path: PathBuf, rx: Receiver<WriteBehindCommand<T>>)
+where
+    T: Serialize,
+{
+    let mut latest_snapshot = None;
+    loop {
+        match rx.recv() {
+            Ok(WriteBehindCommand::Persist(snapshot)) => {
+                latest_snapshot = Some(snapshot);
+            }
+            Ok(WriteBehindCommand::Flush(ack)) => {
+                let _ = ack.send(persist_latest_snapshot(&path, &mut latest_snapshot));
+                continue;
+            }
+            Ok(WriteBehindCommand::Shutdown) => {
+                let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
+                return;
+            }
+            Err(_) => {
+                let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
+                return;
+            }
+        }
+
+        loop {
+            match rx.try_recv() {
+                Ok(WriteBehindCommand::Persist(snapshot)) => {
+                    latest_snapshot = Some(snapshot);
+                }
+                Ok(WriteBehindCommand::Flush(ack)) => {
+                    let _ = ack.send(persist_latest_snapshot(&path, &mut latest_snapshot));
+                }
+                Ok(WriteBehindCommand::Shutdown) => {
+                    let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
+                    return;
+                }
+                Err(TryRecvError::Empty) => {
+                    let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    let _ = persist_latest_snapshot(&path, &mut latest_snapshot);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn persist_latest_snapshot<T>(path: &Path, snapshot: &mut Option<T>) -> io::Result<()>
+where
+    T: Serialize,
+{
+    if let Some(value) = snapshot.take() {
+        persist_snapshot(path, &value)?;
+    }
+    Ok(())
+}
+
+fn persist_snapshot<T>(path: &Path, snapshot: &T) -> io::Result<()>
+where
+    T: Serialize,
+{
+    let encoded = serde_json::to_vec(snapshot).map_err(|error| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to serialize persistence snapshot: {error}"),
+        )
+    })?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, encoded)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+pub(crate) fn load_snapshot_or_default<T>(path: &Path) -> io::Result<T>
+where
+    T: DeserializeOwned + Default,
+{
+    match fs::read(path) {
+        Ok(encoded) => serde_json::from_slice(&encoded).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("failed to deserialize persistence snapshot: {error}"),
+            )
+        }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(error) => Err(error),
+    }
+}
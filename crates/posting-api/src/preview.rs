@@ -0,0 +1,196 @@
+use axum::extract::State;
+use axum::Json;
+use ledger_posting::{validate_balanced, EntrySide, JournalLine, JournalRecord};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiJson, ErrorCode, ErrorEnvelope};
+use crate::{
+    book_requests, complete_journal, draft_journal, ensure_supported_event_type, AppState,
+    PostEventRequest,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PostingPreviewResponse {
+    pub balanced: bool,
+    pub journals: Vec<PreviewJournal>,
+    pub warnings: Vec<PreviewWarning>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewJournal {
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub accounting_date: String,
+    pub book_policy_id: String,
+    pub policy_version: String,
+    pub fx_rate_set_id: String,
+    pub ruleset_version: String,
+    #[schema(value_type = Vec<Object>)]
+    pub lines: Vec<JournalLine>,
+    pub balance: BalanceCheck,
+}
+
+impl PreviewJournal {
+    fn from_record(record: &JournalRecord) -> Self {
+        Self {
+            legal_entity_id: record.header.legal_entity_id.clone(),
+            ledger_book: record.header.ledger_book.clone(),
+            accounting_date: record.header.accounting_date.to_string(),
+            book_policy_id: record.header.book_policy_id.clone(),
+            policy_version: record.header.policy_version.clone(),
+            fx_rate_set_id: record.header.fx_rate_set_id.clone(),
+            ruleset_version: record.header.ruleset_version.clone(),
+            lines: record.lines.clone(),
+            balance: BalanceCheck::of(&record.lines),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BalanceCheck {
+    pub debit_total_minor: i64,
+    pub credit_total_minor: i64,
+    pub base_debit_total_minor: i64,
+    pub base_credit_total_minor: i64,
+    pub balanced: bool,
+}
+
+impl BalanceCheck {
+    fn of(lines: &[JournalLine]) -> Self {
+        let total = |side: EntrySide, base: bool| {
+            lines
+                .iter()
+                .filter(|line| line.entry_side == side)
+                .map(|line| {
+                    if base {
+                        line.base_amount_minor
+                    } else {
+                        line.amount_minor
+                    }
+                })
+                .sum()
+        };
+        Self {
+            debit_total_minor: total(EntrySide::Debit, false),
+            credit_total_minor: total(EntrySide::Credit, false),
+            base_debit_total_minor: total(EntrySide::Debit, true),
+            base_credit_total_minor: total(EntrySide::Credit, true),
+            balanced: validate_balanced(lines).is_ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewWarningCode {
+    JournalUnbalanced,
+    ZeroAmountLine,
+    SourceEventAlreadyPosted,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewWarning {
+    pub code: PreviewWarningCode,
+    pub message: String,
+    pub ledger_book: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_number: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_id: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/posting/preview",
+    tag = "posting",
+    request_body = PostEventRequest,
+    responses(
+        (status = 200, description = "Journals the event would post, with balance checks and warnings", body = PostingPreviewResponse),
+        (status = 400, description = "Event fails a posting validation or rule derivation", body = ErrorEnvelope),
+        (status = 409, description = "Legal hold or subledger conflict", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn preview_posting(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<PostEventRequest>,
+) -> Result<Json<PostingPreviewResponse>, ApiError> {
+    ensure_supported_event_type(&state, &req)?;
+    let book_reqs = if req.ledger_book.is_empty() {
+        book_requests(&state, &req)?
+    } else {
+        vec![req.clone()]
+    };
+
+    let mut journals = Vec::new();
+    for book_req in &book_reqs {
+        let draft = draft_journal(&state, book_req, Uuid::nil())?;
+        if validate_balanced(&draft.record.lines).is_err() {
+            journals.push(PreviewJournal::from_record(&draft.record));
+            continue;
+        }
+        let prepared = complete_journal(&state, book_req, draft)?;
+        journals.push(PreviewJournal::from_record(&prepared.record));
+        journals.extend(
+            prepared
+                .counterpart
+                .as_ref()
+                .map(PreviewJournal::from_record),
+        );
+    }
+
+    let mut warnings = Vec::new();
+    for journal in &journals {
+        if !journal.balance.balanced {
+            warnings.push(PreviewWarning {
+                code: PreviewWarningCode::JournalUnbalanced,
+                message: "debits and credits differ; posting would be rejected".to_string(),
+                ledger_book: journal.ledger_book.clone(),
+                line_number: None,
+                journal_id: None,
+            });
+        }
+        for line in journal.lines.iter().filter(|line| line.amount_minor == 0) {
+            warnings.push(PreviewWarning {
+                code: PreviewWarningCode::ZeroAmountLine,
+                message: format!("line to {} has a zero amount", line.account_id),
+                ledger_book: journal.ledger_book.clone(),
+                line_number: Some(line.line_number),
+                journal_id: None,
+            });
+        }
+    }
+    let posted = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?
+        .all();
+    for record in posted.iter().filter(|record| {
+        record.header.tenant_id == req.tenant_id
+            && record.header.legal_entity_id == req.legal_entity_id
+            && record
+                .header
+                .source_event_ids
+                .contains(&req.source_event_id)
+    }) {
+        warnings.push(PreviewWarning {
+            code: PreviewWarningCode::SourceEventAlreadyPosted,
+            message: format!(
+                "source event {} is already posted to {}",
+                req.source_event_id, record.header.ledger_book
+            ),
+            ledger_book: record.header.ledger_book.clone(),
+            line_number: None,
+            journal_id: Some(record.header.journal_id.to_string()),
+        });
+    }
+
+    Ok(Json(PostingPreviewResponse {
+        balanced: journals.iter().all(|journal| journal.balance.balanced),
+        journals,
+        warnings,
+    }))
+}
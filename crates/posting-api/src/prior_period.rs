@@ -0,0 +1,474 @@
+use axum::extract::State;
+use axum::Json;
+use chrono::{NaiveDate, Utc};
+use ledger_posting::{JournalHeader, JournalRecord, JournalStatus, PriorPeriodAdjustment};
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::change_feed::LedgerChangeType;
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::period::{PeriodActor, PeriodError, PostingAuthority, PostingClass};
+use crate::year_end::{net_balances, AccountBalance};
+use crate::{
+    derive_lines_from_post_lines, deterministic_journal_id, ledger_error_response,
+    period_error_response, rule_engine_error_response, validate_location_boundary, AppState,
+    PostLine, Provenance,
+};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PriorPeriodError {
+    #[error("prior-period adjustment requires a reason code")]
+    MissingReasonCode,
+    #[error("period {affected_period_id} is not before the posting period {period_id}")]
+    AffectedPeriodNotPrior {
+        affected_period_id: String,
+        period_id: String,
+    },
+}
+
+fn prior_period_error_response(error: PriorPeriodError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        PriorPeriodError::MissingReasonCode => ApiError::bad_request(ErrorCode::MissingReasonCode),
+        PriorPeriodError::AffectedPeriodNotPrior {
+            affected_period_id,
+            period_id,
+        } => ApiError::bad_request(ErrorCode::AffectedPeriodNotPrior)
+            .with_detail("affected_period_id", affected_period_id)
+            .with_detail("period_id", period_id),
+    };
+    api_error.with_message(message)
+}
+
+/// Whether a period report shows prior-period adjustments where they were posted or in the
+/// period they correct.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReportingBasis {
+    #[default]
+    AsOriginallyReported,
+    AsRestated,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PriorPeriodAdjustmentRequest {
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub accounting_date: String,
+    pub affected_period_id: String,
+    pub reason_code: String,
+    pub material: bool,
+    pub source_event_id: String,
+    pub posting_run_id: String,
+    #[serde(default)]
+    pub location_id: Option<String>,
+    pub lines: Vec<PostLine>,
+    pub provenance: Provenance,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PriorPeriodAdjustmentResponse {
+    pub journal_id: String,
+    pub period_id: String,
+    pub affected_period_id: String,
+    pub status: String,
+    pub audit_seal: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PeriodResultsQuery {
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub period_id: String,
+    #[serde(default)]
+    #[param(value_type = Option<String>, example = "AS_RESTATED")]
+    pub basis: ReportingBasis,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PeriodAdjustmentLineage {
+    pub journal_id: String,
+    pub accounting_date: NaiveDate,
+    pub reason_code: String,
+    pub material: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PeriodResultsResponse {
+    pub period_id: String,
+    pub basis: ReportingBasis,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub balances: Vec<AccountBalance>,
+    /// Later adjustments that correct this period, whichever basis is shown.
+    pub corrections: Vec<PeriodAdjustmentLineage>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/prior-period-adjustments",
+    tag = "ledger",
+    request_body = PriorPeriodAdjustmentRequest,
+    responses(
+        (status = 200, description = "Adjustment posted in the open period with lineage to the corrected period", body = PriorPeriodAdjustmentResponse),
+        (status = 400, description = "Invalid adjustment or affected period", body = ErrorEnvelope),
+        (status = 409, description = "Posting period closed, on hold, or journal already posted", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn post_prior_period_adjustment(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<PriorPeriodAdjustmentRequest>,
+) -> Result<Json<PriorPeriodAdjustmentResponse>, ApiError> {
+    if req.lines.is_empty() {
+        return Err(ApiError::bad_request(ErrorCode::MissingAdjustmentLines));
+    }
+    if req.reason_code.trim().is_empty() {
+        return Err(prior_period_error_response(
+            PriorPeriodError::MissingReasonCode,
+        ));
+    }
+    let accounting_date = NaiveDate::parse_from_str(&req.accounting_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidAccountingDate))?;
+    if let Some(location_id) = req.location_id.as_deref() {
+        validate_location_boundary(&state, &req.legal_entity_id, location_id)?;
+    }
+    state.validate_posting_policy(&req.provenance, &req.ledger_book)?;
+    state.validate_legal_hold(
+        &req.tenant_id,
+        &req.legal_entity_id,
+        &req.ledger_book,
+        accounting_date,
+    )?;
+    let period_id = {
+        let periods = state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
+        let calendar = periods.calendar(&req.legal_entity_id);
+        let affected = calendar.period(&req.affected_period_id).ok_or_else(|| {
+            period_error_response(PeriodError::InvalidPeriodId(req.affected_period_id.clone()))
+        })?;
+        let posting_period = calendar
+            .period_for(accounting_date)
+            .map_err(|error| period_error_response(error.into()))?;
+        if affected.end_date >= posting_period.start_date {
+            return Err(prior_period_error_response(
+                PriorPeriodError::AffectedPeriodNotPrior {
+                    affected_period_id: req.affected_period_id,
+                    period_id: posting_period.period_id,
+                },
+            ));
+        }
+        periods
+            .ensure_open(
+                &req.tenant_id,
+                &req.legal_entity_id,
+                &req.ledger_book,
+                accounting_date,
+                PostingAuthority::new(PostingClass::Adjustment, req.actor.as_ref()),
+            )
+            .map_err(period_error_response)?;
+        posting_period.period_id
+    };
+
+    let mut lines = derive_lines_from_post_lines(&req.lines).map_err(rule_engine_error_response)?;
+    state.translate_to_base(
+        &req.provenance,
+        &req.legal_entity_id,
+        accounting_date,
+        &mut lines,
+    )?;
+    state.validate_chart_of_accounts(&req.legal_entity_id, &lines)?;
+    let journal_id = deterministic_journal_id(
+        &format!("prior-period:{}", req.source_event_id),
+        &payload_hash(&json!({
+            "affected_period_id": &req.affected_period_id,
+            "reason_code": &req.reason_code,
+            "accounting_date": &req.accounting_date,
+            "lines": &req.lines,
+            "posting_run_id": &req.posting_run_id,
+        })),
+    );
+    let record = JournalRecord {
+        header: JournalHeader {
+            journal_id,
+            journal_number: format!("PPA-{}", &journal_id.to_string()[..8]),
+            status: JournalStatus::Posted,
+            tenant_id: req.tenant_id.clone(),
+            legal_entity_id: req.legal_entity_id.clone(),
+            ledger_book: req.ledger_book.clone(),
+            accounting_date,
+            posted_at: Utc::now(),
+            source_event_ids: vec![req.source_event_id.clone()],
+            posting_run_id: req.posting_run_id.clone(),
+            book_policy_id: req.provenance.book_policy_id.clone(),
+            policy_version: req.provenance.policy_version.clone(),
+            fx_rate_set_id: req.provenance.fx_rate_set_id.clone(),
+            ruleset_version: req.provenance.ruleset_version.clone(),
+            workflow_id: req.provenance.workflow_id.clone(),
+            estimate_version: None,
+            revenue_designation: None,
+            prior_period_adjustment: Some(PriorPeriodAdjustment {
+                affected_period_id: req.affected_period_id.clone(),
+                reason_code: req.reason_code.clone(),
+                material: req.material,
+            }),
+            book_group_id: None,
+            trace_context: None,
+        },
+        lines,
+    };
+    let header = record.header.clone();
+    {
+        let mut journals = state
+            .journals
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+        journals
+            .insert_posted(record)
+            .map_err(ledger_error_response)?;
+        state.record_change(LedgerChangeType::JournalPosted, &header, None)?;
+    }
+
+    let audit_seal = state.append_audit_seal(
+        "journal.prior_period_adjusted",
+        std::slice::from_ref(&req.legal_entity_id),
+        &json!({
+            "journal_id": journal_id,
+            "period_id": &period_id,
+            "affected_period_id": &req.affected_period_id,
+            "reason_code": &req.reason_code,
+            "material": req.material,
+        }),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+
+    Ok(Json(PriorPeriodAdjustmentResponse {
+        journal_id: journal_id.to_string(),
+        period_id,
+        affected_period_id: req.affected_period_id,
+        status: "POSTED".to_string(),
+        audit_seal,
+    }))
+}
+
+/// Whether `record` counts toward `period_id` on `basis`. As restated, material prior-period
+/// adjustments move to the period they correct; immaterial ones stay where they were posted.
+fn in_period_on_basis(
+    record: &JournalRecord,
+    period_id: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    basis: ReportingBasis,
+) -> bool {
+    let dated_in_period =
+        start_date <= record.header.accounting_date && record.header.accounting_date <= end_date;
+    match (basis, &record.header.prior_period_adjustment) {
+        (ReportingBasis::AsRestated, Some(lineage)) if lineage.material => {
+            lineage.affected_period_id == period_id
+        }
+        _ => dated_in_period,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ledger/period-results",
+    tag = "ledger",
+    params(PeriodResultsQuery),
+    responses(
+        (status = 200, description = "Account balances for the period on the requested basis", body = PeriodResultsResponse),
+        (status = 400, description = "Invalid query or period id", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_period_results(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<PeriodResultsQuery>,
+) -> Result<Json<PeriodResultsResponse>, ApiError> {
+    let period = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+        .calendar(&query.legal_entity_id)
+        .period(&query.period_id)
+        .ok_or_else(|| {
+            period_error_response(PeriodError::InvalidPeriodId(query.period_id.clone()))
+        })?;
+    let journals = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?
+        .all();
+    let mut scoped = journals
+        .iter()
+        .filter(|record| {
+            record.header.status == JournalStatus::Posted
+                && record.header.tenant_id == query.tenant_id
+                && record.header.legal_entity_id == query.legal_entity_id
+                && record.header.ledger_book == query.ledger_book
+        })
+        .collect::<Vec<_>>();
+    scoped.sort_by_key(|record| (record.header.accounting_date, record.header.journal_id));
+
+    let balances = net_balances(
+        scoped.iter().copied().filter(|record| {
+            in_period_on_basis(
+                record,
+                &period.period_id,
+                period.start_date,
+                period.end_date,
+                query.basis,
+            )
+        }),
+        |_| true,
+    );
+    let corrections = scoped
+        .iter()
+        .filter_map(|record| {
+            let lineage = record.header.prior_period_adjustment.as_ref()?;
+            (lineage.affected_period_id == period.period_id).then(|| PeriodAdjustmentLineage {
+                journal_id: record.header.journal_id.to_string(),
+                accounting_date: record.header.accounting_date,
+                reason_code: lineage.reason_code.clone(),
+                material: lineage.material,
+            })
+        })
+        .collect();
+
+    Ok(Json(PeriodResultsResponse {
+        period_id: period.period_id,
+        basis: query.basis,
+        start_date: period.start_date,
+        end_date: period.end_date,
+        balances,
+        corrections,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use chrono::{NaiveDate, Utc};
+    use ledger_posting::{JournalHeader, JournalRecord, JournalStatus, PriorPeriodAdjustment};
+    use uuid::Uuid;
+
+    use super::{
+        in_period_on_basis, prior_period_error_response, PriorPeriodError, ReportingBasis,
+    };
+    use crate::error::ErrorCode;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn journal(accounting_date: NaiveDate, adjusts: Option<(&str, bool)>) -> JournalRecord {
+        JournalRecord {
+            header: JournalHeader {
+                journal_id: Uuid::new_v4(),
+                journal_number: "PPA-test".to_string(),
+                status: JournalStatus::Posted,
+                tenant_id: "tenant_1".to_string(),
+                legal_entity_id: "US_CO_01".to_string(),
+                ledger_book: "US_GAAP".to_string(),
+                accounting_date,
+                posted_at: Utc::now(),
+                source_event_ids: vec!["evt_1".to_string()],
+                posting_run_id: "run_1".to_string(),
+                book_policy_id: "policy_us_gaap".to_string(),
+                policy_version: "1.0.0".to_string(),
+                fx_rate_set_id: "fx_1".to_string(),
+                ruleset_version: "v1".to_string(),
+                workflow_id: None,
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: adjusts.map(|(affected_period_id, material)| {
+                    PriorPeriodAdjustment {
+                        affected_period_id: affected_period_id.to_string(),
+                        reason_code: "ERROR_CORRECTION".to_string(),
+                        material,
+                    }
+                }),
+                book_group_id: None,
+                trace_context: None,
+            },
+            lines: Vec::new(),
+        }
+    }
+
+    fn in_february(record: &JournalRecord, basis: ReportingBasis) -> bool {
+        in_period_on_basis(record, "2026-02", date(2, 1), date(2, 28), basis)
+    }
+
+    #[test]
+    fn period_membership_includes_both_boundary_dates() {
+        for basis in [
+            ReportingBasis::AsOriginallyReported,
+            ReportingBasis::AsRestated,
+        ] {
+            assert!(!in_february(&journal(date(1, 31), None), basis));
+            assert!(in_february(&journal(date(2, 1), None), basis));
+            assert!(in_february(&journal(date(2, 28), None), basis));
+            assert!(!in_february(&journal(date(3, 1), None), basis));
+        }
+    }
+
+    #[test]
+    fn only_material_adjustments_move_to_the_corrected_period_when_restated() {
+        let material = journal(date(4, 15), Some(("2026-02", true)));
+        assert!(!in_february(
+            &material,
+            ReportingBasis::AsOriginallyReported
+        ));
+        assert!(in_february(&material, ReportingBasis::AsRestated));
+        assert!(!in_period_on_basis(
+            &material,
+            "2026-04",
+            date(4, 1),
+            date(4, 30),
+            ReportingBasis::AsRestated,
+        ));
+
+        let immaterial = journal(date(4, 15), Some(("2026-02", false)));
+        assert!(!in_february(&immaterial, ReportingBasis::AsRestated));
+        assert!(in_period_on_basis(
+            &immaterial,
+            "2026-04",
+            date(4, 1),
+            date(4, 30),
+            ReportingBasis::AsRestated,
+        ));
+
+        let other_period = journal(date(2, 10), Some(("2026-01", true)));
+        assert!(in_february(
+            &other_period,
+            ReportingBasis::AsOriginallyReported
+        ));
+        assert!(!in_february(&other_period, ReportingBasis::AsRestated));
+    }
+
+    #[test]
+    fn adjustment_errors_are_bad_requests() {
+        let missing = prior_period_error_response(PriorPeriodError::MissingReasonCode);
+        assert_eq!(missing.status, StatusCode::BAD_REQUEST);
+        assert_eq!(missing.code, ErrorCode::MissingReasonCode);
+
+        let not_prior = prior_period_error_response(PriorPeriodError::AffectedPeriodNotPrior {
+            affected_period_id: "2026-03".to_string(),
+            period_id: "2026-03".to_string(),
+        });
+        assert_eq!(not_prior.status, StatusCode::BAD_REQUEST);
+        assert_eq!(not_prior.code, ErrorCode::AffectedPeriodNotPrior);
+        assert_eq!(not_prior.details["affected_period_id"], "2026-03");
+    }
+}
@@ -0,0 +1,1447 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{Duration, NaiveDate};
+use ledger_posting::{EntrySide, JournalHeader, JournalLine, JournalRecord, JournalStatus};
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::calendar::FiscalCalendars;
+use crate::change_feed::LedgerChangeType;
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::period::{PeriodActor, PostingAuthority, PostingClass};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::rule_engine::RuleSetRegistry;
+use crate::{
+    deterministic_journal_id, ledger_error_response, period_error_response,
+    rule_engine_error_response, signed_amount, AppState, RevRecRollforwardResponse,
+};
+
+const REVREC_SCHEDULE_STORE_FILENAME: &str = "revrec_schedule_store.json";
+/// Rule set account roles for the credit side of recognition and cancellation journals.
+pub(crate) const REVENUE_ROLE: &str = "revenue";
+const CANCELLATION_REFUNDS_ROLE: &str = "reservation_refunds_payable";
+const MOVEMENT_DIMENSION: &str = "revrec_movement";
+const MOVEMENT_RECOGNIZED: &str = "recognized";
+const MOVEMENT_RELEASED: &str = "released";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduleStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ScheduleKind {
+    #[default]
+    Reservation,
+    SeasonPass,
+}
+
+impl ScheduleKind {
+    fn reference_dimension(self) -> &'static str {
+        match self {
+            Self::Reservation => "reservation_id",
+            Self::SeasonPass => "pass_id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RecognitionSchedule {
+    pub schedule_id: String,
+    #[serde(default)]
+    pub kind: ScheduleKind,
+    #[serde(alias = "reservation_id")]
+    pub reference_id: String,
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub location_id: String,
+    pub source_journal_id: String,
+    pub deferred_account_id: String,
+    pub currency: String,
+    pub base_currency: String,
+    pub total_minor: i64,
+    pub base_total_minor: i64,
+    pub arrival_date: NaiveDate,
+    pub departure_date: NaiveDate,
+    pub status: ScheduleStatus,
+    pub book_policy_id: String,
+    pub policy_version: String,
+    pub fx_rate_set_id: String,
+    pub ruleset_version: String,
+    pub entries: Vec<ScheduleEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancellation_journal_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleEntry {
+    pub recognition_date: NaiveDate,
+    pub amount_minor: i64,
+    pub base_amount_minor: i64,
+    pub journal_id: Option<String>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RevRecError {
+    #[error("reservation {reservation_id} has invalid stay dates")]
+    InvalidStayDates { reservation_id: String },
+    #[error("recognition schedule {0} not found")]
+    ScheduleNotFound(String),
+    #[error("recognition schedule {0} is not active")]
+    ScheduleNotActive(String),
+    #[error(
+        "forfeited amount {forfeited_minor} exceeds remaining deferred balance {remaining_minor}"
+    )]
+    InvalidForfeitAmount {
+        forfeited_minor: i64,
+        remaining_minor: i64,
+    },
+}
+
+impl RecognitionSchedule {
+    pub fn remaining_minor(&self) -> i64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.journal_id.is_none())
+            .map(|entry| entry.amount_minor)
+            .sum()
+    }
+
+    fn remaining_base_minor(&self) -> i64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.journal_id.is_none())
+            .map(|entry| entry.base_amount_minor)
+            .sum()
+    }
+
+    fn replan(
+        &mut self,
+        arrival_date: NaiveDate,
+        departure_date: NaiveDate,
+    ) -> Result<(), RevRecError> {
+        if self.status != ScheduleStatus::Active {
+            return Err(RevRecError::ScheduleNotActive(self.schedule_id.clone()));
+        }
+        let invalid = || RevRecError::InvalidStayDates {
+            reservation_id: self.reference_id.clone(),
+        };
+        if departure_date <= arrival_date {
+            return Err(invalid());
+        }
+        let first_open_night = self
+            .entries
+            .iter()
+            .filter(|entry| entry.journal_id.is_some())
+            .map(|entry| entry.recognition_date + Duration::days(1))
+            .max()
+            .map_or(arrival_date, |date| date.max(arrival_date));
+        if first_open_night >= departure_date {
+            return Err(invalid());
+        }
+
+        let remaining = self.remaining_minor();
+        let remaining_base = self.remaining_base_minor();
+        self.entries.retain(|entry| entry.journal_id.is_some());
+        self.entries.extend(plan_nights(
+            first_open_night,
+            departure_date,
+            remaining,
+            remaining_base,
+        ));
+        self.arrival_date = arrival_date;
+        self.departure_date = departure_date;
+        Ok(())
+    }
+}
+
+pub(crate) fn schedule_from_capture(
+    record: &JournalRecord,
+    location_id: &str,
+    payload: &Value,
+) -> Result<Option<RecognitionSchedule>, RevRecError> {
+    let header = &record.header;
+    let reservation_id = payload
+        .pointer("/reservation_id")
+        .and_then(Value::as_str)
+        .unwrap_or_else(|| header.source_event_ids[0].as_str())
+        .to_string();
+    let arrival = stay_date(payload, &["/arrival_date", "/stay/arrival_date"]);
+    let departure = stay_date(payload, &["/departure_date", "/stay/departure_date"]);
+    let (arrival, departure) = match (arrival, departure) {
+        (None, None) => return Ok(None),
+        (Some(Some(arrival)), Some(Some(departure))) if arrival < departure => (arrival, departure),
+        _ => return Err(RevRecError::InvalidStayDates { reservation_id }),
+    };
+    Ok(schedule_over(
+        record,
+        location_id,
+        ScheduleKind::Reservation,
+        reservation_id,
+        arrival,
+        departure,
+    ))
+}
+
+pub(crate) fn schedule_over(
+    record: &JournalRecord,
+    location_id: &str,
+    kind: ScheduleKind,
+    reference_id: String,
+    arrival: NaiveDate,
+    departure: NaiveDate,
+) -> Option<RecognitionSchedule> {
+    let header = &record.header;
+    let deferred = record.lines.iter().find(|line| {
+        line.entry_side == EntrySide::Credit && line.account_id.contains("DEFERRED")
+    })?;
+
+    Some(RecognitionSchedule {
+        schedule_id: header.journal_id.to_string(),
+        kind,
+        reference_id,
+        tenant_id: header.tenant_id.clone(),
+        legal_entity_id: header.legal_entity_id.clone(),
+        ledger_book: header.ledger_book.clone(),
+        location_id: location_id.to_string(),
+        source_journal_id: header.journal_id.to_string(),
+        deferred_account_id: deferred.account_id.clone(),
+        currency: deferred.currency.clone(),
+        base_currency: deferred.base_currency.clone(),
+        total_minor: deferred.amount_minor,
+        base_total_minor: deferred.base_amount_minor,
+        arrival_date: arrival,
+        departure_date: departure,
+        status: ScheduleStatus::Active,
+        book_policy_id: header.book_policy_id.clone(),
+        policy_version: header.policy_version.clone(),
+        fx_rate_set_id: header.fx_rate_set_id.clone(),
+        ruleset_version: header.ruleset_version.clone(),
+        entries: plan_nights(
+            arrival,
+            departure,
+            deferred.amount_minor,
+            deferred.base_amount_minor,
+        ),
+        cancellation_journal_id: None,
+    })
+}
+
+fn stay_date(payload: &Value, pointers: &[&str]) -> Option<Option<NaiveDate>> {
+    pointers
+        .iter()
+        .find_map(|pointer| payload.pointer(pointer).filter(|value| !value.is_null()))
+        .map(|value| {
+            value
+                .as_str()
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        })
+}
+
+fn plan_nights(
+    first_night: NaiveDate,
+    departure_date: NaiveDate,
+    amount_minor: i64,
+    base_amount_minor: i64,
+) -> Vec<ScheduleEntry> {
+    let nights = (departure_date - first_night).num_days().max(1) as usize;
+    split_evenly(amount_minor, nights)
+        .into_iter()
+        .zip(split_evenly(base_amount_minor, nights))
+        .enumerate()
+        .map(|(night, (amount_minor, base_amount_minor))| ScheduleEntry {
+            recognition_date: first_night + Duration::days(night as i64),
+            amount_minor,
+            base_amount_minor,
+            journal_id: None,
+        })
+        .collect()
+}
+
+fn split_evenly(total: i64, parts: usize) -> Vec<i64> {
+    let count = parts as i64;
+    let (share, remainder) = (total.div_euclid(count), total.rem_euclid(count));
+    (0..count)
+        .map(|part| share + i64::from(part < remainder))
+        .collect()
+}
+
+#[derive(Default)]
+pub struct InMemoryRevRecScheduleRepository {
+    schedules: BTreeMap<String, RecognitionSchedule>,
+    persistence: Option<Arc<WriteBehind<BTreeMap<String, RecognitionSchedule>>>>,
+}
+
+impl InMemoryRevRecScheduleRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(REVREC_SCHEDULE_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "revrec-write-behind")?);
+        Ok(Self {
+            schedules: loaded,
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self, schedule_id: &str) -> Option<&RecognitionSchedule> {
+        self.schedules.get(schedule_id)
+    }
+
+    pub fn all(&self) -> Vec<RecognitionSchedule> {
+        self.schedules.values().cloned().collect()
+    }
+
+    /// Whether any schedule was created by, recognized through or cancelled with the journal.
+    pub(crate) fn references_journal(&self, journal_id: &str) -> bool {
+        self.schedules.values().any(|schedule| {
+            schedule.source_journal_id == journal_id
+                || schedule.cancellation_journal_id.as_deref() == Some(journal_id)
+                || schedule
+                    .entries
+                    .iter()
+                    .any(|entry| entry.journal_id.as_deref() == Some(journal_id))
+        })
+    }
+
+    pub(crate) fn insert(&mut self, schedules: Vec<RecognitionSchedule>) {
+        if schedules.is_empty() {
+            return;
+        }
+        for schedule in schedules {
+            self.schedules
+                .entry(schedule.schedule_id.clone())
+                .or_insert(schedule);
+        }
+        self.persist();
+    }
+
+    fn update<T>(
+        &mut self,
+        schedule_id: &str,
+        apply: impl FnOnce(&mut RecognitionSchedule) -> Result<T, RevRecError>,
+    ) -> Result<T, RevRecError> {
+        let schedule = self
+            .schedules
+            .get_mut(schedule_id)
+            .ok_or_else(|| RevRecError::ScheduleNotFound(schedule_id.to_string()))?;
+        let mut updated = schedule.clone();
+        let result = apply(&mut updated)?;
+        *schedule = updated;
+        self.persist();
+        Ok(result)
+    }
+
+    fn persist(&self) {
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(self.schedules.clone());
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RecognitionBatchKey {
+    tenant_id: String,
+    legal_entity_id: String,
+    ledger_book: String,
+    accounting_date: NaiveDate,
+    book_policy_id: String,
+    policy_version: String,
+    fx_rate_set_id: String,
+    ruleset_version: String,
+}
+
+struct DueEntry {
+    schedule_id: String,
+    entry_index: usize,
+    reference_dimension: &'static str,
+    reference_id: String,
+    deferred_account_id: String,
+    currency: String,
+    base_currency: String,
+    amount_minor: i64,
+    base_amount_minor: i64,
+}
+
+fn due_entries(
+    schedules: &BTreeMap<String, RecognitionSchedule>,
+    req: &RecognitionRunRequest,
+    through_date: NaiveDate,
+) -> BTreeMap<RecognitionBatchKey, Vec<DueEntry>> {
+    let mut batches = BTreeMap::<_, Vec<_>>::new();
+    for schedule in schedules.values() {
+        if schedule.status != ScheduleStatus::Active
+            || schedule.tenant_id != req.tenant_id
+            || req
+                .legal_entity_id
+                .as_ref()
+                .is_some_and(|legal_entity_id| *legal_entity_id != schedule.legal_entity_id)
+            || req
+                .ledger_book
+                .as_ref()
+                .is_some_and(|ledger_book| *ledger_book != schedule.ledger_book)
+        {
+            continue;
+        }
+        for (entry_index, entry) in schedule.entries.iter().enumerate() {
+            if entry.journal_id.is_some() || entry.recognition_date > through_date {
+                continue;
+            }
+            let key = RecognitionBatchKey {
+                tenant_id: schedule.tenant_id.clone(),
+                legal_entity_id: schedule.legal_entity_id.clone(),
+                ledger_book: schedule.ledger_book.clone(),
+                accounting_date: entry.recognition_date,
+                book_policy_id: schedule.book_policy_id.clone(),
+                policy_version: schedule.policy_version.clone(),
+                fx_rate_set_id: schedule.fx_rate_set_id.clone(),
+                ruleset_version: schedule.ruleset_version.clone(),
+            };
+            batches.entry(key).or_default().push(DueEntry {
+                schedule_id: schedule.schedule_id.clone(),
+                entry_index,
+                reference_dimension: schedule.kind.reference_dimension(),
+                reference_id: schedule.reference_id.clone(),
+                deferred_account_id: schedule.deferred_account_id.clone(),
+                currency: schedule.currency.clone(),
+                base_currency: schedule.base_currency.clone(),
+                amount_minor: entry.amount_minor,
+                base_amount_minor: entry.base_amount_minor,
+            });
+        }
+    }
+    batches
+}
+
+fn recognition_record(
+    key: &RecognitionBatchKey,
+    entries: &[DueEntry],
+    revenue_account: &str,
+    posting_run_id: &str,
+) -> JournalRecord {
+    let journal_id = deterministic_journal_id(
+        &format!(
+            "revrec:{}:{}:{}:{}",
+            key.tenant_id, key.legal_entity_id, key.ledger_book, key.accounting_date
+        ),
+        &payload_hash(&json!(entries
+            .iter()
+            .map(|entry| json!([entry.schedule_id, entry.entry_index, entry.amount_minor]))
+            .collect::<Vec<_>>())),
+    );
+    let mut lines = Vec::with_capacity(entries.len() * 2);
+    for entry in entries {
+        let dimensions = BTreeMap::from([
+            (
+                MOVEMENT_DIMENSION.to_string(),
+                MOVEMENT_RECOGNIZED.to_string(),
+            ),
+            (
+                entry.reference_dimension.to_string(),
+                entry.reference_id.clone(),
+            ),
+        ]);
+        for (account_id, entry_side) in [
+            (entry.deferred_account_id.clone(), EntrySide::Debit),
+            (revenue_account.to_string(), EntrySide::Credit),
+        ] {
+            lines.push(JournalLine {
+                line_number: lines.len() as u32 + 1,
+                account_id,
+                entry_side,
+                amount_minor: entry.amount_minor,
+                currency: entry.currency.clone(),
+                base_amount_minor: entry.base_amount_minor,
+                base_currency: entry.base_currency.clone(),
+                dimensions: dimensions.clone(),
+                fx_rate: None,
+            });
+        }
+    }
+    JournalRecord {
+        header: JournalHeader {
+            journal_id,
+            journal_number: format!("RR-{}", &journal_id.to_string()[..8]),
+            status: JournalStatus::Posted,
+            tenant_id: key.tenant_id.clone(),
+            legal_entity_id: key.legal_entity_id.clone(),
+            ledger_book: key.ledger_book.clone(),
+            accounting_date: key.accounting_date,
+            posted_at: chrono::Utc::now(),
+            source_event_ids: entries
+                .iter()
+                .map(|entry| format!("revrec:{}:{}", entry.schedule_id, key.accounting_date))
+                .collect(),
+            posting_run_id: posting_run_id.to_string(),
+            book_policy_id: key.book_policy_id.clone(),
+            policy_version: key.policy_version.clone(),
+            fx_rate_set_id: key.fx_rate_set_id.clone(),
+            ruleset_version: key.ruleset_version.clone(),
+            workflow_id: None,
+            estimate_version: None,
+            revenue_designation: None,
+            prior_period_adjustment: None,
+            book_group_id: None,
+            trace_context: None,
+        },
+        lines,
+    }
+}
+
+fn cancellation_record(
+    schedule: &RecognitionSchedule,
+    cancellation_date: NaiveDate,
+    forfeited_minor: i64,
+    (revenue_account, refunds_account): (&str, &str),
+    posting_run_id: &str,
+) -> JournalRecord {
+    let remaining = schedule.remaining_minor();
+    let remaining_base = schedule.remaining_base_minor();
+    let forfeited_base = if remaining == 0 {
+        0
+    } else {
+        (i128::from(remaining_base) * i128::from(forfeited_minor) / i128::from(remaining)) as i64
+    };
+    let journal_id = deterministic_journal_id(
+        &format!("revrec-cancel:{}", schedule.schedule_id),
+        &payload_hash(&json!({
+            "cancellation_date": cancellation_date,
+            "forfeited_minor": forfeited_minor,
+            "remaining_minor": remaining
+        })),
+    );
+
+    let mut lines = Vec::new();
+    for (movement, amount_minor, base_amount_minor, credit_account) in [
+        (
+            MOVEMENT_RECOGNIZED,
+            forfeited_minor,
+            forfeited_base,
+            revenue_account,
+        ),
+        (
+            MOVEMENT_RELEASED,
+            remaining - forfeited_minor,
+            remaining_base - forfeited_base,
+            refunds_account,
+        ),
+    ] {
+        if amount_minor == 0 {
+            continue;
+        }
+        let dimensions = BTreeMap::from([
+            (MOVEMENT_DIMENSION.to_string(), movement.to_string()),
+            (
+                schedule.kind.reference_dimension().to_string(),
+                schedule.reference_id.clone(),
+            ),
+        ]);
+        for (account_id, entry_side) in [
+            (schedule.deferred_account_id.as_str(), EntrySide::Debit),
+            (credit_account, EntrySide::Credit),
+        ] {
+            lines.push(JournalLine {
+                line_number: lines.len() as u32 + 1,
+                account_id: account_id.to_string(),
+                entry_side,
+                amount_minor,
+                currency: schedule.currency.clone(),
+                base_amount_minor,
+                base_currency: schedule.base_currency.clone(),
+                dimensions: dimensions.clone(),
+                fx_rate: None,
+            });
+        }
+    }
+
+    JournalRecord {
+        header: JournalHeader {
+            journal_id,
+            journal_number: format!("RC-{}", &journal_id.to_string()[..8]),
+            status: JournalStatus::Posted,
+            tenant_id: schedule.tenant_id.clone(),
+            legal_entity_id: schedule.legal_entity_id.clone(),
+            ledger_book: schedule.ledger_book.clone(),
+            accounting_date: cancellation_date,
+            posted_at: chrono::Utc::now(),
+            source_event_ids: vec![format!("revrec-cancel:{}", schedule.schedule_id)],
+            posting_run_id: posting_run_id.to_string(),
+            book_policy_id: schedule.book_policy_id.clone(),
+            policy_version: schedule.policy_version.clone(),
+            fx_rate_set_id: schedule.fx_rate_set_id.clone(),
+            ruleset_version: schedule.ruleset_version.clone(),
+            workflow_id: None,
+            estimate_version: None,
+            revenue_designation: None,
+            prior_period_adjustment: None,
+            book_group_id: None,
+            trace_context: None,
+        },
+        lines,
+    }
+}
+
+/// Revenue is counted on the account each journal's rule set names as `revenue`.
+pub fn summarize_rollforward(
+    records: &[JournalRecord],
+    calendars: &FiscalCalendars,
+    rule_sets: &RuleSetRegistry,
+    book: &str,
+    period: Option<&str>,
+) -> RevRecRollforwardResponse {
+    let mut rollforward = RevRecRollforwardResponse {
+        book: book.to_string(),
+        period: period.map(ToString::to_string),
+        journal_count: 0,
+        recognized_revenue_minor: 0,
+        opening_deferred_minor: 0,
+        additions_minor: 0,
+        recognized_minor: 0,
+        released_minor: 0,
+        deferred_revenue_ending_minor: 0,
+    };
+    for record in records {
+        if record.header.ledger_book != book || record.header.status != JournalStatus::Posted {
+            continue;
+        }
+        // Period ids are per-entity calendar; an entity whose calendar lacks the id is out of scope.
+        let window = match period {
+            Some(period) => match calendars
+                .calendar(&record.header.legal_entity_id)
+                .period(period)
+            {
+                Some(window) => Some((window.start_date, window.end_date)),
+                None => continue,
+            },
+            None => None,
+        };
+        let accounting_date = record.header.accounting_date;
+        if window.is_some_and(|(_, end)| accounting_date > end) {
+            continue;
+        }
+        let before_period = window.is_some_and(|(start, _)| accounting_date < start);
+        if !before_period {
+            rollforward.journal_count += 1;
+        }
+        let revenue_account = rule_sets
+            .get(&record.header.ruleset_version)
+            .and_then(|rule_set| rule_set.account(REVENUE_ROLE))
+            .ok();
+        for line in &record.lines {
+            let deferred = line.account_id.contains("DEFERRED");
+            if before_period {
+                if deferred {
+                    rollforward.opening_deferred_minor +=
+                        signed_amount(line.entry_side.clone(), line.amount_minor);
+                }
+                continue;
+            }
+            if Some(line.account_id.as_str()) == revenue_account {
+                rollforward.recognized_revenue_minor +=
+                    signed_amount(line.entry_side.clone(), line.amount_minor);
+            }
+            if !deferred {
+                continue;
+            }
+            let recognized = line.dimensions.get(MOVEMENT_DIMENSION).map(String::as_str)
+                == Some(MOVEMENT_RECOGNIZED);
+            match line.entry_side {
+                EntrySide::Credit if recognized => {
+                    rollforward.recognized_minor -= line.amount_minor
+                }
+                EntrySide::Credit => rollforward.additions_minor += line.amount_minor,
+                EntrySide::Debit if recognized => rollforward.recognized_minor += line.amount_minor,
+                EntrySide::Debit => rollforward.released_minor += line.amount_minor,
+            }
+        }
+    }
+    rollforward.deferred_revenue_ending_minor = rollforward.opening_deferred_minor
+        + rollforward.additions_minor
+        - rollforward.recognized_minor
+        - rollforward.released_minor;
+    rollforward
+}
+
+pub(crate) fn revrec_error_response(error: RevRecError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        RevRecError::InvalidStayDates { reservation_id } => {
+            ApiError::bad_request(ErrorCode::InvalidStayDates)
+                .with_detail("reservation_id", reservation_id)
+        }
+        RevRecError::ScheduleNotFound(schedule_id) => {
+            ApiError::not_found(ErrorCode::RecognitionScheduleNotFound)
+                .with_detail("schedule_id", schedule_id)
+        }
+        RevRecError::ScheduleNotActive(schedule_id) => {
+            ApiError::conflict(ErrorCode::RecognitionScheduleNotActive)
+                .with_detail("schedule_id", schedule_id)
+        }
+        RevRecError::InvalidForfeitAmount {
+            forfeited_minor,
+            remaining_minor,
+        } => ApiError::bad_request(ErrorCode::InvalidForfeitAmount)
+            .with_detail("forfeited_minor", forfeited_minor)
+            .with_detail("remaining_minor", remaining_minor),
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RevRecRollforwardQuery {
+    pub book: String,
+    pub period: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecognitionScheduleQuery {
+    pub book: Option<String>,
+    pub legal_entity_id: Option<String>,
+    pub reference_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecognitionScheduleList {
+    pub schedules: Vec<RecognitionSchedule>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RecognitionRunRequest {
+    pub tenant_id: String,
+    #[serde(default)]
+    pub legal_entity_id: Option<String>,
+    #[serde(default)]
+    pub ledger_book: Option<String>,
+    pub through_date: String,
+    pub posting_run_id: String,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecognitionRunResponse {
+    pub through_date: NaiveDate,
+    pub journals: Vec<RecognitionJournal>,
+    /// Due entries left unrecognized because their date is in a closed period or under a legal
+    /// hold. They stay due for a later run.
+    pub skipped: Vec<SkippedRecognitionEntry>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SkippedRecognitionEntry {
+    pub schedule_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub recognition_date: NaiveDate,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecognitionJournal {
+    pub journal_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub accounting_date: NaiveDate,
+    pub schedule_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ModifyScheduleRequest {
+    pub arrival_date: String,
+    pub departure_date: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CancelScheduleRequest {
+    pub cancellation_date: String,
+    pub posting_run_id: String,
+    #[serde(default)]
+    pub forfeited_minor: i64,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CancelScheduleResponse {
+    pub schedule_id: String,
+    pub status: ScheduleStatus,
+    pub journal_id: Option<String>,
+    pub forfeited_minor: i64,
+    pub refundable_minor: i64,
+}
+
+fn parse_date(value: &str, code: ErrorCode, field: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request(code).with_detail(field, value))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/revrec/rollforward",
+    tag = "revrec",
+    params(RevRecRollforwardQuery),
+    responses(
+        (status = 200, description = "Book-scoped deferred revenue rollforward", body = RevRecRollforwardResponse),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_revrec_rollforward(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<RevRecRollforwardQuery>,
+) -> Result<Json<RevRecRollforwardResponse>, ApiError> {
+    let calendars = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+        .fiscal_calendars();
+    if let Some(period) = query.period.as_deref() {
+        if !calendars.knows_period_id(None, period) {
+            return Err(
+                ApiError::bad_request(ErrorCode::InvalidPeriodId).with_detail("period_id", period)
+            );
+        }
+    }
+    let records = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?
+        .all();
+    Ok(Json(summarize_rollforward(
+        &records,
+        &calendars,
+        &state.rule_sets,
+        &query.book,
+        query.period.as_deref(),
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/revrec/schedules",
+    tag = "revrec",
+    params(RecognitionScheduleQuery),
+    responses(
+        (status = 200, description = "Revenue recognition schedules", body = RecognitionScheduleList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_recognition_schedules(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<RecognitionScheduleQuery>,
+) -> Result<Json<RecognitionScheduleList>, ApiError> {
+    let schedules = state
+        .revrec_schedules
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?
+        .all()
+        .into_iter()
+        .filter(|schedule| {
+            query
+                .book
+                .as_ref()
+                .is_none_or(|book| *book == schedule.ledger_book)
+                && query
+                    .legal_entity_id
+                    .as_ref()
+                    .is_none_or(|legal_entity_id| *legal_entity_id == schedule.legal_entity_id)
+                && query
+                    .reference_id
+                    .as_ref()
+                    .is_none_or(|reference_id| *reference_id == schedule.reference_id)
+        })
+        .collect();
+    Ok(Json(RecognitionScheduleList { schedules }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/revrec/recognition-runs",
+    tag = "revrec",
+    request_body = RecognitionRunRequest,
+    responses(
+        (status = 200, description = "Deferred revenue recognized through the run date; entries in closed periods or under holds are skipped", body = RecognitionRunResponse),
+        (status = 400, description = "Invalid run date", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn run_recognition(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<RecognitionRunRequest>,
+) -> Result<Json<RecognitionRunResponse>, ApiError> {
+    let through_date = parse_date(
+        &req.through_date,
+        ErrorCode::InvalidAccountingDate,
+        "through_date",
+    )?;
+
+    // Holds and periods are checked before the journal store is locked, so the run never
+    // holds the period store and the journal store at once. An entry whose date is blocked is
+    // reported and skipped; the rest of the run goes ahead.
+    let mut due = {
+        let schedules = state
+            .revrec_schedules
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?;
+        due_entries(&schedules.schedules, &req, through_date)
+    };
+    let mut skipped = Vec::new();
+    let mut blocked = Vec::new();
+    for (key, entries) in &due {
+        let open = state
+            .validate_legal_hold(
+                &key.tenant_id,
+                &key.legal_entity_id,
+                &key.ledger_book,
+                key.accounting_date,
+            )
+            .and_then(|()| {
+                state
+                    .periods
+                    .lock()
+                    .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+                    .ensure_open(
+                        &key.tenant_id,
+                        &key.legal_entity_id,
+                        &key.ledger_book,
+                        key.accounting_date,
+                        PostingAuthority::new(PostingClass::Close, req.actor.as_ref()),
+                    )
+                    .map_err(period_error_response)
+            });
+        let error = match open {
+            Ok(()) => continue,
+            Err(error) if error.status == StatusCode::CONFLICT => error,
+            Err(error) => return Err(error),
+        };
+        skipped.extend(entries.iter().map(|entry| SkippedRecognitionEntry {
+            schedule_id: entry.schedule_id.clone(),
+            legal_entity_id: key.legal_entity_id.clone(),
+            ledger_book: key.ledger_book.clone(),
+            recognition_date: key.accounting_date,
+            code: error.code,
+            message: error.message.clone(),
+        }));
+        blocked.push(key.clone());
+    }
+    for key in &blocked {
+        due.remove(key);
+    }
+
+    let mut journals = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+    let mut schedules = state
+        .revrec_schedules
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?;
+    // Entries that fell due while the stores were unlocked wait for the next run.
+    let mut batches = due_entries(&schedules.schedules, &req, through_date);
+    batches.retain(|key, _| due.contains_key(key));
+    let records = batches
+        .iter()
+        .map(|(key, entries)| {
+            let revenue_account = state
+                .rule_sets
+                .account(&key.ruleset_version, REVENUE_ROLE)
+                .map_err(rule_engine_error_response)?;
+            Ok(recognition_record(
+                key,
+                entries,
+                &revenue_account,
+                &req.posting_run_id,
+            ))
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    let headers = records
+        .iter()
+        .map(|record| record.header.clone())
+        .collect::<Vec<_>>();
+    journals
+        .insert_posted_batch(records)
+        .map_err(ledger_error_response)?;
+    for header in &headers {
+        state.record_change(LedgerChangeType::JournalPosted, header, None)?;
+    }
+    drop(journals);
+
+    for ((_, entries), header) in batches.iter().zip(&headers) {
+        for entry in entries {
+            schedules
+                .update(&entry.schedule_id, |schedule| {
+                    schedule.entries[entry.entry_index].journal_id =
+                        Some(header.journal_id.to_string());
+                    if schedule
+                        .entries
+                        .iter()
+                        .all(|entry| entry.journal_id.is_some())
+                    {
+                        schedule.status = ScheduleStatus::Completed;
+                    }
+                    Ok(())
+                })
+                .map_err(revrec_error_response)?;
+        }
+    }
+    drop(schedules);
+
+    let mut recognized = Vec::with_capacity(headers.len());
+    for ((_, entries), header) in batches.iter().zip(headers) {
+        state.append_audit_seal(
+            "revrec.recognized",
+            std::slice::from_ref(&header.legal_entity_id),
+            &json!({
+                "journal_id": header.journal_id,
+                "ledger_book": header.ledger_book,
+                "accounting_date": header.accounting_date,
+                "posting_run_id": header.posting_run_id
+            }),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+        )?;
+        recognized.push(RecognitionJournal {
+            journal_id: header.journal_id.to_string(),
+            legal_entity_id: header.legal_entity_id,
+            ledger_book: header.ledger_book,
+            accounting_date: header.accounting_date,
+            schedule_count: entries.len(),
+        });
+    }
+
+    Ok(Json(RecognitionRunResponse {
+        through_date,
+        journals: recognized,
+        skipped,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/revrec/schedules/{schedule_id}/modify",
+    tag = "revrec",
+    params(("schedule_id" = String, Path, description = "Recognition schedule to re-plan")),
+    request_body = ModifyScheduleRequest,
+    responses(
+        (status = 200, description = "Remaining schedule re-planned over the new stay", body = RecognitionSchedule),
+        (status = 400, description = "Invalid stay dates", body = ErrorEnvelope),
+        (status = 404, description = "Schedule not found", body = ErrorEnvelope),
+        (status = 409, description = "Schedule is not active", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn modify_recognition_schedule(
+    State(state): State<AppState>,
+    Path(schedule_id): Path<String>,
+    ApiJson(req): ApiJson<ModifyScheduleRequest>,
+) -> Result<Json<RecognitionSchedule>, ApiError> {
+    let arrival_date = parse_date(
+        &req.arrival_date,
+        ErrorCode::InvalidStayDates,
+        "arrival_date",
+    )?;
+    let departure_date = parse_date(
+        &req.departure_date,
+        ErrorCode::InvalidStayDates,
+        "departure_date",
+    )?;
+    let schedule = {
+        let mut schedules = state
+            .revrec_schedules
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?;
+        schedules
+            .update(&schedule_id, |schedule| {
+                schedule.replan(arrival_date, departure_date)?;
+                Ok(schedule.clone())
+            })
+            .map_err(revrec_error_response)?
+    };
+    state.append_audit_seal(
+        "revrec.schedule_modified",
+        std::slice::from_ref(&schedule.legal_entity_id),
+        &json!({
+            "schedule_id": schedule.schedule_id,
+            "arrival_date": schedule.arrival_date,
+            "departure_date": schedule.departure_date
+        }),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+    Ok(Json(schedule))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/revrec/schedules/{schedule_id}/cancel",
+    tag = "revrec",
+    params(("schedule_id" = String, Path, description = "Recognition schedule to cancel")),
+    request_body = CancelScheduleRequest,
+    responses(
+        (status = 200, description = "Schedule cancelled and remaining deferred balance released", body = CancelScheduleResponse),
+        (status = 400, description = "Invalid cancellation", body = ErrorEnvelope),
+        (status = 404, description = "Schedule not found", body = ErrorEnvelope),
+        (status = 409, description = "Schedule, period or hold conflict", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn cancel_recognition_schedule(
+    State(state): State<AppState>,
+    Path(schedule_id): Path<String>,
+    ApiJson(req): ApiJson<CancelScheduleRequest>,
+) -> Result<Json<CancelScheduleResponse>, ApiError> {
+    let cancellation_date = parse_date(
+        &req.cancellation_date,
+        ErrorCode::InvalidAccountingDate,
+        "cancellation_date",
+    )?;
+
+    // The period check runs before the journal store is locked; the schedule is read again
+    // under the journal lock in case a recognition run or another cancel got there first.
+    let (schedule, remaining_minor) = {
+        let schedules = state
+            .revrec_schedules
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?;
+        cancellable_schedule(&schedules, &schedule_id, req.forfeited_minor)?
+    };
+    if remaining_minor > 0 {
+        state.validate_legal_hold(
+            &schedule.tenant_id,
+            &schedule.legal_entity_id,
+            &schedule.ledger_book,
+            cancellation_date,
+        )?;
+        state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+            .ensure_open(
+                &schedule.tenant_id,
+                &schedule.legal_entity_id,
+                &schedule.ledger_book,
+                cancellation_date,
+                PostingAuthority::new(PostingClass::Adjustment, req.actor.as_ref()),
+            )
+            .map_err(period_error_response)?;
+    }
+
+    let mut journals = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+    let mut schedules = state
+        .revrec_schedules
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::RecognitionScheduleStoreError))?;
+    let (schedule, remaining_minor) =
+        cancellable_schedule(&schedules, &schedule_id, req.forfeited_minor)?;
+
+    let mut journal_header = None;
+    if remaining_minor > 0 {
+        let account = |role| {
+            state
+                .rule_sets
+                .account(&schedule.ruleset_version, role)
+                .map_err(rule_engine_error_response)
+        };
+        let record = cancellation_record(
+            &schedule,
+            cancellation_date,
+            req.forfeited_minor,
+            (
+                &account(REVENUE_ROLE)?,
+                &account(CANCELLATION_REFUNDS_ROLE)?,
+            ),
+            &req.posting_run_id,
+        );
+        let header = record.header.clone();
+        journals
+            .insert_posted(record)
+            .map_err(ledger_error_response)?;
+        state.record_change(LedgerChangeType::JournalPosted, &header, None)?;
+        journal_header = Some(header);
+    }
+    drop(journals);
+
+    schedules
+        .update(&schedule_id, |schedule| {
+            schedule.entries.retain(|entry| entry.journal_id.is_some());
+            schedule.status = ScheduleStatus::Cancelled;
+            schedule.cancellation_journal_id = journal_header
+                .as_ref()
+                .map(|header| header.journal_id.to_string());
+            Ok(())
+        })
+        .map_err(revrec_error_response)?;
+    drop(schedules);
+
+    let journal_id = journal_header.map(|header| header.journal_id.to_string());
+    state.append_audit_seal(
+        "revrec.schedule_cancelled",
+        std::slice::from_ref(&schedule.legal_entity_id),
+        &json!({
+            "schedule_id": schedule.schedule_id,
+            "journal_id": journal_id,
+            "forfeited_minor": req.forfeited_minor,
+            "refundable_minor": remaining_minor - req.forfeited_minor
+        }),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+
+    Ok(Json(CancelScheduleResponse {
+        schedule_id: schedule.schedule_id,
+        status: ScheduleStatus::Cancelled,
+        journal_id,
+        forfeited_minor: req.forfeited_minor,
+        refundable_minor: remaining_minor - req.forfeited_minor,
+    }))
+}
+
+fn cancellable_schedule(
+    schedules: &InMemoryRevRecScheduleRepository,
+    schedule_id: &str,
+    forfeited_minor: i64,
+) -> Result<(RecognitionSchedule, i64), ApiError> {
+    let schedule = schedules.get(schedule_id).cloned().ok_or_else(|| {
+        revrec_error_response(RevRecError::ScheduleNotFound(schedule_id.to_string()))
+    })?;
+    if schedule.status != ScheduleStatus::Active {
+        return Err(revrec_error_response(RevRecError::ScheduleNotActive(
+            schedule_id.to_string(),
+        )));
+    }
+    let remaining_minor = schedule.remaining_minor();
+    if !(0..=remaining_minor).contains(&forfeited_minor) {
+        return Err(revrec_error_response(RevRecError::InvalidForfeitAmount {
+            forfeited_minor,
+            remaining_minor,
+        }));
+    }
+    Ok((schedule, remaining_minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{NaiveDate, Utc};
+    use ledger_posting::{EntrySide, JournalHeader, JournalLine, JournalRecord, JournalStatus};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{
+        schedule_from_capture, split_evenly, summarize_rollforward, RevRecError, ScheduleStatus,
+    };
+    use crate::calendar::{FiscalCalendar, FiscalCalendars};
+    use crate::RuleSetRegistry;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn capture_record(amount_minor: i64) -> JournalRecord {
+        let line = |line_number, account_id: &str, entry_side| JournalLine {
+            line_number,
+            account_id: account_id.to_string(),
+            entry_side,
+            amount_minor,
+            currency: "USD".to_string(),
+            base_amount_minor: amount_minor,
+            base_currency: "USD".to_string(),
+            dimensions: Default::default(),
+            fx_rate: None,
+        };
+        JournalRecord {
+            header: JournalHeader {
+                journal_id: Uuid::new_v4(),
+                journal_number: "S2-00000001".to_string(),
+                status: JournalStatus::Posted,
+                tenant_id: "tenant_1".to_string(),
+                legal_entity_id: "US_CO_01".to_string(),
+                ledger_book: "US_GAAP".to_string(),
+                accounting_date: date("2026-02-21"),
+                posted_at: Utc::now(),
+                source_event_ids: vec!["evt_1".to_string()],
+                posting_run_id: "run_1".to_string(),
+                book_policy_id: "policy_dual_book".to_string(),
+                policy_version: "1.0.0".to_string(),
+                fx_rate_set_id: "fx_1".to_string(),
+                ruleset_version: "v1".to_string(),
+                workflow_id: None,
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: None,
+                book_group_id: None,
+                trace_context: None,
+            },
+            lines: vec![
+                line(1, "1105-CASH-CLEARING", EntrySide::Debit),
+                line(2, "2200-DEFERRED-REVENUE-RESERVATIONS", EntrySide::Credit),
+            ],
+        }
+    }
+
+    #[test]
+    fn capture_plans_one_entry_per_night_with_remainder_on_earliest_nights() {
+        let schedule = schedule_from_capture(
+            &capture_record(10000),
+            "BRECK_BASE_AREA",
+            &json!({"reservation_id": "resv_1", "arrival_date": "2026-03-01", "departure_date": "2026-03-04"}),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(schedule.reference_id, "resv_1");
+        assert_eq!(
+            schedule
+                .entries
+                .iter()
+                .map(|entry| (entry.recognition_date, entry.amount_minor))
+                .collect::<Vec<_>>(),
+            vec![
+                (date("2026-03-01"), 3334),
+                (date("2026-03-02"), 3333),
+                (date("2026-03-03"), 3333),
+            ]
+        );
+        assert_eq!(split_evenly(-5, 2), vec![-2, -3]);
+    }
+
+    #[test]
+    fn capture_without_stay_dates_has_no_schedule_and_bad_dates_are_rejected() {
+        let record = capture_record(10000);
+        assert_eq!(
+            schedule_from_capture(&record, "BRECK_BASE_AREA", &json!({"arrival_date": null}))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            schedule_from_capture(
+                &record,
+                "BRECK_BASE_AREA",
+                &json!({"reservation_id": "resv_1", "arrival_date": "2026-03-04", "departure_date": "2026-03-04"}),
+            ),
+            Err(RevRecError::InvalidStayDates {
+                reservation_id: "resv_1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn replan_spreads_remaining_balance_over_unrecognized_nights() {
+        let mut schedule = schedule_from_capture(
+            &capture_record(9000),
+            "BRECK_BASE_AREA",
+            &json!({"arrival_date": "2026-03-01", "departure_date": "2026-03-04"}),
+        )
+        .unwrap()
+        .unwrap();
+        schedule.entries[0].journal_id = Some("journal_1".to_string());
+
+        schedule
+            .replan(date("2026-03-01"), date("2026-03-06"))
+            .unwrap();
+        assert_eq!(schedule.remaining_minor(), 6000);
+        assert_eq!(
+            schedule
+                .entries
+                .iter()
+                .map(|entry| (entry.recognition_date, entry.amount_minor))
+                .collect::<Vec<_>>(),
+            vec![
+                (date("2026-03-01"), 3000),
+                (date("2026-03-02"), 1500),
+                (date("2026-03-03"), 1500),
+                (date("2026-03-04"), 1500),
+                (date("2026-03-05"), 1500),
+            ]
+        );
+
+        assert!(matches!(
+            schedule.replan(date("2026-02-27"), date("2026-03-02")),
+            Err(RevRecError::InvalidStayDates { .. })
+        ));
+        schedule.status = ScheduleStatus::Cancelled;
+        assert!(matches!(
+            schedule.replan(date("2026-03-01"), date("2026-03-06")),
+            Err(RevRecError::ScheduleNotActive(_))
+        ));
+    }
+
+    #[test]
+    fn rollforward_splits_opening_additions_recognized_and_released() {
+        let capture = capture_record(9000);
+        let mut recognition = capture_record(3000);
+        recognition.header.accounting_date = date("2026-03-01");
+        recognition.lines[0].account_id = "2200-DEFERRED-REVENUE-RESERVATIONS".to_string();
+        recognition.lines[0]
+            .dimensions
+            .insert("revrec_movement".to_string(), "recognized".to_string());
+        recognition.lines[1].account_id = "4000-REVENUE".to_string();
+        let mut cancellation = recognition.clone();
+        cancellation.lines[0].dimensions.clear();
+        cancellation.lines[1].account_id = "2250-RESERVATION-REFUNDS-PAYABLE".to_string();
+        let records = vec![capture, recognition, cancellation];
+
+        let calendars = FiscalCalendars::default();
+        let rule_sets = RuleSetRegistry::default();
+        let march =
+            summarize_rollforward(&records, &calendars, &rule_sets, "US_GAAP", Some("2026-03"));
+        assert_eq!(march.journal_count, 2);
+        assert_eq!(march.opening_deferred_minor, 9000);
+        assert_eq!(march.additions_minor, 0);
+        assert_eq!(march.recognized_minor, 3000);
+        assert_eq!(march.released_minor, 3000);
+        assert_eq!(march.deferred_revenue_ending_minor, 3000);
+        assert_eq!(march.recognized_revenue_minor, 3000);
+
+        let february =
+            summarize_rollforward(&records, &calendars, &rule_sets, "US_GAAP", Some("2026-02"));
+        assert_eq!(february.additions_minor, 9000);
+        assert_eq!(february.deferred_revenue_ending_minor, 9000);
+
+        let fiscal = FiscalCalendars::new(HashMap::from([(
+            records[0].header.legal_entity_id.clone(),
+            FiscalCalendar::FiscalMonth { start_month: 7 },
+        )]));
+        let fiscal_march =
+            summarize_rollforward(&records, &fiscal, &rule_sets, "US_GAAP", Some("FY2026-P09"));
+        assert_eq!(fiscal_march.journal_count, 2);
+        assert_eq!(fiscal_march.opening_deferred_minor, 9000);
+        assert_eq!(fiscal_march.deferred_revenue_ending_minor, 3000);
+        let calendar_ids =
+            summarize_rollforward(&records, &fiscal, &rule_sets, "US_GAAP", Some("2026-03"));
+        assert_eq!(calendar_ids.journal_count, 0);
+    }
+}
@@ -1,7 +1,22 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
 use ledger_posting::EntrySide;
+use serde::Deserialize;
 use serde_json::Value;
 use thiserror::Error;
 
+const BUILTIN_RULESETS: [(&str, &str); 6] = [
+    ("rulesets/v1.json", include_str!("../rulesets/v1.json")),
+    ("rulesets/v2.json", include_str!("../rulesets/v2.json")),
+    ("rulesets/v3.json", include_str!("../rulesets/v3.json")),
+    ("rulesets/v4.json", include_str!("../rulesets/v4.json")),
+    ("rulesets/v5.json", include_str!("../rulesets/v5.json")),
+    ("rulesets/v6.json", include_str!("../rulesets/v6.json")),
+];
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DerivedPostingLine {
     pub account_id: String,
@@ -10,532 +25,1351 @@ pub struct DerivedPostingLine {
     pub currency: String,
     pub base_amount_minor: i64,
     pub base_currency: String,
+    pub dimensions: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum RuleEngineError {
     #[error("unsupported event type: {0}")]
     UnsupportedEventType(String),
+    #[error("unknown ruleset version: {0}")]
+    UnknownRulesetVersion(String),
     #[error("missing required field `{0}`")]
-    MissingField(&'static str),
+    MissingField(String),
     #[error("invalid numeric field `{0}`")]
-    InvalidNumber(&'static str),
+    InvalidNumber(String),
     #[error("invalid settlement math: gross != net + fee")]
     InvalidSettlementMath,
+    #[error("rule check failed: {0}")]
+    CheckFailed(String),
+    #[error("amount expression overflowed for `{0}`")]
+    AmountOverflow(String),
+    #[error("rule for `{0}` derived no lines")]
+    NoLinesDerived(String),
     #[error("invalid entry side `{0}`")]
     InvalidEntrySide(String),
+    #[error("no tax payable account for tax type `{0}`")]
+    UnknownTaxType(String),
+    #[error("rule set `{ruleset_version}` names no `{role}` account")]
+    AccountNotNamed {
+        ruleset_version: String,
+        role: String,
+    },
+    #[error("order components do not reconcile: {check}")]
+    ComponentsUnreconciled {
+        check: String,
+        amounts: BTreeMap<String, i64>,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum RuleSetError {
+    #[error("failed to read rule set {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse rule set {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("rule set `{version}` rule `{event_type}`: {message}")]
+    Invalid {
+        version: String,
+        event_type: String,
+        message: String,
+    },
+    #[error("rule set has an empty ruleset_version")]
+    MissingVersion,
+    #[error("rule set `{0}` is defined more than once")]
+    DuplicateVersion(String),
 }
 
 pub fn derive_lines_v1(
     event_type: &str,
     payload: &Value,
 ) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    match event_type {
-        "order.captured.v1" => order_captured(payload),
-        "payment.settled.v1" => payment_settled(payload),
-        "refund.v1" => refund(payload),
-        "fee.assessed.v1" => fee_assessed(payload),
-        "chargeback.created.v1" => chargeback_created(payload),
-        "payout.cleared.v1" => payout_cleared(payload),
-        "dispute.opened.v1" => dispute_opened(payload),
-        "dispute.won.v1" => dispute_won(payload),
-        "dispute.lost.v1" => dispute_lost(payload),
-        "inntopia.reservation.captured.v1" => inntopia_reservation_captured(payload),
-        "intercompany.due_to_due_from.v1" => intercompany_due_to_due_from(payload),
-        "consolidation.elimination.v1" => consolidation_elimination(payload),
-        "fx.translation.v1" => fx_translation(payload),
-        unsupported => Err(RuleEngineError::UnsupportedEventType(
-            unsupported.to_string(),
-        )),
-    }
-}
-
-fn order_captured(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &[
-            "/amount_minor",
-            "/totals/grand_total_minor",
-            "/totals/grand_total/amount_minor",
-        ],
-        "amount_minor",
-    )?;
-    let currency = first_string(
-        payload,
-        &[
-            "/currency",
-            "/totals/currency",
-            "/totals/grand_total/currency",
-        ],
-    )
-    .unwrap_or_else(|| "USD".to_string());
-    let base_amount_minor = optional_i64(payload, &["/base_amount_minor"]).unwrap_or(amount);
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            base_amount_minor,
-            &base_currency,
-        ),
-        line(
-            "4000-REVENUE",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            base_amount_minor,
-            &base_currency,
-        ),
-    ])
-}
-
-fn payment_settled(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let gross = require_positive_i64(
-        payload,
-        &["/gross_amount_minor", "/amount_minor"],
-        "gross_amount_minor",
-    )?;
-    let fee = optional_i64(payload, &["/fee_amount_minor"]).unwrap_or(0);
-    if fee < 0 {
-        return Err(RuleEngineError::InvalidNumber("fee_amount_minor"));
-    }
-    let net = optional_i64(payload, &["/net_amount_minor"]).unwrap_or(gross - fee);
-    if net < 0 {
-        return Err(RuleEngineError::InvalidNumber("net_amount_minor"));
-    }
-    if gross != net + fee {
-        return Err(RuleEngineError::InvalidSettlementMath);
-    }
-
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    let mut lines = vec![line(
-        "1000-CASH",
-        EntrySide::Debit,
-        net,
-        &currency,
-        net,
-        &base_currency,
-    )];
-    if fee > 0 {
-        lines.push(line(
-            "6100-PAYMENT-FEES",
-            EntrySide::Debit,
-            fee,
-            &currency,
-            fee,
-            &base_currency,
-        ));
+    builtin_v1().derive(event_type, None, payload)
+}
+
+fn builtin_v1() -> &'static Arc<RuleSet> {
+    &builtin_rule_sets()[0]
+}
+
+fn builtin_rule_sets() -> &'static [Arc<RuleSet>] {
+    static BUILTIN: OnceLock<Vec<Arc<RuleSet>>> = OnceLock::new();
+    BUILTIN.get_or_init(|| {
+        BUILTIN_RULESETS
+            .iter()
+            .map(|(path, encoded)| {
+                Arc::new(
+                    RuleSet::from_json(Path::new(path), encoded.as_bytes())
+                        .expect("built-in rule set should be valid"),
+                )
+            })
+            .collect()
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleSetRegistry {
+    rule_sets: HashMap<String, Arc<RuleSet>>,
+}
+
+impl Default for RuleSetRegistry {
+    fn default() -> Self {
+        Self {
+            rule_sets: builtin_rule_sets()
+                .iter()
+                .map(|rule_set| (rule_set.version.clone(), rule_set.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl RuleSetRegistry {
+    pub fn load_dir(dir: &Path) -> Result<Self, RuleSetError> {
+        let read_error = |source| RuleSetError::Read {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let mut paths = fs::read_dir(dir)
+            .map_err(read_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(read_error)?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+        paths.sort();
+
+        let mut registry = Self::default();
+        for path in paths {
+            let rule_set = RuleSet::load(&path)?;
+            if registry.rule_sets.contains_key(&rule_set.version) {
+                return Err(RuleSetError::DuplicateVersion(rule_set.version));
+            }
+            registry.insert(rule_set);
+        }
+        Ok(registry)
+    }
+
+    pub fn insert(&mut self, rule_set: RuleSet) {
+        self.rule_sets
+            .insert(rule_set.version.clone(), Arc::new(rule_set));
+    }
+
+    pub fn get(&self, ruleset_version: &str) -> Result<&RuleSet, RuleEngineError> {
+        self.rule_sets
+            .get(ruleset_version)
+            .map(Arc::as_ref)
+            .ok_or_else(|| RuleEngineError::UnknownRulesetVersion(ruleset_version.to_string()))
+    }
+
+    pub fn account(&self, ruleset_version: &str, role: &str) -> Result<String, RuleEngineError> {
+        self.get(ruleset_version)?.account(role).map(str::to_string)
+    }
+
+    pub fn versions(&self) -> Vec<String> {
+        let mut versions = self.rule_sets.keys().cloned().collect::<Vec<_>>();
+        versions.sort();
+        versions
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    version: String,
+    rules: HashMap<String, Rule>,
+    tax_accounts: BTreeMap<String, String>,
+    accounts: BTreeMap<String, String>,
+}
+
+impl RuleSet {
+    pub fn load(path: &Path) -> Result<Self, RuleSetError> {
+        let encoded = fs::read(path).map_err(|source| RuleSetError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(path, &encoded)
+    }
+
+    pub fn from_json(path: &Path, encoded: &[u8]) -> Result<Self, RuleSetError> {
+        let file: RuleSetFile =
+            serde_json::from_slice(encoded).map_err(|source| RuleSetError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        let version = file.ruleset_version.trim().to_string();
+        if version.is_empty() {
+            return Err(RuleSetError::MissingVersion);
+        }
+        let mut rules = HashMap::new();
+        for (event_type, rule) in file.rules {
+            let compiled =
+                Rule::compile(rule, &file.tax_accounts, &file.accounts).map_err(|message| {
+                    RuleSetError::Invalid {
+                        version: version.clone(),
+                        event_type: event_type.clone(),
+                        message,
+                    }
+                })?;
+            rules.insert(event_type, compiled);
+        }
+        Ok(Self {
+            version,
+            rules,
+            tax_accounts: file.tax_accounts,
+            accounts: file.accounts,
+        })
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The account the rule set names for a role that code books to directly, such as the
+    /// `revenue` account of recognition journals.
+    pub fn account(&self, role: &str) -> Result<&str, RuleEngineError> {
+        self.accounts.get(role).map(String::as_str).ok_or_else(|| {
+            RuleEngineError::AccountNotNamed {
+                ruleset_version: self.version.clone(),
+                role: role.to_string(),
+            }
+        })
+    }
+
+    pub fn supports(&self, event_type: &str) -> bool {
+        self.rules.contains_key(event_type)
+    }
+
+    pub fn reads_amount(&self, event_type: &str, pointer: &str) -> bool {
+        self.rules.get(event_type).is_some_and(|rule| {
+            rule.amounts
+                .iter()
+                .any(|input| input.from.iter().any(|from| from == pointer))
+        })
+    }
+
+    pub fn derive(
+        &self,
+        event_type: &str,
+        ledger_book: Option<&str>,
+        payload: &Value,
+    ) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
+        let rule = self
+            .rules
+            .get(event_type)
+            .ok_or_else(|| RuleEngineError::UnsupportedEventType(event_type.to_string()))?;
+        let lines = rule.derive(ledger_book, false, &self.tax_accounts, payload)?;
+        if lines.is_empty() {
+            return Err(RuleEngineError::NoLinesDerived(event_type.to_string()));
+        }
+        Ok(lines)
+    }
+
+    pub fn derive_counterparty(
+        &self,
+        event_type: &str,
+        ledger_book: Option<&str>,
+        payload: &Value,
+    ) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
+        let rule = self
+            .rules
+            .get(event_type)
+            .ok_or_else(|| RuleEngineError::UnsupportedEventType(event_type.to_string()))?;
+        rule.derive(ledger_book, true, &self.tax_accounts, payload)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSetFile {
+    ruleset_version: String,
+    #[serde(default)]
+    tax_accounts: BTreeMap<String, String>,
+    #[serde(default)]
+    accounts: BTreeMap<String, String>,
+    rules: BTreeMap<String, RuleFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default)]
+    amounts: Vec<AmountInputFile>,
+    #[serde(default)]
+    strings: Vec<StringInputFile>,
+    #[serde(default)]
+    checks: Vec<CheckFile>,
+    lines: Vec<LineTemplateFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AmountInputFile {
+    name: String,
+    field: Option<String>,
+    #[serde(default)]
+    from: Vec<String>,
+    default: Option<String>,
+    #[serde(default)]
+    constraint: AmountConstraint,
+    taxes: Option<TaxSourceFile>,
+    items: Option<ItemSourceFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaxSourceFile {
+    from: String,
+    scale: Option<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemSourceFile {
+    from: String,
+    amount: Vec<String>,
+    #[serde(default)]
+    dimensions: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StringInputFile {
+    name: String,
+    #[serde(default)]
+    from: Vec<String>,
+    default: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CheckFile {
+    assert: String,
+    error: String,
+    #[serde(default)]
+    report: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LineTemplateFile {
+    when: Option<String>,
+    #[serde(default)]
+    books: Vec<String>,
+    #[serde(default)]
+    except_books: Vec<String>,
+    #[serde(default)]
+    counterparty: bool,
+    taxes: Option<String>,
+    items: Option<String>,
+    account: Option<String>,
+    side: String,
+    amount: Option<String>,
+    base_amount: Option<String>,
+    #[serde(default)]
+    dimensions: BTreeMap<String, String>,
+    #[serde(default = "default_line_currency")]
+    currency: String,
+    #[serde(default = "default_line_base_currency")]
+    base_currency: String,
+}
+
+fn default_line_currency() -> String {
+    "$currency".to_string()
+}
+
+fn default_line_base_currency() -> String {
+    "$base_currency".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum AmountConstraint {
+    #[default]
+    Any,
+    Positive,
+    NonNegative,
+    NonZero,
+}
+
+impl AmountConstraint {
+    fn allows(self, value: i64) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Positive => value > 0,
+            Self::NonNegative => value >= 0,
+            Self::NonZero => value != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    amounts: Vec<AmountInput>,
+    strings: Vec<StringInput>,
+    checks: Vec<Check>,
+    lines: Vec<LineTemplate>,
+}
+
+#[derive(Debug, Clone)]
+struct Check {
+    condition: Condition,
+    error: String,
+    report: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct AmountInput {
+    name: String,
+    field: String,
+    from: Vec<String>,
+    default: Option<Expr>,
+    constraint: AmountConstraint,
+    taxes: Option<TaxSource>,
+    items: Option<ItemSource>,
+}
+
+#[derive(Debug, Clone)]
+struct TaxSource {
+    from: String,
+    scale: Option<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+struct ItemSource {
+    from: String,
+    amount: Vec<String>,
+    dimensions: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ItemGroup {
+    dimensions: BTreeMap<String, String>,
+    amount_minor: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TaxComponent {
+    tax_type: String,
+    jurisdiction: String,
+    amount_minor: i64,
+}
+
+#[derive(Debug, Clone)]
+struct StringInput {
+    name: String,
+    from: Vec<String>,
+    default: StringValue,
+}
+
+#[derive(Debug, Clone)]
+struct LineTemplate {
+    when: Option<Condition>,
+    books: Vec<String>,
+    except_books: Vec<String>,
+    counterparty: bool,
+    kind: LineKind,
+    side: EntrySide,
+    currency: StringValue,
+    base_currency: StringValue,
+    dimensions: Vec<(String, StringValue)>,
+}
+
+#[derive(Debug, Clone)]
+enum LineKind {
+    Fixed {
+        account: StringValue,
+        amount: Expr,
+        base_amount: Expr,
+    },
+    Taxes(String),
+    Items {
+        source: String,
+        account: StringValue,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum StringValue {
+    Literal(String),
+    Ref(String),
+}
+
+impl StringValue {
+    fn parse(value: &str, defined: &HashSet<String>) -> Result<Self, String> {
+        match value.strip_prefix('$') {
+            Some(name) if defined.contains(name) => Ok(Self::Ref(name.to_string())),
+            Some(name) => Err(format!("unknown string input `{name}`")),
+            None if value.trim().is_empty() => Err("string value must not be empty".to_string()),
+            None => Ok(Self::Literal(value.to_string())),
+        }
+    }
+
+    /// A line account may also be `@role`, the account the rule set names for that role.
+    fn parse_account(
+        value: &str,
+        defined: &HashSet<String>,
+        accounts: &BTreeMap<String, String>,
+    ) -> Result<Self, String> {
+        match value.strip_prefix('@') {
+            Some(role) => accounts
+                .get(role)
+                .map(|account| Self::Literal(account.clone()))
+                .ok_or_else(|| format!("rule set names no `{role}` account")),
+            None => Self::parse(value, defined),
+        }
+    }
+
+    fn resolve(&self, strings: &HashMap<String, String>) -> String {
+        match self {
+            Self::Literal(value) => value.clone(),
+            Self::Ref(name) => strings[name].clone(),
+        }
+    }
+}
+
+impl LineTemplate {
+    /// `books` limits a line to the listed books; `except_books` drops it from them, so a
+    /// book-specific line can replace the default one. Unscoped derivation sees only lines
+    /// that apply to every book.
+    fn applies_to(&self, ledger_book: Option<&str>) -> bool {
+        let listed =
+            |books: &[String]| ledger_book.is_some_and(|book| books.iter().any(|b| b == book));
+        if !self.books.is_empty() {
+            return listed(&self.books);
+        }
+        !listed(&self.except_books)
+    }
+}
+
+impl Rule {
+    fn compile(
+        file: RuleFile,
+        tax_accounts: &BTreeMap<String, String>,
+        accounts: &BTreeMap<String, String>,
+    ) -> Result<Self, String> {
+        let mut amount_names = HashSet::new();
+        let mut tax_names = HashSet::new();
+        let mut item_names = HashSet::new();
+        let mut amounts = Vec::new();
+        for input in file.amounts {
+            let items = match input.items {
+                Some(_) if input.taxes.is_some() => {
+                    return Err(format!(
+                        "amount `{}` cannot read both taxes and items",
+                        input.name
+                    ));
+                }
+                Some(_) if !input.from.is_empty() || input.default.is_some() => {
+                    return Err(format!(
+                        "item amount `{}` cannot also declare from or default",
+                        input.name
+                    ));
+                }
+                Some(source) => {
+                    validate_pointers(std::slice::from_ref(&source.from))?;
+                    if source.amount.is_empty() {
+                        return Err(format!(
+                            "item amount `{}` needs amount pointers",
+                            input.name
+                        ));
+                    }
+                    validate_pointers(&source.amount)?;
+                    for pointers in source.dimensions.values() {
+                        validate_pointers(pointers)?;
+                    }
+                    item_names.insert(input.name.clone());
+                    Some(ItemSource {
+                        from: source.from,
+                        amount: source.amount,
+                        dimensions: source.dimensions,
+                    })
+                }
+                None => None,
+            };
+            let taxes = match input.taxes {
+                Some(_) if !input.from.is_empty() || input.default.is_some() => {
+                    return Err(format!(
+                        "tax amount `{}` cannot also declare from or default",
+                        input.name
+                    ));
+                }
+                Some(source) => {
+                    validate_pointers(std::slice::from_ref(&source.from))?;
+                    let scale = match source.scale {
+                        Some([numerator, denominator]) => {
+                            for name in [&numerator, &denominator] {
+                                if !amount_names.contains(name) {
+                                    return Err(format!("unknown amount `{name}` in tax scale"));
+                                }
+                            }
+                            Some((numerator, denominator))
+                        }
+                        None => None,
+                    };
+                    tax_names.insert(input.name.clone());
+                    Some(TaxSource {
+                        from: source.from,
+                        scale,
+                    })
+                }
+                None if items.is_none() && input.from.is_empty() && input.default.is_none() => {
+                    return Err(format!(
+                        "amount `{}` needs a payload pointer or a default",
+                        input.name
+                    ));
+                }
+                None => None,
+            };
+            validate_pointers(&input.from)?;
+            let default = input
+                .default
+                .as_deref()
+                .map(|expr| Expr::parse(expr, &amount_names))
+                .transpose()?;
+            if !amount_names.insert(input.name.clone()) {
+                return Err(format!("amount `{}` is declared twice", input.name));
+            }
+            amounts.push(AmountInput {
+                field: input.field.unwrap_or_else(|| input.name.clone()),
+                name: input.name,
+                from: input.from,
+                default,
+                constraint: input.constraint,
+                taxes,
+                items,
+            });
+        }
+
+        let mut string_names = HashSet::new();
+        let mut strings = Vec::new();
+        for input in file.strings {
+            validate_pointers(&input.from)?;
+            let default = StringValue::parse(&input.default, &string_names)?;
+            if !string_names.insert(input.name.clone()) {
+                return Err(format!("string `{}` is declared twice", input.name));
+            }
+            strings.push(StringInput {
+                name: input.name,
+                from: input.from,
+                default,
+            });
+        }
+
+        let checks = file
+            .checks
+            .into_iter()
+            .map(|check| {
+                if let Some(name) = check.report.iter().find(|n| !amount_names.contains(*n)) {
+                    return Err(format!("unknown amount `{name}` in check report"));
+                }
+                Ok(Check {
+                    condition: Condition::parse(&check.assert, &amount_names)?,
+                    error: check.error,
+                    report: check.report,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if file.lines.is_empty() {
+            return Err("rule must declare at least one line".to_string());
+        }
+        let lines = file
+            .lines
+            .into_iter()
+            .map(|line| {
+                if !line.books.is_empty() && !line.except_books.is_empty() {
+                    return Err("line cannot set both books and except_books".to_string());
+                }
+                let kind = match (line.taxes, line.items, line.account, line.amount) {
+                    (None, Some(source), Some(account), None) if line.base_amount.is_none() => {
+                        if !item_names.contains(&source) {
+                            return Err(format!("unknown item amount `{source}`"));
+                        }
+                        LineKind::Items {
+                            source,
+                            account: StringValue::parse_account(&account, &string_names, accounts)?,
+                        }
+                    }
+                    (Some(tax), None, None, None) if line.base_amount.is_none() => {
+                        if !tax_names.contains(&tax) {
+                            return Err(format!("unknown tax amount `{tax}`"));
+                        }
+                        if tax_accounts.is_empty() {
+                            return Err("tax lines require rule set tax_accounts".to_string());
+                        }
+                        LineKind::Taxes(tax)
+                    }
+                    (None, None, Some(account), Some(amount)) => {
+                        let amount = Expr::parse(&amount, &amount_names)?;
+                        let base_amount = match line.base_amount.as_deref() {
+                            Some(expr) => Expr::parse(expr, &amount_names)?,
+                            None => amount.clone(),
+                        };
+                        LineKind::Fixed {
+                            account: StringValue::parse_account(&account, &string_names, accounts)?,
+                            amount,
+                            base_amount,
+                        }
+                    }
+                    _ => {
+                        return Err(
+                            "line needs taxes, items with an account, or an account and amount"
+                                .to_string(),
+                        )
+                    }
+                };
+                Ok(LineTemplate {
+                    when: line
+                        .when
+                        .as_deref()
+                        .map(|condition| Condition::parse(condition, &amount_names))
+                        .transpose()?,
+                    books: line.books,
+                    except_books: line.except_books,
+                    counterparty: line.counterparty,
+                    kind,
+                    side: parse_side(&line.side)?,
+                    dimensions: line
+                        .dimensions
+                        .iter()
+                        .map(|(name, value)| {
+                            Ok((name.clone(), StringValue::parse(value, &string_names)?))
+                        })
+                        .collect::<Result<Vec<_>, String>>()?,
+                    currency: StringValue::parse(&line.currency, &string_names)?,
+                    base_currency: StringValue::parse(&line.base_currency, &string_names)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            amounts,
+            strings,
+            checks,
+            lines,
+        })
+    }
+
+    fn derive(
+        &self,
+        ledger_book: Option<&str>,
+        counterparty: bool,
+        tax_accounts: &BTreeMap<String, String>,
+        payload: &Value,
+    ) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
+        let mut amounts = HashMap::new();
+        let mut taxes = HashMap::new();
+        let mut items = HashMap::new();
+        for input in &self.amounts {
+            if let Some(source) = &input.items {
+                let groups = read_item_groups(payload, source)?;
+                let total = groups
+                    .iter()
+                    .try_fold(0_i64, |total, group| total.checked_add(group.amount_minor))
+                    .ok_or_else(|| RuleEngineError::AmountOverflow(input.field.clone()))?;
+                amounts.insert(input.name.clone(), total);
+                items.insert(input.name.clone(), groups);
+                continue;
+            }
+            if let Some(source) = &input.taxes {
+                let components = read_tax_components(payload, source, &amounts)?;
+                let total = components
+                    .iter()
+                    .try_fold(0_i64, |total, component| {
+                        total.checked_add(component.amount_minor)
+                    })
+                    .ok_or_else(|| RuleEngineError::AmountOverflow(input.field.clone()))?;
+                amounts.insert(input.name.clone(), total);
+                taxes.insert(input.name.clone(), components);
+                continue;
+            }
+            let value = match optional_i64(payload, &input.from) {
+                Some(value) => value,
+                None => match &input.default {
+                    Some(expr) => expr.eval(&amounts, &input.field)?,
+                    None => return Err(RuleEngineError::MissingField(input.field.clone())),
+                },
+            };
+            if !input.constraint.allows(value) {
+                return Err(RuleEngineError::InvalidNumber(input.field.clone()));
+            }
+            amounts.insert(input.name.clone(), value);
+        }
+
+        let mut strings = HashMap::new();
+        for input in &self.strings {
+            let value = first_string(payload, &input.from)
+                .unwrap_or_else(|| input.default.resolve(&strings));
+            strings.insert(input.name.clone(), value);
+        }
+
+        for check in &self.checks {
+            if !check.condition.eval(&amounts, &check.error)? {
+                return Err(match check.error.as_str() {
+                    "invalid_settlement_math" => RuleEngineError::InvalidSettlementMath,
+                    _ if !check.report.is_empty() => RuleEngineError::ComponentsUnreconciled {
+                        check: check.error.clone(),
+                        amounts: check
+                            .report
+                            .iter()
+                            .map(|name| (name.clone(), amounts[name]))
+                            .collect(),
+                    },
+                    _ => RuleEngineError::CheckFailed(check.error.clone()),
+                });
+            }
+        }
+
+        let mut lines = Vec::new();
+        for template in &self.lines {
+            if template.counterparty != counterparty {
+                continue;
+            }
+            if !template.applies_to(ledger_book) {
+                continue;
+            }
+            if let Some(condition) = &template.when {
+                if !condition.eval(&amounts, "when")? {
+                    continue;
+                }
+            }
+            let dimensions = template
+                .dimensions
+                .iter()
+                .map(|(name, value)| (name.clone(), value.resolve(&strings)))
+                .collect::<BTreeMap<_, _>>();
+            match &template.kind {
+                LineKind::Fixed {
+                    account,
+                    amount,
+                    base_amount,
+                } => {
+                    let account_id = account.resolve(&strings);
+                    lines.push(DerivedPostingLine {
+                        amount_minor: amount.eval(&amounts, &account_id)?,
+                        base_amount_minor: base_amount.eval(&amounts, &account_id)?,
+                        account_id,
+                        entry_side: template.side.clone(),
+                        currency: template.currency.resolve(&strings),
+                        base_currency: template.base_currency.resolve(&strings),
+                        dimensions: dimensions.clone(),
+                    });
+                }
+                LineKind::Taxes(tax) => {
+                    for component in &taxes[tax] {
+                        if component.amount_minor == 0 {
+                            continue;
+                        }
+                        let account_id = tax_accounts
+                            .get(&component.tax_type)
+                            .cloned()
+                            .ok_or_else(|| {
+                                RuleEngineError::UnknownTaxType(component.tax_type.clone())
+                            })?;
+                        lines.push(DerivedPostingLine {
+                            account_id,
+                            entry_side: template.side.clone(),
+                            amount_minor: component.amount_minor,
+                            currency: template.currency.resolve(&strings),
+                            base_amount_minor: component.amount_minor,
+                            base_currency: template.base_currency.resolve(&strings),
+                            dimensions: dimensions
+                                .clone()
+                                .into_iter()
+                                .chain([
+                                    (
+                                        "tax_jurisdiction".to_string(),
+                                        component.jurisdiction.clone(),
+                                    ),
+                                    ("tax_type".to_string(), component.tax_type.clone()),
+                                ])
+                                .collect(),
+                        });
+                    }
+                }
+                LineKind::Items { source, account } => {
+                    let account_id = account.resolve(&strings);
+                    for group in &items[source] {
+                        if group.amount_minor == 0 {
+                            continue;
+                        }
+                        let mut line_dimensions = dimensions.clone();
+                        line_dimensions.extend(group.dimensions.clone());
+                        lines.push(DerivedPostingLine {
+                            account_id: account_id.clone(),
+                            entry_side: template.side.clone(),
+                            amount_minor: group.amount_minor,
+                            currency: template.currency.resolve(&strings),
+                            base_amount_minor: group.amount_minor,
+                            base_currency: template.base_currency.resolve(&strings),
+                            dimensions: line_dimensions,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(lines)
     }
-    lines.push(line(
-        "1105-CASH-CLEARING",
-        EntrySide::Credit,
-        gross,
-        &currency,
-        gross,
-        &base_currency,
-    ));
-    Ok(lines)
-}
-
-fn refund(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/refund_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "4050-REFUNDS",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn fee_assessed(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/fee_amount_minor", "/amount_minor"],
-        "fee_amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "6100-PAYMENT-FEES",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn chargeback_created(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/chargeback_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "6150-CHARGEBACK-LOSSES",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn payout_cleared(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/net_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "1010-BANK-OPERATING",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn dispute_opened(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/dispute_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "1205-DISPUTE-RECEIVABLE",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "6150-CHARGEBACK-LOSSES",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn dispute_won(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/dispute_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "1205-DISPUTE-RECEIVABLE",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn dispute_lost(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/dispute_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "6150-CHARGEBACK-LOSSES",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "1205-DISPUTE-RECEIVABLE",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn inntopia_reservation_captured(
+}
+
+fn read_item_groups(
     payload: &Value,
-) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/total_amount_minor", "/amount_minor"],
-        "total_amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    Ok(vec![
-        line(
-            "1105-CASH-CLEARING",
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            "2200-DEFERRED-REVENUE-RESERVATIONS",
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn intercompany_due_to_due_from(
+    source: &ItemSource,
+) -> Result<Vec<ItemGroup>, RuleEngineError> {
+    let Some(items) = payload.pointer(&source.from) else {
+        return Ok(Vec::new());
+    };
+    let field = source.from.trim_start_matches('/');
+    let items = items
+        .as_array()
+        .ok_or_else(|| RuleEngineError::InvalidNumber(field.to_string()))?;
+
+    let mut groups: Vec<ItemGroup> = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let amount_field = format!("{field}[{index}].amount");
+        let amount_minor = optional_i64(item, &source.amount)
+            .ok_or_else(|| RuleEngineError::MissingField(amount_field.clone()))?;
+        if amount_minor < 0 {
+            return Err(RuleEngineError::InvalidNumber(amount_field));
+        }
+        let dimensions = source
+            .dimensions
+            .iter()
+            .filter_map(|(name, pointers)| {
+                first_string(item, pointers).map(|value| (name.clone(), value))
+            })
+            .collect::<BTreeMap<_, _>>();
+        match groups.iter_mut().find(|g| g.dimensions == dimensions) {
+            Some(group) => {
+                group.amount_minor = group
+                    .amount_minor
+                    .checked_add(amount_minor)
+                    .ok_or(RuleEngineError::AmountOverflow(amount_field))?;
+            }
+            None => groups.push(ItemGroup {
+                dimensions,
+                amount_minor,
+            }),
+        }
+    }
+    Ok(groups)
+}
+
+fn read_tax_components(
     payload: &Value,
-) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/due_to_due_from_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    let due_from_account = first_string(payload, &["/due_from_account_id"])
-        .unwrap_or_else(|| "1305-DUE-FROM-AFFILIATES".to_string());
-    let due_to_account = first_string(payload, &["/due_to_account_id"])
-        .unwrap_or_else(|| "2305-DUE-TO-AFFILIATES".to_string());
-
-    Ok(vec![
-        line(
-            &due_from_account,
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            &due_to_account,
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn consolidation_elimination(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let amount = require_positive_i64(
-        payload,
-        &["/amount_minor", "/elimination_amount_minor"],
-        "amount_minor",
-    )?;
-    let currency = first_string(payload, &["/currency"]).unwrap_or_else(|| "USD".to_string());
-    let base_currency = first_string(payload, &["/base_currency"]).unwrap_or(currency.clone());
-
-    let debit_account = first_string(payload, &["/elimination_debit_account_id"])
-        .unwrap_or_else(|| "4999-INTERCOMPANY-ELIMINATION".to_string());
-    let credit_account = first_string(payload, &["/elimination_credit_account_id"])
-        .unwrap_or_else(|| "5999-INTERCOMPANY-ELIMINATION".to_string());
-
-    Ok(vec![
-        line(
-            &debit_account,
-            EntrySide::Debit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-        line(
-            &credit_account,
-            EntrySide::Credit,
-            amount,
-            &currency,
-            amount,
-            &base_currency,
-        ),
-    ])
-}
-
-fn fx_translation(payload: &Value) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
-    let translation_amount = optional_i64(
-        payload,
-        &[
-            "/translation_amount_minor",
-            "/fx_translation_amount_minor",
-            "/amount_minor",
-        ],
-    )
-    .ok_or(RuleEngineError::MissingField("translation_amount_minor"))?;
-    if translation_amount == 0 {
-        return Err(RuleEngineError::InvalidNumber("translation_amount_minor"));
-    }
-
-    let amount = translation_amount.abs();
-    let currency = first_string(payload, &["/base_currency", "/currency"])
-        .unwrap_or_else(|| "USD".to_string());
-
-    if translation_amount > 0 {
-        Ok(vec![
-            line(
-                "3100-CUMULATIVE-TRANSLATION-ADJUSTMENT",
-                EntrySide::Debit,
-                amount,
-                &currency,
-                amount,
-                &currency,
-            ),
-            line(
-                "7300-FX-TRANSLATION-GAIN-LOSS",
-                EntrySide::Credit,
-                amount,
-                &currency,
-                amount,
-                &currency,
-            ),
-        ])
+    source: &TaxSource,
+    amounts: &HashMap<String, i64>,
+) -> Result<Vec<TaxComponent>, RuleEngineError> {
+    let Some(tax_lines) = payload.pointer(&source.from) else {
+        return Ok(Vec::new());
+    };
+    let field = source.from.trim_start_matches('/');
+    let tax_lines = tax_lines
+        .as_array()
+        .ok_or_else(|| RuleEngineError::InvalidNumber(field.to_string()))?;
+
+    let mut components: Vec<TaxComponent> = Vec::new();
+    for (index, tax_line) in tax_lines.iter().enumerate() {
+        let required = |name: &str| {
+            tax_line
+                .get(name)
+                .and_then(Value::as_str)
+                .filter(|value| !value.trim().is_empty())
+                .map(ToString::to_string)
+                .ok_or_else(|| RuleEngineError::MissingField(format!("{field}[{index}].{name}")))
+        };
+        let tax_type = required("tax_type")?;
+        let jurisdiction = required("jurisdiction")?;
+        let amount_field = format!("{field}[{index}].amount_minor");
+        let amount_minor = optional_i64(
+            tax_line,
+            &[
+                "/amount_minor".to_string(),
+                "/amount/amount_minor".to_string(),
+            ],
+        )
+        .ok_or_else(|| RuleEngineError::MissingField(amount_field.clone()))?;
+        if amount_minor < 0 {
+            return Err(RuleEngineError::InvalidNumber(amount_field));
+        }
+        match components
+            .iter_mut()
+            .find(|c| c.tax_type == tax_type && c.jurisdiction == jurisdiction)
+        {
+            Some(component) => {
+                component.amount_minor = component
+                    .amount_minor
+                    .checked_add(amount_minor)
+                    .ok_or(RuleEngineError::AmountOverflow(amount_field))?;
+            }
+            None => components.push(TaxComponent {
+                tax_type,
+                jurisdiction,
+                amount_minor,
+            }),
+        }
+    }
+
+    match &source.scale {
+        Some((numerator, denominator)) => {
+            allocate_proportionally(components, amounts[numerator], amounts[denominator])
+                .ok_or_else(|| RuleEngineError::InvalidNumber(denominator.clone()))
+        }
+        None => Ok(components),
+    }
+}
+
+fn allocate_proportionally(
+    components: Vec<TaxComponent>,
+    numerator: i64,
+    denominator: i64,
+) -> Option<Vec<TaxComponent>> {
+    if denominator <= 0 || numerator < 0 {
+        return None;
+    }
+    let (numerator, denominator) = (i128::from(numerator), i128::from(denominator));
+    let total = components
+        .iter()
+        .map(|component| i128::from(component.amount_minor))
+        .sum::<i128>();
+    let target = (total * numerator * 2 + denominator) / (denominator * 2);
+
+    let mut remainders = Vec::with_capacity(components.len());
+    let mut allocated = Vec::with_capacity(components.len());
+    for component in &components {
+        let scaled = i128::from(component.amount_minor) * numerator;
+        allocated.push(scaled / denominator);
+        remainders.push(scaled % denominator);
+    }
+    let mut order = (0..components.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| remainders[*b].cmp(&remainders[*a]).then(a.cmp(b)));
+    let shortfall = target - allocated.iter().sum::<i128>();
+    for index in order.into_iter().take(usize::try_from(shortfall).ok()?) {
+        allocated[index] += 1;
+    }
+
+    components
+        .into_iter()
+        .zip(allocated)
+        .map(|(component, amount)| {
+            Some(TaxComponent {
+                amount_minor: i64::try_from(amount).ok()?,
+                ..component
+            })
+        })
+        .collect()
+}
+
+fn validate_pointers(pointers: &[String]) -> Result<(), String> {
+    match pointers.iter().find(|pointer| !pointer.starts_with('/')) {
+        Some(pointer) => Err(format!("`{pointer}` is not a JSON pointer")),
+        None => Ok(()),
+    }
+}
+
+fn parse_side(side: &str) -> Result<EntrySide, String> {
+    if side.eq_ignore_ascii_case("debit") {
+        Ok(EntrySide::Debit)
+    } else if side.eq_ignore_ascii_case("credit") {
+        Ok(EntrySide::Credit)
     } else {
-        Ok(vec![
-            line(
-                "7300-FX-TRANSLATION-GAIN-LOSS",
-                EntrySide::Debit,
-                amount,
-                &currency,
-                amount,
-                &currency,
-            ),
-            line(
-                "3100-CUMULATIVE-TRANSLATION-ADJUSTMENT",
-                EntrySide::Credit,
-                amount,
-                &currency,
-                amount,
-                &currency,
-            ),
-        ])
+        Err(format!("invalid entry side `{side}`"))
     }
 }
 
-fn line(
-    account_id: &str,
-    entry_side: EntrySide,
-    amount_minor: i64,
-    currency: &str,
-    base_amount_minor: i64,
-    base_currency: &str,
-) -> DerivedPostingLine {
-    DerivedPostingLine {
-        account_id: account_id.to_string(),
-        entry_side,
-        amount_minor,
-        currency: currency.to_string(),
-        base_amount_minor,
-        base_currency: base_currency.to_string(),
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Literal(i64),
+    Var(String),
+    Neg(Box<Expr>),
+    Abs(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Compare(Expr, Comparison, Expr),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Expr {
+    fn parse(source: &str, defined: &HashSet<String>) -> Result<Self, String> {
+        let mut parser = Parser::new(source, defined)?;
+        let expr = parser.expr()?;
+        parser.finish()?;
+        Ok(expr)
+    }
+
+    fn eval(&self, amounts: &HashMap<String, i64>, context: &str) -> Result<i64, RuleEngineError> {
+        let overflow = || RuleEngineError::AmountOverflow(context.to_string());
+        match self {
+            Self::Literal(value) => Ok(*value),
+            Self::Var(name) => Ok(amounts[name]),
+            Self::Neg(inner) => inner
+                .eval(amounts, context)?
+                .checked_neg()
+                .ok_or_else(overflow),
+            Self::Abs(inner) => inner
+                .eval(amounts, context)?
+                .checked_abs()
+                .ok_or_else(overflow),
+            Self::Add(left, right) => left
+                .eval(amounts, context)?
+                .checked_add(right.eval(amounts, context)?)
+                .ok_or_else(overflow),
+            Self::Sub(left, right) => left
+                .eval(amounts, context)?
+                .checked_sub(right.eval(amounts, context)?)
+                .ok_or_else(overflow),
+            Self::Mul(left, right) => left
+                .eval(amounts, context)?
+                .checked_mul(right.eval(amounts, context)?)
+                .ok_or_else(overflow),
+        }
     }
 }
 
-fn require_positive_i64(
-    payload: &Value,
-    pointers: &[&str],
-    field_name: &'static str,
-) -> Result<i64, RuleEngineError> {
-    let value = optional_i64(payload, pointers).ok_or(RuleEngineError::MissingField(field_name))?;
-    if value <= 0 {
-        return Err(RuleEngineError::InvalidNumber(field_name));
+impl Condition {
+    fn parse(source: &str, defined: &HashSet<String>) -> Result<Self, String> {
+        let mut parser = Parser::new(source, defined)?;
+        let condition = parser.condition()?;
+        parser.finish()?;
+        Ok(condition)
     }
-    Ok(value)
+
+    fn eval(&self, amounts: &HashMap<String, i64>, context: &str) -> Result<bool, RuleEngineError> {
+        match self {
+            Self::Compare(left, comparison, right) => {
+                let left = left.eval(amounts, context)?;
+                let right = right.eval(amounts, context)?;
+                Ok(match comparison {
+                    Comparison::Eq => left == right,
+                    Comparison::Ne => left != right,
+                    Comparison::Lt => left < right,
+                    Comparison::Le => left <= right,
+                    Comparison::Gt => left > right,
+                    Comparison::Ge => left >= right,
+                })
+            }
+            Self::And(left, right) => {
+                Ok(left.eval(amounts, context)? && right.eval(amounts, context)?)
+            }
+            Self::Or(left, right) => {
+                Ok(left.eval(amounts, context)? || right.eval(amounts, context)?)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    LParen,
+    RParen,
+    Compare(Comparison),
 }
 
-fn optional_i64(payload: &Value, pointers: &[&str]) -> Option<i64> {
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    defined: &'a HashSet<String>,
+    source: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str, defined: &'a HashSet<String>) -> Result<Self, String> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            position: 0,
+            defined,
+            source,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(format!(
+                "unexpected {token:?} in expression `{}`",
+                self.source
+            )),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition, String> {
+        let mut left = self.conjunction()?;
+        while self.peek() == Some(&Token::Ident("or".to_string())) {
+            self.next();
+            left = Condition::Or(Box::new(left), Box::new(self.conjunction()?));
+        }
+        Ok(left)
+    }
+
+    fn conjunction(&mut self) -> Result<Condition, String> {
+        let mut left = self.comparison()?;
+        while self.peek() == Some(&Token::Ident("and".to_string())) {
+            self.next();
+            left = Condition::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Condition, String> {
+        let left = self.expr()?;
+        match self.next() {
+            Some(Token::Compare(comparison)) => {
+                Ok(Condition::Compare(left, comparison, self.expr()?))
+            }
+            _ => Err(format!("expected a comparison in `{}`", self.source)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.next();
+                    left = Expr::Add(Box::new(left), Box::new(self.term()?));
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    left = Expr::Sub(Box::new(left), Box::new(self.term()?));
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::Star) {
+            self.next();
+            left = Expr::Mul(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Minus) {
+            self.next();
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Literal(value)),
+            Some(Token::LParen) => {
+                let inner = self.expr()?;
+                self.expect_rparen()?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) if name == "abs" => {
+                if self.next() != Some(Token::LParen) {
+                    return Err(format!("expected `(` after abs in `{}`", self.source));
+                }
+                let inner = self.expr()?;
+                self.expect_rparen()?;
+                Ok(Expr::Abs(Box::new(inner)))
+            }
+            Some(Token::Ident(name)) if self.defined.contains(&name) => Ok(Expr::Var(name)),
+            Some(Token::Ident(name)) => {
+                Err(format!("unknown amount `{name}` in `{}`", self.source))
+            }
+            _ => Err(format!("incomplete expression `{}`", self.source)),
+        }
+    }
+
+    fn expect_rparen(&mut self) -> Result<(), String> {
+        match self.next() {
+            Some(Token::RParen) => Ok(()),
+            _ => Err(format!("expected `)` in `{}`", self.source)),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        let (token, width) = match c {
+            ' ' | '\t' => {
+                index += 1;
+                continue;
+            }
+            '+' => (Token::Plus, 1),
+            '-' => (Token::Minus, 1),
+            '*' => (Token::Star, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '=' if next == Some('=') => (Token::Compare(Comparison::Eq), 2),
+            '!' if next == Some('=') => (Token::Compare(Comparison::Ne), 2),
+            '<' if next == Some('=') => (Token::Compare(Comparison::Le), 2),
+            '>' if next == Some('=') => (Token::Compare(Comparison::Ge), 2),
+            '<' => (Token::Compare(Comparison::Lt), 1),
+            '>' => (Token::Compare(Comparison::Gt), 1),
+            c if c.is_ascii_digit() => {
+                let digits = chars[index..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>();
+                let value = digits
+                    .parse()
+                    .map_err(|_| format!("number `{digits}` is out of range"))?;
+                (Token::Number(value), digits.len())
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let ident = chars[index..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                    .collect::<String>();
+                let width = ident.len();
+                (Token::Ident(ident), width)
+            }
+            other => return Err(format!("unexpected character `{other}` in `{source}`")),
+        };
+        tokens.push(token);
+        index += width;
+    }
+    Ok(tokens)
+}
+
+fn optional_i64(payload: &Value, pointers: &[String]) -> Option<i64> {
     pointers.iter().find_map(|pointer| {
         payload.pointer(pointer).and_then(|value| match value {
             Value::Number(number) => number.as_i64(),
@@ -545,7 +1379,7 @@ fn optional_i64(payload: &Value, pointers: &[&str]) -> Option<i64> {
     })
 }
 
-fn first_string(payload: &Value, pointers: &[&str]) -> Option<String> {
+fn first_string(payload: &Value, pointers: &[String]) -> Option<String> {
     pointers
         .iter()
         .find_map(|pointer| payload.pointer(pointer).and_then(Value::as_str))
@@ -557,7 +1391,12 @@ mod tests {
     use ledger_posting::EntrySide;
     use serde_json::json;
 
-    use super::{derive_lines_v1, DerivedPostingLine, RuleEngineError};
+    use std::path::Path;
+
+    use super::{
+        derive_lines_v1, DerivedPostingLine, RuleEngineError, RuleSet, RuleSetError,
+        RuleSetRegistry,
+    };
 
     #[test]
     fn order_captured_maps_to_revenue_and_cash_clearing() {
@@ -716,7 +1555,10 @@ mod tests {
     #[test]
     fn dispute_opened_requires_positive_amount() {
         let error = derive_lines_v1("dispute.opened.v1", &json!({"amount_minor": 0})).unwrap_err();
-        assert_eq!(error, RuleEngineError::InvalidNumber("amount_minor"));
+        assert_eq!(
+            error,
+            RuleEngineError::InvalidNumber("amount_minor".to_string())
+        );
     }
 
     #[test]
@@ -774,6 +1616,602 @@ mod tests {
         );
     }
 
+    #[test]
+    fn order_captured_carries_explicit_base_amount() {
+        let lines = derive_lines_v1(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 10000,
+                "currency": "CAD",
+                "base_amount_minor": 7300,
+                "base_currency": "USD"
+            }),
+        )
+        .unwrap();
+
+        assert!(lines
+            .iter()
+            .all(|line| line.base_amount_minor == 7300 && line.base_currency == "USD"));
+    }
+
+    #[test]
+    fn payment_settled_without_fee_omits_fee_line() {
+        let lines = derive_lines_v1(
+            "payment.settled.v1",
+            &json!({"gross_amount_minor": 10000, "net_amount_minor": 10000}),
+        )
+        .unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_balanced(&lines);
+
+        let error = derive_lines_v1(
+            "payment.settled.v1",
+            &json!({"gross_amount_minor": 10000, "fee_amount_minor": -1}),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            RuleEngineError::InvalidNumber("fee_amount_minor".to_string())
+        );
+    }
+
+    #[test]
+    fn custom_rule_set_supports_conditions_and_checks() {
+        let rule_set = RuleSet::from_json(
+            Path::new("v2.json"),
+            json!({
+                "ruleset_version": "v2",
+                "rules": {
+                    "refund.v1": {
+                        "amounts": [
+                            {"name": "refund", "from": ["/refund_amount_minor"], "constraint": "positive"},
+                            {"name": "restocking", "from": ["/restocking_fee_minor"], "default": "0"}
+                        ],
+                        "strings": [
+                            {"name": "currency", "from": ["/currency"], "default": "USD"},
+                            {"name": "base_currency", "default": "$currency"}
+                        ],
+                        "checks": [{"assert": "restocking >= 0 and restocking < refund", "error": "restocking_exceeds_refund"}],
+                        "lines": [
+                            {"account": "4050-REFUNDS", "side": "debit", "amount": "refund"},
+                            {"account": "1105-CASH-CLEARING", "side": "credit", "amount": "refund - restocking"},
+                            {"when": "restocking > 0", "account": "4060-RESTOCKING", "side": "credit", "amount": "restocking"}
+                        ]
+                    }
+                }
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let lines = rule_set
+            .derive(
+                "refund.v1",
+                None,
+                &json!({"refund_amount_minor": 1500, "restocking_fee_minor": 200}),
+            )
+            .unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].amount_minor, 1300);
+        assert_balanced(&lines);
+
+        let lines = rule_set
+            .derive("refund.v1", None, &json!({"refund_amount_minor": 1500}))
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+
+        let error = rule_set
+            .derive(
+                "refund.v1",
+                None,
+                &json!({"refund_amount_minor": 100, "restocking_fee_minor": 200}),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RuleEngineError::CheckFailed("restocking_exceeds_refund".to_string())
+        );
+    }
+
+    #[test]
+    fn book_scoped_lines_apply_only_to_listed_books() {
+        let rule_set = RuleSet::from_json(
+            Path::new("v2.json"),
+            json!({
+                "ruleset_version": "v2",
+                "rules": {
+                    "order.captured.v1": {
+                        "amounts": [{"name": "amount", "from": ["/amount_minor"], "constraint": "positive"}],
+                        "strings": [
+                            {"name": "currency", "default": "USD"},
+                            {"name": "base_currency", "default": "USD"}
+                        ],
+                        "lines": [
+                            {"account": "1105-CASH-CLEARING", "side": "debit", "amount": "amount"},
+                            {"books": ["US_GAAP"], "account": "4000-REVENUE", "side": "credit", "amount": "amount"},
+                            {"books": ["IFRS"], "account": "4001-REVENUE-IFRS", "side": "credit", "amount": "amount"}
+                        ]
+                    }
+                }
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+        let payload = json!({"amount_minor": 500});
+
+        let ifrs = rule_set
+            .derive("order.captured.v1", Some("IFRS"), &payload)
+            .unwrap();
+        assert_eq!(ifrs[1].account_id, "4001-REVENUE-IFRS");
+        assert_balanced(&ifrs);
+        let us_gaap = rule_set
+            .derive("order.captured.v1", Some("US_GAAP"), &payload)
+            .unwrap();
+        assert_eq!(us_gaap[1].account_id, "4000-REVENUE");
+        let unscoped = rule_set
+            .derive("order.captured.v1", None, &payload)
+            .unwrap();
+        assert_eq!(unscoped.len(), 1);
+    }
+
+    #[test]
+    fn counterparty_lines_derive_separately_from_the_posting_entity() {
+        let registry = RuleSetRegistry::default();
+        let rule_set = registry.get("v5").unwrap();
+        let payload = json!({
+            "card_id": "gc_1",
+            "program_id": "RESORT_GIFT",
+            "amount_minor": 2500,
+            "channel": "POS",
+            "issuing_legal_entity_id": "US_CO_01",
+            "redeeming_legal_entity_id": "CA_BC_01"
+        });
+
+        let redeemer = rule_set
+            .derive("giftcard.redeemed_cross_entity.v1", None, &payload)
+            .unwrap();
+        assert_eq!(redeemer[0].account_id, "1305-DUE-FROM-AFFILIATES");
+        assert_eq!(redeemer[0].dimensions["intercompany"], "US_CO_01");
+        assert_balanced(&redeemer);
+
+        let issuer = rule_set
+            .derive_counterparty("giftcard.redeemed_cross_entity.v1", None, &payload)
+            .unwrap();
+        assert_eq!(issuer[0].account_id, "2400-GIFT-CARD-LIABILITY");
+        assert_eq!(issuer[0].dimensions["card_program"], "RESORT_GIFT");
+        assert_eq!(issuer[1].dimensions["intercompany"], "CA_BC_01");
+        assert_balanced(&issuer);
+
+        assert!(rule_set
+            .derive_counterparty("giftcard.redeemed.v1", None, &payload)
+            .unwrap()
+            .is_empty());
+    }
+
+    fn derive_v2(event_type: &str, payload: &serde_json::Value) -> Vec<DerivedPostingLine> {
+        RuleSetRegistry::default()
+            .get("v2")
+            .unwrap()
+            .derive(event_type, None, payload)
+            .unwrap()
+    }
+
+    fn tax_dimensions(line: &DerivedPostingLine) -> (&str, &str) {
+        (
+            line.dimensions["tax_jurisdiction"].as_str(),
+            line.dimensions["tax_type"].as_str(),
+        )
+    }
+
+    #[test]
+    fn v2_order_captured_posts_tax_payable_per_jurisdiction() {
+        let lines = derive_v2(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 17120,
+                "currency": "USD",
+                "tax_lines": [
+                    {"tax_id": "CO_SALES_TAX", "tax_type": "US_STATE", "jurisdiction": "US-CO", "amount_minor": 520},
+                    {"tax_id": "SUMMIT_COUNTY", "tax_type": "US_LOCAL", "jurisdiction": "US-CO-SUMMIT", "amount": {"amount_minor": 400}},
+                    {"tax_id": "BRECK_TOWN", "tax_type": "US_LOCAL", "jurisdiction": "US-CO-BRECKENRIDGE", "amount_minor": 200}
+                ]
+            }),
+        );
+
+        assert_balanced(&lines);
+        assert_eq!(lines[1].account_id, "4000-REVENUE");
+        assert_eq!(lines[1].amount_minor, 16000);
+        assert_eq!(lines[2].account_id, "2105-SALES-TAX-PAYABLE");
+        assert_eq!(tax_dimensions(&lines[2]), ("US-CO", "US_STATE"));
+        assert_eq!(lines[3].account_id, "2106-LOCAL-SALES-TAX-PAYABLE");
+        assert_eq!(
+            tax_dimensions(&lines[4]),
+            ("US-CO-BRECKENRIDGE", "US_LOCAL")
+        );
+    }
+
+    #[test]
+    fn v2_order_captured_posts_canadian_sales_taxes() {
+        let lines = derive_v2(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 11200,
+                "currency": "CAD",
+                "tax_lines": [
+                    {"tax_type": "CA_GST", "jurisdiction": "CA", "amount_minor": 500},
+                    {"tax_type": "CA_PST_BC", "jurisdiction": "CA-BC", "amount_minor": 700}
+                ]
+            }),
+        );
+        assert_balanced(&lines);
+        let accounts = lines
+            .iter()
+            .map(|l| l.account_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            accounts,
+            vec![
+                "1105-CASH-CLEARING",
+                "4000-REVENUE",
+                "2110-GST-HST-PAYABLE",
+                "2115-BC-PST-PAYABLE"
+            ]
+        );
+
+        let lines = derive_v2(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 11498,
+                "currency": "CAD",
+                "tax_lines": [
+                    {"tax_type": "CA_GST", "jurisdiction": "CA", "amount_minor": 500},
+                    {"tax_type": "CA_QST", "jurisdiction": "CA-QC", "amount_minor": 998}
+                ]
+            }),
+        );
+        assert_eq!(lines[3].account_id, "2120-QST-PAYABLE");
+        assert_eq!(lines[1].amount_minor, 10000);
+    }
+
+    #[test]
+    fn v2_refund_reverses_tax_proportionally() {
+        let tax_lines = json!([
+            {"tax_type": "CA_GST", "jurisdiction": "CA", "amount_minor": 500},
+            {"tax_type": "CA_PST_BC", "jurisdiction": "CA-BC", "amount_minor": 700}
+        ]);
+        let lines = derive_v2(
+            "refund.v1",
+            &json!({
+                "refund_amount_minor": 3733,
+                "order_total_minor": 11200,
+                "currency": "CAD",
+                "tax_lines": tax_lines
+            }),
+        );
+
+        assert_balanced(&lines);
+        assert_eq!(lines[1].entry_side, EntrySide::Debit);
+        assert_eq!(lines[1].amount_minor, 167);
+        assert_eq!(lines[2].amount_minor, 233);
+        assert_eq!(lines[0].amount_minor, 3733 - 400);
+
+        let full = derive_v2(
+            "refund.v1",
+            &json!({"refund_amount_minor": 11200, "currency": "CAD", "tax_lines": tax_lines}),
+        );
+        assert_eq!(full[1].amount_minor, 500);
+        assert_eq!(full[2].amount_minor, 700);
+
+        let error = RuleSetRegistry::default()
+            .get("v2")
+            .unwrap()
+            .derive(
+                "refund.v1",
+                None,
+                &json!({"refund_amount_minor": 12000, "order_total_minor": 11200, "tax_lines": tax_lines}),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RuleEngineError::CheckFailed("refund_exceeds_order_total".to_string())
+        );
+    }
+
+    #[test]
+    fn v2_rejects_unknown_tax_types_and_v1_ignores_tax_lines() {
+        let payload = json!({
+            "amount_minor": 10000,
+            "tax_lines": [{"tax_type": "VAT", "jurisdiction": "FR", "amount_minor": 100}]
+        });
+        let error = RuleSetRegistry::default()
+            .get("v2")
+            .unwrap()
+            .derive("order.captured.v1", None, &payload)
+            .unwrap_err();
+        assert_eq!(error, RuleEngineError::UnknownTaxType("VAT".to_string()));
+
+        let v1 = derive_lines_v1("order.captured.v1", &payload).unwrap();
+        assert_eq!(v1[1].account_id, "4000-REVENUE");
+        assert_eq!(v1[1].amount_minor, 10000);
+    }
+
+    fn derive_v3(
+        event_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Vec<DerivedPostingLine>, RuleEngineError> {
+        RuleSetRegistry::default()
+            .get("v3")
+            .unwrap()
+            .derive(event_type, None, payload)
+    }
+
+    #[test]
+    fn v3_order_captured_decomposes_line_items_and_components() {
+        let lines = derive_v3(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 12990,
+                "currency": "USD",
+                "channel": "web",
+                "line_items": [
+                    {"product_id": "LIFT-1DAY", "line_subtotal_minor": 8000},
+                    {"product_id": "RENTAL-SKI", "line_subtotal": {"amount_minor": 3000}},
+                    {"product_id": "LIFT-1DAY", "line_subtotal_minor": 2000, "channel": "kiosk"}
+                ],
+                "discounts": [{"discount_id": "EARLYBIRD", "amount_minor": 1500}],
+                "service_charges": [{"type": "resort_fee", "amount_minor": 500}],
+                "tips": [{"staff_id": "emp_7", "amount_minor": 300}],
+                "tax_lines": [
+                    {"tax_type": "US_STATE", "jurisdiction": "US-CO", "amount_minor": 690}
+                ]
+            }),
+        )
+        .unwrap();
+
+        assert_balanced(&lines);
+        let summary = lines
+            .iter()
+            .map(|line| {
+                (
+                    line.account_id.as_str(),
+                    line.amount_minor,
+                    line.dimensions.get("product").map(String::as_str),
+                    line.dimensions.get("channel").map(String::as_str),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("1105-CASH-CLEARING", 12990, None, None),
+                ("4000-REVENUE", 8000, Some("LIFT-1DAY"), Some("web")),
+                ("4000-REVENUE", 3000, Some("RENTAL-SKI"), Some("web")),
+                ("4000-REVENUE", 2000, Some("LIFT-1DAY"), Some("kiosk")),
+                ("4070-DISCOUNTS-PROMOTIONS", 1500, None, Some("web")),
+                ("4200-SERVICE-CHARGE-REVENUE", 500, None, Some("web")),
+                ("2150-TIPS-PAYABLE", 300, None, None),
+                ("2105-SALES-TAX-PAYABLE", 690, None, None),
+            ]
+        );
+        assert_eq!(lines[4].entry_side, EntrySide::Debit);
+        assert_eq!(lines[4].dimensions["discount_id"], "EARLYBIRD");
+        assert_eq!(lines[6].dimensions["staff_id"], "emp_7");
+    }
+
+    #[test]
+    fn v3_order_without_line_items_posts_remainder_as_revenue() {
+        let lines = derive_v3(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 10800,
+                "tips": [{"staff_id": "emp_7", "amount_minor": 800}]
+            }),
+        )
+        .unwrap();
+
+        assert_balanced(&lines);
+        assert_eq!(lines[1].account_id, "4000-REVENUE");
+        assert_eq!(lines[1].amount_minor, 10000);
+        assert_eq!(lines[1].dimensions["channel"], "UNSPECIFIED");
+        assert_eq!(lines[2].account_id, "2150-TIPS-PAYABLE");
+    }
+
+    #[test]
+    fn v3_reports_components_that_do_not_reconcile_to_tender_total() {
+        let error = derive_v3(
+            "order.captured.v1",
+            &json!({
+                "amount_minor": 10000,
+                "line_items": [{"product_id": "LIFT-1DAY", "line_subtotal_minor": 9000}],
+                "tips": [{"staff_id": "emp_7", "amount_minor": 500}]
+            }),
+        )
+        .unwrap_err();
+
+        let RuleEngineError::ComponentsUnreconciled { check, amounts } = error else {
+            panic!("expected unreconciled components, got {error:?}");
+        };
+        assert_eq!(check, "order_components_unreconciled");
+        assert_eq!(amounts["line_items"], 9000);
+        assert_eq!(amounts["tips"], 500);
+        assert_eq!(amounts["unallocated"], 500);
+
+        assert_eq!(
+            derive_v3(
+                "order.captured.v1",
+                &json!({
+                    "amount_minor": 1000,
+                    "line_items": [{"product_id": "LIFT-1DAY"}]
+                }),
+            ),
+            Err(RuleEngineError::MissingField(
+                "line_items[0].amount".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn except_books_lines_give_way_to_book_specific_replacements() {
+        let registry = RuleSetRegistry::default();
+        let v1 = registry.get("v1").unwrap();
+        let payload = json!({"amount_minor": 2500});
+
+        let accounts = |lines: Vec<DerivedPostingLine>| {
+            assert_balanced(&lines);
+            lines
+                .into_iter()
+                .map(|line| (line.account_id, line.entry_side))
+                .collect::<Vec<_>>()
+        };
+        let refunds = (String::from("4050-REFUNDS"), EntrySide::Debit);
+        let revenue = (String::from("4000-REVENUE"), EntrySide::Debit);
+        let cash = (String::from("1105-CASH-CLEARING"), EntrySide::Credit);
+        assert_eq!(
+            accounts(v1.derive("refund.v1", Some("US_GAAP"), &payload).unwrap()),
+            vec![refunds.clone(), cash.clone()]
+        );
+        assert_eq!(
+            accounts(v1.derive("refund.v1", Some("IFRS"), &payload).unwrap()),
+            vec![revenue, cash.clone()]
+        );
+        assert_eq!(
+            accounts(v1.derive("refund.v1", None, &payload).unwrap()),
+            vec![refunds, cash]
+        );
+    }
+
+    #[test]
+    fn line_accounts_can_name_a_rule_set_account_role() {
+        let rule_set = RuleSet::from_json(
+            Path::new("roles.json"),
+            json!({
+                "ruleset_version": "roles",
+                "accounts": {"revenue": "4100-LODGING-REVENUE"},
+                "rules": {"e.v1": {
+                    "amounts": [{"name": "amount", "from": ["/amount_minor"]}],
+                    "strings": [
+                        {"name": "currency", "default": "USD"},
+                        {"name": "base_currency", "default": "$currency"}
+                    ],
+                    "lines": [
+                        {"account": "1105-CASH-CLEARING", "side": "debit", "amount": "amount"},
+                        {"account": "@revenue", "side": "credit", "amount": "amount"}
+                    ]
+                }}
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let lines = rule_set
+            .derive("e.v1", None, &json!({"amount_minor": 500}))
+            .unwrap();
+        assert_eq!(lines[1].account_id, "4100-LODGING-REVENUE");
+        assert_eq!(rule_set.account("revenue").unwrap(), "4100-LODGING-REVENUE");
+        assert_eq!(
+            rule_set.account("reservation_refunds_payable").unwrap_err(),
+            RuleEngineError::AccountNotNamed {
+                ruleset_version: "roles".to_string(),
+                role: "reservation_refunds_payable".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_rule_sets_are_rejected_at_load() {
+        let load = |rules: serde_json::Value| {
+            RuleSet::from_json(
+                Path::new("bad.json"),
+                json!({"ruleset_version": "bad", "rules": rules})
+                    .to_string()
+                    .as_bytes(),
+            )
+        };
+        let line = json!({"account": "4000-REVENUE", "side": "credit", "amount": "amount"});
+        let amount = json!({"name": "amount", "from": ["/amount_minor"]});
+
+        for rules in [
+            json!({"e.v1": {"amounts": [amount], "lines": []}}),
+            json!({"e.v1": {"lines": [line]}}),
+            json!({"e.v1": {"amounts": [amount], "lines": [{"account": "$missing", "side": "credit", "amount": "amount"}]}}),
+            json!({"e.v1": {"amounts": [amount], "lines": [{"account": "4000", "side": "sideways", "amount": "amount"}]}}),
+            json!({"e.v1": {"amounts": [amount], "lines": [{"account": "4000", "side": "debit", "amount": "amount +"}]}}),
+            json!({"e.v1": {"amounts": [amount], "checks": [{"assert": "amount", "error": "x"}], "lines": [line]}}),
+            json!({"e.v1": {"amounts": [{"name": "amount", "from": ["amount_minor"]}], "lines": [line]}}),
+            json!({"e.v1": {"amounts": [amount], "lines": [{"books": ["IFRS"], "except_books": ["US_GAAP"], "account": "4000", "side": "debit", "amount": "amount"}]}}),
+            json!({"e.v1": {"amounts": [amount], "lines": [{"account": "@revenue", "side": "credit", "amount": "amount"}]}}),
+        ] {
+            assert!(
+                matches!(load(rules.clone()), Err(RuleSetError::Invalid { .. })),
+                "{rules}"
+            );
+        }
+        assert!(matches!(
+            load(json!({"e.v1": {"amounts": [amount], "lines": [line], "extra": true}})),
+            Err(RuleSetError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn registry_keeps_builtin_v1_and_rejects_duplicate_versions() {
+        let registry = RuleSetRegistry::default();
+        assert_eq!(
+            registry.versions(),
+            vec![
+                "v1".to_string(),
+                "v2".to_string(),
+                "v3".to_string(),
+                "v4".to_string(),
+                "v5".to_string(),
+                "v6".to_string()
+            ]
+        );
+        assert!(registry.get("v1").unwrap().supports("fx.translation.v1"));
+        assert_eq!(
+            registry.get("v0").unwrap_err(),
+            RuleEngineError::UnknownRulesetVersion("v0".to_string())
+        );
+
+        let dir = std::env::temp_dir().join(format!(
+            "posting-api-rulesets-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rule_set = json!({
+            "ruleset_version": "tenant-v1",
+            "rules": {"e.v1": {
+                "amounts": [{"name": "amount", "from": ["/amount_minor"]}],
+                "strings": [{"name": "currency", "default": "USD"}, {"name": "base_currency", "default": "USD"}],
+                "lines": [{"account": "4000-REVENUE", "side": "credit", "amount": "amount"}]
+            }}
+        })
+        .to_string();
+        std::fs::write(dir.join("a.json"), &rule_set).unwrap();
+        let loaded = RuleSetRegistry::load_dir(&dir).unwrap();
+        assert_eq!(
+            loaded.versions(),
+            vec![
+                "tenant-v1".to_string(),
+                "v1".to_string(),
+                "v2".to_string(),
+                "v3".to_string(),
+                "v4".to_string(),
+                "v5".to_string(),
+                "v6".to_string()
+            ]
+        );
+
+        std::fs::write(dir.join("b.json"), &rule_set).unwrap();
+        let duplicate = RuleSetRegistry::load_dir(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(matches!(duplicate, Err(RuleSetError::DuplicateVersion(v)) if v == "tenant-v1"));
+    }
+
     fn assert_balanced(lines: &[DerivedPostingLine]) {
         let debit_total = lines
             .iter()
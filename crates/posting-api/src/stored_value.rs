@@ -0,0 +1,907 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use ledger_posting::{JournalHeader, JournalRecord, JournalStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::rule_engine::RuleEngineError;
+use crate::{
+    deterministic_journal_id, first_string, journal_lines, period_error_response,
+    rule_engine_error_response, signed_amount, AppState, PostEventRequest,
+};
+
+const STORED_VALUE_STORE_FILENAME: &str = "stored_value_store.json";
+const ISSUED_EVENT: &str = "giftcard.issued.v1";
+const RELOADED_EVENT: &str = "giftcard.reloaded.v1";
+const REDEEMED_EVENT: &str = "giftcard.redeemed.v1";
+pub(crate) const CROSS_ENTITY_REDEEMED_EVENT: &str = "giftcard.redeemed_cross_entity.v1";
+const ESCHEATED_EVENT: &str = "giftcard.escheated.v1";
+const BREAKAGE_EVENT: &str = "giftcard.breakage_recognized.v1";
+const LIABILITY_ACCOUNT: &str = "2400-GIFT-CARD-LIABILITY";
+const UNSPECIFIED: &str = "UNSPECIFIED";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CardStatus {
+    Active,
+    Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StoredValueCard {
+    pub card_id: String,
+    pub program_id: String,
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub currency: String,
+    pub status: CardStatus,
+    pub balance_minor: i64,
+    pub issued_minor: i64,
+    pub reloaded_minor: i64,
+    pub redeemed_minor: i64,
+    pub cross_entity_redeemed_minor: i64,
+    pub escheated_minor: i64,
+    pub breakage_minor: i64,
+    pub redeemed_by_channel: BTreeMap<String, i64>,
+}
+
+impl StoredValueCard {
+    fn key(&self) -> String {
+        card_key(
+            &self.tenant_id,
+            &self.legal_entity_id,
+            &self.ledger_book,
+            &self.card_id,
+        )
+    }
+}
+
+fn card_key(tenant_id: &str, legal_entity_id: &str, ledger_book: &str, card_id: &str) -> String {
+    format!("{tenant_id}:{legal_entity_id}:{ledger_book}:{card_id}")
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StoredValueError {
+    #[error("gift card {0} not found")]
+    CardNotFound(String),
+    #[error("gift card {0} already exists")]
+    CardAlreadyExists(String),
+    #[error("gift card {0} is not active")]
+    CardNotActive(String),
+    #[error("gift card {card_id} is denominated in {card_currency}, not {currency}")]
+    CurrencyMismatch {
+        card_id: String,
+        card_currency: String,
+        currency: String,
+    },
+    #[error("{amount_minor} exceeds the {balance_minor} balance of gift card {card_id}")]
+    InsufficientBalance {
+        card_id: String,
+        amount_minor: i64,
+        balance_minor: i64,
+    },
+    #[error("gift card {0} changed while the event was being posted")]
+    CardStateChanged(String),
+    #[error("rule for `{0}` derived no gift card liability movement")]
+    NoLiabilityMovement(String),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredValueStore {
+    cards: BTreeMap<String, StoredValueCard>,
+}
+
+#[derive(Default)]
+pub struct InMemoryStoredValueRepository {
+    cards: BTreeMap<String, StoredValueCard>,
+    persistence: Option<Arc<WriteBehind<StoredValueStore>>>,
+}
+
+impl InMemoryStoredValueRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(STORED_VALUE_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: StoredValueStore = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "stored-value-write-behind")?);
+        Ok(Self {
+            cards: loaded.cards,
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        card_id: &str,
+    ) -> Option<&StoredValueCard> {
+        self.cards
+            .get(&card_key(tenant_id, legal_entity_id, ledger_book, card_id))
+    }
+
+    pub fn all(&self) -> Vec<StoredValueCard> {
+        self.cards.values().cloned().collect()
+    }
+
+    pub(crate) fn ensure_current(&self, updates: &[CardUpdate]) -> Result<(), StoredValueError> {
+        for update in updates {
+            if self.cards.get(&update.next.key()) != update.previous.as_ref() {
+                return Err(match update.previous {
+                    None => StoredValueError::CardAlreadyExists(update.next.card_id.clone()),
+                    Some(_) => StoredValueError::CardStateChanged(update.next.card_id.clone()),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn apply(&mut self, updates: Vec<CardUpdate>) {
+        if updates.is_empty() {
+            return;
+        }
+        for update in updates {
+            self.cards.insert(update.next.key(), update.next);
+        }
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(StoredValueStore {
+                cards: self.cards.clone(),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CardUpdate {
+    previous: Option<StoredValueCard>,
+    next: StoredValueCard,
+}
+
+pub(crate) struct StoredValueEvent {
+    event_type: String,
+    card_id: String,
+    program_id: String,
+    issuing_legal_entity_id: String,
+    payload: Value,
+    previous: Option<StoredValueCard>,
+}
+
+impl StoredValueEvent {
+    pub(crate) fn payload(&self) -> &Value {
+        &self.payload
+    }
+
+    pub(crate) fn counterpart_journal(
+        &self,
+        state: &AppState,
+        req: &PostEventRequest,
+        record: &JournalRecord,
+    ) -> Result<Option<JournalRecord>, ApiError> {
+        if self.event_type != CROSS_ENTITY_REDEEMED_EVENT {
+            return Ok(None);
+        }
+        let header = &record.header;
+        state.validate_legal_hold(
+            &header.tenant_id,
+            &self.issuing_legal_entity_id,
+            &header.ledger_book,
+            header.accounting_date,
+        )?;
+        state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+            .ensure_open(
+                &header.tenant_id,
+                &self.issuing_legal_entity_id,
+                &header.ledger_book,
+                header.accounting_date,
+                req.posting_authority(),
+            )
+            .map_err(period_error_response)?;
+        let derived = state
+            .rule_sets
+            .get(&header.ruleset_version)
+            .and_then(|rule_set| {
+                rule_set.derive_counterparty(
+                    &req.event_type,
+                    Some(&header.ledger_book),
+                    &self.payload,
+                )
+            })
+            .map_err(rule_engine_error_response)?;
+        let journal_id = counterpart_journal_id(header.journal_id, &self.issuing_legal_entity_id);
+        Ok(Some(JournalRecord {
+            header: JournalHeader {
+                journal_id,
+                journal_number: format!("S2-{}", &journal_id.to_string()[..8]),
+                legal_entity_id: self.issuing_legal_entity_id.clone(),
+                ..header.clone()
+            },
+            lines: journal_lines(derived),
+        }))
+    }
+
+    pub(crate) fn complete(
+        self,
+        record: &JournalRecord,
+        counterpart: Option<&JournalRecord>,
+    ) -> Result<CardUpdate, StoredValueError> {
+        let lines = counterpart.map_or(&record.lines, |counterpart| &counterpart.lines);
+        let liability_lines = lines
+            .iter()
+            .filter(|line| line.account_id == LIABILITY_ACCOUNT)
+            .collect::<Vec<_>>();
+        let Some(first) = liability_lines.first() else {
+            return Err(StoredValueError::NoLiabilityMovement(self.event_type));
+        };
+        let currency = first.currency.clone();
+        let channel = first
+            .dimensions
+            .get("channel")
+            .cloned()
+            .unwrap_or_else(|| UNSPECIFIED.to_string());
+        let movement = liability_lines
+            .iter()
+            .map(|line| signed_amount(line.entry_side.clone(), line.amount_minor))
+            .sum::<i64>();
+
+        let header = &record.header;
+        let Some(previous) = self.previous else {
+            return Ok(CardUpdate {
+                previous: None,
+                next: StoredValueCard {
+                    card_id: self.card_id,
+                    program_id: self.program_id,
+                    tenant_id: header.tenant_id.clone(),
+                    legal_entity_id: self.issuing_legal_entity_id,
+                    ledger_book: header.ledger_book.clone(),
+                    currency,
+                    status: CardStatus::Active,
+                    balance_minor: movement,
+                    issued_minor: movement,
+                    reloaded_minor: 0,
+                    redeemed_minor: 0,
+                    cross_entity_redeemed_minor: 0,
+                    escheated_minor: 0,
+                    breakage_minor: 0,
+                    redeemed_by_channel: BTreeMap::new(),
+                },
+            });
+        };
+        if currency != previous.currency {
+            return Err(StoredValueError::CurrencyMismatch {
+                card_id: previous.card_id,
+                card_currency: previous.currency,
+                currency,
+            });
+        }
+        if -movement > previous.balance_minor {
+            return Err(StoredValueError::InsufficientBalance {
+                card_id: previous.card_id,
+                amount_minor: -movement,
+                balance_minor: previous.balance_minor,
+            });
+        }
+
+        let mut next = previous.clone();
+        next.balance_minor += movement;
+        match self.event_type.as_str() {
+            RELOADED_EVENT => next.reloaded_minor += movement,
+            REDEEMED_EVENT | CROSS_ENTITY_REDEEMED_EVENT => {
+                next.redeemed_minor -= movement;
+                *next.redeemed_by_channel.entry(channel).or_default() -= movement;
+                if self.event_type == CROSS_ENTITY_REDEEMED_EVENT {
+                    next.cross_entity_redeemed_minor -= movement;
+                }
+            }
+            ESCHEATED_EVENT => next.escheated_minor -= movement,
+            _ => next.breakage_minor -= movement,
+        }
+        if matches!(self.event_type.as_str(), ESCHEATED_EVENT | BREAKAGE_EVENT)
+            && next.balance_minor == 0
+        {
+            next.status = CardStatus::Closed;
+        }
+        Ok(CardUpdate {
+            previous: Some(previous),
+            next,
+        })
+    }
+}
+
+/// Whether the journal moved a card balance. Reversing it would leave the card out of step with
+/// the GL, so corrections go through an offsetting gift card event instead.
+pub(crate) fn moves_card_balance(record: &JournalRecord) -> bool {
+    record
+        .lines
+        .iter()
+        .any(|line| line.account_id == LIABILITY_ACCOUNT && line.dimensions.contains_key("card_id"))
+}
+
+fn counterpart_journal_id(journal_id: Uuid, legal_entity_id: &str) -> Uuid {
+    deterministic_journal_id(&format!("{journal_id}:counterparty"), legal_entity_id)
+}
+
+pub(crate) fn prepare_stored_value_event(
+    state: &AppState,
+    req: &PostEventRequest,
+) -> Result<Option<StoredValueEvent>, ApiError> {
+    if !matches!(
+        req.event_type.as_str(),
+        ISSUED_EVENT
+            | RELOADED_EVENT
+            | REDEEMED_EVENT
+            | CROSS_ENTITY_REDEEMED_EVENT
+            | ESCHEATED_EVENT
+            | BREAKAGE_EVENT
+    ) {
+        return Ok(None);
+    }
+    let required = |field: &str, pointers: &[&str]| {
+        first_string(&req.payload, pointers)
+            .map(ToString::to_string)
+            .ok_or_else(|| {
+                rule_engine_error_response(RuleEngineError::MissingField(field.to_string()))
+            })
+    };
+    let card_id = required("card_id", &["/card_id"])?;
+    let issuing_legal_entity_id = if req.event_type == CROSS_ENTITY_REDEEMED_EVENT {
+        required("issuing_legal_entity_id", &["/issuing_legal_entity_id"])?
+    } else {
+        req.legal_entity_id.clone()
+    };
+
+    let cards = state
+        .stored_value
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::StoredValueStoreError))?;
+    let previous = cards
+        .get(
+            &req.tenant_id,
+            &issuing_legal_entity_id,
+            &req.ledger_book,
+            &card_id,
+        )
+        .cloned();
+    drop(cards);
+
+    let mut payload = req.payload.as_object().cloned().unwrap_or_default();
+    let program_id = match (&previous, req.event_type.as_str()) {
+        (Some(_), ISSUED_EVENT) => {
+            return Err(stored_value_error_response(
+                StoredValueError::CardAlreadyExists(card_id),
+            ));
+        }
+        (None, ISSUED_EVENT) => required("program_id", &["/program_id", "/card_program_id"])?,
+        (None, _) => {
+            return Err(stored_value_error_response(StoredValueError::CardNotFound(
+                card_id,
+            )));
+        }
+        (Some(card), _) if card.status != CardStatus::Active => {
+            return Err(stored_value_error_response(
+                StoredValueError::CardNotActive(card_id),
+            ));
+        }
+        (Some(card), event_type) => {
+            if matches!(event_type, ESCHEATED_EVENT | BREAKAGE_EVENT)
+                && payload.get("amount_minor").is_none_or(Value::is_null)
+            {
+                payload.insert("amount_minor".to_string(), json!(card.balance_minor));
+            }
+            card.program_id.clone()
+        }
+    };
+    payload.extend([
+        ("card_id".to_string(), json!(card_id)),
+        ("program_id".to_string(), json!(program_id)),
+        (
+            "issuing_legal_entity_id".to_string(),
+            json!(issuing_legal_entity_id),
+        ),
+        (
+            "redeeming_legal_entity_id".to_string(),
+            json!(req.legal_entity_id),
+        ),
+    ]);
+    Ok(Some(StoredValueEvent {
+        event_type: req.event_type.clone(),
+        card_id,
+        program_id,
+        issuing_legal_entity_id,
+        payload: Value::Object(payload),
+        previous,
+    }))
+}
+
+pub(crate) fn stored_value_error_response(error: StoredValueError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        StoredValueError::CardNotFound(card_id) => {
+            ApiError::not_found(ErrorCode::GiftCardNotFound).with_detail("card_id", card_id)
+        }
+        StoredValueError::CardAlreadyExists(card_id) => {
+            ApiError::conflict(ErrorCode::GiftCardAlreadyExists).with_detail("card_id", card_id)
+        }
+        StoredValueError::CardNotActive(card_id) => {
+            ApiError::conflict(ErrorCode::GiftCardNotActive).with_detail("card_id", card_id)
+        }
+        StoredValueError::CurrencyMismatch {
+            card_id,
+            card_currency,
+            currency,
+        } => ApiError::bad_request(ErrorCode::GiftCardCurrencyMismatch)
+            .with_detail("card_id", card_id)
+            .with_detail("card_currency", card_currency)
+            .with_detail("currency", currency),
+        StoredValueError::InsufficientBalance {
+            card_id,
+            amount_minor,
+            balance_minor,
+        } => ApiError::bad_request(ErrorCode::InsufficientStoredValue)
+            .with_detail("card_id", card_id)
+            .with_detail("amount_minor", amount_minor)
+            .with_detail("balance_minor", balance_minor),
+        StoredValueError::CardStateChanged(card_id) => {
+            ApiError::conflict(ErrorCode::GiftCardStateChanged).with_detail("card_id", card_id)
+        }
+        StoredValueError::NoLiabilityMovement(event_type) => {
+            ApiError::bad_request(ErrorCode::NoStoredValueMovement)
+                .with_detail("event_type", event_type)
+        }
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StoredValueCardQuery {
+    pub book: Option<String>,
+    pub legal_entity_id: Option<String>,
+    pub program_id: Option<String>,
+    pub card_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StoredValueCardList {
+    pub cards: Vec<StoredValueCard>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StoredValueReconciliationQuery {
+    pub book: String,
+    pub legal_entity_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, ToSchema)]
+pub struct StoredValueReconciliation {
+    pub book: String,
+    pub reconciled: bool,
+    pub rows: Vec<StoredValueReconciliationRow>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, ToSchema)]
+pub struct StoredValueReconciliationRow {
+    pub legal_entity_id: String,
+    pub program_id: String,
+    pub currency: String,
+    pub account_id: String,
+    pub active_cards: usize,
+    pub subledger_balance_minor: i64,
+    pub gl_balance_minor: i64,
+    pub difference_minor: i64,
+}
+
+pub fn reconcile_stored_value(
+    cards: &[StoredValueCard],
+    records: &[JournalRecord],
+    query: &StoredValueReconciliationQuery,
+) -> Vec<StoredValueReconciliationRow> {
+    let in_scope = |ledger_book: &str, legal_entity_id: &str| {
+        ledger_book == query.book
+            && query
+                .legal_entity_id
+                .as_ref()
+                .is_none_or(|wanted| wanted == legal_entity_id)
+    };
+    let mut balances = BTreeMap::<(String, String, String), (usize, i64, i64)>::new();
+    for card in cards {
+        if !in_scope(&card.ledger_book, &card.legal_entity_id) {
+            continue;
+        }
+        let entry = balances
+            .entry((
+                card.legal_entity_id.clone(),
+                card.program_id.clone(),
+                card.currency.clone(),
+            ))
+            .or_default();
+        if card.status == CardStatus::Active {
+            entry.0 += 1;
+        }
+        entry.1 += card.balance_minor;
+    }
+    for record in records {
+        let header = &record.header;
+        if header.status != JournalStatus::Posted
+            || !in_scope(&header.ledger_book, &header.legal_entity_id)
+        {
+            continue;
+        }
+        for line in record
+            .lines
+            .iter()
+            .filter(|line| line.account_id == LIABILITY_ACCOUNT)
+        {
+            let program_id = line
+                .dimensions
+                .get("card_program")
+                .map_or(UNSPECIFIED, String::as_str);
+            let entry = balances
+                .entry((
+                    header.legal_entity_id.clone(),
+                    program_id.to_string(),
+                    line.currency.clone(),
+                ))
+                .or_default();
+            entry.2 += signed_amount(line.entry_side.clone(), line.amount_minor);
+        }
+    }
+    balances
+        .into_iter()
+        .map(
+            |((legal_entity_id, program_id, currency), (active_cards, subledger, gl))| {
+                StoredValueReconciliationRow {
+                    legal_entity_id,
+                    program_id,
+                    currency,
+                    account_id: LIABILITY_ACCOUNT.to_string(),
+                    active_cards,
+                    subledger_balance_minor: subledger,
+                    gl_balance_minor: gl,
+                    difference_minor: gl - subledger,
+                }
+            },
+        )
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/stored-value/cards",
+    tag = "stored-value",
+    params(StoredValueCardQuery),
+    responses(
+        (status = 200, description = "Gift card balances and activity totals", body = StoredValueCardList),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn list_cards(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<StoredValueCardQuery>,
+) -> Result<Json<StoredValueCardList>, ApiError> {
+    let cards = state
+        .stored_value
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::StoredValueStoreError))?
+        .all()
+        .into_iter()
+        .filter(|card| {
+            query
+                .book
+                .as_ref()
+                .is_none_or(|book| *book == card.ledger_book)
+                && query
+                    .legal_entity_id
+                    .as_ref()
+                    .is_none_or(|legal_entity_id| *legal_entity_id == card.legal_entity_id)
+                && query
+                    .program_id
+                    .as_ref()
+                    .is_none_or(|program_id| *program_id == card.program_id)
+                && query
+                    .card_id
+                    .as_ref()
+                    .is_none_or(|card_id| *card_id == card.card_id)
+        })
+        .collect();
+    Ok(Json(StoredValueCardList { cards }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/stored-value/reconciliation",
+    tag = "stored-value",
+    params(StoredValueReconciliationQuery),
+    responses(
+        (status = 200, description = "Outstanding gift card balances against the GL liability", body = StoredValueReconciliation),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_reconciliation(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<StoredValueReconciliationQuery>,
+) -> Result<Json<StoredValueReconciliation>, ApiError> {
+    let records = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?
+        .all();
+    let cards = state
+        .stored_value
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::StoredValueStoreError))?
+        .all();
+    let rows = reconcile_stored_value(&cards, &records, &query);
+    Ok(Json(StoredValueReconciliation {
+        book: query.book,
+        reconciled: rows.iter().all(|row| row.difference_minor == 0),
+        rows,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Utc};
+    use ledger_posting::{EntrySide, JournalLine};
+
+    use super::*;
+
+    fn card(program_id: &str, balance_minor: i64) -> StoredValueCard {
+        StoredValueCard {
+            card_id: format!("gc_{program_id}"),
+            program_id: program_id.to_string(),
+            tenant_id: "tenant_1".to_string(),
+            legal_entity_id: "US_CO_01".to_string(),
+            ledger_book: "US_GAAP".to_string(),
+            currency: "USD".to_string(),
+            status: CardStatus::Active,
+            balance_minor,
+            issued_minor: balance_minor,
+            reloaded_minor: 0,
+            redeemed_minor: 0,
+            cross_entity_redeemed_minor: 0,
+            escheated_minor: 0,
+            breakage_minor: 0,
+            redeemed_by_channel: BTreeMap::new(),
+        }
+    }
+
+    fn liability_journal(
+        status: JournalStatus,
+        program_id: &str,
+        amount_minor: i64,
+    ) -> JournalRecord {
+        JournalRecord {
+            header: JournalHeader {
+                journal_id: Uuid::new_v4(),
+                journal_number: "S2-test".to_string(),
+                status,
+                tenant_id: "tenant_1".to_string(),
+                legal_entity_id: "US_CO_01".to_string(),
+                ledger_book: "US_GAAP".to_string(),
+                accounting_date: NaiveDate::from_ymd_opt(2026, 2, 21).unwrap(),
+                posted_at: Utc::now(),
+                source_event_ids: vec!["evt_1".to_string()],
+                posting_run_id: "run_1".to_string(),
+                book_policy_id: "policy_dual_book".to_string(),
+                policy_version: "1.0.0".to_string(),
+                fx_rate_set_id: "fx_1".to_string(),
+                ruleset_version: "v5".to_string(),
+                workflow_id: None,
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: None,
+                book_group_id: None,
+                trace_context: None,
+            },
+            lines: vec![JournalLine {
+                line_number: 1,
+                account_id: LIABILITY_ACCOUNT.to_string(),
+                entry_side: EntrySide::Credit,
+                amount_minor,
+                currency: "USD".to_string(),
+                base_amount_minor: amount_minor,
+                base_currency: "USD".to_string(),
+                dimensions: BTreeMap::from([("card_program".to_string(), program_id.to_string())]),
+                fx_rate: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn reconciliation_flags_gl_activity_missing_from_the_subledger() {
+        let cards = vec![card("RESORT_GIFT", 7000), card("PROMO", 1000)];
+        let records = vec![
+            liability_journal(JournalStatus::Posted, "RESORT_GIFT", 7000),
+            liability_journal(JournalStatus::Reversed, "PROMO", 1000),
+            liability_journal(JournalStatus::Posted, "UNSPECIFIED", 300),
+        ];
+        let query = StoredValueReconciliationQuery {
+            book: "US_GAAP".to_string(),
+            legal_entity_id: None,
+        };
+
+        let rows = reconcile_stored_value(&cards, &records, &query);
+        let differences = rows
+            .iter()
+            .map(|row| (row.program_id.as_str(), row.difference_minor))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            differences,
+            vec![("PROMO", -1000), ("RESORT_GIFT", 0), ("UNSPECIFIED", 300)]
+        );
+        assert_eq!(rows[1].active_cards, 1);
+    }
+
+    fn complete(
+        event_type: &str,
+        previous: Option<StoredValueCard>,
+        entry_side: EntrySide,
+        amount_minor: i64,
+    ) -> Result<CardUpdate, StoredValueError> {
+        let mut record = liability_journal(JournalStatus::Posted, "RESORT_GIFT", amount_minor);
+        record.lines[0].entry_side = entry_side;
+        let event = StoredValueEvent {
+            event_type: event_type.to_string(),
+            card_id: "gc_RESORT_GIFT".to_string(),
+            program_id: "RESORT_GIFT".to_string(),
+            issuing_legal_entity_id: "US_CO_01".to_string(),
+            payload: Value::Null,
+            previous,
+        };
+        event.complete(&record, None)
+    }
+
+    #[test]
+    fn redemptions_may_drain_but_not_overdraw_a_card() {
+        let drained = complete(
+            REDEEMED_EVENT,
+            Some(card("RESORT_GIFT", 2500)),
+            EntrySide::Debit,
+            2500,
+        )
+        .unwrap();
+        assert_eq!(drained.next.balance_minor, 0);
+        assert_eq!(drained.next.redeemed_minor, 2500);
+        assert_eq!(drained.next.redeemed_by_channel[UNSPECIFIED], 2500);
+        assert_eq!(drained.next.status, CardStatus::Active);
+
+        assert_eq!(
+            complete(
+                REDEEMED_EVENT,
+                Some(card("RESORT_GIFT", 2500)),
+                EntrySide::Debit,
+                2501,
+            )
+            .err(),
+            Some(StoredValueError::InsufficientBalance {
+                card_id: "gc_RESORT_GIFT".to_string(),
+                amount_minor: 2501,
+                balance_minor: 2500,
+            })
+        );
+
+        let empty = complete(
+            REDEEMED_EVENT,
+            Some(card("RESORT_GIFT", 0)),
+            EntrySide::Debit,
+            0,
+        )
+        .unwrap();
+        assert_eq!(empty.next.balance_minor, 0);
+        assert_eq!(empty.next.redeemed_minor, 0);
+    }
+
+    #[test]
+    fn only_breakage_or_escheatment_of_the_full_balance_closes_a_card() {
+        let partial = complete(
+            BREAKAGE_EVENT,
+            Some(card("RESORT_GIFT", 1000)),
+            EntrySide::Debit,
+            400,
+        )
+        .unwrap();
+        assert_eq!(partial.next.balance_minor, 600);
+        assert_eq!(partial.next.breakage_minor, 400);
+        assert_eq!(partial.next.status, CardStatus::Active);
+
+        let escheated = complete(
+            ESCHEATED_EVENT,
+            Some(card("RESORT_GIFT", 1000)),
+            EntrySide::Debit,
+            1000,
+        )
+        .unwrap();
+        assert_eq!(escheated.next.escheated_minor, 1000);
+        assert_eq!(escheated.next.status, CardStatus::Closed);
+    }
+
+    #[test]
+    fn movements_must_touch_the_liability_in_the_card_currency() {
+        let issued = complete(ISSUED_EVENT, None, EntrySide::Credit, 5000).unwrap();
+        assert!(issued.previous.is_none());
+        assert_eq!(issued.next.balance_minor, 5000);
+        assert_eq!(issued.next.issued_minor, 5000);
+
+        let mut record = liability_journal(JournalStatus::Posted, "RESORT_GIFT", 5000);
+        record.lines[0].account_id = "1105-CASH-CLEARING".to_string();
+        let event = StoredValueEvent {
+            event_type: RELOADED_EVENT.to_string(),
+            card_id: "gc_RESORT_GIFT".to_string(),
+            program_id: "RESORT_GIFT".to_string(),
+            issuing_legal_entity_id: "US_CO_01".to_string(),
+            payload: Value::Null,
+            previous: Some(card("RESORT_GIFT", 100)),
+        };
+        assert_eq!(
+            event.complete(&record, None).err(),
+            Some(StoredValueError::NoLiabilityMovement(
+                RELOADED_EVENT.to_string()
+            ))
+        );
+
+        let mut euro_card = card("RESORT_GIFT", 100);
+        euro_card.currency = "EUR".to_string();
+        assert_eq!(
+            complete(RELOADED_EVENT, Some(euro_card), EntrySide::Credit, 500).err(),
+            Some(StoredValueError::CurrencyMismatch {
+                card_id: "gc_RESORT_GIFT".to_string(),
+                card_currency: "EUR".to_string(),
+                currency: "USD".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn reconciliation_scopes_to_the_legal_entity_and_counts_only_active_cards() {
+        let mut closed = card("RESORT_GIFT", 0);
+        closed.card_id = "gc_closed".to_string();
+        closed.status = CardStatus::Closed;
+        let mut other_entity = card("RESORT_GIFT", 900);
+        other_entity.legal_entity_id = "CA_BC_01".to_string();
+        let cards = vec![card("RESORT_GIFT", 700), closed, other_entity];
+        let records = vec![liability_journal(JournalStatus::Posted, "RESORT_GIFT", 700)];
+        let query = StoredValueReconciliationQuery {
+            book: "US_GAAP".to_string(),
+            legal_entity_id: Some("US_CO_01".to_string()),
+        };
+
+        let rows = reconcile_stored_value(&cards, &records, &query);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].active_cards, 1);
+        assert_eq!(rows[0].subledger_balance_minor, 700);
+        assert_eq!(rows[0].difference_minor, 0);
+
+        let other_book = StoredValueReconciliationQuery {
+            book: "IFRS".to_string(),
+            legal_entity_id: None,
+        };
+        assert!(reconcile_stored_value(&cards, &records, &other_book).is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+use axum::extract::State;
+use axum::Json;
+use ledger_posting::{EntrySide, JournalRecord, JournalStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::calendar::{CalendarError, FiscalCalendars};
+use crate::error::{ApiError, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::{period_error_response, AppState};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TaxLiabilityQuery {
+    pub book: String,
+    pub legal_entity_id: Option<String>,
+    pub period: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, ToSchema)]
+pub struct TaxLiabilityReport {
+    pub book: String,
+    pub period: Option<String>,
+    pub rows: Vec<TaxLiabilityRow>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, ToSchema)]
+pub struct TaxLiabilityRow {
+    pub legal_entity_id: String,
+    pub period: String,
+    pub jurisdiction: String,
+    pub tax_type: String,
+    pub account_id: String,
+    pub currency: String,
+    pub collected_minor: i64,
+    pub refunded_minor: i64,
+    pub net_liability_minor: i64,
+}
+
+pub fn summarize_tax_liabilities(
+    records: &[JournalRecord],
+    calendars: &FiscalCalendars,
+    query: &TaxLiabilityQuery,
+) -> Result<Vec<TaxLiabilityRow>, CalendarError> {
+    let mut rows = BTreeMap::<_, TaxLiabilityRow>::new();
+    for record in records {
+        let header = &record.header;
+        if header.ledger_book != query.book || header.status != JournalStatus::Posted {
+            continue;
+        }
+        if query
+            .legal_entity_id
+            .as_ref()
+            .is_some_and(|legal_entity_id| *legal_entity_id != header.legal_entity_id)
+        {
+            continue;
+        }
+        let period = calendars
+            .calendar(&header.legal_entity_id)
+            .period_for(header.accounting_date)?
+            .period_id;
+        if query
+            .period
+            .as_ref()
+            .is_some_and(|wanted| *wanted != period)
+        {
+            continue;
+        }
+        for line in &record.lines {
+            let (Some(jurisdiction), Some(tax_type)) = (
+                line.dimensions.get("tax_jurisdiction"),
+                line.dimensions.get("tax_type"),
+            ) else {
+                continue;
+            };
+            let key = (
+                header.legal_entity_id.clone(),
+                period.clone(),
+                jurisdiction.clone(),
+                tax_type.clone(),
+                line.account_id.clone(),
+                line.currency.clone(),
+            );
+            let row = rows.entry(key).or_insert_with(|| TaxLiabilityRow {
+                legal_entity_id: header.legal_entity_id.clone(),
+                period: period.clone(),
+                jurisdiction: jurisdiction.clone(),
+                tax_type: tax_type.clone(),
+                account_id: line.account_id.clone(),
+                currency: line.currency.clone(),
+                collected_minor: 0,
+                refunded_minor: 0,
+                net_liability_minor: 0,
+            });
+            match line.entry_side {
+                EntrySide::Credit => row.collected_minor += line.amount_minor,
+                EntrySide::Debit => row.refunded_minor += line.amount_minor,
+            }
+            row.net_liability_minor = row.collected_minor - row.refunded_minor;
+        }
+    }
+    Ok(rows.into_values().collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/tax/liabilities",
+    tag = "tax",
+    params(TaxLiabilityQuery),
+    responses(
+        (status = 200, description = "Tax payable by jurisdiction, tax type and period", body = TaxLiabilityReport),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_tax_liabilities(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<TaxLiabilityQuery>,
+) -> Result<Json<TaxLiabilityReport>, ApiError> {
+    let calendars = state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+        .fiscal_calendars();
+    if let Some(period) = query.period.as_deref() {
+        if !calendars.knows_period_id(query.legal_entity_id.as_deref(), period) {
+            return Err(
+                ApiError::bad_request(ErrorCode::InvalidPeriodId).with_detail("period_id", period)
+            );
+        }
+    }
+    let records = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?
+        .all();
+    let rows = summarize_tax_liabilities(&records, &calendars, &query)
+        .map_err(|error| period_error_response(error.into()))?;
+    Ok(Json(TaxLiabilityReport {
+        book: query.book,
+        period: query.period,
+        rows,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{NaiveDate, Utc};
+    use ledger_posting::{JournalHeader, JournalLine};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::calendar::FiscalCalendar;
+
+    fn tax_line(side: EntrySide, amount_minor: i64, jurisdiction: &str) -> JournalLine {
+        JournalLine {
+            line_number: 1,
+            account_id: "2110-GST-HST-PAYABLE".to_string(),
+            entry_side: side,
+            amount_minor,
+            currency: "CAD".to_string(),
+            base_amount_minor: amount_minor,
+            base_currency: "CAD".to_string(),
+            dimensions: BTreeMap::from([
+                ("tax_jurisdiction".to_string(), jurisdiction.to_string()),
+                ("tax_type".to_string(), "CA_GST".to_string()),
+            ]),
+            fx_rate: None,
+        }
+    }
+
+    fn record(
+        date: (i32, u32, u32),
+        status: JournalStatus,
+        lines: Vec<JournalLine>,
+    ) -> JournalRecord {
+        JournalRecord {
+            header: JournalHeader {
+                journal_id: Uuid::new_v4(),
+                journal_number: "S2-test".to_string(),
+                status,
+                tenant_id: "tenant_1".to_string(),
+                legal_entity_id: "CA_BC_01".to_string(),
+                ledger_book: "IFRS".to_string(),
+                accounting_date: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+                posted_at: Utc::now(),
+                source_event_ids: vec!["evt_1".to_string()],
+                posting_run_id: "run_1".to_string(),
+                book_policy_id: "policy_dual_book".to_string(),
+                policy_version: "1.0.0".to_string(),
+                fx_rate_set_id: "fx_1".to_string(),
+                ruleset_version: "v2".to_string(),
+                workflow_id: None,
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: None,
+                book_group_id: None,
+                trace_context: None,
+            },
+            lines,
+        }
+    }
+
+    #[test]
+    fn nets_collected_and_refunded_tax_per_jurisdiction_and_period() {
+        let records = vec![
+            record(
+                (2026, 2, 3),
+                JournalStatus::Posted,
+                vec![tax_line(EntrySide::Credit, 500, "CA")],
+            ),
+            record(
+                (2026, 2, 9),
+                JournalStatus::Posted,
+                vec![tax_line(EntrySide::Debit, 125, "CA")],
+            ),
+            record(
+                (2026, 2, 10),
+                JournalStatus::Reversed,
+                vec![tax_line(EntrySide::Credit, 900, "CA")],
+            ),
+            record(
+                (2026, 3, 1),
+                JournalStatus::Posted,
+                vec![tax_line(EntrySide::Credit, 70, "CA")],
+            ),
+        ];
+        let query = TaxLiabilityQuery {
+            book: "IFRS".to_string(),
+            legal_entity_id: None,
+            period: None,
+        };
+
+        let calendars = FiscalCalendars::default();
+        let rows = summarize_tax_liabilities(&records, &calendars, &query).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].period, "2026-02");
+        assert_eq!(rows[0].collected_minor, 500);
+        assert_eq!(rows[0].refunded_minor, 125);
+        assert_eq!(rows[0].net_liability_minor, 375);
+        assert_eq!(rows[1].period, "2026-03");
+
+        let february = TaxLiabilityQuery {
+            period: Some("2026-02".to_string()),
+            ..query
+        };
+        assert_eq!(
+            summarize_tax_liabilities(&records, &calendars, &february)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn groups_tax_by_the_entity_fiscal_period() {
+        let records = vec![
+            record(
+                (2026, 6, 30),
+                JournalStatus::Posted,
+                vec![tax_line(EntrySide::Credit, 500, "CA")],
+            ),
+            record(
+                (2026, 7, 1),
+                JournalStatus::Posted,
+                vec![tax_line(EntrySide::Credit, 70, "CA")],
+            ),
+        ];
+        let calendars = FiscalCalendars::new(HashMap::from([(
+            "CA_BC_01".to_string(),
+            FiscalCalendar::FiscalMonth { start_month: 7 },
+        )]));
+        let query = TaxLiabilityQuery {
+            book: "IFRS".to_string(),
+            legal_entity_id: Some("CA_BC_01".to_string()),
+            period: Some("FY2027-P01".to_string()),
+        };
+
+        let rows = summarize_tax_liabilities(&records, &calendars, &query).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].period, "FY2027-P01");
+        assert_eq!(rows[0].collected_minor, 70);
+        assert!(calendars.knows_period_id(Some("CA_BC_01"), "FY2027-P01"));
+        assert!(!calendars.knows_period_id(Some("CA_BC_01"), "2026-07"));
+        assert!(calendars.knows_period_id(None, "FY2027-P01"));
+    }
+}
@@ -0,0 +1,907 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use ledger_posting::{EntrySide, JournalHeader, JournalLine, JournalRecord, JournalStatus};
+use platform_core::payload_hash;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::change_feed::LedgerChangeType;
+use crate::error::{ApiError, ApiJson, ApiQuery, ErrorCode, ErrorEnvelope};
+use crate::period::{PeriodActor, PostingAuthority, PostingClass};
+use crate::persistence::{load_snapshot_or_default, WriteBehind};
+use crate::{
+    deterministic_journal_id, ledger_error_response, period_error_response, signed_amount,
+    AppState, Provenance,
+};
+
+const YEAR_END_CLOSE_STORE_FILENAME: &str = "year_end_close_store.json";
+const RETAINED_EARNINGS_ACCOUNT: &str = "3200-RETAINED-EARNINGS";
+const CLOSING_JOURNAL_PREFIX: &str = "YE-";
+const CLOSING_EVENT_PREFIX: &str = "year-end:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum YearEndCloseStatus {
+    Closed,
+    Reversed,
+}
+
+/// A net account balance. Credit balances are positive, as in the rollforward reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AccountBalance {
+    pub account_id: String,
+    pub currency: String,
+    pub base_currency: String,
+    pub balance_minor: i64,
+    pub base_balance_minor: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct YearEndClose {
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub fiscal_year: i32,
+    pub status: YearEndCloseStatus,
+    pub year_start_date: NaiveDate,
+    pub year_end_date: NaiveDate,
+    pub retained_earnings_account: String,
+    #[schema(value_type = Option<String>)]
+    pub closing_journal_id: Option<Uuid>,
+    /// Income-statement balances the closing journal zeroed.
+    pub closed_balances: Vec<AccountBalance>,
+    /// Net income per base currency; positive is a profit.
+    pub net_income: BTreeMap<String, i64>,
+    /// Balance-sheet balances the next fiscal year opens with.
+    pub opening_date: NaiveDate,
+    pub carry_forward: Vec<AccountBalance>,
+    pub revision: u32,
+    pub closed_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum YearEndError {
+    #[error("fiscal year {0} is out of range")]
+    InvalidFiscalYear(i32),
+    #[error("retained earnings account `{0}` is not a balance-sheet account")]
+    InvalidRetainedEarningsAccount(String),
+    #[error(
+        "fiscal year {fiscal_year} is already closed; reverse the close before running it again"
+    )]
+    AlreadyClosed { fiscal_year: i32 },
+    #[error("fiscal year {fiscal_year} has no year-end close")]
+    NotFound { fiscal_year: i32 },
+    #[error("year-end close for fiscal year {fiscal_year} is already reversed")]
+    AlreadyReversed { fiscal_year: i32 },
+}
+
+type YearEndKey = (String, String, String, i32);
+
+#[derive(Default)]
+pub struct InMemoryYearEndCloseRepository {
+    closes: BTreeMap<YearEndKey, YearEndClose>,
+    persistence: Option<Arc<WriteBehind<Vec<YearEndClose>>>>,
+}
+
+impl InMemoryYearEndCloseRepository {
+    pub fn with_persistence_dir(dir: impl AsRef<FsPath>) -> io::Result<Self> {
+        let path = dir.as_ref().join(YEAR_END_CLOSE_STORE_FILENAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let loaded: Vec<YearEndClose> = load_snapshot_or_default(&path)?;
+        let persistence = Arc::new(WriteBehind::new(path, "year-end-close-write-behind")?);
+        Ok(Self {
+            closes: loaded
+                .into_iter()
+                .map(|close| (close_key(&close), close))
+                .collect(),
+            persistence: Some(persistence),
+        })
+    }
+
+    pub fn flush_persistence(&self) -> io::Result<()> {
+        match &self.persistence {
+            Some(persistence) => persistence.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        fiscal_year: i32,
+    ) -> Option<&YearEndClose> {
+        self.closes.get(&(
+            tenant_id.to_string(),
+            legal_entity_id.to_string(),
+            ledger_book.to_string(),
+            fiscal_year,
+        ))
+    }
+
+    /// The revision the next close of this year runs as. A year that is closed and not
+    /// reversed cannot be closed again.
+    pub fn next_revision(
+        &self,
+        tenant_id: &str,
+        legal_entity_id: &str,
+        ledger_book: &str,
+        fiscal_year: i32,
+    ) -> Result<u32, YearEndError> {
+        match self.get(tenant_id, legal_entity_id, ledger_book, fiscal_year) {
+            Some(close) if close.status == YearEndCloseStatus::Closed => {
+                Err(YearEndError::AlreadyClosed { fiscal_year })
+            }
+            Some(close) => Ok(close.revision + 1),
+            None => Ok(1),
+        }
+    }
+
+    pub fn store(&mut self, close: YearEndClose) {
+        self.closes.insert(close_key(&close), close);
+        if let Some(persistence) = &self.persistence {
+            persistence.persist(self.closes.values().cloned().collect());
+        }
+    }
+}
+
+fn close_key(close: &YearEndClose) -> YearEndKey {
+    (
+        close.tenant_id.clone(),
+        close.legal_entity_id.clone(),
+        close.ledger_book.clone(),
+        close.fiscal_year,
+    )
+}
+
+/// Accounts numbered 4xxx and above are income-statement accounts; 1xxx-3xxx are balance sheet.
+pub fn is_income_statement_account(account_id: &str) -> bool {
+    account_id
+        .chars()
+        .next()
+        .is_some_and(|first| ('4'..='9').contains(&first))
+}
+
+/// Net balances per account and currency over posted journals dated in `from..=through`.
+fn account_balances(
+    journals: &[JournalRecord],
+    scope: (&str, &str, &str),
+    from: Option<NaiveDate>,
+    through: NaiveDate,
+    include: impl Fn(&str) -> bool,
+) -> Vec<AccountBalance> {
+    let (tenant_id, legal_entity_id, ledger_book) = scope;
+    net_balances(
+        journals.iter().filter(|record| {
+            let header = &record.header;
+            header.status == JournalStatus::Posted
+                && header.tenant_id == tenant_id
+                && header.legal_entity_id == legal_entity_id
+                && header.ledger_book == ledger_book
+                && from.is_none_or(|from| header.accounting_date >= from)
+                && header.accounting_date <= through
+        }),
+        include,
+    )
+}
+
+/// Net balances per account and currency over `records`, skipping accounts that net to zero.
+pub(crate) fn net_balances<'a>(
+    records: impl IntoIterator<Item = &'a JournalRecord>,
+    include: impl Fn(&str) -> bool,
+) -> Vec<AccountBalance> {
+    let mut balances: BTreeMap<(String, String, String), (i64, i64)> = BTreeMap::new();
+    for record in records {
+        for line in record.lines.iter().filter(|line| include(&line.account_id)) {
+            let balance = balances
+                .entry((
+                    line.account_id.clone(),
+                    line.currency.clone(),
+                    line.base_currency.clone(),
+                ))
+                .or_default();
+            balance.0 += signed_amount(line.entry_side.clone(), line.amount_minor);
+            balance.1 += signed_amount(line.entry_side.clone(), line.base_amount_minor);
+        }
+    }
+    balances
+        .into_iter()
+        .filter(|(_, (balance, base_balance))| *balance != 0 || *base_balance != 0)
+        .map(
+            |((account_id, currency, base_currency), (balance_minor, base_balance_minor))| {
+                AccountBalance {
+                    account_id,
+                    currency,
+                    base_currency,
+                    balance_minor,
+                    base_balance_minor,
+                }
+            },
+        )
+        .collect()
+}
+
+/// Lines that move `balance` to zero. When an FX-translated balance has opposite signs in
+/// transaction and base currency, the two amounts are cleared on separate lines.
+fn clearing_lines(balance: &AccountBalance) -> Vec<(EntrySide, i64, i64)> {
+    let side = |amount: i64| {
+        if amount > 0 {
+            EntrySide::Debit
+        } else {
+            EntrySide::Credit
+        }
+    };
+    let (amount, base_amount) = (balance.balance_minor, balance.base_balance_minor);
+    if amount.signum() * base_amount.signum() >= 0 {
+        let lead = if amount != 0 { amount } else { base_amount };
+        vec![(side(lead), amount.abs(), base_amount.abs())]
+    } else {
+        vec![
+            (side(amount), amount.abs(), 0),
+            (side(base_amount), 0, base_amount.abs()),
+        ]
+    }
+}
+
+/// Closing lines for the income-statement balances, with one retained earnings line per
+/// currency pair so each currency nets to zero on its own.
+fn closing_lines(
+    closed_balances: &[AccountBalance],
+    retained_earnings_account: &str,
+) -> Vec<JournalLine> {
+    let mut retained: BTreeMap<(String, String), (i64, i64)> = BTreeMap::new();
+    let mut entries = Vec::new();
+    for balance in closed_balances {
+        entries.push((balance.clone(), clearing_lines(balance)));
+        let total = retained
+            .entry((balance.currency.clone(), balance.base_currency.clone()))
+            .or_default();
+        total.0 += balance.balance_minor;
+        total.1 += balance.base_balance_minor;
+    }
+    for ((currency, base_currency), (amount, base_amount)) in retained {
+        let offset = AccountBalance {
+            account_id: retained_earnings_account.to_string(),
+            currency,
+            base_currency,
+            balance_minor: -amount,
+            base_balance_minor: -base_amount,
+        };
+        if amount != 0 || base_amount != 0 {
+            entries.push((offset.clone(), clearing_lines(&offset)));
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (balance, clearing) in entries {
+        for (entry_side, amount_minor, base_amount_minor) in clearing {
+            lines.push(JournalLine {
+                line_number: lines.len() as u32 + 1,
+                account_id: balance.account_id.clone(),
+                entry_side,
+                amount_minor,
+                currency: balance.currency.clone(),
+                base_amount_minor,
+                base_currency: balance.base_currency.clone(),
+                dimensions: Default::default(),
+                fx_rate: None,
+            });
+        }
+    }
+    lines
+}
+
+fn year_end_error_response(error: YearEndError) -> ApiError {
+    let message = error.to_string();
+    let api_error = match error {
+        YearEndError::InvalidFiscalYear(fiscal_year) => {
+            ApiError::bad_request(ErrorCode::InvalidFiscalYear)
+                .with_detail("fiscal_year", fiscal_year)
+        }
+        YearEndError::InvalidRetainedEarningsAccount(account_id) => {
+            ApiError::bad_request(ErrorCode::InvalidRetainedEarningsAccount)
+                .with_detail("account_id", account_id)
+        }
+        YearEndError::AlreadyClosed { fiscal_year } => {
+            ApiError::conflict(ErrorCode::YearEndAlreadyClosed)
+                .with_detail("fiscal_year", fiscal_year)
+        }
+        YearEndError::NotFound { fiscal_year } => {
+            ApiError::not_found(ErrorCode::YearEndCloseNotFound)
+                .with_detail("fiscal_year", fiscal_year)
+        }
+        YearEndError::AlreadyReversed { fiscal_year } => {
+            ApiError::conflict(ErrorCode::YearEndCloseAlreadyReversed)
+                .with_detail("fiscal_year", fiscal_year)
+        }
+    };
+    api_error.with_message(message)
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct YearEndCloseRequest {
+    pub tenant_id: String,
+    pub legal_entity_id: String,
+    pub ledger_book: String,
+    pub fiscal_year: i32,
+    pub posting_run_id: String,
+    pub provenance: Provenance,
+    #[serde(default)]
+    pub retained_earnings_account: Option<String>,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ReverseYearEndCloseRequest {
+    pub tenant_id: String,
+    pub ledger_book: String,
+    #[serde(default)]
+    pub actor: Option<PeriodActor>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct YearEndCloseQuery {
+    pub tenant_id: String,
+    pub ledger_book: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct YearEndCloseResponse {
+    #[serde(flatten)]
+    pub close: YearEndClose,
+    pub audit_seal: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/year-end-closes",
+    tag = "ledger",
+    request_body = YearEndCloseRequest,
+    responses(
+        (status = 200, description = "Fiscal year closed into retained earnings", body = YearEndCloseResponse),
+        (status = 400, description = "Invalid fiscal year, account or provenance", body = ErrorEnvelope),
+        (status = 409, description = "Year already closed, or last period not open for close entries", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn close_fiscal_year(
+    State(state): State<AppState>,
+    ApiJson(req): ApiJson<YearEndCloseRequest>,
+) -> Result<Json<YearEndCloseResponse>, ApiError> {
+    if !(1..=9998).contains(&req.fiscal_year) {
+        return Err(year_end_error_response(YearEndError::InvalidFiscalYear(
+            req.fiscal_year,
+        )));
+    }
+    let retained_earnings_account = req
+        .retained_earnings_account
+        .clone()
+        .unwrap_or_else(|| RETAINED_EARNINGS_ACCOUNT.to_string());
+    if is_income_statement_account(&retained_earnings_account) {
+        return Err(year_end_error_response(
+            YearEndError::InvalidRetainedEarningsAccount(retained_earnings_account),
+        ));
+    }
+    state.validate_posting_policy(&req.provenance, &req.ledger_book)?;
+
+    let mut closes = state
+        .year_end_closes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::YearEndCloseStoreError))?;
+    let revision = closes
+        .next_revision(
+            &req.tenant_id,
+            &req.legal_entity_id,
+            &req.ledger_book,
+            req.fiscal_year,
+        )
+        .map_err(year_end_error_response)?;
+
+    let (year_start_date, year_end_date) = {
+        let periods = state
+            .periods
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?;
+        let fiscal_periods = periods
+            .calendar(&req.legal_entity_id)
+            .periods(req.fiscal_year)
+            .map_err(|error| period_error_response(error.into()))?;
+        let year_start_date = fiscal_periods[0].start_date;
+        let year_end_date = fiscal_periods[fiscal_periods.len() - 1].end_date;
+        periods
+            .ensure_open(
+                &req.tenant_id,
+                &req.legal_entity_id,
+                &req.ledger_book,
+                year_end_date,
+                PostingAuthority::new(PostingClass::Close, req.actor.as_ref()),
+            )
+            .map_err(period_error_response)?;
+        (year_start_date, year_end_date)
+    };
+    state.validate_legal_hold(
+        &req.tenant_id,
+        &req.legal_entity_id,
+        &req.ledger_book,
+        year_end_date,
+    )?;
+
+    let scope = (
+        req.tenant_id.as_str(),
+        req.legal_entity_id.as_str(),
+        req.ledger_book.as_str(),
+    );
+    let mut journals = state
+        .journals
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+    let closed_balances = account_balances(
+        &journals.all(),
+        scope,
+        Some(year_start_date),
+        year_end_date,
+        is_income_statement_account,
+    );
+    let mut net_income = BTreeMap::new();
+    for balance in &closed_balances {
+        *net_income.entry(balance.base_currency.clone()).or_insert(0) += balance.base_balance_minor;
+    }
+
+    let closing_journal_id = if closed_balances.is_empty() {
+        None
+    } else {
+        let journal_id = deterministic_journal_id(
+            &format!(
+                "year-end:{}:{}:{}:{}",
+                req.tenant_id, req.legal_entity_id, req.ledger_book, req.fiscal_year
+            ),
+            &payload_hash(&json!({
+                "revision": revision,
+                "posting_run_id": &req.posting_run_id,
+            })),
+        );
+        let record = JournalRecord {
+            header: JournalHeader {
+                journal_id,
+                journal_number: format!("{CLOSING_JOURNAL_PREFIX}{}", &journal_id.to_string()[..8]),
+                status: JournalStatus::Posted,
+                tenant_id: req.tenant_id.clone(),
+                legal_entity_id: req.legal_entity_id.clone(),
+                ledger_book: req.ledger_book.clone(),
+                accounting_date: year_end_date,
+                posted_at: Utc::now(),
+                source_event_ids: vec![format!(
+                    "{CLOSING_EVENT_PREFIX}{}:r{revision}",
+                    req.fiscal_year
+                )],
+                posting_run_id: req.posting_run_id.clone(),
+                book_policy_id: req.provenance.book_policy_id.clone(),
+                policy_version: req.provenance.policy_version.clone(),
+                fx_rate_set_id: req.provenance.fx_rate_set_id.clone(),
+                ruleset_version: req.provenance.ruleset_version.clone(),
+                workflow_id: req.provenance.workflow_id.clone(),
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: None,
+                book_group_id: None,
+                trace_context: None,
+            },
+            lines: closing_lines(&closed_balances, &retained_earnings_account),
+        };
+        let header = record.header.clone();
+        journals
+            .insert_posted(record)
+            .map_err(ledger_error_response)?;
+        state.record_change(LedgerChangeType::JournalPosted, &header, None)?;
+        Some(journal_id)
+    };
+    let carry_forward = account_balances(&journals.all(), scope, None, year_end_date, |account| {
+        !is_income_statement_account(account)
+    });
+    drop(journals);
+
+    let close = YearEndClose {
+        tenant_id: req.tenant_id.clone(),
+        legal_entity_id: req.legal_entity_id.clone(),
+        ledger_book: req.ledger_book.clone(),
+        fiscal_year: req.fiscal_year,
+        status: YearEndCloseStatus::Closed,
+        year_start_date,
+        year_end_date,
+        retained_earnings_account,
+        closing_journal_id,
+        closed_balances,
+        net_income,
+        opening_date: year_end_date + Days::new(1),
+        carry_forward,
+        revision,
+        closed_at: Utc::now(),
+        reversed_at: None,
+    };
+    closes.store(close.clone());
+    drop(closes);
+
+    let audit_seal = state.append_audit_seal(
+        "ledger.year_end_closed",
+        std::slice::from_ref(&close.legal_entity_id),
+        &json!({
+            "tenant_id": &close.tenant_id,
+            "ledger_book": &close.ledger_book,
+            "fiscal_year": close.fiscal_year,
+            "revision": close.revision,
+            "closing_journal_id": close.closing_journal_id,
+            "net_income": &close.net_income,
+        }),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+    Ok(Json(YearEndCloseResponse { close, audit_seal }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/ledger/year-end-closes/{legal_entity_id}/{fiscal_year}",
+    tag = "ledger",
+    params(
+        ("legal_entity_id" = String, Path, description = "Legal entity that closed the year"),
+        ("fiscal_year" = i32, Path, description = "Fiscal year"),
+        YearEndCloseQuery
+    ),
+    responses(
+        (status = 200, description = "Year-end close with its carry-forward balances", body = YearEndClose),
+        (status = 400, description = "Invalid query", body = ErrorEnvelope),
+        (status = 404, description = "Year not closed", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn get_year_end_close(
+    State(state): State<AppState>,
+    Path((legal_entity_id, fiscal_year)): Path<(String, i32)>,
+    ApiQuery(query): ApiQuery<YearEndCloseQuery>,
+) -> Result<Json<YearEndClose>, ApiError> {
+    state
+        .year_end_closes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::YearEndCloseStoreError))?
+        .get(
+            &query.tenant_id,
+            &legal_entity_id,
+            &query.ledger_book,
+            fiscal_year,
+        )
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| year_end_error_response(YearEndError::NotFound { fiscal_year }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/ledger/year-end-closes/{legal_entity_id}/{fiscal_year}/reverse",
+    tag = "ledger",
+    params(
+        ("legal_entity_id" = String, Path, description = "Legal entity that closed the year"),
+        ("fiscal_year" = i32, Path, description = "Fiscal year to reopen")
+    ),
+    request_body = ReverseYearEndCloseRequest,
+    responses(
+        (status = 200, description = "Closing journal reversed; the year may be closed again", body = YearEndCloseResponse),
+        (status = 404, description = "Year not closed", body = ErrorEnvelope),
+        (status = 409, description = "Close already reversed, or last period not open for close entries", body = ErrorEnvelope),
+        (status = 422, description = "Malformed request body", body = ErrorEnvelope),
+        (status = 500, description = "Store failure", body = ErrorEnvelope)
+    )
+)]
+pub(crate) async fn reverse_year_end_close(
+    State(state): State<AppState>,
+    Path((legal_entity_id, fiscal_year)): Path<(String, i32)>,
+    ApiJson(req): ApiJson<ReverseYearEndCloseRequest>,
+) -> Result<Json<YearEndCloseResponse>, ApiError> {
+    let mut closes = state
+        .year_end_closes
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::YearEndCloseStoreError))?;
+    let mut close = closes
+        .get(
+            &req.tenant_id,
+            &legal_entity_id,
+            &req.ledger_book,
+            fiscal_year,
+        )
+        .cloned()
+        .ok_or_else(|| year_end_error_response(YearEndError::NotFound { fiscal_year }))?;
+    if close.status == YearEndCloseStatus::Reversed {
+        return Err(year_end_error_response(YearEndError::AlreadyReversed {
+            fiscal_year,
+        }));
+    }
+    state
+        .periods
+        .lock()
+        .map_err(|_| ApiError::internal(ErrorCode::PeriodStoreError))?
+        .ensure_open(
+            &close.tenant_id,
+            &close.legal_entity_id,
+            &close.ledger_book,
+            close.year_end_date,
+            PostingAuthority::new(PostingClass::Close, req.actor.as_ref()),
+        )
+        .map_err(period_error_response)?;
+
+    if let Some(journal_id) = close.closing_journal_id {
+        let mut journals = state
+            .journals
+            .lock()
+            .map_err(|_| ApiError::internal(ErrorCode::JournalStoreError))?;
+        journals
+            .reverse(&journal_id)
+            .map_err(ledger_error_response)?;
+        if let Some(record) = journals.get(&journal_id) {
+            state.record_change(LedgerChangeType::JournalReversed, &record.header, None)?;
+        }
+    }
+    close.status = YearEndCloseStatus::Reversed;
+    close.reversed_at = Some(Utc::now());
+    closes.store(close.clone());
+    drop(closes);
+
+    let audit_seal = state.append_audit_seal(
+        "ledger.year_end_reversed",
+        std::slice::from_ref(&close.legal_entity_id),
+        &json!({
+            "tenant_id": &close.tenant_id,
+            "ledger_book": &close.ledger_book,
+            "fiscal_year": close.fiscal_year,
+            "revision": close.revision,
+            "closing_journal_id": close.closing_journal_id,
+        }),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+    )?;
+    Ok(Json(YearEndCloseResponse { close, audit_seal }))
+}
+
+/// Whether the journal is a year-end closing journal. Those are only reversed together with
+/// their close, so the close's status follows the journal.
+pub(crate) fn is_closing_journal(header: &JournalHeader) -> bool {
+    header.journal_number.starts_with(CLOSING_JOURNAL_PREFIX)
+        && header
+            .source_event_ids
+            .iter()
+            .any(|source_event_id| source_event_id.starts_with(CLOSING_EVENT_PREFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Utc};
+    use ledger_posting::{
+        validate_balanced, EntrySide, JournalHeader, JournalLine, JournalRecord, JournalStatus,
+    };
+    use uuid::Uuid;
+
+    use super::{
+        account_balances, clearing_lines, closing_lines, is_income_statement_account,
+        AccountBalance,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn revenue_journal(accounting_date: NaiveDate, amount_minor: i64) -> JournalRecord {
+        let line = |line_number, account_id: &str, entry_side| JournalLine {
+            line_number,
+            account_id: account_id.to_string(),
+            entry_side,
+            amount_minor,
+            currency: "USD".to_string(),
+            base_amount_minor: amount_minor,
+            base_currency: "USD".to_string(),
+            dimensions: Default::default(),
+            fx_rate: None,
+        };
+        JournalRecord {
+            header: JournalHeader {
+                journal_id: Uuid::new_v4(),
+                journal_number: "S2-test".to_string(),
+                status: JournalStatus::Posted,
+                tenant_id: "tenant_1".to_string(),
+                legal_entity_id: "US_CO_01".to_string(),
+                ledger_book: "US_GAAP".to_string(),
+                accounting_date,
+                posted_at: Utc::now(),
+                source_event_ids: vec!["evt_1".to_string()],
+                posting_run_id: "run_1".to_string(),
+                book_policy_id: "policy_us_gaap".to_string(),
+                policy_version: "1.0.0".to_string(),
+                fx_rate_set_id: "fx_1".to_string(),
+                ruleset_version: "v1".to_string(),
+                workflow_id: None,
+                estimate_version: None,
+                revenue_designation: None,
+                prior_period_adjustment: None,
+                book_group_id: None,
+                trace_context: None,
+            },
+            lines: vec![
+                line(1, "1105-CASH-CLEARING", EntrySide::Debit),
+                line(2, "4000-REVENUE", EntrySide::Credit),
+            ],
+        }
+    }
+
+    fn balance(account_id: &str, currency: &str, amount: i64, base_amount: i64) -> AccountBalance {
+        AccountBalance {
+            account_id: account_id.to_string(),
+            currency: currency.to_string(),
+            base_currency: "USD".to_string(),
+            balance_minor: amount,
+            base_balance_minor: base_amount,
+        }
+    }
+
+    #[test]
+    fn closing_lines_zero_income_statement_into_retained_earnings_per_currency() {
+        assert!(is_income_statement_account("4000-REVENUE"));
+        assert!(is_income_statement_account("7300-FX-TRANSLATION-GAIN-LOSS"));
+        assert!(!is_income_statement_account("3200-RETAINED-EARNINGS"));
+
+        let lines = closing_lines(
+            &[
+                balance("4000-REVENUE", "USD", 10000, 10000),
+                balance("6100-PAYMENT-FEES", "USD", -300, -300),
+                balance("4000-REVENUE", "EUR", 5000, 5400),
+                balance("7300-FX-TRANSLATION-GAIN-LOSS", "EUR", 10, -20),
+            ],
+            "3200-RETAINED-EARNINGS",
+        );
+        validate_balanced(&lines).unwrap();
+        let summary = lines
+            .iter()
+            .map(|line| {
+                (
+                    line.account_id.as_str(),
+                    line.currency.as_str(),
+                    line.entry_side.clone(),
+                    line.amount_minor,
+                    line.base_amount_minor,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("4000-REVENUE", "USD", EntrySide::Debit, 10000, 10000),
+                ("6100-PAYMENT-FEES", "USD", EntrySide::Credit, 300, 300),
+                ("4000-REVENUE", "EUR", EntrySide::Debit, 5000, 5400),
+                (
+                    "7300-FX-TRANSLATION-GAIN-LOSS",
+                    "EUR",
+                    EntrySide::Debit,
+                    10,
+                    0
+                ),
+                (
+                    "7300-FX-TRANSLATION-GAIN-LOSS",
+                    "EUR",
+                    EntrySide::Credit,
+                    0,
+                    20
+                ),
+                (
+                    "3200-RETAINED-EARNINGS",
+                    "EUR",
+                    EntrySide::Credit,
+                    5010,
+                    5380
+                ),
+                (
+                    "3200-RETAINED-EARNINGS",
+                    "USD",
+                    EntrySide::Credit,
+                    9700,
+                    9700
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn balances_include_both_ends_of_the_fiscal_year_and_only_posted_journals() {
+        let mut reversed = revenue_journal(date(6, 30), 700);
+        reversed.header.status = JournalStatus::Reversed;
+        let mut other_book = revenue_journal(date(6, 30), 900);
+        other_book.header.ledger_book = "IFRS".to_string();
+        let journals = vec![
+            revenue_journal(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(), 1),
+            revenue_journal(date(1, 1), 10),
+            revenue_journal(date(12, 31), 100),
+            revenue_journal(NaiveDate::from_ymd_opt(2027, 1, 1).unwrap(), 1000),
+            reversed,
+            other_book,
+        ];
+
+        let balances = account_balances(
+            &journals,
+            ("tenant_1", "US_CO_01", "US_GAAP"),
+            Some(date(1, 1)),
+            date(12, 31),
+            is_income_statement_account,
+        );
+        assert_eq!(balances, vec![balance("4000-REVENUE", "USD", 110, 110)]);
+
+        let cumulative = account_balances(
+            &journals,
+            ("tenant_1", "US_CO_01", "US_GAAP"),
+            None,
+            date(12, 31),
+            |account_id| !is_income_statement_account(account_id),
+        );
+        assert_eq!(
+            cumulative,
+            vec![balance("1105-CASH-CLEARING", "USD", -111, -111)]
+        );
+    }
+
+    #[test]
+    fn accounts_that_net_to_zero_need_no_closing_entry() {
+        let journals = vec![
+            revenue_journal(date(3, 1), 2500),
+            revenue_journal(date(3, 2), -2500),
+        ];
+        assert!(account_balances(
+            &journals,
+            ("tenant_1", "US_CO_01", "US_GAAP"),
+            Some(date(1, 1)),
+            date(12, 31),
+            is_income_statement_account,
+        )
+        .is_empty());
+        assert!(closing_lines(&[], "3200-RETAINED-EARNINGS").is_empty());
+
+        let offsetting = closing_lines(
+            &[
+                balance("4000-REVENUE", "USD", 400, 400),
+                balance("6100-PAYMENT-FEES", "USD", -400, -400),
+            ],
+            "3200-RETAINED-EARNINGS",
+        );
+        validate_balanced(&offsetting).unwrap();
+        assert!(offsetting
+            .iter()
+            .all(|line| line.account_id != "3200-RETAINED-EARNINGS"));
+    }
+
+    #[test]
+    fn base_only_residuals_are_cleared_on_the_base_amount_sign() {
+        assert_eq!(
+            clearing_lines(&balance("7300-FX-TRANSLATION-GAIN-LOSS", "EUR", 0, -15)),
+            vec![(EntrySide::Credit, 0, 15)]
+        );
+        assert_eq!(
+            clearing_lines(&balance("7300-FX-TRANSLATION-GAIN-LOSS", "EUR", 0, 15)),
+            vec![(EntrySide::Debit, 0, 15)]
+        );
+        assert_eq!(
+            clearing_lines(&balance("6100-PAYMENT-FEES", "USD", -300, -300)),
+            vec![(EntrySide::Credit, 300, 300)]
+        );
+        assert!(!is_income_statement_account(""));
+        assert!(!is_income_statement_account("REVENUE"));
+    }
+}
@@ -941,11 +941,7 @@ fn is_valid_close_dependency_transition(
 }
 
 fn normalize(input: &str) -> String {
-    input
-        .trim()
-        .to_ascii_uppercase()
-        .replace(' ', "_")
-        .replace('-', "_")
+    input.trim().to_ascii_uppercase().replace([' ', '-'], "_")
 }
 
 fn normalize_currency(input: &str) -> String {
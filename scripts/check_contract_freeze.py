@@ -6,6 +6,7 @@ required_paths = [
     "contracts/canonical_event_v1.schema.json",
     "contracts/reconciliation_model_v0.json",
     "contracts/exception_taxonomy_v0.json",
+    "contracts/posting_api_openapi_v1.json",
     "finance/POSTING_RULE_TEMPLATE_PACK_V1.md",
     "finance/DUAL_BOOK_POLICY_PACKAGE_V1.md",
     "controls/ACCESS_MODEL_V0.md",